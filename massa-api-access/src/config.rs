@@ -0,0 +1,59 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+/// Token bucket quota of a client
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct Quota {
+    /// maximum number of tokens a client can accumulate, i.e. the maximum burst weight
+    pub burst: u32,
+    /// number of tokens given back to a client every second
+    pub rate: u32,
+}
+
+/// API access configuration
+#[derive(Debug, Deserialize, Clone)]
+pub struct AccessConfig {
+    /// whether authentication and rate limiting are enforced
+    pub enabled: bool,
+    /// whether requests without a valid API key are rejected
+    pub require_api_key: bool,
+    /// accepted API keys
+    pub api_keys: HashSet<String>,
+    /// whether the last address of the `X-Forwarded-For` header identifies the client,
+    /// to be enabled only when the APIs are behind a trusted reverse proxy
+    pub trust_forwarded_for: bool,
+    /// quota of each client identified by its IP address
+    pub ip_quota: Quota,
+    /// quota of each client identified by its API key
+    pub api_key_quota: Quota,
+    /// weight of the methods missing from `method_weights`
+    pub default_method_weight: u32,
+    /// weight of methods, by snake_case name (gRPC method names are converted to snake_case)
+    pub method_weights: HashMap<String, u32>,
+    /// maximum number of clients whose token buckets are tracked
+    pub max_tracked_clients: usize,
+}
+
+impl Default for AccessConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            require_api_key: false,
+            api_keys: HashSet::new(),
+            trust_forwarded_for: false,
+            ip_quota: Quota {
+                burst: 200,
+                rate: 50,
+            },
+            api_key_quota: Quota {
+                burst: 2000,
+                rate: 500,
+            },
+            default_method_weight: 1,
+            method_weights: HashMap::new(),
+            max_tracked_clients: 100_000,
+        }
+    }
+}
@@ -0,0 +1,448 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::config::{AccessConfig, Quota};
+use crate::error::{AccessError, AccessResult};
+use hyper::header::AUTHORIZATION;
+use hyper::HeaderMap;
+use massa_metrics::MassaMetrics;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info};
+
+/// Header carrying the API key of a client
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Header carrying the addresses of a client and of the proxies it went through
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+/// Loads a fresh access configuration, typically from the configuration files of the node
+pub type AccessConfigLoader = Arc<dyn Fn() -> Result<AccessConfig, String> + Send + Sync>;
+
+/// API whose requests are checked, used to label metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKind {
+    /// public JSON-RPC APIs
+    JsonRpc,
+    /// public gRPC API
+    Grpc,
+}
+
+impl ApiKind {
+    fn label(&self) -> &'static str {
+        match self {
+            ApiKind::JsonRpc => "jsonrpc",
+            ApiKind::Grpc => "grpc",
+        }
+    }
+}
+
+/// Identity of a client, owning a token bucket
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ClientId {
+    /// client sending a valid API key
+    ApiKey(String),
+    /// client identified by its IP address
+    Ip(IpAddr),
+    /// clients whose address is unknown, sharing a single bucket
+    Unidentified,
+}
+
+/// Token bucket of a client
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    /// available tokens
+    tokens: f64,
+    /// last time tokens were given back
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Full bucket
+    fn new(quota: &Quota, now: Instant) -> Self {
+        Self {
+            tokens: quota.burst as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Tokens available at `now`
+    fn available(&self, quota: &Quota, now: Instant) -> f64 {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        (self.tokens + elapsed * quota.rate as f64).min(quota.burst as f64)
+    }
+
+    /// Consume `weight` tokens if available.
+    /// A weight above the burst of the quota consumes a full bucket.
+    fn try_consume(&mut self, quota: &Quota, weight: u32, now: Instant) -> bool {
+        self.tokens = self.available(quota, now);
+        self.last_refill = now;
+        let weight = weight.min(quota.burst) as f64;
+        if self.tokens >= weight {
+            self.tokens -= weight;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Configuration and buckets, guarded together so that a reload resets the buckets atomically
+struct AccessState {
+    config: AccessConfig,
+    buckets: HashMap<ClientId, TokenBucket>,
+}
+
+impl AccessState {
+    /// Authenticate a request and charge its weight to its client.
+    /// Returns the charged weight.
+    fn check(
+        &mut self,
+        headers: &HeaderMap,
+        remote_ip: Option<IpAddr>,
+        methods: &[&str],
+        now: Instant,
+    ) -> AccessResult<u32> {
+        let (client, quota) = match api_key(headers) {
+            Some(key) if self.config.api_keys.contains(key) => {
+                (ClientId::ApiKey(key.to_string()), self.config.api_key_quota)
+            }
+            Some(_) => return Err(AccessError::Unauthorized("invalid API key".to_string())),
+            None if self.config.require_api_key => {
+                return Err(AccessError::Unauthorized("missing API key".to_string()))
+            }
+            None => {
+                let ip = if self.config.trust_forwarded_for {
+                    forwarded_for(headers).or(remote_ip)
+                } else {
+                    remote_ip
+                };
+                (
+                    ip.map_or(ClientId::Unidentified, ClientId::Ip),
+                    self.config.ip_quota,
+                )
+            }
+        };
+        let weight = self.weight(methods);
+
+        if !self.buckets.contains_key(&client)
+            && self.buckets.len() >= self.config.max_tracked_clients
+        {
+            // forget the clients whose bucket is full again: they start from a full bucket anyway
+            let config = &self.config;
+            self.buckets.retain(|client, bucket| {
+                let quota = match client {
+                    ClientId::ApiKey(_) => &config.api_key_quota,
+                    _ => &config.ip_quota,
+                };
+                bucket.available(quota, now) < quota.burst as f64
+            });
+            if self.buckets.len() >= self.config.max_tracked_clients {
+                return Err(AccessError::RateLimited(
+                    "too many clients are being tracked".to_string(),
+                ));
+            }
+        }
+
+        let bucket = self
+            .buckets
+            .entry(client)
+            .or_insert_with(|| TokenBucket::new(&quota, now));
+        if bucket.try_consume(&quota, weight, now) {
+            Ok(weight)
+        } else {
+            Err(AccessError::RateLimited(format!(
+                "quota exceeded by a request of weight {}",
+                weight
+            )))
+        }
+    }
+
+    /// Weight of a request calling `methods`, the default weight if they are unknown
+    fn weight(&self, methods: &[&str]) -> u32 {
+        if methods.is_empty() {
+            return self.config.default_method_weight;
+        }
+        methods.iter().fold(0u32, |total, method| {
+            total.saturating_add(
+                *self
+                    .config
+                    .method_weights
+                    .get(*method)
+                    .unwrap_or(&self.config.default_method_weight),
+            )
+        })
+    }
+}
+
+/// Checks the requests of the public APIs against the access configuration.
+/// Shared by all the public APIs so that a client has a single quota.
+#[derive(Clone)]
+pub struct AccessController {
+    state: Arc<Mutex<AccessState>>,
+    loader: Option<AccessConfigLoader>,
+    metrics: MassaMetrics,
+}
+
+impl AccessController {
+    /// Create a controller
+    ///
+    /// # Arguments
+    /// * `config`: initial access configuration
+    /// * `loader`: source of the configuration on reload, reloading is not supported if `None`
+    /// * `metrics`: counters of allowed and rejected requests
+    pub fn new(
+        config: AccessConfig,
+        loader: Option<AccessConfigLoader>,
+        metrics: MassaMetrics,
+    ) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(AccessState {
+                config,
+                buckets: HashMap::new(),
+            })),
+            loader,
+            metrics,
+        }
+    }
+
+    /// Whether authentication and rate limiting are enforced
+    pub fn is_enabled(&self) -> bool {
+        self.state.lock().config.enabled
+    }
+
+    /// Current access configuration
+    pub fn config(&self) -> AccessConfig {
+        self.state.lock().config.clone()
+    }
+
+    /// Replace the access configuration. All the buckets are reset.
+    pub fn set_config(&self, config: AccessConfig) {
+        let mut state = self.state.lock();
+        state.config = config;
+        state.buckets.clear();
+    }
+
+    /// Load the access configuration again from its source
+    pub fn reload(&self) -> AccessResult<()> {
+        let loader = self.loader.as_ref().ok_or_else(|| {
+            AccessError::ReloadError("no configuration source to reload from".to_string())
+        })?;
+        let config = loader().map_err(AccessError::ReloadError)?;
+        self.set_config(config);
+        info!("API access configuration reloaded");
+        Ok(())
+    }
+
+    /// Check a request and update the metrics
+    ///
+    /// # Arguments
+    /// * `api`: API receiving the request
+    /// * `headers`: request headers, carrying the API key
+    /// * `remote_ip`: address of the peer of the connection, if known
+    /// * `methods`: methods called by the request, empty if unknown
+    pub(crate) fn check(
+        &self,
+        api: ApiKind,
+        headers: &HeaderMap,
+        remote_ip: Option<IpAddr>,
+        methods: &[&str],
+    ) -> AccessResult<()> {
+        let result = {
+            let mut state = self.state.lock();
+            if !state.config.enabled {
+                return Ok(());
+            }
+            state.check(headers, remote_ip, methods, Instant::now())
+        };
+        match result {
+            Ok(weight) => {
+                self.metrics
+                    .inc_api_requests_allowed(api.label(), weight as u64);
+                Ok(())
+            }
+            Err(err) => {
+                debug!(
+                    "{} request from {:?} rejected: {}",
+                    api.label(),
+                    remote_ip,
+                    err
+                );
+                if let AccessError::Unauthorized(_) = err {
+                    self.metrics.inc_api_requests_unauthorized(api.label());
+                } else {
+                    self.metrics.inc_api_requests_rate_limited(api.label());
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+/// API key sent in the `x-api-key` header or as a bearer token
+fn api_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get(API_KEY_HEADER) {
+        return key.to_str().ok().map(str::trim);
+    }
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+/// Address appended to `X-Forwarded-For` by the reverse proxy in front of the node
+fn forwarded_for(headers: &HeaderMap) -> Option<IpAddr> {
+    headers
+        .get(FORWARDED_FOR_HEADER)?
+        .to_str()
+        .ok()?
+        .rsplit(',')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn state(config: AccessConfig) -> AccessState {
+        AccessState {
+            config,
+            buckets: HashMap::new(),
+        }
+    }
+
+    fn config() -> AccessConfig {
+        AccessConfig {
+            enabled: true,
+            api_keys: ["secret".to_string()].into_iter().collect(),
+            ip_quota: Quota { burst: 10, rate: 5 },
+            api_key_quota: Quota {
+                burst: 100,
+                rate: 50,
+            },
+            method_weights: [("execute_read_only_call".to_string(), 4)]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn key_headers(key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, key.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_ip_quota_is_refilled() {
+        let mut state = state(config());
+        let ip = Some("10.0.0.1".parse().unwrap());
+        let now = Instant::now();
+        let heavy = ["execute_read_only_call"];
+
+        assert_eq!(state.check(&HeaderMap::new(), ip, &heavy, now).unwrap(), 4);
+        assert_eq!(state.check(&HeaderMap::new(), ip, &heavy, now).unwrap(), 4);
+        assert!(matches!(
+            state.check(&HeaderMap::new(), ip, &heavy, now),
+            Err(AccessError::RateLimited(_))
+        ));
+        // another client has its own bucket
+        let other_ip = Some("10.0.0.2".parse().unwrap());
+        state
+            .check(&HeaderMap::new(), other_ip, &heavy, now)
+            .unwrap();
+        // 2 tokens left, 2.5 given back after half a second
+        state
+            .check(
+                &HeaderMap::new(),
+                ip,
+                &heavy,
+                now + Duration::from_millis(500),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_batch_weight() {
+        let state = state(config());
+        assert_eq!(state.weight(&[]), 1);
+        assert_eq!(state.weight(&["get_status", "execute_read_only_call"]), 5);
+    }
+
+    #[test]
+    fn test_api_keys() {
+        let mut config = config();
+        config.require_api_key = true;
+        let mut state = state(config);
+        let now = Instant::now();
+
+        assert!(matches!(
+            state.check(&HeaderMap::new(), None, &[], now),
+            Err(AccessError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            state.check(&key_headers("wrong"), None, &[], now),
+            Err(AccessError::Unauthorized(_))
+        ));
+        state.check(&key_headers("secret"), None, &[], now).unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, "Bearer secret".parse().unwrap());
+        state.check(&headers, None, &[], now).unwrap();
+        assert_eq!(
+            state.buckets[&ClientId::ApiKey("secret".to_string())].tokens,
+            98.0
+        );
+    }
+
+    #[test]
+    fn test_forwarded_for() {
+        let mut config = config();
+        config.trust_forwarded_for = true;
+        let mut state = state(config);
+        let mut headers = HeaderMap::new();
+        headers.insert(FORWARDED_FOR_HEADER, "1.2.3.4, 10.0.0.3".parse().unwrap());
+        state
+            .check(
+                &headers,
+                Some("127.0.0.1".parse().unwrap()),
+                &[],
+                Instant::now(),
+            )
+            .unwrap();
+        assert!(state
+            .buckets
+            .contains_key(&ClientId::Ip("10.0.0.3".parse().unwrap())));
+    }
+
+    #[test]
+    fn test_max_tracked_clients() {
+        let mut config = config();
+        config.max_tracked_clients = 1;
+        let mut state = state(config);
+        let now = Instant::now();
+        let first = Some("10.0.0.1".parse().unwrap());
+        let second = Some("10.0.0.2".parse().unwrap());
+
+        state.check(&HeaderMap::new(), first, &[], now).unwrap();
+        assert!(matches!(
+            state.check(&HeaderMap::new(), second, &[], now),
+            Err(AccessError::RateLimited(_))
+        ));
+        // once the bucket of the first client is full again, it is forgotten
+        state
+            .check(&HeaderMap::new(), second, &[], now + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(state.buckets.len(), 1);
+    }
+}
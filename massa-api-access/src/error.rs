@@ -0,0 +1,19 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use displaydoc::Display;
+use thiserror::Error;
+
+/// API access result
+pub type AccessResult<T, E = AccessError> = core::result::Result<T, E>;
+
+/// API access error
+#[non_exhaustive]
+#[derive(Display, Error, Debug, Clone)]
+pub enum AccessError {
+    /// Unauthorized: {0}
+    Unauthorized(String),
+    /// Rate limited: {0}
+    RateLimited(String),
+    /// Configuration reload error: {0}
+    ReloadError(String),
+}
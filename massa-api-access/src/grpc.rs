@@ -0,0 +1,117 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Wrapper checking the requests of a gRPC service.
+//! gRPC method names are converted to snake_case to share the method weights of JSON-RPC.
+
+use crate::controller::{AccessController, ApiKind};
+use crate::error::AccessError;
+use futures::future::{ready, Either, Ready};
+use hyper::{Request, Response};
+use std::net::IpAddr;
+use std::task::{Context, Poll};
+use tonic::body::BoxBody;
+use tonic::transport::server::{TcpConnectInfo, TlsConnectInfo};
+use tonic::transport::NamedService;
+use tonic::Status;
+use tower::Service;
+
+/// gRPC service checking the requests before forwarding them to the wrapped service
+#[derive(Clone)]
+pub struct GrpcAccessService<S> {
+    inner: S,
+    controller: AccessController,
+}
+
+impl<S> GrpcAccessService<S> {
+    /// Wrap a gRPC service
+    pub fn new(inner: S, controller: AccessController) -> Self {
+        Self { inner, controller }
+    }
+}
+
+impl<S: NamedService> NamedService for GrpcAccessService<S> {
+    const NAME: &'static str = S::NAME;
+}
+
+impl<S, B> Service<Request<B>> for GrpcAccessService<S>
+where
+    S: Service<Request<B>, Response = Response<BoxBody>>,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Either<S::Future, Ready<Result<Self::Response, Self::Error>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let method = request
+            .uri()
+            .path()
+            .rsplit('/')
+            .next()
+            .map(to_snake_case)
+            .unwrap_or_default();
+
+        match self.controller.check(
+            ApiKind::Grpc,
+            request.headers(),
+            remote_ip(&request),
+            &[method.as_str()],
+        ) {
+            Ok(()) => Either::Left(self.inner.call(request)),
+            Err(err) => {
+                let status = match err {
+                    AccessError::Unauthorized(msg) => Status::unauthenticated(msg),
+                    err => Status::resource_exhausted(err.to_string()),
+                };
+                Either::Right(ready(Ok(status.to_http())))
+            }
+        }
+    }
+}
+
+/// Address of the peer of the connection, inserted by the gRPC server
+fn remote_ip<B>(request: &Request<B>) -> Option<IpAddr> {
+    let extensions = request.extensions();
+    extensions
+        .get::<TcpConnectInfo>()
+        .or_else(|| {
+            extensions
+                .get::<TlsConnectInfo<TcpConnectInfo>>()
+                .map(|info| info.get_ref())
+        })
+        .and_then(|info| info.remote_addr())
+        .map(|addr| addr.ip())
+}
+
+/// `GetBlocks` => `get_blocks`
+fn to_snake_case(name: &str) -> String {
+    let mut snake_case = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake_case.push('_');
+            }
+            snake_case.push(c.to_ascii_lowercase());
+        } else {
+            snake_case.push(c);
+        }
+    }
+    snake_case
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(
+            to_snake_case("ExecuteReadOnlyCall"),
+            "execute_read_only_call"
+        );
+        assert_eq!(to_snake_case("GetStatus"), "get_status");
+    }
+}
@@ -0,0 +1,330 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! HTTP middleware checking the requests of the JSON-RPC servers.
+//!
+//! The HTTP server inserts the address of the peer of the connection in the request extensions,
+//! clients without API key are identified by it, or through `X-Forwarded-For` when it is trusted.
+//! WebSocket upgrades are checked like HTTP requests, then each call on the connection
+//! is charged to the client that opened it. Subscriptions are only charged at the upgrade.
+
+use crate::controller::{AccessController, ApiKind};
+use crate::error::AccessError;
+use futures::future::BoxFuture;
+use hyper::body::HttpBody;
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::{Body, HeaderMap, Request, Response, StatusCode};
+use jsonrpsee::core::server::MethodCallback;
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::core::{Error as JsonRpseeError, JsonRawValue};
+use jsonrpsee::types::error::INTERNAL_ERROR_CODE;
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned, Params};
+use jsonrpsee::{Methods, RpcModule};
+use serde_json::{json, Value};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// JSON-RPC error code of rejected requests
+const ACCESS_DENIED_CODE: i32 = -32099;
+
+/// Layer adding access checks to a JSON-RPC server
+#[derive(Clone)]
+pub struct JsonRpcAccessLayer {
+    controller: AccessController,
+    max_request_body_size: u32,
+}
+
+impl JsonRpcAccessLayer {
+    /// Create the layer
+    ///
+    /// # Arguments
+    /// * `controller`: access controller shared by the public APIs
+    /// * `max_request_body_size`: maximum size of the buffered request bodies
+    pub fn new(controller: AccessController, max_request_body_size: u32) -> Self {
+        Self {
+            controller,
+            max_request_body_size,
+        }
+    }
+}
+
+impl<S> Layer<S> for JsonRpcAccessLayer {
+    type Service = JsonRpcAccessService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        JsonRpcAccessService {
+            inner,
+            controller: self.controller.clone(),
+            max_request_body_size: self.max_request_body_size,
+        }
+    }
+}
+
+/// Service checking the requests before forwarding them to the JSON-RPC server
+#[derive(Clone)]
+pub struct JsonRpcAccessService<S> {
+    inner: S,
+    controller: AccessController,
+    max_request_body_size: u32,
+}
+
+impl<S> Service<Request<Body>> for JsonRpcAccessService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // the service that was polled ready handles the request
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let controller = self.controller.clone();
+        let max_request_body_size = self.max_request_body_size as usize;
+
+        Box::pin(async move {
+            if !controller.is_enabled() {
+                return inner.call(request).await;
+            }
+
+            // the body is buffered to read the called methods, then handed to the server
+            let (parts, body) = request.into_parts();
+            let body = match read_body(&parts.headers, body, max_request_body_size).await {
+                Ok(body) => body,
+                Err(status) => {
+                    let mut response = Response::new(Body::empty());
+                    *response.status_mut() = status;
+                    return Ok(response);
+                }
+            };
+            let methods = called_methods(&body);
+            let methods: Vec<&str> = methods.iter().map(String::as_str).collect();
+            let remote_ip = parts.extensions.get::<SocketAddr>().map(|addr| addr.ip());
+
+            match controller.check(ApiKind::JsonRpc, &parts.headers, remote_ip, &methods) {
+                Ok(()) => {
+                    inner
+                        .call(Request::from_parts(parts, Body::from(body)))
+                        .await
+                }
+                Err(err) => Ok(rejection(&err)),
+            }
+        })
+    }
+}
+
+/// Methods served on a WebSocket connection, charging each call to the client that opened it.
+/// Subscriptions are served unchanged.
+///
+/// # Arguments
+/// * `methods`: methods of the JSON-RPC server
+/// * `controller`: access controller shared by the public APIs
+/// * `headers`: headers of the upgrade request, carrying the API key
+/// * `remote_ip`: address of the peer of the connection, if known
+pub fn charge_ws_calls(
+    methods: &Methods,
+    controller: AccessController,
+    headers: HeaderMap,
+    remote_ip: Option<IpAddr>,
+) -> Methods {
+    let headers = Arc::new(headers);
+    let mut charged_methods = methods.clone();
+    let mut charged_calls = RpcModule::new(());
+    for name in methods.method_names() {
+        if !matches!(
+            methods.method(name),
+            Some(MethodCallback::Sync(_) | MethodCallback::Async(_))
+        ) {
+            continue;
+        }
+        charged_methods.remove_method(name);
+        let methods = methods.clone();
+        let controller = controller.clone();
+        let headers = headers.clone();
+        charged_calls
+            .register_async_method(name, move |params, _| {
+                let methods = methods.clone();
+                let controller = controller.clone();
+                let headers = headers.clone();
+                async move {
+                    controller
+                        .check(ApiKind::JsonRpc, &headers, remote_ip, &[name])
+                        .map_err(|err| access_denied(&err))?;
+                    methods
+                        .call::<_, Box<JsonRawValue>>(name, RawParams(params))
+                        .await
+                        .map_err(|err| match err {
+                            JsonRpseeError::Call(err) => err,
+                            err => {
+                                ErrorObject::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>)
+                            }
+                        })
+                }
+            })
+            .expect("method names are unique");
+    }
+    charged_methods
+        .merge(charged_calls)
+        .expect("charged methods were removed before");
+    charged_methods
+}
+
+/// Parameters of a call forwarded unchanged to the wrapped method
+struct RawParams(Params<'static>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<JsonRawValue>>, serde_json::Error> {
+        self.0
+            .as_str()
+            .map(|params| JsonRawValue::from_string(params.to_string()))
+            .transpose()
+    }
+}
+
+/// Read a request body, without reading more than `max_size` bytes
+async fn read_body(
+    headers: &hyper::HeaderMap,
+    mut body: Body,
+    max_size: usize,
+) -> Result<Vec<u8>, StatusCode> {
+    let announced_size = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if announced_size.map_or(false, |size| size > max_size) {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    let mut buffer = Vec::with_capacity(announced_size.unwrap_or_default());
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+        if buffer.len() + chunk.len() > max_size {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+    Ok(buffer)
+}
+
+/// Methods called by a single or batch JSON-RPC request, empty if it cannot be parsed
+fn called_methods(body: &[u8]) -> Vec<String> {
+    let method = |call: &Value| call.get("method")?.as_str().map(str::to_string);
+    match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Array(calls)) => calls.iter().filter_map(method).collect(),
+        Ok(call) => method(&call).into_iter().collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// JSON-RPC error of a rejected call
+fn access_denied(err: &AccessError) -> ErrorObjectOwned {
+    ErrorObject::owned(ACCESS_DENIED_CODE, err.to_string(), None::<()>)
+}
+
+/// HTTP response to a rejected request, with a JSON-RPC error body
+fn rejection(err: &AccessError) -> Response<Body> {
+    let status = match err {
+        AccessError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+        _ => StatusCode::TOO_MANY_REQUESTS,
+    };
+    let body = json!({
+        "jsonrpc": "2.0",
+        "error": access_denied(err),
+        "id": Value::Null,
+    });
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AccessConfig, Quota};
+    use jsonrpsee::core::params::ArrayParams;
+    use massa_metrics::MassaMetrics;
+    use std::time::Duration;
+
+    #[test]
+    fn test_charge_ws_calls() {
+        let mut module = RpcModule::new(());
+        module
+            .register_method("get_status", |_, _| Ok::<_, ErrorObjectOwned>(0u64))
+            .unwrap();
+        module
+            .register_method("execute_read_only_call", |params, _| {
+                params.one::<u64>().map(|value| value + 1)
+            })
+            .unwrap();
+        let controller = AccessController::new(
+            AccessConfig {
+                enabled: true,
+                ip_quota: Quota { burst: 5, rate: 0 },
+                method_weights: [("execute_read_only_call".to_string(), 4)]
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            },
+            None,
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                Duration::from_secs(5),
+            )
+            .0,
+        );
+        let methods = charge_ws_calls(
+            &module.into(),
+            controller,
+            HeaderMap::new(),
+            Some("10.0.0.1".parse().unwrap()),
+        );
+        let heavy_params = || {
+            let mut params = ArrayParams::new();
+            params.insert(1u64).unwrap();
+            params
+        };
+
+        // the parameters and results of the calls are forwarded
+        let result: u64 =
+            futures::executor::block_on(methods.call("execute_read_only_call", heavy_params()))
+                .unwrap();
+        assert_eq!(result, 2);
+        // each call consumes its weight from the bucket of the client
+        match futures::executor::block_on(
+            methods.call::<_, u64>("execute_read_only_call", heavy_params()),
+        ) {
+            Err(JsonRpseeError::Call(err)) => assert_eq!(err.code(), ACCESS_DENIED_CODE),
+            res => panic!("call should have been rejected: {:?}", res),
+        }
+        futures::executor::block_on(methods.call::<_, u64>("get_status", ArrayParams::new()))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_called_methods() {
+        assert_eq!(
+            called_methods(br#"{"jsonrpc":"2.0","method":"get_status","params":[],"id":1}"#),
+            vec!["get_status".to_string()]
+        );
+        assert_eq!(
+            called_methods(
+                br#"[{"jsonrpc":"2.0","method":"get_status","id":1},{"id":2},{"method":"get_addresses","id":3}]"#
+            ),
+            vec!["get_status".to_string(), "get_addresses".to_string()]
+        );
+        assert!(called_methods(b"not json").is_empty());
+    }
+}
@@ -0,0 +1,20 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Authentication and per-client rate limiting shared by the public JSON-RPC and gRPC APIs.
+//!
+//! Clients are identified by their API key, sent in the `x-api-key` header or as a bearer token,
+//! or by their IP address otherwise. Each client owns a token bucket,
+//! and each request consumes the weight of the methods it calls.
+
+#![warn(missing_docs)]
+
+mod config;
+mod controller;
+mod error;
+mod grpc;
+mod jsonrpc;
+
+pub use config::{AccessConfig, Quota};
+pub use controller::{AccessConfigLoader, AccessController, ApiKind, API_KEY_HEADER};
+pub use error::*;
+pub use grpc::GrpcAccessService;
+pub use jsonrpc::{charge_ws_calls, JsonRpcAccessLayer, JsonRpcAccessService};
@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_async_pool::AsyncMessageId;
 use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::endorsement::EndorsementId;
 use massa_models::operation::OperationId;
@@ -155,3 +156,73 @@ impl std::fmt::Display for CompactAddressInfo {
         Ok(())
     }
 }
+
+/// What affected an address at a final slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressHistoryItem {
+    /// operation created by the address, sending coins to it, calling it,
+    /// or moving its coins during the execution of a smart contract
+    Operation(OperationId),
+    /// asynchronous message sent by the address, targeting it,
+    /// or moving its coins during the execution of its handler
+    AsyncMessage(AsyncMessageId),
+    /// new roll count of the address
+    RollCount(u64),
+    /// deferred credit of the address: scheduled if `slot` is after the slot of the entry, paid otherwise
+    DeferredCredit {
+        /// slot at which the credit is paid
+        slot: Slot,
+        /// credited amount, zero if a scheduled credit was cancelled
+        amount: Amount,
+    },
+}
+
+/// Entry of the history of an address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressHistoryEntry {
+    /// final slot at which the address was affected
+    pub slot: Slot,
+    /// index of the entry among the entries of the address at this slot
+    pub index: u32,
+    /// what affected the address
+    pub item: AddressHistoryItem,
+}
+
+impl From<massa_execution_exports::AddressHistoryEntry> for AddressHistoryEntry {
+    fn from(entry: massa_execution_exports::AddressHistoryEntry) -> Self {
+        use massa_execution_exports::AddressHistoryItem as Item;
+        AddressHistoryEntry {
+            slot: entry.slot,
+            index: entry.index,
+            item: match entry.item {
+                Item::Operation(id) => AddressHistoryItem::Operation(id),
+                Item::AsyncMessage(id) => AddressHistoryItem::AsyncMessage(id),
+                Item::RollCount(roll_count) => AddressHistoryItem::RollCount(roll_count),
+                Item::DeferredCredit { slot, amount } => {
+                    AddressHistoryItem::DeferredCredit { slot, amount }
+                }
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for AddressHistoryEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Slot {} (index {}): ", self.slot, self.index)?;
+        match &self.item {
+            AddressHistoryItem::Operation(id) => writeln!(f, "operation {}", id),
+            AddressHistoryItem::AsyncMessage(id) => writeln!(
+                f,
+                "async message emitted at slot {} with index {}",
+                id.1, id.2
+            ),
+            AddressHistoryItem::RollCount(roll_count) => {
+                writeln!(f, "roll count set to {}", roll_count)
+            }
+            AddressHistoryItem::DeferredCredit { slot, amount } => {
+                writeln!(f, "deferred credit of {} at slot {}", amount, slot)
+            }
+        }
+    }
+}
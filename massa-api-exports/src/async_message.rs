@@ -0,0 +1,56 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_async_pool::{AsyncMessage, AsyncMessageId};
+use serde::{Deserialize, Serialize};
+
+/// Asynchronous message query output structure
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct AsyncMessageOutput {
+    /// message id
+    pub id: AsyncMessageId,
+    /// message in the final pool, None if it is absent from it
+    pub final_message: Option<AsyncMessage>,
+    /// message in the candidate pool, None if it is absent from it (executed or evicted)
+    pub candidate_message: Option<AsyncMessage>,
+}
+
+impl std::fmt::Display for AsyncMessageOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Message emitted at slot {} with index {}",
+            self.id.1, self.id.2
+        )?;
+        for (status, message) in [
+            ("final", &self.final_message),
+            ("candidate", &self.candidate_message),
+        ] {
+            match message {
+                Some(msg) => {
+                    writeln!(f, "\t{}:", status)?;
+                    writeln!(f, "\t\tSender: {}", msg.sender)?;
+                    writeln!(f, "\t\tDestination: {}", msg.destination)?;
+                    writeln!(f, "\t\tHandler: {}", msg.handler)?;
+                    writeln!(f, "\t\tMax gas: {}", msg.max_gas)?;
+                    writeln!(f, "\t\tFee: {}", msg.fee)?;
+                    writeln!(f, "\t\tCoins: {}", msg.coins)?;
+                    writeln!(
+                        f,
+                        "\t\tValidity: from {} (included) to {} (excluded)",
+                        msg.validity_start, msg.validity_end
+                    )?;
+                    if let Some(trigger) = &msg.trigger {
+                        writeln!(
+                            f,
+                            "\t\tTrigger: address {}, datastore key {:?}",
+                            trigger.address, trigger.datastore_key
+                        )?;
+                    }
+                    writeln!(f, "\t\tCan be executed: {}", msg.can_be_executed)?;
+                }
+                None => writeln!(f, "\t{}: not in pool", status)?,
+            }
+        }
+        Ok(())
+    }
+}
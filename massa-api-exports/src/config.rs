@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_models::config::CompactConfig;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use std::net::SocketAddr;
@@ -69,6 +70,8 @@ pub struct APIConfig {
     pub t0: MassaTime,
     /// periods per cycle
     pub periods_per_cycle: u64,
+    /// network parameters shown by the status endpoint
+    pub compact_config: CompactConfig,
     /// keypair file
     pub keypair: KeyPair,
     /// last_start_period value, used to know if we are during a restart or not
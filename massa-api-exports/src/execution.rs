@@ -1,9 +1,15 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_final_state::StateChanges;
-use massa_models::{address::Address, output_event::SCOutputEvent, slot::Slot};
+use massa_models::{
+    address::Address, amount::Amount, operation::OperationType, output_event::SCOutputEvent,
+    slot::Slot,
+};
 use serde::{Deserialize, Serialize};
-use std::{collections::VecDeque, fmt::Display};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Display,
+};
 
 /// The result of the read-only execution.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -85,3 +91,67 @@ pub struct ReadOnlyCall {
     #[serde(default)]
     pub is_final: bool,
 }
+
+/// operation simulation request
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct OperationSimulation {
+    /// sender's address
+    pub sender_address: Address,
+    /// fee of the operation
+    pub fee: Amount,
+    /// expiration period of the operation, optional.
+    /// Defaults to the period at which the operation is simulated
+    pub expire_period: Option<u64>,
+    /// operation to simulate
+    pub op: OperationType,
+}
+
+/// The response to an operation simulation request.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OperationSimulationResponse {
+    /// The slot at which the operation was simulated.
+    pub executed_at: Slot,
+    /// VM gas used by the operation, up to its failure if it failed, zero for operations that do not run bytecode.
+    /// None if the operation could not be executed because its fee could not be spent.
+    pub gas_used: Option<u64>,
+    /// `max_gas` to set in the operation, with a safety margin over `gas_used`
+    pub suggested_max_gas: Option<u64>,
+    /// fee debited from the sender
+    pub fee: Amount,
+    /// `(balance_before, balance_after)` of every address whose balance changed, fee included
+    pub balance_changes: BTreeMap<Address, (Amount, Amount)>,
+    /// The output events generated by the simulation.
+    pub output_events: VecDeque<SCOutputEvent>,
+    /// The error that the operation would produce, None if it succeeds.
+    pub error: Option<String>,
+}
+
+impl Display for OperationSimulationResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Simulated at slot: {}", self.executed_at)?;
+        match &self.error {
+            Some(e) => writeln!(f, "Result: an error occurred during the simulation: {}", e)?,
+            None => writeln!(f, "Result: success")?,
+        }
+        if let Some(gas_used) = self.gas_used {
+            writeln!(f, "Gas used: {}", gas_used)?;
+        }
+        if let Some(suggested_max_gas) = self.suggested_max_gas {
+            writeln!(f, "Suggested max gas: {}", suggested_max_gas)?;
+        }
+        writeln!(f, "Fee: {}", self.fee)?;
+        if !self.balance_changes.is_empty() {
+            writeln!(f, "Balance changes:")?;
+            for (address, (before, after)) in self.balance_changes.iter() {
+                writeln!(f, "\t{}: {} -> {}", address, before, after)?;
+            }
+        }
+        if !self.output_events.is_empty() {
+            writeln!(f, "Generated events:",)?;
+            for event in self.output_events.iter() {
+                writeln!(f, "{}", event)?; // id already displayed in event
+            }
+        }
+        Ok(())
+    }
+}
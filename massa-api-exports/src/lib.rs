@@ -11,6 +11,8 @@ use serde::{Deserialize, Serialize};
 
 /// address related structures
 pub mod address;
+/// asynchronous messages
+pub mod async_message;
 /// block-related structures
 pub mod block;
 /// node configuration
@@ -3,10 +3,14 @@
 use massa_models::node::NodeId;
 use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
 use massa_models::{config::CompactConfig, slot::Slot, version::Version};
+use massa_protocol_exports::{ConnectedPeerInfo, PeerConnectionType};
+pub use massa_protocol_exports::{
+    ConnectedPeersFilter, PeerMessageCounts, PeerState, TransportType,
+};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 
 /// node status
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -89,3 +93,94 @@ impl std::fmt::Display for NodeStatus {
         Ok(())
     }
 }
+
+/// detailed information about a connected peer
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConnectedPeer {
+    /// node id of the peer
+    pub node_id: NodeId,
+    /// remote address of the connection
+    pub address: SocketAddr,
+    /// true if the connection is outgoing, false if incoming
+    pub is_outgoing: bool,
+    /// peer category of the connection, none for the default category
+    pub category: Option<String>,
+    /// time elapsed since the connection was established, if known
+    pub connection_age: Option<MassaTime>,
+    /// bytes sent to the peer on this connection
+    pub bytes_sent: u64,
+    /// bytes received from the peer on this connection
+    pub bytes_received: u64,
+    /// messages received from the peer on this connection, by message type
+    pub messages_received: PeerMessageCounts,
+    /// listeners announced by the peer
+    pub listeners: Vec<SocketAddr>,
+    /// state of the peer in the peer database, if known
+    pub state: Option<PeerState>,
+}
+
+impl ConnectedPeer {
+    /// Build from the protocol information, computing the connection age at `now`
+    pub fn from_info(info: ConnectedPeerInfo, now: MassaTime) -> Self {
+        let mut listeners: Vec<SocketAddr> = info.listeners.into_keys().collect();
+        listeners.sort();
+        ConnectedPeer {
+            node_id: NodeId::new(info.peer_id.get_public_key()),
+            address: info.address,
+            is_outgoing: info.connection_type == PeerConnectionType::OUT,
+            category: info.category,
+            connection_age: info
+                .connected_since
+                .map(|connected_since| now.saturating_sub(connected_since)),
+            bytes_sent: info.bytes_sent,
+            bytes_received: info.bytes_received,
+            messages_received: info.messages_received,
+            listeners,
+            state: info.state,
+        }
+    }
+}
+
+impl std::fmt::Display for ConnectedPeer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Node's ID: {}", self.node_id)?;
+        writeln!(
+            f,
+            "\tAddress: {} / {} connection / category: {}",
+            self.address,
+            if self.is_outgoing { "Out" } else { "In" },
+            self.category.as_deref().unwrap_or("default")
+        )?;
+        if let Some(connection_age) = self.connection_age {
+            if let Ok(age) = connection_age.format_duration() {
+                writeln!(f, "\tConnected for: {}", age)?;
+            }
+        }
+        if let Some(state) = &self.state {
+            writeln!(f, "\tState: {:?}", state)?;
+        }
+        writeln!(
+            f,
+            "\tBytes sent: {} / received: {}",
+            self.bytes_sent, self.bytes_received
+        )?;
+        writeln!(
+            f,
+            "\tMessages received: {} blocks, {} endorsements, {} operations, {} peer management",
+            self.messages_received.block,
+            self.messages_received.endorsement,
+            self.messages_received.operation,
+            self.messages_received.peer_management
+        )?;
+        writeln!(
+            f,
+            "\tListeners: {}",
+            self.listeners
+                .iter()
+                .map(|listener| listener.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        Ok(())
+    }
+}
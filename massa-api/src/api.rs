@@ -9,6 +9,7 @@ use futures::future::{self, Either};
 use futures::StreamExt;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult, SubscriptionResult};
 use jsonrpsee::{PendingSubscriptionSink, SubscriptionMessage};
+use massa_api_access::AccessController;
 use massa_api_exports::config::APIConfig;
 use massa_api_exports::error::ApiError;
 use massa_api_exports::page::{PageRequest, PagedVec, PagedVecV2};
@@ -34,6 +35,7 @@ impl API<ApiV2> {
         pool_channels: PoolChannels,
         api_settings: APIConfig,
         version: Version,
+        access_controller: AccessController,
     ) -> Self {
         API(ApiV2 {
             consensus_controller,
@@ -42,6 +44,7 @@ impl API<ApiV2> {
             pool_channels,
             api_settings,
             version,
+            access_controller,
         })
     }
 }
@@ -53,7 +56,8 @@ impl ApiServer for API<ApiV2> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        let access_controller = self.0.access_controller.clone();
+        crate::serve(self.into_rpc(), url, api_config, Some(access_controller)).await
     }
 }
 
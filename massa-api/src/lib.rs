@@ -5,27 +5,37 @@
 #![warn(unused_crate_dependencies)]
 
 use api_trait::MassaApiServer;
-use hyper::Method;
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method};
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::server::middleware::HostFilterLayer;
-use jsonrpsee::server::{BatchRequestConfig, ServerBuilder, ServerHandle};
-use jsonrpsee::RpcModule;
+use jsonrpsee::server::{stop_channel, BatchRequestConfig, ServerBuilder, ServerHandle};
+use jsonrpsee::{Methods, RpcModule};
+use massa_api_access::{charge_ws_calls, AccessController, JsonRpcAccessLayer, API_KEY_HEADER};
 use massa_api_exports::{
-    address::AddressInfo,
+    address::{AddressHistoryEntry, AddressInfo},
+    async_message::AsyncMessageOutput,
     block::{BlockInfo, BlockSummary},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     error::ApiError::WrongAPI,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    execution::{
+        ExecuteReadOnlyResponse, OperationSimulation, OperationSimulationResponse,
+        ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    node::{ConnectedPeer, NodeStatus},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
     TimeInterval,
 };
+use massa_async_pool::{AsyncMessageFilter, AsyncMessageId};
+use massa_block_archive_exports::BlockArchiveController;
 use massa_consensus_exports::{ConsensusChannels, ConsensusController};
 use massa_execution_exports::ExecutionController;
+use massa_factory_exports::FactoryController;
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
 use massa_models::node::NodeId;
@@ -36,16 +46,22 @@ use massa_models::{
     address::Address, block::Block, block_id::BlockId, endorsement::EndorsementId,
     execution::EventFilter, slot::Slot, version::Version,
 };
-use massa_pool_exports::{PoolChannels, PoolController};
+use massa_pool_exports::{OperationPoolStats, OperationPoolStatus, PoolChannels, PoolController};
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{ProtocolConfig, ProtocolController};
+use massa_protocol_exports::{
+    ConnectedPeersFilter, ProtocolConfig, ProtocolController, TransportType,
+};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_versioning::keypair_factory::KeyPairFactory;
-use massa_wallet::Wallet;
+use massa_wallet::{Signer, Wallet};
 use parking_lot::RwLock;
 use serde_json::Value;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::{Arc, Condvar, Mutex};
+use tower::Service;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
 
@@ -78,6 +94,10 @@ pub struct Public {
     pub node_id: NodeId,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// link to the block archive, if final blocks are archived
+    pub block_archive_controller: Option<Box<dyn BlockArchiveController>>,
+    /// authentication and rate limiting of the public APIs
+    pub access_controller: AccessController,
 }
 
 /// Private API content
@@ -86,6 +106,8 @@ pub struct Private {
     pub protocol_controller: Box<dyn ProtocolController>,
     /// link to the execution component
     pub execution_controller: Box<dyn ExecutionController>,
+    /// link to the factory component, driving block production in dev mode
+    pub factory_controller: Box<dyn FactoryController>,
     /// API settings
     pub api_settings: APIConfig,
     /// Mechanism by which to gracefully shut down.
@@ -93,6 +115,10 @@ pub struct Private {
     pub stop_cv: Arc<(Mutex<bool>, Condvar)>,
     /// User wallet
     pub node_wallet: Arc<RwLock<Wallet>>,
+    /// holder of the staking keys
+    pub signer: Arc<dyn Signer>,
+    /// authentication and rate limiting of the public APIs, reloaded through this API
+    pub access_controller: AccessController,
 }
 
 /// API v2 content
@@ -109,6 +135,8 @@ pub struct ApiV2 {
     pub api_settings: APIConfig,
     /// node version
     pub version: Version,
+    /// authentication and rate limiting of the public APIs
+    pub access_controller: AccessController,
 }
 
 /// The API wrapper
@@ -140,6 +168,7 @@ async fn serve<T>(
     api: RpcModule<T>,
     url: &SocketAddr,
     api_config: &APIConfig,
+    access_controller: Option<AccessController>,
 ) -> Result<StopHandle, JsonRpseeError> {
     let mut server_builder = ServerBuilder::new()
         .max_request_body_size(api_config.max_request_body_size)
@@ -165,7 +194,11 @@ async fn serve<T>(
         .allow_methods([Method::POST, Method::OPTIONS])
         // Allow requests from any origin
         .allow_origin(Any)
-        .allow_headers([hyper::header::CONTENT_TYPE]);
+        .allow_headers([
+            hyper::header::CONTENT_TYPE,
+            hyper::header::AUTHORIZATION,
+            hyper::header::HeaderName::from_static(API_KEY_HEADER),
+        ]);
 
     let hosts = if api_config.allow_hosts.is_empty() {
         vec!["*:*"]
@@ -179,20 +212,67 @@ async fn serve<T>(
 
     let allowed_hosts = HostFilterLayer::new(hosts).expect("failed to build allowed hosts filter");
 
+    let access = access_controller
+        .map(|controller| JsonRpcAccessLayer::new(controller, api_config.max_request_body_size));
+
     let middleware = tower::ServiceBuilder::new()
         .layer(cors)
-        .layer(allowed_hosts);
+        .layer(allowed_hosts)
+        .option_layer(access);
 
-    let server = server_builder
+    // the server is run on a hyper server to know the address of the peer of each connection
+    let service_builder = server_builder
         .set_middleware(middleware)
-        .build(url)
-        .await
-        .expect("failed to build server");
+        .to_service_builder();
+    let methods: Methods = api.into();
+    let (stop_handle, server_handler) = stop_channel();
+    let make_service = {
+        let stop_handle = stop_handle.clone();
+        make_service_fn(move |conn: &AddrStream| {
+            let remote_addr = conn.remote_addr();
+            let service_builder = service_builder.clone();
+            let methods = methods.clone();
+            let access_controller = access_controller.clone();
+            let stop_handle = stop_handle.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |mut request: hyper::Request<Body>| {
+                    request.extensions_mut().insert(remote_addr);
+                    // each call of a WebSocket connection is charged to the client that opened it
+                    let methods = match &access_controller {
+                        Some(controller) if is_ws_upgrade(&request) => charge_ws_calls(
+                            &methods,
+                            controller.clone(),
+                            request.headers().clone(),
+                            Some(remote_addr.ip()),
+                        ),
+                        _ => methods.clone(),
+                    };
+                    let mut service = service_builder.build(methods, stop_handle.clone());
+                    service.call(request)
+                }))
+            }
+        })
+    };
+    let server = hyper::Server::try_bind(url)
+        .expect("failed to build server")
+        .serve(make_service)
+        .with_graceful_shutdown(async move { stop_handle.shutdown().await });
+    tokio::spawn(async move {
+        if let Err(err) = server.await {
+            warn!("API server error: {}", err);
+        }
+    });
 
-    let server_handler = server.start(api);
-    let stop_handler = StopHandle { server_handler };
+    Ok(StopHandle { server_handler })
+}
 
-    Ok(stop_handler)
+/// Whether a request upgrades its connection to WebSocket
+fn is_ws_upgrade(request: &hyper::Request<Body>) -> bool {
+    request
+        .headers()
+        .get(hyper::header::UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value.eq_ignore_ascii_case("websocket"))
 }
 
 /// Used to be able to stop the API
@@ -244,6 +324,14 @@ pub trait MassaRpc {
         arg: Vec<ReadOnlyCall>,
     ) -> RpcResult<Vec<ExecuteReadOnlyResponse>>;
 
+    /// Simulate operations on top of the candidate state, without signing nor sending them.
+    /// Returns the gas they use, a suggested `max_gas`, their balance changes and events.
+    #[method(name = "simulate_operations")]
+    async fn simulate_operations(
+        &self,
+        arg: Vec<OperationSimulation>,
+    ) -> RpcResult<Vec<OperationSimulationResponse>>;
+
     /// Remove a vector of addresses used to stake.
     /// No confirmation to expect.
     #[method(name = "remove_staking_addresses")]
@@ -253,6 +341,17 @@ pub trait MassaRpc {
     #[method(name = "get_staking_addresses")]
     async fn get_staking_addresses(&self) -> RpcResult<PreHashSet<Address>>;
 
+    /// Dev mode only (`--dev`): produce a block at each of the next given number of slots,
+    /// moving the clock to each slot. A block is final as soon as the next one is produced.
+    /// Returns the ids of the produced blocks.
+    #[method(name = "node_dev_advance_slots")]
+    async fn node_dev_advance_slots(&self, arg: u64) -> RpcResult<Vec<BlockId>>;
+
+    /// Dev mode only (`--dev`): move the clock forward by the given duration in milliseconds,
+    /// without producing blocks. Returns the new time.
+    #[method(name = "node_dev_advance_time")]
+    async fn node_dev_advance_time(&self, arg: MassaTime) -> RpcResult<MassaTime>;
+
     /// Bans given IP address(es).
     /// No confirmation to expect.
     #[method(name = "node_ban_by_ip")]
@@ -263,6 +362,32 @@ pub trait MassaRpc {
     #[method(name = "node_ban_by_id")]
     async fn node_ban_by_id(&self, arg: Vec<NodeId>) -> RpcResult<()>;
 
+    /// Returns detailed information about the connected peers matching the optional filter:
+    /// address, connection type and age, bandwidth, messages received by type, announced listeners and state.
+    #[method(name = "node_connected_peers")]
+    async fn node_connected_peers(
+        &self,
+        arg: Option<ConnectedPeersFilter>,
+    ) -> RpcResult<Vec<ConnectedPeer>>;
+
+    /// Returns the static peers: listeners that the node always keeps connected.
+    #[method(name = "node_static_peers")]
+    async fn node_static_peers(&self) -> RpcResult<BTreeMap<SocketAddr, TransportType>>;
+
+    /// Add static peers, given as listener address and transport.
+    /// They are kept connected across restarts and don't take any connection slot.
+    /// No confirmation to expect.
+    #[method(name = "node_add_static_peers")]
+    async fn node_add_static_peers(
+        &self,
+        arg: BTreeMap<SocketAddr, TransportType>,
+    ) -> RpcResult<()>;
+
+    /// Remove static peers. Their current connections are kept but not pinned anymore.
+    /// No confirmation to expect.
+    #[method(name = "node_remove_static_peers")]
+    async fn node_remove_static_peers(&self, arg: Vec<SocketAddr>) -> RpcResult<()>;
+
     /// Returns node peers whitelist IP address(es).
     #[method(name = "node_peers_whitelist")]
     async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>>;
@@ -308,6 +433,10 @@ pub trait MassaRpc {
     #[method(name = "node_remove_from_bootstrap_blacklist")]
     async fn node_remove_from_bootstrap_blacklist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
 
+    /// Reload the API keys and rate limits of the public APIs from the configuration files.
+    #[method(name = "node_reload_api_access")]
+    async fn node_reload_api_access(&self) -> RpcResult<()>;
+
     /// Unban given IP address(es).
     /// No confirmation to expect.
     #[method(name = "node_unban_by_ip")]
@@ -337,6 +466,19 @@ pub trait MassaRpc {
     #[method(name = "get_operations")]
     async fn get_operations(&self, arg: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>>;
 
+    /// Returns statistics about the operation pool: occupancy per thread,
+    /// fee percentiles, and minimum fee of the operations recently included in blocks.
+    #[method(name = "get_operation_pool_stats")]
+    async fn get_operation_pool_stats(&self) -> RpcResult<OperationPoolStats>;
+
+    /// Returns the status of operation(s) in the pool: their rank if pooled,
+    /// or the reason why they recently left the pool.
+    #[method(name = "get_operation_pool_status")]
+    async fn get_operation_pool_status(
+        &self,
+        arg: Vec<OperationId>,
+    ) -> RpcResult<Vec<OperationPoolStatus>>;
+
     /// Returns endorsement(s) information associated to a given list of endorsement(s) ID(s)
     #[method(name = "get_endorsements")]
     async fn get_endorsements(&self, arg: Vec<EndorsementId>) -> RpcResult<Vec<EndorsementInfo>>;
@@ -366,6 +508,15 @@ pub trait MassaRpc {
     #[method(name = "get_addresses")]
     async fn get_addresses(&self, arg: Vec<Address>) -> RpcResult<Vec<AddressInfo>>;
 
+    /// Get the history of an address from its newest final entries to its oldest ones:
+    /// operations, asynchronous messages, roll changes and deferred credits that affected it.
+    #[method(name = "get_address_history")]
+    async fn get_address_history(
+        &self,
+        address: Address,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<AddressHistoryEntry>>;
+
     /// Adds operations to pool. Returns operations that were ok and sent to pool.
     #[method(name = "send_operations")]
     async fn send_operations(&self, arg: Vec<OperationInput>) -> RpcResult<Vec<OperationId>>;
@@ -380,6 +531,30 @@ pub trait MassaRpc {
     async fn get_filtered_sc_output_event(&self, arg: EventFilter)
         -> RpcResult<Vec<SCOutputEvent>>;
 
+    /// Get the asynchronous messages of the final and candidate pools, optionally filtered by:
+    /// * sender address
+    /// * destination address
+    /// * handler
+    /// * validity range
+    /// * trigger
+    /// * executability
+    ///
+    /// The messages are sorted by id and at most `max_arguments` of them are returned.
+    /// The next ones are fetched by passing the id of the last returned message as `start_after`.
+    #[method(name = "get_async_messages")]
+    async fn get_async_messages(
+        &self,
+        filter: AsyncMessageFilter,
+        start_after: Option<AsyncMessageId>,
+    ) -> RpcResult<Vec<AsyncMessageOutput>>;
+
+    /// Get asynchronous messages from the final and candidate pools by id.
+    #[method(name = "get_async_messages_by_id")]
+    async fn get_async_messages_by_id(
+        &self,
+        arg: Vec<AsyncMessageId>,
+    ) -> RpcResult<Vec<AsyncMessageOutput>>;
+
     /// Get OpenRPC specification.
     #[method(name = "rpc.discover")]
     async fn get_openrpc_spec(&self) -> RpcResult<Value>;
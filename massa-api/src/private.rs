@@ -4,35 +4,47 @@ use crate::{MassaRpcServer, Private, RpcServer, StopHandle, Value, API};
 
 use async_trait::async_trait;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
+use massa_api_access::AccessController;
 use massa_api_exports::{
-    address::AddressInfo,
+    address::{AddressHistoryEntry, AddressInfo},
+    async_message::AsyncMessageOutput,
     block::{BlockInfo, BlockSummary},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    execution::{
+        ExecuteReadOnlyResponse, OperationSimulation, OperationSimulationResponse,
+        ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    node::{ConnectedPeer, NodeStatus},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
     ListType, ScrudOperation, TimeInterval,
 };
+use massa_async_pool::{AsyncMessageFilter, AsyncMessageId};
 use massa_execution_exports::ExecutionController;
+use massa_factory_exports::FactoryController;
 use massa_hash::Hash;
 use massa_models::{
     address::Address, block::Block, block_id::BlockId, clique::Clique, composite::PubkeySig,
     endorsement::EndorsementId, execution::EventFilter, node::NodeId, operation::OperationId,
     output_event::SCOutputEvent, prehash::PreHashSet, slot::Slot,
 };
-use massa_protocol_exports::{PeerId, ProtocolController};
+use massa_pool_exports::{OperationPoolStats, OperationPoolStatus};
+use massa_protocol_exports::{ConnectedPeersFilter, PeerId, ProtocolController, TransportType};
 use massa_signature::KeyPair;
-use massa_wallet::Wallet;
+use massa_time::MassaTime;
+use massa_wallet::{Signer, Wallet};
 use parking_lot::RwLock;
 use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::{collections::BTreeSet, sync::Mutex};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+};
 use std::{
     fs::{remove_file, OpenOptions},
     sync::Condvar,
@@ -43,16 +55,22 @@ impl API<Private> {
     pub fn new(
         protocol_controller: Box<dyn ProtocolController>,
         execution_controller: Box<dyn ExecutionController>,
+        factory_controller: Box<dyn FactoryController>,
         api_settings: APIConfig,
         stop_cv: Arc<(Mutex<bool>, Condvar)>,
         node_wallet: Arc<RwLock<Wallet>>,
+        signer: Arc<dyn Signer>,
+        access_controller: AccessController,
     ) -> Self {
         API(Private {
             protocol_controller,
             execution_controller,
+            factory_controller,
             api_settings,
             stop_cv,
             node_wallet,
+            signer,
+            access_controller,
         })
     }
 }
@@ -64,7 +82,7 @@ impl RpcServer for API<Private> {
         url: &SocketAddr,
         settings: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, settings).await
+        crate::serve(self.into_rpc(), url, settings, None).await
     }
 }
 
@@ -125,6 +143,13 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<_>()
     }
 
+    async fn simulate_operations(
+        &self,
+        _: Vec<OperationSimulation>,
+    ) -> RpcResult<Vec<OperationSimulationResponse>> {
+        crate::wrong_api::<Vec<OperationSimulationResponse>>()
+    }
+
     async fn remove_staking_addresses(&self, addresses: Vec<Address>) -> RpcResult<()> {
         let node_wallet = self.0.node_wallet.clone();
 
@@ -142,21 +167,33 @@ impl MassaRpcServer for API<Private> {
     }
 
     async fn get_staking_addresses(&self) -> RpcResult<PreHashSet<Address>> {
-        let node_wallet = self.0.node_wallet.clone();
-        let w_wallet = node_wallet.read();
-        Ok(w_wallet.get_wallet_address_list())
+        self.0
+            .signer
+            .get_addresses()
+            .map_err(|e| ApiError::WalletError(e).into())
     }
 
-    async fn node_ban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
-        //TODO: Reinvoke
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // network_command_sender
-        //    .node_ban_by_ips(ips)
-        //    .await
-        //    .map_err(|e| ApiError::NetworkError(e).into())
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
+    async fn node_dev_advance_slots(&self, count: u64) -> RpcResult<Vec<BlockId>> {
+        let factory_controller = self.0.factory_controller.clone();
+        // blocks until the blocks are produced and processed by consensus
+        tokio::task::spawn_blocking(move || factory_controller.advance_slots(count))
+            .await
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))?
+            .map_err(|e| ApiError::FactoryError(e).into())
+    }
+
+    async fn node_dev_advance_time(&self, duration: MassaTime) -> RpcResult<MassaTime> {
+        self.0
+            .factory_controller
+            .advance_time(duration)
+            .map_err(|e| ApiError::FactoryError(e).into())
+    }
+
+    async fn node_ban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .ban_ips(ips)
+            .map_err(|e| ApiError::ProtocolError(e).into())
     }
 
     async fn node_ban_by_id(&self, ids: Vec<NodeId>) -> RpcResult<()> {
@@ -183,16 +220,11 @@ impl MassaRpcServer for API<Private> {
             .map_err(|e| ApiError::ProtocolError(e).into())
     }
 
-    async fn node_unban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
-        //TODO: Reinvoke
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // network_command_sender
-        //     .node_unban_ips(ips)
-        //     .await
-        //     .map_err(|e| ApiError::NetworkError(e).into())
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
+    async fn node_unban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .unban_ips(ips)
+            .map_err(|e| ApiError::ProtocolError(e).into())
     }
 
     async fn get_status(&self) -> RpcResult<NodeStatus> {
@@ -211,6 +243,17 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<OperationInfo>>()
     }
 
+    async fn get_operation_pool_stats(&self) -> RpcResult<OperationPoolStats> {
+        crate::wrong_api::<OperationPoolStats>()
+    }
+
+    async fn get_operation_pool_status(
+        &self,
+        _: Vec<OperationId>,
+    ) -> RpcResult<Vec<OperationPoolStatus>> {
+        crate::wrong_api::<Vec<OperationPoolStatus>>()
+    }
+
     async fn get_endorsements(&self, _: Vec<EndorsementId>) -> RpcResult<Vec<EndorsementInfo>> {
         crate::wrong_api::<Vec<EndorsementInfo>>()
     }
@@ -238,6 +281,14 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<AddressInfo>>()
     }
 
+    async fn get_address_history(
+        &self,
+        _: Address,
+        _: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<AddressHistoryEntry>> {
+        crate::wrong_api::<PagedVec<AddressHistoryEntry>>()
+    }
+
     async fn send_operations(&self, _: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
         crate::wrong_api::<Vec<OperationId>>()
     }
@@ -246,41 +297,84 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<SCOutputEvent>>()
     }
 
+    async fn get_async_messages(
+        &self,
+        _: AsyncMessageFilter,
+        _: Option<AsyncMessageId>,
+    ) -> RpcResult<Vec<AsyncMessageOutput>> {
+        crate::wrong_api::<Vec<AsyncMessageOutput>>()
+    }
+
+    async fn get_async_messages_by_id(
+        &self,
+        _: Vec<AsyncMessageId>,
+    ) -> RpcResult<Vec<AsyncMessageOutput>> {
+        crate::wrong_api::<Vec<AsyncMessageOutput>>()
+    }
+
+    async fn node_connected_peers(
+        &self,
+        filter: Option<ConnectedPeersFilter>,
+    ) -> RpcResult<Vec<ConnectedPeer>> {
+        let now = MassaTime::now().map_err(ApiError::TimeError)?;
+        let filter = filter.unwrap_or_default();
+        let mut peers: Vec<ConnectedPeer> = self
+            .0
+            .protocol_controller
+            .get_connected_peers()
+            .map_err(ApiError::ProtocolError)?
+            .into_iter()
+            .filter(|peer| filter.matches(peer))
+            .map(|peer| ConnectedPeer::from_info(peer, now))
+            .collect();
+        peers.sort_by_key(|peer| peer.node_id);
+        Ok(peers)
+    }
+
+    async fn node_static_peers(&self) -> RpcResult<BTreeMap<SocketAddr, TransportType>> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .get_static_peers()
+            .map(|peers| peers.into_iter().collect())
+            .map_err(|e| ApiError::ProtocolError(e).into())
+    }
+
+    async fn node_add_static_peers(
+        &self,
+        peers: BTreeMap<SocketAddr, TransportType>,
+    ) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .add_static_peers(peers.into_iter().collect())
+            .map_err(|e| ApiError::ProtocolError(e).into())
+    }
+
+    async fn node_remove_static_peers(&self, addrs: Vec<SocketAddr>) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .remove_static_peers(addrs)
+            .map_err(|e| ApiError::ProtocolError(e).into())
+    }
+
     async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
-        //TODO: Reinvoke
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // match network_command_sender.get_peers().await {
-        //     Ok(peers) => Ok(peers.peers.into_keys().sorted().collect::<Vec<IpAddr>>()),
-        //     Err(e) => Err(ApiError::NetworkError(e).into()),
-        // }
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
-    }
-
-    async fn node_add_to_peers_whitelist(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
-        //TODO: Readd in network refactoring
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // network_command_sender
-        //     .add_to_whitelist(ips)
-        //     .await
-        //     .map_err(|e| ApiError::NetworkError(e).into())
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
-    }
-
-    async fn node_remove_from_peers_whitelist(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
-        //TODO: Reinvoke
-        //TODO: Readd in network refactoring
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // network_command_sender
-        //     .remove_from_whitelist(ips)
-        //     .await
-        //     .map_err(|e| ApiError::NetworkError(e).into())
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .get_peers_whitelist()
+            .map_err(|e| ApiError::ProtocolError(e).into())
+    }
+
+    async fn node_add_to_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .add_to_peers_whitelist(ips)
+            .map_err(|e| ApiError::ProtocolError(e).into())
+    }
+
+    async fn node_remove_from_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .remove_from_peers_whitelist(ips)
+            .map_err(|e| ApiError::ProtocolError(e).into())
     }
 
     async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
@@ -343,6 +437,16 @@ impl MassaRpcServer for API<Private> {
         )
     }
 
+    async fn node_reload_api_access(&self) -> RpcResult<()> {
+        self.0.access_controller.reload().map_err(|e| {
+            ApiError::InternalServerError(format!(
+                "failed to reload the API access configuration: {}",
+                e
+            ))
+            .into()
+        })
+    }
+
     async fn get_openrpc_spec(&self) -> RpcResult<Value> {
         crate::wrong_api::<Value>()
     }
@@ -5,24 +5,33 @@ use crate::{MassaRpcServer, Public, RpcServer, StopHandle, Value, API};
 use async_trait::async_trait;
 use itertools::{izip, Itertools};
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
+use massa_api_access::AccessController;
 use massa_api_exports::{
-    address::AddressInfo,
+    address::{AddressHistoryEntry, AddressInfo},
+    async_message::AsyncMessageOutput,
     block::{BlockInfo, BlockInfoContent, BlockSummary},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult},
-    node::NodeStatus,
+    execution::{
+        ExecuteReadOnlyResponse, OperationSimulation, OperationSimulationResponse,
+        ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult,
+    },
+    node::{ConnectedPeer, NodeStatus},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
     slot::SlotAmount,
     TimeInterval,
 };
+use massa_async_pool::{AsyncMessage, AsyncMessageFilter, AsyncMessageId};
+use massa_block_archive_exports::BlockArchiveController;
 use massa_consensus_exports::block_status::DiscardReason;
 use massa_consensus_exports::ConsensusController;
 use massa_execution_exports::{
-    ExecutionController, ExecutionStackElement, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ExecutionController, ExecutionQueryError, ExecutionQueryRequest, ExecutionQueryRequestItem,
+    ExecutionQueryResponseItem, ExecutionStackElement, OperationSimulationRequest,
+    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
 };
 use massa_models::{
     address::Address,
@@ -30,7 +39,6 @@ use massa_models::{
     block_id::BlockId,
     clique::Clique,
     composite::PubkeySig,
-    config::CompactConfig,
     datastore::DatastoreDeserializer,
     endorsement::EndorsementId,
     endorsement::SecureShareEndorsement,
@@ -39,7 +47,7 @@ use massa_models::{
     node::NodeId,
     operation::OperationDeserializer,
     operation::OperationId,
-    operation::{OperationType, SecureShareOperation},
+    operation::SecureShareOperation,
     output_event::SCOutputEvent,
     prehash::{PreHashMap, PreHashSet},
     secure_share::SecureShareDeserializer,
@@ -48,9 +56,11 @@ use massa_models::{
     timeslots::{get_latest_block_slot_at_timestamp, time_range_to_slot_range},
     version::Version,
 };
-use massa_pool_exports::PoolController;
+use massa_pool_exports::{OperationPoolStats, OperationPoolStatus, PoolController};
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{PeerConnectionType, ProtocolConfig, ProtocolController};
+use massa_protocol_exports::{
+    ConnectedPeersFilter, PeerConnectionType, ProtocolConfig, ProtocolController, TransportType,
+};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
 use massa_time::MassaTime;
@@ -75,6 +85,8 @@ impl API<Public> {
         node_id: NodeId,
         storage: Storage,
         mip_store: MipStore,
+        block_archive_controller: Option<Box<dyn BlockArchiveController>>,
+        access_controller: AccessController,
     ) -> Self {
         API(Public {
             consensus_controller,
@@ -88,8 +100,40 @@ impl API<Public> {
             protocol_config,
             storage,
             keypair_factory: KeyPairFactory { mip_store },
+            block_archive_controller,
+            access_controller,
         })
     }
+
+    /// Fetches the final and candidate versions of a list of asynchronous messages
+    fn fetch_async_messages(&self, ids: Vec<AsyncMessageId>) -> RpcResult<Vec<AsyncMessageOutput>> {
+        let requests = ids
+            .iter()
+            .flat_map(|id| {
+                [
+                    ExecutionQueryRequestItem::AsyncMessageFinal(*id),
+                    ExecutionQueryRequestItem::AsyncMessageCandidate(*id),
+                ]
+            })
+            .collect();
+        let response = self
+            .0
+            .execution_controller
+            .query_state(ExecutionQueryRequest { requests });
+
+        let mut responses = response.responses.into_iter();
+        ids.into_iter()
+            .map(|id| {
+                Ok(AsyncMessageOutput {
+                    id,
+                    final_message: to_async_message(responses.next().ok_or(ApiError::NotFound)?)?,
+                    candidate_message: to_async_message(
+                        responses.next().ok_or(ApiError::NotFound)?,
+                    )?,
+                })
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -99,7 +143,8 @@ impl RpcServer for API<Public> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        let access_controller = self.0.access_controller.clone();
+        crate::serve(self.into_rpc(), url, api_config, Some(access_controller)).await
     }
 }
 
@@ -302,6 +347,59 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    async fn simulate_operations(
+        &self,
+        reqs: Vec<OperationSimulation>,
+    ) -> RpcResult<Vec<OperationSimulationResponse>> {
+        if reqs.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        let mut res: Vec<OperationSimulationResponse> = Vec::with_capacity(reqs.len());
+        for OperationSimulation {
+            sender_address,
+            fee,
+            expire_period,
+            op,
+        } in reqs
+        {
+            // translate request
+            let req = OperationSimulationRequest {
+                sender: sender_address,
+                fee,
+                expire_period,
+                op,
+            };
+
+            // run
+            let result = match self.0.execution_controller.simulate_operation(req) {
+                Ok(output) => OperationSimulationResponse {
+                    executed_at: output.slot,
+                    gas_used: output.gas_used,
+                    suggested_max_gas: output.suggested_max_gas,
+                    fee: output.fee,
+                    balance_changes: output.balance_changes,
+                    output_events: output.events.into(),
+                    error: output.error.map(|err| err.to_string()),
+                },
+                Err(err) => OperationSimulationResponse {
+                    executed_at: Slot::new(0, 0),
+                    gas_used: None,
+                    suggested_max_gas: None,
+                    fee: Default::default(),
+                    balance_changes: Default::default(),
+                    output_events: Default::default(),
+                    error: Some(format!("operation simulation failed: {}", err)),
+                },
+            };
+
+            res.push(result);
+        }
+
+        // return result
+        Ok(res)
+    }
+
     async fn remove_staking_addresses(&self, _: Vec<Address>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -310,6 +408,14 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<PreHashSet<Address>>()
     }
 
+    async fn node_dev_advance_slots(&self, _: u64) -> RpcResult<Vec<BlockId>> {
+        crate::wrong_api::<Vec<BlockId>>()
+    }
+
+    async fn node_dev_advance_time(&self, _: MassaTime) -> RpcResult<MassaTime> {
+        crate::wrong_api::<MassaTime>()
+    }
+
     async fn node_ban_by_ip(&self, _: Vec<IpAddr>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -335,7 +441,7 @@ impl MassaRpcServer for API<Public> {
         let protocol_config = self.0.protocol_config.clone();
         let pool_command_sender = self.0.pool_command_sender.clone();
         let node_id = self.0.node_id;
-        let config = CompactConfig::default();
+        let config = api_settings.compact_config;
         let now = match MassaTime::now() {
             Ok(now) => now,
             Err(e) => return Err(ApiError::TimeError(e).into()),
@@ -487,7 +593,7 @@ impl MassaRpcServer for API<Public> {
 
     async fn get_operations(&self, ops: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>> {
         // get the operations and the list of blocks that contain them from storage
-        let storage_info: Vec<(SecureShareOperation, PreHashSet<BlockId>)> = {
+        let mut storage_info: Vec<(SecureShareOperation, PreHashSet<BlockId>)> = {
             let read_blocks = self.0.storage.read_blocks();
             let read_ops = self.0.storage.read_operations();
             ops.iter()
@@ -505,7 +611,27 @@ impl MassaRpcServer for API<Public> {
                 .collect()
         };
 
-        // keep only the ops id (found in storage)
+        // operations pruned from storage may be found in the block archive, they are final
+        let mut archived_ops: PreHashSet<OperationId> = PreHashSet::default();
+        if let Some(block_archive_controller) = &self.0.block_archive_controller {
+            let found: PreHashSet<OperationId> = storage_info.iter().map(|(op, _)| op.id).collect();
+            let missing: Vec<OperationId> = ops
+                .iter()
+                .filter(|id| !found.contains(id))
+                .copied()
+                .collect();
+            for (op, block_id) in block_archive_controller
+                .get_operations(&missing)
+                .map_err(|err| ApiError::InternalServerError(err.to_string()))?
+                .into_iter()
+                .flatten()
+            {
+                archived_ops.insert(op.id);
+                storage_info.push((op, std::iter::once(block_id).collect()));
+            }
+        }
+
+        // keep only the ops id (found in storage or in the archive)
         let ops: Vec<OperationId> = storage_info.iter().map(|(op, _)| op.id).collect();
 
         let api_cfg = self.0.api_settings.clone();
@@ -548,7 +674,11 @@ impl MassaRpcServer for API<Public> {
             res.push(OperationInfo {
                 id,
                 in_pool,
-                is_operation_final,
+                is_operation_final: if archived_ops.contains(&id) {
+                    Some(true)
+                } else {
+                    is_operation_final
+                },
                 thread: operation
                     .content_creator_address
                     .get_thread(api_cfg.thread_count),
@@ -562,9 +692,23 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    async fn get_operation_pool_stats(&self) -> RpcResult<OperationPoolStats> {
+        Ok(self.0.pool_command_sender.get_operation_pool_stats())
+    }
+
+    async fn get_operation_pool_status(
+        &self,
+        ops: Vec<OperationId>,
+    ) -> RpcResult<Vec<OperationPoolStatus>> {
+        if ops.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        Ok(self.0.pool_command_sender.get_operation_pool_status(&ops))
+    }
+
     async fn get_endorsements(&self, eds: Vec<EndorsementId>) -> RpcResult<Vec<EndorsementInfo>> {
         // get the endorsements and the list of blocks that contain them from storage
-        let storage_info: Vec<(SecureShareEndorsement, PreHashSet<BlockId>)> = {
+        let mut storage_info: Vec<(SecureShareEndorsement, PreHashSet<BlockId>)> = {
             let read_blocks = self.0.storage.read_blocks();
             let read_endos = self.0.storage.read_endorsements();
             eds.iter()
@@ -582,7 +726,28 @@ impl MassaRpcServer for API<Public> {
                 .collect()
         };
 
-        // keep only the ops found in storage
+        // endorsements pruned from storage may be found in the block archive, they are final
+        let mut archived_eds: PreHashSet<EndorsementId> = PreHashSet::default();
+        if let Some(block_archive_controller) = &self.0.block_archive_controller {
+            let found: PreHashSet<EndorsementId> =
+                storage_info.iter().map(|(ed, _)| ed.id).collect();
+            let missing: Vec<EndorsementId> = eds
+                .iter()
+                .filter(|id| !found.contains(id))
+                .copied()
+                .collect();
+            for (ed, block_id) in block_archive_controller
+                .get_endorsements(&missing)
+                .map_err(|err| ApiError::InternalServerError(err.to_string()))?
+                .into_iter()
+                .flatten()
+            {
+                archived_eds.insert(ed.id);
+                storage_info.push((ed, std::iter::once(block_id).collect()));
+            }
+        }
+
+        // keep only the endorsements found in storage or in the archive
         let eds: Vec<EndorsementId> = storage_info.iter().map(|(ed, _)| ed.id).collect();
 
         // ask pool whether it carries the operations
@@ -612,9 +777,11 @@ impl MassaRpcServer for API<Public> {
                 .collect();
             storage_info
                 .iter()
-                .map(|(_ed, bs)| {
-                    bs.iter()
-                        .any(|b| block_statuses.get(b) == Some(&BlockGraphStatus::Final))
+                .map(|(ed, bs)| {
+                    archived_eds.contains(&ed.id)
+                        || bs
+                            .iter()
+                            .any(|b| block_statuses.get(b) == Some(&BlockGraphStatus::Final))
                 })
                 .collect()
         };
@@ -642,14 +809,47 @@ impl MassaRpcServer for API<Public> {
     }
 
     /// gets a block(s). Returns nothing if not found
-    /// only active blocks are returned
+    /// only active blocks and archived final blocks are returned
     async fn get_blocks(&self, ids: Vec<BlockId>) -> RpcResult<Vec<BlockInfo>> {
         let consensus_controller = self.0.consensus_controller.clone();
+
+        // blocks pruned from storage may be found in the block archive, they are final
+        let archived_blocks: PreHashMap<BlockId, Block> = match &self.0.block_archive_controller {
+            Some(block_archive_controller) => {
+                let missing: Vec<BlockId> = {
+                    let read_blocks = self.0.storage.read_blocks();
+                    ids.iter()
+                        .filter(|id| !read_blocks.contains(id))
+                        .copied()
+                        .collect()
+                };
+                block_archive_controller
+                    .get_blocks(&missing)
+                    .map_err(|err| ApiError::InternalServerError(err.to_string()))?
+                    .into_iter()
+                    .flatten()
+                    .map(|block| (block.id, block.content))
+                    .collect()
+            }
+            None => PreHashMap::default(),
+        };
+
         let blocks = ids
             .into_iter()
             .filter_map(|id| {
                 let content = if let Some(wrapped_block) = self.0.storage.read_blocks().get(&id) {
                     wrapped_block.content.clone()
+                } else if let Some(block) = archived_blocks.get(&id) {
+                    return Some(BlockInfo {
+                        id,
+                        content: Some(BlockInfoContent {
+                            is_final: true,
+                            is_in_blockclique: false,
+                            is_candidate: false,
+                            is_discarded: false,
+                            block: block.clone(),
+                        }),
+                    });
                 } else {
                     return None;
                 };
@@ -934,6 +1134,35 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    async fn get_address_history(
+        &self,
+        address: Address,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<AddressHistoryEntry>> {
+        let max_entries = self.0.api_settings.max_arguments as usize;
+        // the history is read from the newest entry up to the end of the requested page
+        let entry_count = match &page_request {
+            Some(PageRequest { limit, .. }) if *limit > max_entries => {
+                return Err(ApiError::BadRequest("too many entries requested".into()).into());
+            }
+            Some(PageRequest { limit, offset }) => {
+                offset.saturating_mul(*limit).saturating_add(*limit)
+            }
+            None => max_entries,
+        };
+
+        let entries = self
+            .0
+            .execution_controller
+            .get_address_history(&address, None, entry_count)
+            .map_err(ApiError::from)?
+            .into_iter()
+            .map(AddressHistoryEntry::from)
+            .collect::<Vec<_>>();
+
+        Ok(PagedVec::new(entries, page_request))
+    }
+
     async fn send_operations(&self, ops: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
         let mut cmd_sender = self.0.pool_command_sender.clone();
         let protocol_sender = self.0.protocol_controller.clone();
@@ -973,14 +1202,9 @@ impl MassaRpcServer for API<Public> {
                     .map_err(|err| {
                         ApiError::ModelsError(ModelsError::DeserializeError(err.to_string()))
                     })?;
-                match op.content.op {
-                    OperationType::CallSC { max_gas, .. } | OperationType::ExecuteSC { max_gas, .. } => {
-                        if max_gas > api_cfg.max_gas_per_block {
-                            return Err(ApiError::InconsistencyError("Gas limit of the operation is higher than the block gas limit. Your operation will never be included in a block.".into()).into());
-                        }
-                    },
-                    _ => {}
-                };
+                if op.content.get_gas_usage() > api_cfg.max_gas_per_block {
+                    return Err(ApiError::InconsistencyError("Gas limit of the operation is higher than the block gas limit. Your operation will never be included in a block.".into()).into());
+                }
                 if let Some(slot) = last_slot {
                     if op.content.expire_period < slot.period {
                         return Err(ApiError::InconsistencyError("Operation expire_period is lower than the current period of this node. Your operation will never be included in a block.".into()).into());
@@ -1001,6 +1225,9 @@ impl MassaRpcServer for API<Public> {
                         Ok(()) => (),
                         Err(e) => return Err(ApiError::ModelsError(e).into()),
                     };
+                    if let Err(e) = operation.verify_multisig_signatures() {
+                        return Err(ApiError::ModelsError(e).into());
+                    }
                     Ok(operation)
                 }
                 Err(e) => Err(e),
@@ -1038,6 +1265,103 @@ impl MassaRpcServer for API<Public> {
         Ok(events)
     }
 
+    async fn get_async_messages(
+        &self,
+        filter: AsyncMessageFilter,
+        start_after: Option<AsyncMessageId>,
+    ) -> RpcResult<Vec<AsyncMessageOutput>> {
+        let limit = self.0.api_settings.max_arguments as usize;
+        let response = self
+            .0
+            .execution_controller
+            .query_state(ExecutionQueryRequest {
+                requests: vec![
+                    ExecutionQueryRequestItem::AsyncMessagesFinal {
+                        filter: filter.clone(),
+                        start_after,
+                        limit,
+                    },
+                    ExecutionQueryRequestItem::AsyncMessagesCandidate {
+                        filter,
+                        start_after,
+                        limit,
+                    },
+                ],
+            });
+
+        let mut outputs: BTreeMap<AsyncMessageId, AsyncMessageOutput> = BTreeMap::new();
+        for (is_final, response_item) in [true, false].into_iter().zip(response.responses) {
+            let messages = match response_item {
+                Ok(ExecutionQueryResponseItem::AsyncMessages(messages)) => messages,
+                Ok(_) => {
+                    return Err(ApiError::InconsistencyError(
+                        "unexpected execution query response".to_string(),
+                    )
+                    .into())
+                }
+                Err(err) => return Err(ApiError::InternalServerError(err.to_string()).into()),
+            };
+            for (id, message) in messages {
+                let output = outputs.entry(id).or_insert(AsyncMessageOutput {
+                    id,
+                    final_message: None,
+                    candidate_message: None,
+                });
+                if is_final {
+                    output.final_message = Some(message);
+                } else {
+                    output.candidate_message = Some(message);
+                }
+            }
+        }
+        // both pools returned their first matching messages: the first ones of their union are complete
+        while outputs.len() > limit {
+            outputs.pop_last();
+        }
+
+        // a message may match the filter in only one of the pools:
+        // fetch its other version by id so that both are always reported
+        let incomplete_ids: Vec<AsyncMessageId> = outputs
+            .values()
+            .filter(|output| output.final_message.is_none() || output.candidate_message.is_none())
+            .map(|output| output.id)
+            .collect();
+        for output in self.fetch_async_messages(incomplete_ids)? {
+            outputs.insert(output.id, output);
+        }
+
+        Ok(outputs.into_values().collect())
+    }
+
+    async fn get_async_messages_by_id(
+        &self,
+        ids: Vec<AsyncMessageId>,
+    ) -> RpcResult<Vec<AsyncMessageOutput>> {
+        if ids.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        self.fetch_async_messages(ids)
+    }
+
+    async fn node_connected_peers(
+        &self,
+        _: Option<ConnectedPeersFilter>,
+    ) -> RpcResult<Vec<ConnectedPeer>> {
+        crate::wrong_api::<Vec<ConnectedPeer>>()
+    }
+
+    async fn node_static_peers(&self) -> RpcResult<BTreeMap<SocketAddr, TransportType>> {
+        crate::wrong_api::<BTreeMap<SocketAddr, TransportType>>()
+    }
+
+    async fn node_add_static_peers(&self, _: BTreeMap<SocketAddr, TransportType>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_remove_static_peers(&self, _: Vec<SocketAddr>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
     async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         crate::wrong_api::<Vec<IpAddr>>()
     }
@@ -1078,6 +1402,10 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
+    async fn node_reload_api_access(&self) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
     async fn get_openrpc_spec(&self) -> RpcResult<Value> {
         let openrpc_spec_path = self.0.api_settings.openrpc_spec_path.clone();
         let openrpc: RpcResult<Value> = std::fs::read_to_string(openrpc_spec_path)
@@ -1101,3 +1429,18 @@ impl MassaRpcServer for API<Public> {
         openrpc
     }
 }
+
+/// Extracts the message of an asynchronous message query response, None if it was not found
+fn to_async_message(
+    response_item: Result<ExecutionQueryResponseItem, ExecutionQueryError>,
+) -> RpcResult<Option<AsyncMessage>> {
+    match response_item {
+        Ok(ExecutionQueryResponseItem::AsyncMessage(message)) => Ok(Some(message)),
+        Err(ExecutionQueryError::NotFound(_)) => Ok(None),
+        Err(err) => Err(ApiError::InconsistencyError(err.to_string()).into()),
+        Ok(_) => Err(ApiError::InconsistencyError(
+            "unexpected execution query response".to_string(),
+        )
+        .into()),
+    }
+}
@@ -16,7 +16,7 @@ impl From<AsyncMessage> for grpc_model::AsyncMessage {
             fee: Some(value.fee.into()),
             coins: Some(value.coins.into()),
             validity_start: Some(value.validity_start.into()),
-            validity_end: Some(value.validity_start.into()),
+            validity_end: Some(value.validity_end.into()),
             data: value.data,
             trigger: value.trigger.map(|trigger| trigger.into()),
             can_be_executed: value.can_be_executed,
@@ -368,6 +368,84 @@ impl AsyncMessage {
     }
 }
 
+/// Filter used when listing the messages of an asynchronous pool
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AsyncMessageFilter {
+    /// optional sender address
+    pub sender: Option<Address>,
+    /// optional destination address
+    pub destination: Option<Address>,
+    /// optional handler function name
+    pub handler: Option<String>,
+    /// optional slot from which the message must be valid (included)
+    pub validity_start: Option<Slot>,
+    /// optional slot until which the message must be valid (excluded)
+    pub validity_end: Option<Slot>,
+    /// optional trigger
+    ///
+    /// Some(address, None) matches every trigger on `address`
+    /// Some(address, Some(key)) matches only triggers on that datastore key of `address`
+    pub trigger: Option<AsyncMessageTrigger>,
+    /// optional executability status
+    ///
+    /// Some(true) means messages that can be executed
+    /// Some(false) means messages still waiting for their trigger
+    /// None means both
+    pub can_be_executed: Option<bool>,
+}
+
+impl AsyncMessageFilter {
+    /// Returns true if the message satisfies every criteria of the filter.
+    ///
+    /// The validity bounds keep the messages whose validity range `[validity_start, validity_end)`
+    /// intersects the range defined by the filter.
+    pub fn matches(&self, message: &AsyncMessage) -> bool {
+        if let Some(sender) = &self.sender {
+            if &message.sender != sender {
+                return false;
+            }
+        }
+        if let Some(destination) = &self.destination {
+            if &message.destination != destination {
+                return false;
+            }
+        }
+        if let Some(handler) = &self.handler {
+            if &message.handler != handler {
+                return false;
+            }
+        }
+        if let Some(start) = &self.validity_start {
+            if &message.validity_end <= start {
+                return false;
+            }
+        }
+        if let Some(end) = &self.validity_end {
+            if &message.validity_start >= end {
+                return false;
+            }
+        }
+        if let Some(filter_trigger) = &self.trigger {
+            match &message.trigger {
+                Some(trigger) if trigger.address == filter_trigger.address => {
+                    if filter_trigger.datastore_key.is_some()
+                        && trigger.datastore_key != filter_trigger.datastore_key
+                    {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+        if let Some(can_be_executed) = self.can_be_executed {
+            if message.can_be_executed != can_be_executed {
+                return false;
+            }
+        }
+        true
+    }
+}
+
 #[derive(Clone)]
 pub struct AsyncMessageSerializer {
     pub slot_serializer: SlotSerializer,
@@ -1037,7 +1115,9 @@ impl Applicable<AsyncMessageUpdate> for AsyncMessageInfo {
 mod tests {
     use massa_serialization::{DeserializeError, Deserializer, Serializer};
 
-    use crate::{AsyncMessage, AsyncMessageDeserializer, AsyncMessageSerializer};
+    use crate::{
+        AsyncMessage, AsyncMessageDeserializer, AsyncMessageFilter, AsyncMessageSerializer,
+    };
     use massa_models::{
         address::Address,
         amount::Amount,
@@ -1085,4 +1165,92 @@ mod tests {
             .deserialize::<DeserializeError>(&serialized)
             .unwrap_err();
     }
+
+    #[test]
+    fn filter_messages() {
+        let sender =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        let destination =
+            Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+        let message = AsyncMessage::new(
+            Slot::new(1, 2),
+            0,
+            sender,
+            destination,
+            String::from("test"),
+            10000000,
+            Amount::from_str("1").unwrap(),
+            Amount::from_str("1").unwrap(),
+            Slot::new(2, 0),
+            Slot::new(3, 0),
+            vec![1, 2, 3, 4],
+            Some(AsyncMessageTrigger {
+                address: destination,
+                datastore_key: Some(vec![1]),
+            }),
+            None,
+        );
+
+        assert!(AsyncMessageFilter::default().matches(&message));
+        assert!(AsyncMessageFilter {
+            sender: Some(sender),
+            destination: Some(destination),
+            handler: Some(String::from("test")),
+            ..Default::default()
+        }
+        .matches(&message));
+        assert!(!AsyncMessageFilter {
+            sender: Some(destination),
+            ..Default::default()
+        }
+        .matches(&message));
+        assert!(!AsyncMessageFilter {
+            handler: Some(String::from("other")),
+            ..Default::default()
+        }
+        .matches(&message));
+
+        // validity range [2.0, 3.0) must intersect the filter range
+        assert!(AsyncMessageFilter {
+            validity_start: Some(Slot::new(2, 5)),
+            validity_end: Some(Slot::new(4, 0)),
+            ..Default::default()
+        }
+        .matches(&message));
+        assert!(!AsyncMessageFilter {
+            validity_start: Some(Slot::new(3, 0)),
+            ..Default::default()
+        }
+        .matches(&message));
+        assert!(!AsyncMessageFilter {
+            validity_end: Some(Slot::new(2, 0)),
+            ..Default::default()
+        }
+        .matches(&message));
+
+        // trigger without key matches any key of the address
+        assert!(AsyncMessageFilter {
+            trigger: Some(AsyncMessageTrigger {
+                address: destination,
+                datastore_key: None,
+            }),
+            ..Default::default()
+        }
+        .matches(&message));
+        assert!(!AsyncMessageFilter {
+            trigger: Some(AsyncMessageTrigger {
+                address: destination,
+                datastore_key: Some(vec![2]),
+            }),
+            ..Default::default()
+        }
+        .matches(&message));
+
+        // the trigger has not been matched yet
+        assert!(!AsyncMessageFilter {
+            can_be_executed: Some(true),
+            ..Default::default()
+        }
+        .matches(&message));
+    }
 }
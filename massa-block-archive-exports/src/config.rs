@@ -0,0 +1,49 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// How long final blocks are kept in the archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockArchiveRetention {
+    /// keep every final block
+    All,
+    /// keep the final blocks of the given number of most recent cycles
+    Cycles(u64),
+    /// do not archive final blocks
+    None,
+}
+
+/// Block archive configuration
+#[derive(Debug, Clone)]
+pub struct BlockArchiveConfig {
+    /// path of the archive database
+    pub path: PathBuf,
+    /// retention of the archived blocks
+    pub retention: BlockArchiveRetention,
+    /// thread count
+    pub thread_count: u8,
+    /// periods per cycle
+    pub periods_per_cycle: u64,
+    /// number of endorsements in a block
+    pub endorsement_count: u32,
+    /// maximum number of operations in a block
+    pub max_operations_per_block: u32,
+    /// maximum number of denunciations in a block header
+    pub max_denunciations_per_block_header: u32,
+    /// maximum datastore value length
+    pub max_datastore_value_length: u64,
+    /// maximum function name length
+    pub max_function_name_length: u16,
+    /// maximum parameters size
+    pub max_parameter_size: u32,
+    /// maximum number of datastore entries in an operation
+    pub max_op_datastore_entry_count: u64,
+    /// maximum datastore key length in an operation
+    pub max_op_datastore_key_length: u8,
+    /// maximum datastore value length in an operation
+    pub max_op_datastore_value_length: u64,
+    /// size of the channel of blocks waiting to be archived
+    pub channel_size: usize,
+}
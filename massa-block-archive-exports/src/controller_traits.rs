@@ -0,0 +1,86 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module exports generic traits representing interfaces for interacting
+//! with the block archive worker.
+
+use crate::{ArchivedItemId, ArchivedItemKind, BlockArchiveResult};
+use massa_models::{
+    address::Address,
+    block::SecureShareBlock,
+    block_id::BlockId,
+    endorsement::{EndorsementId, SecureShareEndorsement},
+    operation::{OperationId, SecureShareOperation},
+    slot::Slot,
+};
+use massa_storage::Storage;
+
+/// Interface to interact with the block archive
+pub trait BlockArchiveController: Send + Sync {
+    /// Queues newly finalized blocks for archiving.
+    /// Each storage must hold its final block with the operations of the block.
+    fn archive_final_blocks(&self, blocks: Vec<(BlockId, Storage)>);
+
+    /// Get archived blocks, `None` for the blocks that are not in the archive
+    fn get_blocks(
+        &self,
+        block_ids: &[BlockId],
+    ) -> BlockArchiveResult<Vec<Option<SecureShareBlock>>>;
+
+    /// Get the ids of the archived blocks in a slot range (end excluded), in slot order
+    /// # Arguments
+    /// * `limit`: maximum number of returned ids
+    fn get_block_ids_by_slot_range(
+        &self,
+        start: Slot,
+        end: Slot,
+        limit: usize,
+    ) -> BlockArchiveResult<Vec<BlockId>>;
+
+    /// Get archived operations with the block that includes them,
+    /// `None` for the operations that are not in the archive
+    fn get_operations(
+        &self,
+        operation_ids: &[OperationId],
+    ) -> BlockArchiveResult<Vec<Option<(SecureShareOperation, BlockId)>>>;
+
+    /// Get archived endorsements with the block that includes them,
+    /// `None` for the endorsements that are not in the archive
+    fn get_endorsements(
+        &self,
+        endorsement_ids: &[EndorsementId],
+    ) -> BlockArchiveResult<Vec<Option<(SecureShareEndorsement, BlockId)>>>;
+
+    /// Get the archived items of a kind created or sent by an address in a slot range (end excluded),
+    /// with the slot of their block, in slot order
+    /// # Arguments
+    /// * `limit`: maximum number of returned items
+    fn get_address_history(
+        &self,
+        address: &Address,
+        kind: ArchivedItemKind,
+        start: Slot,
+        end: Slot,
+        limit: usize,
+    ) -> BlockArchiveResult<Vec<(Slot, ArchivedItemId)>>;
+
+    /// Returns a boxed clone of self.
+    /// Useful to allow cloning `Box<dyn BlockArchiveController>`.
+    fn clone_box(&self) -> Box<dyn BlockArchiveController>;
+}
+
+/// Allow cloning `Box<dyn BlockArchiveController>`
+/// Uses `BlockArchiveController::clone_box` internally
+impl Clone for Box<dyn BlockArchiveController> {
+    fn clone(&self) -> Box<dyn BlockArchiveController> {
+        self.clone_box()
+    }
+}
+
+/// Block archive manager used to stop the block archive thread
+pub trait BlockArchiveManager {
+    /// Stop the block archive thread, after the queued blocks are archived
+    /// Note that we do not take self by value to consume it
+    /// because it is not allowed to move out of `Box<dyn BlockArchiveManager>`
+    /// This will improve if the `unsized_fn_params` feature stabilizes enough to be safely usable.
+    fn stop(&mut self);
+}
@@ -0,0 +1,21 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use displaydoc::Display;
+use thiserror::Error;
+
+/// Block archive result
+pub type BlockArchiveResult<T, E = BlockArchiveError> = core::result::Result<T, E>;
+
+/// Block archive error
+#[non_exhaustive]
+#[derive(Display, Error, Debug, Clone)]
+pub enum BlockArchiveError {
+    /// Database error: {0}
+    DatabaseError(String),
+    /// Serialization error: {0}
+    SerializationError(String),
+    /// Deserialization error: {0}
+    DeserializationError(String),
+    /// Communication channel was down: {0}
+    ChannelDown(String),
+}
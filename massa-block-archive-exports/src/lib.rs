@@ -0,0 +1,17 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Definition and exports of the block archive types and errors.
+//!
+//! The block archive persists the final blocks, with their operations and endorsements,
+//! after consensus and storage have forgotten them, so that they can still be served by the APIs.
+
+#![warn(missing_docs)]
+
+mod config;
+mod controller_traits;
+mod error;
+mod types;
+
+pub use config::{BlockArchiveConfig, BlockArchiveRetention};
+pub use controller_traits::{BlockArchiveController, BlockArchiveManager};
+pub use error::*;
+pub use types::*;
@@ -0,0 +1,36 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{block_id::BlockId, endorsement::EndorsementId, operation::OperationId};
+
+/// Kind of the items indexed by address in the archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArchivedItemKind {
+    /// blocks created by the address
+    Block,
+    /// operations sent by the address
+    Operation,
+    /// endorsements created by the address
+    Endorsement,
+}
+
+/// Identifier of an archived item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArchivedItemId {
+    /// block id
+    Block(BlockId),
+    /// operation id
+    Operation(OperationId),
+    /// endorsement id
+    Endorsement(EndorsementId),
+}
+
+impl ArchivedItemId {
+    /// Get the kind of the identified item
+    pub fn kind(&self) -> ArchivedItemKind {
+        match self {
+            ArchivedItemId::Block(_) => ArchivedItemKind::Block,
+            ArchivedItemId::Operation(_) => ArchivedItemKind::Operation,
+            ArchivedItemId::Endorsement(_) => ArchivedItemKind::Endorsement,
+        }
+    }
+}
@@ -0,0 +1,468 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! RocksDB layout of the block archive.
+//!
+//! Ids are stored as their 32-byte hash. Column families:
+//! * `blocks`: block id => serialized block
+//! * `slots`: slot key => block id
+//! * `operations`: operation id => id of the including block, serialized operation
+//! * `endorsements`: endorsement id => id of the including block, serialized endorsement
+//! * `addresses`: serialized address, item kind, slot key, item id => empty value
+
+use massa_block_archive_exports::{
+    ArchivedItemId, ArchivedItemKind, BlockArchiveConfig, BlockArchiveError, BlockArchiveResult,
+    BlockArchiveRetention,
+};
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_models::{
+    address::{Address, AddressSerializer},
+    block::{Block, BlockDeserializer, BlockDeserializerArgs, SecureShareBlock},
+    block_id::BlockId,
+    endorsement::{Endorsement, EndorsementDeserializer, EndorsementId, SecureShareEndorsement},
+    operation::{Operation, OperationDeserializer, OperationId, SecureShareOperation},
+    secure_share::{Id, SecureShareDeserializer, SecureShareSerializer},
+    slot::{Slot, SLOT_KEY_SIZE},
+};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use massa_storage::Storage;
+use rocksdb::{ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch, DB};
+use tracing::warn;
+
+const BLOCKS_CF: &str = "blocks";
+const SLOTS_CF: &str = "slots";
+const OPERATIONS_CF: &str = "operations";
+const ENDORSEMENTS_CF: &str = "endorsements";
+const ADDRESSES_CF: &str = "addresses";
+
+const CF_ERROR: &str = "critical: rocksdb column family operation failed";
+
+/// Block archive database
+pub(crate) struct BlockArchiveDB {
+    db: DB,
+    config: BlockArchiveConfig,
+    block_deserializer: SecureShareDeserializer<Block, BlockDeserializer>,
+    operation_deserializer: SecureShareDeserializer<Operation, OperationDeserializer>,
+    endorsement_deserializer: SecureShareDeserializer<Endorsement, EndorsementDeserializer>,
+}
+
+impl BlockArchiveDB {
+    /// Opens the archive database, creating it if needed
+    pub fn open(config: BlockArchiveConfig) -> BlockArchiveResult<Self> {
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+        let db = DB::open_cf_descriptors(
+            &db_opts,
+            &config.path,
+            vec![
+                ColumnFamilyDescriptor::new(BLOCKS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(SLOTS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(OPERATIONS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(ENDORSEMENTS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(ADDRESSES_CF, Options::default()),
+            ],
+        )
+        .map_err(db_error)?;
+
+        let block_deserializer =
+            SecureShareDeserializer::new(BlockDeserializer::new(BlockDeserializerArgs {
+                thread_count: config.thread_count,
+                max_operations_per_block: config.max_operations_per_block,
+                endorsement_count: config.endorsement_count,
+                max_denunciations_per_block_header: config.max_denunciations_per_block_header,
+                last_start_period: None,
+            }));
+        let operation_deserializer = SecureShareDeserializer::new(OperationDeserializer::new(
+            config.max_datastore_value_length,
+            config.max_function_name_length,
+            config.max_parameter_size,
+            config.max_op_datastore_entry_count,
+            config.max_op_datastore_key_length,
+            config.max_op_datastore_value_length,
+        ));
+        let endorsement_deserializer = SecureShareDeserializer::new(EndorsementDeserializer::new(
+            config.thread_count,
+            config.endorsement_count,
+        ));
+
+        Ok(BlockArchiveDB {
+            db,
+            config,
+            block_deserializer,
+            operation_deserializer,
+            endorsement_deserializer,
+        })
+    }
+
+    /// Writes final blocks with their operations and endorsements to the archive
+    pub fn archive_blocks(&self, blocks: &[(BlockId, Storage)]) -> BlockArchiveResult<()> {
+        let handle_blocks = self.db.cf_handle(BLOCKS_CF).expect(CF_ERROR);
+        let handle_slots = self.db.cf_handle(SLOTS_CF).expect(CF_ERROR);
+        let handle_operations = self.db.cf_handle(OPERATIONS_CF).expect(CF_ERROR);
+        let handle_endorsements = self.db.cf_handle(ENDORSEMENTS_CF).expect(CF_ERROR);
+        let handle_addresses = self.db.cf_handle(ADDRESSES_CF).expect(CF_ERROR);
+
+        let mut batch = WriteBatch::default();
+        for (block_id, storage) in blocks {
+            let read_blocks = storage.read_blocks();
+            let read_operations = storage.read_operations();
+            let Some(block) = read_blocks.get(block_id) else {
+                warn!(
+                    "final block {} to archive is missing from storage",
+                    block_id
+                );
+                continue;
+            };
+            let slot = block.content.header.content.slot;
+            let block_key = id_key(&block.id);
+
+            batch.put_cf(handle_blocks, block_key, serialize(block)?);
+            batch.put_cf(handle_slots, slot.to_bytes_key(), block_key);
+            batch.put_cf(
+                handle_addresses,
+                address_key(
+                    &block.content_creator_address,
+                    ArchivedItemKind::Block,
+                    &slot,
+                    &block.id,
+                )?,
+                b"",
+            );
+
+            for op_id in &block.content.operations {
+                let Some(operation) = read_operations.get(op_id) else {
+                    warn!(
+                        "operation {} of final block {} to archive is missing from storage",
+                        op_id, block_id
+                    );
+                    continue;
+                };
+                let mut value = block_key.to_vec();
+                value.extend(serialize(operation)?);
+                batch.put_cf(handle_operations, id_key(op_id), value);
+                batch.put_cf(
+                    handle_addresses,
+                    address_key(
+                        &operation.content_creator_address,
+                        ArchivedItemKind::Operation,
+                        &slot,
+                        op_id,
+                    )?,
+                    b"",
+                );
+            }
+
+            for endorsement in &block.content.header.content.endorsements {
+                let mut value = block_key.to_vec();
+                value.extend(serialize(endorsement)?);
+                batch.put_cf(handle_endorsements, id_key(&endorsement.id), value);
+                batch.put_cf(
+                    handle_addresses,
+                    address_key(
+                        &endorsement.content_creator_address,
+                        ArchivedItemKind::Endorsement,
+                        &slot,
+                        &endorsement.id,
+                    )?,
+                    b"",
+                );
+            }
+        }
+        self.db.write(batch).map_err(db_error)
+    }
+
+    /// Get the slot of the latest archived block
+    pub fn get_last_slot(&self) -> BlockArchiveResult<Option<Slot>> {
+        let handle_slots = self.db.cf_handle(SLOTS_CF).expect(CF_ERROR);
+        match self.db.iterator_cf(handle_slots, IteratorMode::End).next() {
+            Some(entry) => {
+                let (key, _) = entry.map_err(db_error)?;
+                Ok(Some(slot_from_key(&key)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Get the first slot that must be kept according to the retention policy,
+    /// given the slot of the latest archived block
+    pub fn get_retention_start(&self, last_slot: Slot) -> Option<Slot> {
+        match self.config.retention {
+            BlockArchiveRetention::Cycles(cycles) => {
+                let first_cycle = last_slot
+                    .get_cycle(self.config.periods_per_cycle)
+                    .saturating_add(1)
+                    .saturating_sub(cycles);
+                Some(Slot::new(
+                    first_cycle.saturating_mul(self.config.periods_per_cycle),
+                    0,
+                ))
+            }
+            BlockArchiveRetention::All | BlockArchiveRetention::None => None,
+        }
+    }
+
+    /// Removes the archived blocks before the given slot, with their operations and endorsements.
+    /// Returns the number of removed blocks.
+    pub fn prune_before(&self, slot: Slot) -> BlockArchiveResult<usize> {
+        let handle_blocks = self.db.cf_handle(BLOCKS_CF).expect(CF_ERROR);
+        let handle_slots = self.db.cf_handle(SLOTS_CF).expect(CF_ERROR);
+        let handle_operations = self.db.cf_handle(OPERATIONS_CF).expect(CF_ERROR);
+        let handle_endorsements = self.db.cf_handle(ENDORSEMENTS_CF).expect(CF_ERROR);
+        let handle_addresses = self.db.cf_handle(ADDRESSES_CF).expect(CF_ERROR);
+
+        let end_key = slot.to_bytes_key();
+        let mut batch = WriteBatch::default();
+        let mut count = 0;
+        for entry in self.db.iterator_cf(handle_slots, IteratorMode::Start) {
+            let (slot_key, block_key) = entry.map_err(db_error)?;
+            if *slot_key >= end_key[..] {
+                break;
+            }
+            let block_slot = slot_from_key(&slot_key)?;
+            batch.delete_cf(handle_slots, &slot_key);
+            count += 1;
+
+            let block_id: BlockId = id_from_key(&block_key)?;
+            let Some(block) = self.get_block(&block_id)? else {
+                continue;
+            };
+            batch.delete_cf(handle_blocks, &block_key);
+            batch.delete_cf(
+                handle_addresses,
+                address_key(
+                    &block.content_creator_address,
+                    ArchivedItemKind::Block,
+                    &block_slot,
+                    &block_id,
+                )?,
+            );
+            for op_id in &block.content.operations {
+                if let Some((operation, _)) = self.get_operation(op_id)? {
+                    batch.delete_cf(
+                        handle_addresses,
+                        address_key(
+                            &operation.content_creator_address,
+                            ArchivedItemKind::Operation,
+                            &block_slot,
+                            op_id,
+                        )?,
+                    );
+                }
+                batch.delete_cf(handle_operations, id_key(op_id));
+            }
+            for endorsement in &block.content.header.content.endorsements {
+                batch.delete_cf(
+                    handle_addresses,
+                    address_key(
+                        &endorsement.content_creator_address,
+                        ArchivedItemKind::Endorsement,
+                        &block_slot,
+                        &endorsement.id,
+                    )?,
+                );
+                batch.delete_cf(handle_endorsements, id_key(&endorsement.id));
+            }
+        }
+        self.db.write(batch).map_err(db_error)?;
+        Ok(count)
+    }
+
+    /// Get an archived block
+    pub fn get_block(&self, block_id: &BlockId) -> BlockArchiveResult<Option<SecureShareBlock>> {
+        let handle_blocks = self.db.cf_handle(BLOCKS_CF).expect(CF_ERROR);
+        self.db
+            .get_cf(handle_blocks, id_key(block_id))
+            .map_err(db_error)?
+            .map(|bytes| deserialize(&self.block_deserializer, &bytes))
+            .transpose()
+    }
+
+    /// Get the ids of the archived blocks in a slot range (end excluded)
+    pub fn get_block_ids_by_slot_range(
+        &self,
+        start: Slot,
+        end: Slot,
+        limit: usize,
+    ) -> BlockArchiveResult<Vec<BlockId>> {
+        let handle_slots = self.db.cf_handle(SLOTS_CF).expect(CF_ERROR);
+        let start_key = start.to_bytes_key();
+        let end_key = end.to_bytes_key();
+        let mut block_ids = Vec::new();
+        for entry in self.db.iterator_cf(
+            handle_slots,
+            IteratorMode::From(&start_key, Direction::Forward),
+        ) {
+            if block_ids.len() >= limit {
+                break;
+            }
+            let (slot_key, block_key) = entry.map_err(db_error)?;
+            if *slot_key >= end_key[..] {
+                break;
+            }
+            block_ids.push(id_from_key(&block_key)?);
+        }
+        Ok(block_ids)
+    }
+
+    /// Get an archived operation with the id of its block
+    pub fn get_operation(
+        &self,
+        operation_id: &OperationId,
+    ) -> BlockArchiveResult<Option<(SecureShareOperation, BlockId)>> {
+        let handle_operations = self.db.cf_handle(OPERATIONS_CF).expect(CF_ERROR);
+        self.db
+            .get_cf(handle_operations, id_key(operation_id))
+            .map_err(db_error)?
+            .map(|bytes| self.split_included_value(&bytes, &self.operation_deserializer))
+            .transpose()
+    }
+
+    /// Get an archived endorsement with the id of its block
+    pub fn get_endorsement(
+        &self,
+        endorsement_id: &EndorsementId,
+    ) -> BlockArchiveResult<Option<(SecureShareEndorsement, BlockId)>> {
+        let handle_endorsements = self.db.cf_handle(ENDORSEMENTS_CF).expect(CF_ERROR);
+        self.db
+            .get_cf(handle_endorsements, id_key(endorsement_id))
+            .map_err(db_error)?
+            .map(|bytes| self.split_included_value(&bytes, &self.endorsement_deserializer))
+            .transpose()
+    }
+
+    /// Get the archived items of a kind created by an address in a slot range (end excluded)
+    pub fn get_address_history(
+        &self,
+        address: &Address,
+        kind: ArchivedItemKind,
+        start: Slot,
+        end: Slot,
+        limit: usize,
+    ) -> BlockArchiveResult<Vec<(Slot, ArchivedItemId)>> {
+        let handle_addresses = self.db.cf_handle(ADDRESSES_CF).expect(CF_ERROR);
+        let prefix = address_prefix(address, kind)?;
+        let mut start_key = prefix.clone();
+        start_key.extend(start.to_bytes_key());
+        let end_key = end.to_bytes_key();
+
+        let mut items = Vec::new();
+        for entry in self.db.iterator_cf(
+            handle_addresses,
+            IteratorMode::From(&start_key, Direction::Forward),
+        ) {
+            if items.len() >= limit {
+                break;
+            }
+            let (key, _) = entry.map_err(db_error)?;
+            let Some(rest) = key.strip_prefix(prefix.as_slice()) else {
+                break;
+            };
+            if rest.len() != SLOT_KEY_SIZE + HASH_SIZE_BYTES {
+                return Err(BlockArchiveError::DeserializationError(
+                    "invalid address index key".to_string(),
+                ));
+            }
+            let (slot_key, id_bytes) = rest.split_at(SLOT_KEY_SIZE);
+            if *slot_key >= end_key[..] {
+                break;
+            }
+            let id = match kind {
+                ArchivedItemKind::Block => ArchivedItemId::Block(id_from_key(id_bytes)?),
+                ArchivedItemKind::Operation => ArchivedItemId::Operation(id_from_key(id_bytes)?),
+                ArchivedItemKind::Endorsement => {
+                    ArchivedItemId::Endorsement(id_from_key(id_bytes)?)
+                }
+            };
+            items.push((slot_from_key(slot_key)?, id));
+        }
+        Ok(items)
+    }
+
+    /// Splits a value made of the id of the including block followed by a serialized item
+    fn split_included_value<T, D: Deserializer<T>>(
+        &self,
+        bytes: &[u8],
+        deserializer: &D,
+    ) -> BlockArchiveResult<(T, BlockId)> {
+        if bytes.len() < HASH_SIZE_BYTES {
+            return Err(BlockArchiveError::DeserializationError(
+                "archived value is too short".to_string(),
+            ));
+        }
+        let (block_key, serialized) = bytes.split_at(HASH_SIZE_BYTES);
+        Ok((
+            deserialize(deserializer, serialized)?,
+            id_from_key(block_key)?,
+        ))
+    }
+}
+
+fn db_error(err: rocksdb::Error) -> BlockArchiveError {
+    BlockArchiveError::DatabaseError(err.to_string())
+}
+
+fn id_key<ID: Id>(id: &ID) -> [u8; HASH_SIZE_BYTES] {
+    *id.get_hash().to_bytes()
+}
+
+fn id_from_key<ID: Id>(bytes: &[u8]) -> BlockArchiveResult<ID> {
+    let bytes: &[u8; HASH_SIZE_BYTES] = bytes
+        .try_into()
+        .map_err(|_| BlockArchiveError::DeserializationError("invalid archived id".to_string()))?;
+    Ok(ID::new(Hash::from_bytes(bytes)))
+}
+
+fn slot_from_key(bytes: &[u8]) -> BlockArchiveResult<Slot> {
+    let bytes: &[u8; SLOT_KEY_SIZE] = bytes.try_into().map_err(|_| {
+        BlockArchiveError::DeserializationError("invalid archived slot".to_string())
+    })?;
+    Ok(Slot::from_bytes_key(bytes))
+}
+
+fn address_prefix(address: &Address, kind: ArchivedItemKind) -> BlockArchiveResult<Vec<u8>> {
+    let mut key = Vec::new();
+    AddressSerializer::new()
+        .serialize(address, &mut key)
+        .map_err(|err| BlockArchiveError::SerializationError(err.to_string()))?;
+    key.push(match kind {
+        ArchivedItemKind::Block => 0,
+        ArchivedItemKind::Operation => 1,
+        ArchivedItemKind::Endorsement => 2,
+    });
+    Ok(key)
+}
+
+fn address_key<ID: Id>(
+    address: &Address,
+    kind: ArchivedItemKind,
+    slot: &Slot,
+    id: &ID,
+) -> BlockArchiveResult<Vec<u8>> {
+    let mut key = address_prefix(address, kind)?;
+    key.extend(slot.to_bytes_key());
+    key.extend(id_key(id));
+    Ok(key)
+}
+
+fn serialize<T>(value: &T) -> BlockArchiveResult<Vec<u8>>
+where
+    SecureShareSerializer: Serializer<T>,
+{
+    let mut buffer = Vec::new();
+    SecureShareSerializer::new()
+        .serialize(value, &mut buffer)
+        .map_err(|err| BlockArchiveError::SerializationError(err.to_string()))?;
+    Ok(buffer)
+}
+
+fn deserialize<T, D: Deserializer<T>>(deserializer: &D, bytes: &[u8]) -> BlockArchiveResult<T> {
+    let (rest, value) = deserializer
+        .deserialize::<DeserializeError>(bytes)
+        .map_err(|err| BlockArchiveError::DeserializationError(err.to_string()))?;
+    if !rest.is_empty() {
+        return Err(BlockArchiveError::DeserializationError(
+            "trailing bytes after archived value".to_string(),
+        ));
+    }
+    Ok(value)
+}
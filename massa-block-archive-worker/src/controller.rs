@@ -0,0 +1,123 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module implements a block archive controller.
+//! See `massa-block-archive-exports/controller_traits.rs` for functional details.
+
+use crate::{archive::BlockArchiveDB, Command};
+use massa_block_archive_exports::{
+    ArchivedItemId, ArchivedItemKind, BlockArchiveController, BlockArchiveManager,
+    BlockArchiveResult,
+};
+use massa_models::{
+    address::Address,
+    block::SecureShareBlock,
+    block_id::BlockId,
+    endorsement::{EndorsementId, SecureShareEndorsement},
+    operation::{OperationId, SecureShareOperation},
+    slot::Slot,
+};
+use massa_storage::Storage;
+use std::sync::{mpsc::SyncSender, Arc};
+use tracing::{info, warn};
+
+#[derive(Clone)]
+/// implementation of the block archive controller
+pub struct BlockArchiveControllerImpl {
+    /// archive database, read directly by the controller
+    pub(crate) archive: Arc<BlockArchiveDB>,
+    /// MPSC to send commands to the block archive thread
+    pub(crate) input_mpsc: SyncSender<Command>,
+}
+
+impl BlockArchiveController for BlockArchiveControllerImpl {
+    fn archive_final_blocks(&self, blocks: Vec<(BlockId, Storage)>) {
+        if blocks.is_empty() {
+            return;
+        }
+        if self.input_mpsc.send(Command::Archive(blocks)).is_err() {
+            warn!("could not archive final blocks: block archive thread is down");
+        }
+    }
+
+    fn get_blocks(
+        &self,
+        block_ids: &[BlockId],
+    ) -> BlockArchiveResult<Vec<Option<SecureShareBlock>>> {
+        block_ids
+            .iter()
+            .map(|block_id| self.archive.get_block(block_id))
+            .collect()
+    }
+
+    fn get_block_ids_by_slot_range(
+        &self,
+        start: Slot,
+        end: Slot,
+        limit: usize,
+    ) -> BlockArchiveResult<Vec<BlockId>> {
+        self.archive.get_block_ids_by_slot_range(start, end, limit)
+    }
+
+    fn get_operations(
+        &self,
+        operation_ids: &[OperationId],
+    ) -> BlockArchiveResult<Vec<Option<(SecureShareOperation, BlockId)>>> {
+        operation_ids
+            .iter()
+            .map(|operation_id| self.archive.get_operation(operation_id))
+            .collect()
+    }
+
+    fn get_endorsements(
+        &self,
+        endorsement_ids: &[EndorsementId],
+    ) -> BlockArchiveResult<Vec<Option<(SecureShareEndorsement, BlockId)>>> {
+        endorsement_ids
+            .iter()
+            .map(|endorsement_id| self.archive.get_endorsement(endorsement_id))
+            .collect()
+    }
+
+    fn get_address_history(
+        &self,
+        address: &Address,
+        kind: ArchivedItemKind,
+        start: Slot,
+        end: Slot,
+        limit: usize,
+    ) -> BlockArchiveResult<Vec<(Slot, ArchivedItemId)>> {
+        self.archive
+            .get_address_history(address, kind, start, end, limit)
+    }
+
+    fn clone_box(&self) -> Box<dyn BlockArchiveController> {
+        Box::new(self.clone())
+    }
+}
+
+/// Implementation of the block archive manager
+/// Allows stopping the block archive worker
+pub struct BlockArchiveManagerImpl {
+    /// handle used to join the worker thread
+    pub(crate) thread_handle: Option<std::thread::JoinHandle<BlockArchiveResult<()>>>,
+    /// Input data mpsc (used to stop the block archive thread)
+    pub(crate) input_mpsc: SyncSender<Command>,
+}
+
+impl BlockArchiveManager for BlockArchiveManagerImpl {
+    /// stops the worker
+    fn stop(&mut self) {
+        info!("stopping block archive worker...");
+        let _ = self.input_mpsc.send(Command::Stop);
+        // join the block archive thread
+        if let Some(join_handle) = self.thread_handle.take() {
+            if let Err(err) = join_handle
+                .join()
+                .expect("block archive thread panicked on try to join")
+            {
+                warn!("{}", err);
+            }
+        }
+        info!("block archive worker stopped");
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Block archive worker: persists the final blocks, with their operations and endorsements,
+//! to a RocksDB database, and serves them once consensus and storage have pruned them.
+
+#![warn(missing_docs)]
+
+mod archive;
+mod controller;
+mod worker;
+
+#[cfg(test)]
+mod tests;
+
+use massa_models::block_id::BlockId;
+use massa_storage::Storage;
+
+pub use worker::start_block_archive_worker;
+
+/// Commands sent to the block archive thread
+pub(crate) enum Command {
+    /// Archive final blocks
+    Archive(Vec<(BlockId, Storage)>),
+    /// Stop the thread once the previous commands are processed
+    Stop,
+}
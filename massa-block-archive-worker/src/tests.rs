@@ -0,0 +1,230 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::start_block_archive_worker;
+use massa_block_archive_exports::{
+    ArchivedItemId, ArchivedItemKind, BlockArchiveConfig, BlockArchiveRetention,
+};
+use massa_hash::Hash;
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    block::{Block, BlockSerializer, SecureShareBlock},
+    block_header::{BlockHeader, BlockHeaderSerializer},
+    block_id::BlockId,
+    config::{
+        ENDORSEMENT_COUNT, MAX_DATASTORE_VALUE_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        MAX_FUNCTION_NAME_LENGTH, MAX_OPERATIONS_PER_BLOCK, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        MAX_PARAMETERS_SIZE,
+    },
+    endorsement::{Endorsement, EndorsementSerializer},
+    operation::{
+        compute_operations_hash, Operation, OperationIdSerializer, OperationSerializer,
+        OperationType, SecureShareOperation,
+    },
+    secure_share::SecureShareContent,
+    slot::Slot,
+};
+use massa_signature::KeyPair;
+use massa_storage::Storage;
+use tempfile::TempDir;
+
+const THREAD_COUNT: u8 = 2;
+
+fn archive_config(path: &TempDir, retention: BlockArchiveRetention) -> BlockArchiveConfig {
+    BlockArchiveConfig {
+        path: path.path().to_path_buf(),
+        retention,
+        thread_count: THREAD_COUNT,
+        periods_per_cycle: 2,
+        endorsement_count: ENDORSEMENT_COUNT,
+        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+        max_parameter_size: MAX_PARAMETERS_SIZE,
+        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        channel_size: 16,
+    }
+}
+
+fn create_operation(sender: &KeyPair) -> SecureShareOperation {
+    let content = Operation {
+        fee: Amount::default(),
+        op: OperationType::Transaction {
+            recipient_address: Address::from_public_key(
+                &KeyPair::generate(0).unwrap().get_public_key(),
+            ),
+            amount: Amount::default(),
+        },
+        expire_period: 10,
+    };
+    Operation::new_verifiable(content, OperationSerializer::new(), sender).unwrap()
+}
+
+/// Creates a final block with its operations and an endorsement, stored in a new storage
+fn create_final_block(
+    creator: &KeyPair,
+    slot: Slot,
+    operations: Vec<SecureShareOperation>,
+) -> (SecureShareBlock, Storage) {
+    let parents: Vec<BlockId> = (0..THREAD_COUNT)
+        .map(|i| BlockId::generate_from_hash(Hash::compute_from(&[i])))
+        .collect();
+    let endorsement = Endorsement::new_verifiable(
+        Endorsement {
+            slot,
+            index: 0,
+            endorsed_block: parents[slot.thread as usize],
+        },
+        EndorsementSerializer::new(),
+        creator,
+    )
+    .unwrap();
+    let operation_ids = operations.iter().map(|op| op.id).collect::<Vec<_>>();
+    let header = BlockHeader::new_verifiable(
+        BlockHeader {
+            current_version: 0,
+            announced_version: None,
+            slot,
+            parents,
+            operation_merkle_root: compute_operations_hash(
+                &operation_ids,
+                &OperationIdSerializer::new(),
+            ),
+            endorsements: vec![endorsement],
+            denunciations: Vec::new(),
+        },
+        BlockHeaderSerializer::new(),
+        creator,
+    )
+    .unwrap();
+    let block = Block::new_verifiable(
+        Block {
+            header,
+            operations: operation_ids,
+        },
+        BlockSerializer::new(),
+        creator,
+    )
+    .unwrap();
+
+    let mut storage = Storage::create_root();
+    storage.store_operations(operations);
+    storage.store_block(block.clone());
+    (block, storage)
+}
+
+#[test]
+fn test_archive_and_read_final_blocks() {
+    let path = TempDir::new().unwrap();
+    let (mut manager, controller) =
+        start_block_archive_worker(archive_config(&path, BlockArchiveRetention::All)).unwrap();
+
+    let creator = KeyPair::generate(0).unwrap();
+    let sender = KeyPair::generate(0).unwrap();
+    let operation = create_operation(&sender);
+    let (block_1, storage_1) = create_final_block(&creator, Slot::new(1, 0), vec![operation]);
+    let (block_2, storage_2) = create_final_block(&creator, Slot::new(1, 1), vec![]);
+    controller.archive_final_blocks(vec![(block_1.id, storage_1), (block_2.id, storage_2)]);
+    // stopping the worker flushes the queued blocks
+    manager.stop();
+
+    let unknown_id = BlockId::generate_from_hash(Hash::compute_from(b"unknown"));
+    let blocks = controller.get_blocks(&[block_1.id, unknown_id]).unwrap();
+    assert_eq!(blocks[0].as_ref().map(|block| block.id), Some(block_1.id));
+    assert!(blocks[1].is_none());
+
+    assert_eq!(
+        controller
+            .get_block_ids_by_slot_range(Slot::new(0, 0), Slot::new(2, 0), 10)
+            .unwrap(),
+        vec![block_1.id, block_2.id]
+    );
+    assert_eq!(
+        controller
+            .get_block_ids_by_slot_range(Slot::new(1, 1), Slot::new(2, 0), 10)
+            .unwrap(),
+        vec![block_2.id]
+    );
+
+    let operation_id = block_1.content.operations[0];
+    let operations = controller.get_operations(&[operation_id]).unwrap();
+    let (archived_operation, including_block) = operations[0].clone().unwrap();
+    assert_eq!(archived_operation.id, operation_id);
+    assert_eq!(including_block, block_1.id);
+
+    let endorsement_id = block_2.content.header.content.endorsements[0].id;
+    let endorsements = controller.get_endorsements(&[endorsement_id]).unwrap();
+    assert_eq!(
+        endorsements[0].as_ref().map(|(_, id)| *id),
+        Some(block_2.id)
+    );
+
+    let sender_address = Address::from_public_key(&sender.get_public_key());
+    assert_eq!(
+        controller
+            .get_address_history(
+                &sender_address,
+                ArchivedItemKind::Operation,
+                Slot::new(0, 0),
+                Slot::new(10, 0),
+                10
+            )
+            .unwrap(),
+        vec![(Slot::new(1, 0), ArchivedItemId::Operation(operation_id))]
+    );
+    let creator_address = Address::from_public_key(&creator.get_public_key());
+    assert_eq!(
+        controller
+            .get_address_history(
+                &creator_address,
+                ArchivedItemKind::Block,
+                Slot::new(0, 0),
+                Slot::new(10, 0),
+                1
+            )
+            .unwrap(),
+        vec![(Slot::new(1, 0), ArchivedItemId::Block(block_1.id))]
+    );
+}
+
+#[test]
+fn test_archive_retention() {
+    let path = TempDir::new().unwrap();
+    let (mut manager, controller) =
+        start_block_archive_worker(archive_config(&path, BlockArchiveRetention::Cycles(1)))
+            .unwrap();
+
+    let creator = KeyPair::generate(0).unwrap();
+    let sender = KeyPair::generate(0).unwrap();
+    let operation = create_operation(&sender);
+    let operation_id = operation.id;
+    // cycle 0
+    let (old_block, old_storage) = create_final_block(&creator, Slot::new(1, 0), vec![operation]);
+    controller.archive_final_blocks(vec![(old_block.id, old_storage)]);
+    // cycle 2
+    let (new_block, new_storage) = create_final_block(&creator, Slot::new(5, 0), vec![]);
+    controller.archive_final_blocks(vec![(new_block.id, new_storage)]);
+    manager.stop();
+
+    let blocks = controller
+        .get_blocks(&[old_block.id, new_block.id])
+        .unwrap();
+    assert!(blocks[0].is_none());
+    assert!(blocks[1].is_some());
+    assert!(controller.get_operations(&[operation_id]).unwrap()[0].is_none());
+    let sender_address = Address::from_public_key(&sender.get_public_key());
+    assert!(controller
+        .get_address_history(
+            &sender_address,
+            ArchivedItemKind::Operation,
+            Slot::new(0, 0),
+            Slot::new(10, 0),
+            10
+        )
+        .unwrap()
+        .is_empty());
+}
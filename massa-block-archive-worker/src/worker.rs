@@ -0,0 +1,107 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::archive::BlockArchiveDB;
+use crate::controller::{BlockArchiveControllerImpl, BlockArchiveManagerImpl};
+use crate::Command;
+use massa_block_archive_exports::{
+    BlockArchiveConfig, BlockArchiveController, BlockArchiveManager, BlockArchiveResult,
+};
+use massa_models::slot::Slot;
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+use tracing::{debug, error};
+
+/// Structure gathering all elements needed by the block archive thread
+pub(crate) struct BlockArchiveThread {
+    /// Incoming commands
+    input_mpsc: Receiver<Command>,
+    /// Archive database
+    archive: Arc<BlockArchiveDB>,
+    /// First slot kept by the retention policy at the last pruning
+    retention_start: Option<Slot>,
+}
+
+impl BlockArchiveThread {
+    /// Spawns the block archive worker thread
+    pub(crate) fn spawn(
+        input_mpsc: Receiver<Command>,
+        archive: Arc<BlockArchiveDB>,
+    ) -> JoinHandle<BlockArchiveResult<()>> {
+        let thread_builder = thread::Builder::new().name("block-archive".into());
+        thread_builder
+            .spawn(|| {
+                let mut this = Self {
+                    input_mpsc,
+                    archive,
+                    retention_start: None,
+                };
+                this.run()
+            })
+            .expect("failed to spawn thread : block-archive")
+    }
+
+    /// Drops the blocks that the retention policy no longer keeps
+    fn prune(&mut self) -> BlockArchiveResult<()> {
+        let Some(last_slot) = self.archive.get_last_slot()? else {
+            return Ok(());
+        };
+        let retention_start = self.archive.get_retention_start(last_slot);
+        if retention_start > self.retention_start {
+            if let Some(slot) = retention_start {
+                let count = self.archive.prune_before(slot)?;
+                debug!(
+                    "pruned {} blocks before slot {} from the archive",
+                    count, slot
+                );
+            }
+            self.retention_start = retention_start;
+        }
+        Ok(())
+    }
+
+    /// Thread loop.
+    ///
+    /// Archives the final blocks it receives until a `Stop` command is received.
+    fn run(mut self) -> BlockArchiveResult<()> {
+        self.prune()?;
+        while let Ok(Command::Archive(blocks)) = self.input_mpsc.recv() {
+            if let Err(err) = self.archive.archive_blocks(&blocks) {
+                error!("could not archive final blocks: {}", err);
+                continue;
+            }
+            self.prune()?;
+        }
+        Ok(())
+    }
+}
+
+/// Launches a block archive worker thread and returns a pair to interact with it.
+///
+/// # Returns
+/// A pair `(block_archive_manager, block_archive_controller)` where:
+/// * `block_archive_manager`: allows to stop the worker
+/// * `block_archive_controller`: allows to archive final blocks and to read the archive
+pub fn start_block_archive_worker(
+    config: BlockArchiveConfig,
+) -> BlockArchiveResult<(
+    Box<dyn BlockArchiveManager>,
+    Box<dyn BlockArchiveController>,
+)> {
+    let (input_sender, input_receiver) = sync_channel(config.channel_size);
+    let archive = Arc::new(BlockArchiveDB::open(config)?);
+    let controller = BlockArchiveControllerImpl {
+        archive: archive.clone(),
+        input_mpsc: input_sender.clone(),
+    };
+
+    // launch the block archive thread
+    let thread_handle = BlockArchiveThread::spawn(input_receiver, archive);
+
+    let manager = BlockArchiveManagerImpl {
+        thread_handle: Some(thread_handle),
+        input_mpsc: input_sender,
+    };
+    Ok((Box::new(manager), Box::new(controller)))
+}
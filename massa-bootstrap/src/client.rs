@@ -1,6 +1,7 @@
 use humantime::format_duration;
-use massa_db_exports::DBBatch;
+use massa_db_exports::{DBBatch, LEDGER_PREFIX};
 use massa_final_state::{FinalState, FinalStateError};
+use massa_hash::Hash;
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::{node::NodeId, slot::Slot, streaming_step::StreamingStep, version::Version};
@@ -14,7 +15,7 @@ use rand::{
 };
 use std::collections::BTreeMap;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     io,
     net::{SocketAddr, TcpStream},
     sync::{Arc, Condvar, Mutex},
@@ -30,6 +31,110 @@ use crate::{
     BootstrapConfig, GlobalBootstrapState,
 };
 
+/// Interval at which a fully streamed key range of the final state is refreshed
+/// while the other ranges are still being streamed from other servers
+const STATE_RANGE_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Key range `[start, end)` of the final state, streamed from a single server in a parallel bootstrap
+#[derive(Debug, Clone)]
+struct StateKeyRange {
+    /// first key of the range
+    start: Vec<u8>,
+    /// key following the range, `None` if the range goes to the end of the state
+    end: Option<Vec<u8>>,
+}
+
+/// Progress of the key ranges of the final state streamed in parallel
+#[derive(Debug)]
+struct RangesStatus {
+    /// number of ranges not fully streamed yet
+    remaining: usize,
+    /// whether the streaming of a range failed
+    failed: bool,
+}
+
+/// Shared progress of the key ranges of the final state streamed in parallel
+struct RangesProgress {
+    status: Mutex<RangesStatus>,
+    condvar: Condvar,
+}
+
+impl RangesProgress {
+    fn new(range_count: usize) -> Self {
+        RangesProgress {
+            status: Mutex::new(RangesStatus {
+                remaining: range_count,
+                failed: false,
+            }),
+            condvar: Condvar::new(),
+        }
+    }
+
+    /// Mark a range as fully streamed
+    fn range_finished(&self) {
+        let mut status = self.status.lock().expect("ranges progress mutex poisoned");
+        status.remaining = status.remaining.saturating_sub(1);
+        self.condvar.notify_all();
+    }
+
+    /// Mark the streaming of a range as failed, stopping the others
+    fn fail(&self) {
+        self.status
+            .lock()
+            .expect("ranges progress mutex poisoned")
+            .failed = true;
+        self.condvar.notify_all();
+    }
+
+    fn is_failed(&self) -> bool {
+        self.status
+            .lock()
+            .expect("ranges progress mutex poisoned")
+            .failed
+    }
+
+    /// Wait at most `timeout` for all the ranges to be fully streamed.
+    /// Returns whether they are, or an error if the streaming of a range failed.
+    fn wait_all_finished(&self, timeout: Duration) -> Result<bool, BootstrapError> {
+        let status = self.status.lock().expect("ranges progress mutex poisoned");
+        let (status, _) = self
+            .condvar
+            .wait_timeout_while(status, timeout, |status| {
+                status.remaining > 0 && !status.failed
+            })
+            .expect("ranges progress mutex poisoned");
+        if status.failed {
+            return Err(BootstrapError::Interupted(
+                "the streaming of another state range failed".to_string(),
+            ));
+        }
+        Ok(status.remaining == 0)
+    }
+}
+
+/// Split the key space of the final state in `count` ranges.
+///
+/// Most of the state is made of the ledger entries of user addresses, whose keys are `LEDGER_PREFIX`
+/// followed by the key version, the address category and the address version (all 0, serialized as one-byte varints)
+/// and the address hash: the bounds split them evenly on the first byte of the hash.
+/// The first range starts at the beginning of the state and the last one goes to its end.
+fn split_state_key_space(count: usize) -> Vec<StateKeyRange> {
+    let count = count.clamp(1, 256);
+    let mut ranges = Vec::with_capacity(count);
+    let mut start = Vec::new();
+    for i in 1..count {
+        let mut bound = LEDGER_PREFIX.as_bytes().to_vec();
+        bound.extend([0u8, 0, 0, (i * 256 / count) as u8]);
+        ranges.push(StateKeyRange {
+            start,
+            end: Some(bound.clone()),
+        });
+        start = bound;
+    }
+    ranges.push(StateKeyRange { start, end: None });
+    ranges
+}
+
 /// Specifies a common interface that can be used by standard, or mockers
 #[cfg_attr(test, mockall::automock)]
 pub trait BSConnector {
@@ -156,6 +261,12 @@ fn stream_final_state_and_consensus(
                     // Update MIP store by reading from the disk
                     let mut guard = global_bootstrap_state.final_state.write();
                     let db = guard.db.clone();
+                    db.write().clear_bootstrap_progress().map_err(|e| {
+                        BootstrapError::GeneralError(format!(
+                            "Cannot clear the bootstrap progress: {}",
+                            e
+                        ))
+                    })?;
                     let (updated, added) = guard
                         .mip_store
                         .extend_from_db(db)
@@ -197,6 +308,248 @@ fn stream_final_state_and_consensus(
     }
 }
 
+/// Stream a key range of the final state from a bootstrap server.
+/// Once the range is fully streamed, it is kept up to date with the server until all the ranges are, or until one of them fails.
+/// Returns the slot the range is attached to.
+fn stream_state_range(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    range: &StateKeyRange,
+    final_state: &Arc<RwLock<FinalState>>,
+    progress: &RangesProgress,
+    interupted: &Arc<(Mutex<bool>, Condvar)>,
+) -> Result<Slot, BootstrapError> {
+    let mut last_slot = None;
+    let mut last_state_step = StreamingStep::Started;
+    let mut fully_streamed = false;
+    loop {
+        client.send_timeout(
+            &BootstrapClientMessage::AskStateRangePart {
+                range_start: range.start.clone(),
+                range_end: range.end.clone(),
+                last_slot,
+                last_state_step: last_state_step.clone(),
+            },
+            Some(cfg.write_timeout.to_duration()),
+        )?;
+        let slot = loop {
+            if *interupted.0.lock().expect("double-lock on interupt-mutex") {
+                return Err(BootstrapError::Interupted(
+                    "Sig INT received while getting state".to_string(),
+                ));
+            }
+            if progress.is_failed() {
+                return Err(BootstrapError::Interupted(
+                    "the streaming of another state range failed".to_string(),
+                ));
+            }
+            match client.next_timeout(Some(cfg.read_timeout.to_duration()))? {
+                BootstrapServerMessage::StateRangePart { state_part, .. } => {
+                    final_state
+                        .read()
+                        .db
+                        .write()
+                        .write_range_batch_bootstrap_client(state_part)
+                        .map_err(|e| {
+                            BootstrapError::GeneralError(format!(
+                                "Cannot write received stream batch to disk: {}",
+                                e
+                            ))
+                        })?;
+                }
+                BootstrapServerMessage::StateRangeFinished { slot } => break slot,
+                BootstrapServerMessage::BootstrapError { error } => {
+                    return Err(BootstrapError::ReceivedError(error))
+                }
+                other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+            }
+        };
+        last_slot = Some(slot);
+        last_state_step = StreamingStep::Finished(None);
+        if !fully_streamed {
+            fully_streamed = true;
+            progress.range_finished();
+        }
+        if progress.wait_all_finished(STATE_RANGE_REFRESH_INTERVAL)? {
+            return Ok(slot);
+        }
+    }
+}
+
+/// Check the final state fingerprints reported by the bootstrap servers against the one of the bootstrapped state.
+/// The servers not reporting the most common fingerprint (ties going to the bootstrapped one) are added to `blacklist`.
+fn check_state_fingerprints(
+    bootstrapped: Hash,
+    reported: &[(SocketAddr, NodeId, Hash)],
+    blacklist: &mut HashSet<NodeId>,
+) -> Result<(), BootstrapError> {
+    let mut counts: HashMap<Hash, usize> = HashMap::new();
+    for (_, _, fingerprint) in reported {
+        *counts.entry(*fingerprint).or_default() += 1;
+    }
+    let Some(reference) = counts
+        .into_iter()
+        .max_by_key(|(fingerprint, count)| (*count, *fingerprint == bootstrapped))
+        .map(|(fingerprint, _)| fingerprint)
+    else {
+        return Err(BootstrapError::StateFingerprintMismatch(
+            "no bootstrap server reported its fingerprint".to_string(),
+        ));
+    };
+
+    let disagreeing: Vec<String> = reported
+        .iter()
+        .filter(|(_, _, fingerprint)| *fingerprint != reference)
+        .map(|(addr, node_id, _)| {
+            blacklist.insert(*node_id);
+            format!("{} ({})", addr, node_id)
+        })
+        .collect();
+    if !disagreeing.is_empty() {
+        return Err(BootstrapError::StateFingerprintMismatch(format!(
+            "blacklisting the servers disagreeing with the fingerprint {} of the majority: {}",
+            reference,
+            disagreeing.join(", ")
+        )));
+    }
+    if reference != bootstrapped {
+        return Err(BootstrapError::StateFingerprintMismatch(format!(
+            "the bootstrapped state has the fingerprint {} instead of {}",
+            bootstrapped, reference
+        )));
+    }
+    Ok(())
+}
+
+/// Gets the state from several bootstrap servers at once, the handshake being done with each of them:
+/// 1. each server streams a key range of the final state, in parallel
+/// 2. the server with the most recent range streams the state changes since the oldest range, then the versioning and the consensus graph
+/// 3. every server reports the fingerprint of its final state at the bootstrapped slot, see `check_state_fingerprints`
+/// 4. the peers are asked to the server of step 2
+fn bootstrap_from_servers(
+    cfg: &BootstrapConfig,
+    servers: &mut [(SocketAddr, NodeId, BootstrapClientBinder)],
+    global_bootstrap_state: &mut GlobalBootstrapState,
+    interupted: &Arc<(Mutex<bool>, Condvar)>,
+    blacklist: &mut HashSet<NodeId>,
+) -> Result<(), BootstrapError> {
+    massa_trace!("bootstrap.lib.bootstrap_from_servers", {});
+
+    let ranges = split_state_key_space(servers.len());
+    let progress = RangesProgress::new(ranges.len());
+    let final_state = global_bootstrap_state.final_state.clone();
+    let range_slots = std::thread::scope(|scope| {
+        let handles: Vec<_> = servers
+            .iter_mut()
+            .zip(ranges.iter())
+            .map(|((addr, _, client), range)| {
+                let (final_state, progress) = (&final_state, &progress);
+                scope.spawn(move || {
+                    let res =
+                        stream_state_range(cfg, client, range, final_state, progress, interupted);
+                    if let Err(e) = &res {
+                        warn!(
+                            "Error while streaming a state range from bootstrap server {}: {}",
+                            addr, e
+                        );
+                        progress.fail();
+                    }
+                    res
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .expect("state range streaming thread panicked")
+            })
+            .collect::<Result<Vec<Slot>, BootstrapError>>()
+    })?;
+
+    // the server with the most recent range catches the others up
+    let Some((primary, _)) = range_slots
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, slot)| **slot)
+    else {
+        return Err(BootstrapError::GeneralError(
+            "no bootstrap server to stream the state from".to_string(),
+        ));
+    };
+    info!(
+        "State ranges streamed, catching up from bootstrap server {}",
+        servers[primary].0
+    );
+    let mut next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+        last_slot: range_slots.iter().min().copied(),
+        last_state_step: StreamingStep::Finished(None),
+        last_versioning_step: StreamingStep::Started,
+        last_consensus_step: StreamingStep::Started,
+        send_last_start_period: true,
+    };
+    stream_final_state_and_consensus(
+        cfg,
+        &mut servers[primary].2,
+        &mut next_bootstrap_message,
+        global_bootstrap_state,
+    )?;
+
+    // cross-check the bootstrapped state with every server
+    let (slot, fingerprint) = {
+        let final_state = final_state.read();
+        (final_state.get_slot(), final_state.get_fingerprint())
+    };
+    let mut reported = Vec::with_capacity(servers.len());
+    for (addr, node_id, client) in servers.iter_mut() {
+        match send_client_message(
+            &BootstrapClientMessage::AskStateFingerprint { slot },
+            client,
+            cfg.write_timeout.into(),
+            cfg.read_timeout.into(),
+            "ask state fingerprint timed out",
+        )? {
+            BootstrapServerMessage::StateFingerprint {
+                slot: fingerprint_slot,
+                fingerprint: Some(server_fingerprint),
+            } if fingerprint_slot == slot => reported.push((*addr, *node_id, server_fingerprint)),
+            BootstrapServerMessage::StateFingerprint { .. } => warn!(
+                "Bootstrap server {} could not provide its state fingerprint at slot {}",
+                addr, slot
+            ),
+            BootstrapServerMessage::BootstrapError { error } => {
+                return Err(BootstrapError::ReceivedError(error))
+            }
+            other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+        }
+    }
+    check_state_fingerprints(fingerprint, &reported, blacklist)?;
+    info!(
+        "State fingerprint {} at slot {} confirmed by {} bootstrap servers",
+        fingerprint,
+        slot,
+        reported.len()
+    );
+
+    exchange_with_server(
+        cfg,
+        &mut servers[primary].2,
+        &mut next_bootstrap_message,
+        global_bootstrap_state,
+    )?;
+    for (index, (_, _, client)) in servers.iter_mut().enumerate() {
+        if index != primary {
+            // the state is already bootstrapped, a failure here does not matter
+            let _ = client.send_timeout(
+                &BootstrapClientMessage::BootstrapSuccess,
+                Some(cfg.write_timeout.into()),
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Gets the state from a bootstrap server (internal private function)
 /// needs to be CANCELLABLE
 fn bootstrap_from_server(
@@ -207,7 +560,16 @@ fn bootstrap_from_server(
     our_version: Version,
 ) -> Result<(), BootstrapError> {
     massa_trace!("bootstrap.lib.bootstrap_from_server", {});
+    handshake_with_server(cfg, client, our_version)?;
+    exchange_with_server(cfg, client, next_bootstrap_message, global_bootstrap_state)
+}
 
+/// Reads the error sent by the server at connection, if any, then does the handshake and checks the ping and the clocks
+fn handshake_with_server(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    our_version: Version,
+) -> Result<(), BootstrapError> {
     // read error (if sent by the server)
     // client.next() is not cancel-safe but we drop the whole client object if cancelled => it's OK
     match client.next_timeout(Some(cfg.read_error_timeout.to_duration())) {
@@ -285,7 +647,16 @@ fn bootstrap_from_server(
         );
         return Err(BootstrapError::ClockError(message));
     }
+    Ok(())
+}
 
+/// Asks the server the data following `next_bootstrap_message`, until the bootstrap succeeds
+fn exchange_with_server(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    next_bootstrap_message: &mut BootstrapClientMessage,
+    global_bootstrap_state: &mut GlobalBootstrapState,
+) -> Result<(), BootstrapError> {
     let write_timeout: std::time::Duration = cfg.write_timeout.into();
     // Loop to ask data to the server depending on the last message we sent
     loop {
@@ -322,6 +693,10 @@ fn bootstrap_from_server(
             BootstrapClientMessage::BootstrapError { error: _ } => {
                 panic!("The next message to send shouldn't be BootstrapError");
             }
+            BootstrapClientMessage::AskStateRangePart { .. }
+            | BootstrapClientMessage::AskStateFingerprint { .. } => {
+                panic!("The next message to send shouldn't be a parallel bootstrap request");
+            }
         };
     }
     info!("Successful bootstrap");
@@ -473,17 +848,46 @@ pub fn get_state(
 
     // If the two conditions above are not verified, we need to bootstrap
     // we filter the bootstrap list to keep only the ip addresses we are compatible with
-    let filtered_bootstrap_list = get_bootstrap_list_iter(bootstrap_config)?;
-
-    let mut next_bootstrap_message: BootstrapClientMessage =
-        BootstrapClientMessage::AskBootstrapPart {
-            last_slot: None,
-            last_state_step: StreamingStep::Started,
-            last_versioning_step: StreamingStep::Started,
-            last_consensus_step: StreamingStep::Started,
-            send_last_start_period: true,
-        };
+    let mut filtered_bootstrap_list = get_bootstrap_list_iter(bootstrap_config)?;
+
+    // resume the bootstrap interrupted by a restart, if any
+    let bootstrap_progress = final_state.read().db.read().get_bootstrap_progress();
+    let mut next_bootstrap_message: BootstrapClientMessage = match bootstrap_progress {
+        Ok(Some(progress)) => {
+            info!(
+                "Resuming the bootstrap interrupted at slot {}",
+                progress.last_change_id
+            );
+            BootstrapClientMessage::AskBootstrapPart {
+                last_slot: Some(progress.last_change_id),
+                last_state_step: progress.last_state_step,
+                last_versioning_step: progress.last_versioning_step,
+                // the consensus graph is kept in memory only
+                last_consensus_step: StreamingStep::Started,
+                send_last_start_period: true,
+            }
+        }
+        progress => {
+            if let Err(err) = progress {
+                warn!(
+                    "Could not read the progress of the interrupted bootstrap, restarting it from scratch: {}",
+                    err
+                );
+                // drop the partial state along with the corrupted progress
+                final_state.write().reset();
+            }
+            BootstrapClientMessage::AskBootstrapPart {
+                last_slot: None,
+                last_state_step: StreamingStep::Started,
+                last_versioning_step: StreamingStep::Started,
+                last_consensus_step: StreamingStep::Started,
+                send_last_start_period: true,
+            }
+        }
+    };
     let mut global_bootstrap_state = GlobalBootstrapState::new(final_state);
+    // servers whose state fingerprint disagreed with the others during a parallel bootstrap
+    let mut blacklist: HashSet<NodeId> = HashSet::new();
 
     let limit = bootstrap_config.rate_limit;
     loop {
@@ -493,7 +897,88 @@ pub fn get_state(
                 "Sig INT received while getting state".to_string(),
             ));
         }
+        if filtered_bootstrap_list
+            .iter()
+            .all(|(_, node_id)| blacklist.contains(node_id))
+        {
+            return Err(BootstrapError::StateFingerprintMismatch(
+                "every bootstrap server was blacklisted".to_string(),
+            ));
+        }
+
+        // a parallel bootstrap starts from scratch: it is not used once some data has been received
+        let nothing_received = matches!(
+            next_bootstrap_message,
+            BootstrapClientMessage::AskBootstrapPart {
+                last_slot: None,
+                ..
+            }
+        );
+        if bootstrap_config.max_parallel_bootstrap_servers > 1 && nothing_received {
+            let mut servers = Vec::new();
+            for (addr, node_id) in filtered_bootstrap_list
+                .iter()
+                .filter(|(_, node_id)| !blacklist.contains(node_id))
+                .take(bootstrap_config.max_parallel_bootstrap_servers)
+            {
+                info!("Start bootstrapping from {} in parallel", addr);
+                match connect_to_server(
+                    &mut connector,
+                    bootstrap_config,
+                    addr,
+                    &node_id.get_public_key(),
+                    Some(limit),
+                ) {
+                    Ok(mut client) => {
+                        massa_metrics.inc_bootstrap_counter();
+                        match handshake_with_server(bootstrap_config, &mut client, version) {
+                            Ok(()) => servers.push((*addr, *node_id, client)),
+                            Err(e) => {
+                                warn!("Error while starting to bootstrap from {}: {}", addr, e)
+                            }
+                        }
+                    }
+                    Err(e) => warn!("Error while connecting to bootstrap server: {}", e),
+                }
+            }
+            if servers.len() > 1 {
+                // the ranges are streamed into an empty state
+                reset_bootstrap_state(&mut global_bootstrap_state, &mut next_bootstrap_message);
+                match bootstrap_from_servers(
+                    bootstrap_config,
+                    &mut servers,
+                    &mut global_bootstrap_state,
+                    &interupted,
+                    &mut blacklist,
+                ) {
+                    Ok(()) => return Ok(global_bootstrap_state),
+                    Err(e) => {
+                        warn!("Error while bootstrapping from several servers: {}", &e);
+                        for (_, _, client) in servers.iter_mut() {
+                            let _ = client.send_timeout(
+                                &BootstrapClientMessage::BootstrapError {
+                                    error: e.to_string(),
+                                },
+                                Some(bootstrap_config.write_error_timeout.into()),
+                            );
+                        }
+                        reset_bootstrap_state(
+                            &mut global_bootstrap_state,
+                            &mut next_bootstrap_message,
+                        );
+                    }
+                }
+                // use other servers, or the same ones for other ranges, at the next attempt
+                filtered_bootstrap_list.rotate_left(1);
+                info!("Parallel bootstrap failed. Your node will try to bootstrap from a single server in {}.", format_duration(bootstrap_config.retry_delay.to_duration()).to_string());
+                wait_retry_delay(bootstrap_config, &interupted)?;
+            }
+        }
+
         for (addr, node_id) in filtered_bootstrap_list.iter() {
+            if blacklist.contains(node_id) {
+                continue;
+            }
             if let Some(end) = end_timestamp {
                 if MassaTime::now().expect("could not get now time") > end {
                     panic!("This episode has come to an end, please get the latest testnet node version to continue");
@@ -541,41 +1026,66 @@ pub fn get_state(
             };
 
             info!("Bootstrap from server {} failed. Your node will try to bootstrap from another server in {}.", addr, format_duration(bootstrap_config.retry_delay.to_duration()).to_string());
-
-            // Before, we would use a simple sleep(...), and that was fine
-            // in a cancellable async context: the runtime could
-            // catch the interupt signal, and just cancel this thread:
-            //
-            // let state = tokio::select!{
-            //    /* detect interupt */ => /* return, cancelling the async get_state */
-            //    get_state(...) => well, we got the state, and it didn't have to worry about interupts
-            // };
-            //
-            // Without an external system to preempt this context, we use a condvar to manage the sleep.
-            //
-            // Condvar::wait is basically std::thread::sleep(/* until some magic happens */)
-            // Condvar::wait_timeout(..., duration) is much the same, but for a max-len of `duration`
-            //
-            // The _magic_ happens when, somewhere else, a clone of the Arc<(Mutex<bool>, Condvar)>\
-            // calls Condvar::notify_[one | all], which prompts this thread to wake up. Assuming that
-            // the mutex-wrapped variable has been set appropriately before the notify, this thread
-            let int_sig = interupted
-                .0
-                .lock()
-                .expect("double-lock() on interupted signal mutex");
-            let wake = interupted
-                .1
-                .wait_timeout(int_sig, bootstrap_config.retry_delay.to_duration())
-                .expect("interupt signal mutex poisoned");
-            if *wake.0 {
-                return Err(BootstrapError::Interupted(
-                    "Sig INT during bootstrap retry-wait".to_string(),
-                ));
-            }
+            wait_retry_delay(bootstrap_config, &interupted)?;
         }
     }
 }
 
+/// Resets the bootstrapped state, the next bootstrap starting from scratch
+fn reset_bootstrap_state(
+    global_bootstrap_state: &mut GlobalBootstrapState,
+    next_bootstrap_message: &mut BootstrapClientMessage,
+) {
+    global_bootstrap_state.final_state.write().reset();
+    global_bootstrap_state.graph = None;
+    global_bootstrap_state.peers = None;
+    *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+        last_slot: None,
+        last_state_step: StreamingStep::Started,
+        last_versioning_step: StreamingStep::Started,
+        last_consensus_step: StreamingStep::Started,
+        send_last_start_period: true,
+    };
+}
+
+/// Waits for the retry delay between two bootstrap attempts, unless interupted
+fn wait_retry_delay(
+    bootstrap_config: &BootstrapConfig,
+    interupted: &Arc<(Mutex<bool>, Condvar)>,
+) -> Result<(), BootstrapError> {
+    // Before, we would use a simple sleep(...), and that was fine
+    // in a cancellable async context: the runtime could
+    // catch the interupt signal, and just cancel this thread:
+    //
+    // let state = tokio::select!{
+    //    /* detect interupt */ => /* return, cancelling the async get_state */
+    //    get_state(...) => well, we got the state, and it didn't have to worry about interupts
+    // };
+    //
+    // Without an external system to preempt this context, we use a condvar to manage the sleep.
+    //
+    // Condvar::wait is basically std::thread::sleep(/* until some magic happens */)
+    // Condvar::wait_timeout(..., duration) is much the same, but for a max-len of `duration`
+    //
+    // The _magic_ happens when, somewhere else, a clone of the Arc<(Mutex<bool>, Condvar)>\
+    // calls Condvar::notify_[one | all], which prompts this thread to wake up. Assuming that
+    // the mutex-wrapped variable has been set appropriately before the notify, this thread
+    let int_sig = interupted
+        .0
+        .lock()
+        .expect("double-lock() on interupted signal mutex");
+    let wake = interupted
+        .1
+        .wait_timeout(int_sig, bootstrap_config.retry_delay.to_duration())
+        .expect("interupt signal mutex poisoned");
+    if *wake.0 {
+        return Err(BootstrapError::Interupted(
+            "Sig INT during bootstrap retry-wait".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 fn get_bootstrap_list_iter(
     bootstrap_config: &BootstrapConfig,
 ) -> Result<Vec<(SocketAddr, NodeId)>, BootstrapError> {
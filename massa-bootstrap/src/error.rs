@@ -67,6 +67,8 @@ pub enum BootstrapError {
     WhiteListed(String),
     /// The bootstrap process ended prematurely - e.g. too much time elapsed
     Interupted(String),
+    /// the final state fingerprints of the bootstrap servers do not match: {0}
+    StateFingerprintMismatch(String),
 }
 
 /// # Platform-specific behavior
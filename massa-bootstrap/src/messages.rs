@@ -5,6 +5,7 @@ use massa_consensus_exports::bootstrapable_graph::{
     BootstrapableGraph, BootstrapableGraphDeserializer, BootstrapableGraphSerializer,
 };
 use massa_db_exports::StreamBatch;
+use massa_hash::{Hash, HashDeserializer, HashSerializer};
 use massa_models::block_id::{BlockId, BlockIdDeserializer, BlockIdSerializer};
 use massa_models::prehash::PreHashSet;
 use massa_models::serialization::{
@@ -78,6 +79,25 @@ pub enum BootstrapServerMessage {
         /// Error message
         error: String,
     },
+    /// Part of a key range of the final state, when the state is streamed from several servers
+    StateRangePart {
+        /// Slot the state changes are attached to
+        slot: Slot,
+        /// Part of the key range of the state in a serialized way
+        state_part: StreamBatch<Slot>,
+    },
+    /// Message sent when the key range of the final state is fully streamed
+    StateRangeFinished {
+        /// Slot the streamed key range is attached to
+        slot: Slot,
+    },
+    /// Fingerprint of the final state at a slot, to cross-check the state streamed from several servers
+    StateFingerprint {
+        /// Slot of the fingerprint
+        slot: Slot,
+        /// Fingerprint of the final state at the end of the slot, `None` if the slot is not in the history of the server
+        fingerprint: Option<Hash>,
+    },
 }
 
 impl ToString for BootstrapServerMessage {
@@ -91,6 +111,9 @@ impl ToString for BootstrapServerMessage {
             BootstrapServerMessage::BootstrapError { error } => {
                 format!("BootstrapError {{ error: {} }}", error)
             }
+            BootstrapServerMessage::StateRangePart { .. } => "StateRangePart".to_string(),
+            BootstrapServerMessage::StateRangeFinished { .. } => "StateRangeFinished".to_string(),
+            BootstrapServerMessage::StateFingerprint { .. } => "StateFingerprint".to_string(),
         }
     }
 }
@@ -104,6 +127,9 @@ enum MessageServerTypeId {
     FinalStateFinished = 3u32,
     SlotTooOld = 4u32,
     BootstrapError = 5u32,
+    StateRangePart = 6u32,
+    StateRangeFinished = 7u32,
+    StateFingerprint = 8u32,
 }
 
 /// Serializer for `BootstrapServerMessage`
@@ -121,6 +147,7 @@ pub struct BootstrapServerMessageSerializer {
     opt_last_start_period_serializer: OptionSerializer<u64, U64VarIntSerializer>,
     opt_last_slot_before_downtime_serializer:
         OptionSerializer<Option<Slot>, OptionSerializer<Slot, SlotSerializer>>,
+    opt_hash_serializer: OptionSerializer<Hash, HashSerializer>,
 }
 
 impl Default for BootstrapServerMessageSerializer {
@@ -147,6 +174,7 @@ impl BootstrapServerMessageSerializer {
             opt_last_slot_before_downtime_serializer: OptionSerializer::new(OptionSerializer::new(
                 SlotSerializer::new(),
             )),
+            opt_hash_serializer: OptionSerializer::new(HashSerializer::new()),
         }
     }
 }
@@ -268,6 +296,38 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
                 )?;
                 buffer.extend(error.as_bytes())
             }
+            BootstrapServerMessage::StateRangePart { slot, state_part } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::StateRangePart), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+                self.u64_serializer
+                    .serialize(&(state_part.new_elements.len() as u64), buffer)?;
+                for (key, value) in state_part.new_elements.iter() {
+                    self.vec_u8_serializer.serialize(key, buffer)?;
+                    self.vec_u8_serializer.serialize(value, buffer)?;
+                }
+                self.u64_serializer.serialize(
+                    &(state_part.updates_on_previous_elements.len() as u64),
+                    buffer,
+                )?;
+                for (key, value) in state_part.updates_on_previous_elements.iter() {
+                    self.vec_u8_serializer.serialize(key, buffer)?;
+                    self.opt_vec_u8_serializer.serialize(value, buffer)?;
+                }
+                self.slot_serializer
+                    .serialize(&state_part.change_id, buffer)?;
+            }
+            BootstrapServerMessage::StateRangeFinished { slot } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::StateRangeFinished), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+            }
+            BootstrapServerMessage::StateFingerprint { slot, fingerprint } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::StateFingerprint), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+                self.opt_hash_serializer.serialize(fingerprint, buffer)?;
+            }
         }
         Ok(())
     }
@@ -290,6 +350,7 @@ pub struct BootstrapServerMessageDeserializer {
     opt_last_start_period_deserializer: OptionDeserializer<u64, U64VarIntDeserializer>,
     opt_last_slot_before_downtime_deserializer:
         OptionDeserializer<Option<Slot>, OptionDeserializer<Slot, SlotDeserializer>>,
+    opt_hash_deserializer: OptionDeserializer<Hash, HashDeserializer>,
 }
 
 impl BootstrapServerMessageDeserializer {
@@ -349,6 +410,7 @@ impl BootstrapServerMessageDeserializer {
                     (Included(0), Excluded(args.thread_count)),
                 )),
             ),
+            opt_hash_deserializer: OptionDeserializer::new(HashDeserializer::new()),
         }
     }
 }
@@ -575,6 +637,72 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                     error: String::from_utf8_lossy(error).into_owned(),
                 })
                 .parse(input),
+                MessageServerTypeId::StateRangePart => tuple((
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    }),
+                    context(
+                        "Failed new_elements deserialization",
+                        length_count(
+                            context("Failed length deserialization", |input| {
+                                self.state_new_elements_length_deserializer
+                                    .deserialize(input)
+                            }),
+                            tuple((
+                                |input| self.vec_u8_deserializer.deserialize(input),
+                                |input| self.vec_u8_deserializer.deserialize(input),
+                            )),
+                        ),
+                    ),
+                    context(
+                        "Failed updates deserialization",
+                        length_count(
+                            context("Failed length deserialization", |input| {
+                                self.state_updates_length_deserializer.deserialize(input)
+                            }),
+                            tuple((
+                                |input| self.vec_u8_deserializer.deserialize(input),
+                                |input| self.opt_vec_u8_deserializer.deserialize(input),
+                            )),
+                        ),
+                    ),
+                    context("Failed change_id deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(|(slot, new_elements, updates, change_id)| {
+                    BootstrapServerMessage::StateRangePart {
+                        slot,
+                        state_part: StreamBatch::<Slot> {
+                            new_elements: new_elements.into_iter().collect(),
+                            updates_on_previous_elements: updates.into_iter().collect(),
+                            change_id,
+                        },
+                    }
+                })
+                .parse(input),
+                MessageServerTypeId::StateRangeFinished => {
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    })
+                    .map(|slot| BootstrapServerMessage::StateRangeFinished { slot })
+                    .parse(input)
+                }
+                MessageServerTypeId::StateFingerprint => tuple((
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed fingerprint deserialization", |input| {
+                        self.opt_hash_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(
+                    |(slot, fingerprint)| BootstrapServerMessage::StateFingerprint {
+                        slot,
+                        fingerprint,
+                    },
+                )
+                .parse(input),
             }
         })
         .parse(buffer)
@@ -607,6 +735,22 @@ pub enum BootstrapClientMessage {
     },
     /// Bootstrap succeed
     BootstrapSuccess,
+    /// Ask for a part of a key range of the final state, when the state is streamed from several servers
+    AskStateRangePart {
+        /// First key of the range
+        range_start: Vec<u8>,
+        /// Key following the range, `None` if the range goes to the end of the state
+        range_end: Option<Vec<u8>>,
+        /// Slot we are attached to for changes
+        last_slot: Option<Slot>,
+        /// Last received state key of the range
+        last_state_step: StreamingStep<Vec<u8>>,
+    },
+    /// Ask for the fingerprint of the final state at a slot
+    AskStateFingerprint {
+        /// Slot of the fingerprint
+        slot: Slot,
+    },
 }
 
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
@@ -616,6 +760,8 @@ enum MessageClientTypeId {
     AskFinalStatePart = 1u32,
     BootstrapError = 2u32,
     BootstrapSuccess = 3u32,
+    AskStateRangePart = 4u32,
+    AskStateFingerprint = 5u32,
 }
 
 /// Serializer for `BootstrapClientMessage`
@@ -628,6 +774,9 @@ pub struct BootstrapClientMessageSerializer {
         PreHashSetSerializer<BlockId, BlockIdSerializer>,
     >,
     bool_serializer: BoolSerializer,
+    vec_u8_serializer: VecU8Serializer,
+    opt_vec_u8_serializer: OptionSerializer<Vec<u8>, VecU8Serializer>,
+    opt_slot_serializer: OptionSerializer<Slot, SlotSerializer>,
 }
 
 impl BootstrapClientMessageSerializer {
@@ -641,6 +790,9 @@ impl BootstrapClientMessageSerializer {
                 BlockIdSerializer::new(),
             )),
             bool_serializer: BoolSerializer::new(),
+            vec_u8_serializer: VecU8Serializer::new(),
+            opt_vec_u8_serializer: OptionSerializer::new(VecU8Serializer::new()),
+            opt_slot_serializer: OptionSerializer::new(SlotSerializer::new()),
         }
     }
 }
@@ -711,6 +863,25 @@ impl Serializer<BootstrapClientMessage> for BootstrapClientMessageSerializer {
                 self.u32_serializer
                     .serialize(&u32::from(MessageClientTypeId::BootstrapSuccess), buffer)?;
             }
+            BootstrapClientMessage::AskStateRangePart {
+                range_start,
+                range_end,
+                last_slot,
+                last_state_step,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskStateRangePart), buffer)?;
+                self.vec_u8_serializer.serialize(range_start, buffer)?;
+                self.opt_vec_u8_serializer.serialize(range_end, buffer)?;
+                self.opt_slot_serializer.serialize(last_slot, buffer)?;
+                self.state_step_serializer
+                    .serialize(last_state_step, buffer)?;
+            }
+            BootstrapClientMessage::AskStateFingerprint { slot } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskStateFingerprint), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+            }
         }
         Ok(())
     }
@@ -727,6 +898,9 @@ pub struct BootstrapClientMessageDeserializer {
         PreHashSetDeserializer<BlockId, BlockIdDeserializer>,
     >,
     bool_deserializer: BoolDeserializer,
+    key_deserializer: VecU8Deserializer,
+    opt_key_deserializer: OptionDeserializer<Vec<u8>, VecU8Deserializer>,
+    opt_slot_deserializer: OptionDeserializer<Slot, SlotDeserializer>,
 }
 
 impl BootstrapClientMessageDeserializer {
@@ -755,6 +929,18 @@ impl BootstrapClientMessageDeserializer {
                 ),
             ),
             bool_deserializer: BoolDeserializer::new(),
+            key_deserializer: VecU8Deserializer::new(
+                Included(0),
+                Included(max_datastore_value_length as u64),
+            ),
+            opt_key_deserializer: OptionDeserializer::new(VecU8Deserializer::new(
+                Included(0),
+                Included(max_datastore_value_length as u64),
+            )),
+            opt_slot_deserializer: OptionDeserializer::new(SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            )),
         }
     }
 }
@@ -864,6 +1050,36 @@ impl Deserializer<BootstrapClientMessage> for BootstrapClientMessageDeserializer
                 MessageClientTypeId::BootstrapSuccess => {
                     Ok((input, BootstrapClientMessage::BootstrapSuccess))
                 }
+                MessageClientTypeId::AskStateRangePart => tuple((
+                    context("Failed range_start deserialization", |input| {
+                        self.key_deserializer.deserialize(input)
+                    }),
+                    context("Failed range_end deserialization", |input| {
+                        self.opt_key_deserializer.deserialize(input)
+                    }),
+                    context("Failed last_slot deserialization", |input| {
+                        self.opt_slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed last_state_step deserialization", |input| {
+                        self.state_step_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(|(range_start, range_end, last_slot, last_state_step)| {
+                    BootstrapClientMessage::AskStateRangePart {
+                        range_start,
+                        range_end,
+                        last_slot,
+                        last_state_step,
+                    }
+                })
+                .parse(input),
+                MessageClientTypeId::AskStateFingerprint => {
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    })
+                    .map(|slot| BootstrapClientMessage::AskStateFingerprint { slot })
+                    .parse(input)
+                }
             }
         })
         .parse(buffer)
@@ -63,6 +63,9 @@ use crate::{
     white_black_list::SharedWhiteBlackList,
     BootstrapConfig,
 };
+/// Interval at which the final slot is checked while waiting for it to reach the slot of a requested state fingerprint
+const FINGERPRINT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Specifies a common interface that can be used by standard, or mockers
 #[cfg_attr(test, mockall::automock)]
 pub trait BSEventPoller {
@@ -689,6 +692,87 @@ pub fn stream_bootstrap_information(
     Ok(())
 }
 
+/// Stream the keys of the final state in `[range_start, range_end)`, along with their changes,
+/// to a client bootstrapping the other key ranges from other servers
+#[allow(clippy::too_many_arguments)]
+fn stream_state_range(
+    server: &mut BootstrapServerBinder,
+    final_state: Arc<RwLock<FinalState>>,
+    range_start: Vec<u8>,
+    range_end: Option<Vec<u8>>,
+    mut last_slot: Option<Slot>,
+    mut last_state_step: StreamingStep<Vec<u8>>,
+    bs_deadline: &Instant,
+    write_timeout: Duration,
+) -> Result<(), BootstrapError> {
+    loop {
+        let state_part;
+        let current_slot;
+        {
+            let final_state_read = final_state.read();
+            let db = final_state_read.db.read();
+            state_part = db
+                .get_range_batch_to_stream(
+                    &last_state_step,
+                    last_slot,
+                    &range_start,
+                    range_end.as_deref(),
+                )
+                .map_err(|e| {
+                    BootstrapError::GeneralError(format!("Error get_range_batch_to_stream: {}", e))
+                })?;
+            current_slot = db.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+        }
+        if let Some(slot) = last_slot {
+            if slot > current_slot {
+                return Err(BootstrapError::GeneralError(
+                    "Bootstrap cursor set to future slot".to_string(),
+                ));
+            }
+        }
+
+        // an empty part means that the client has the whole range at the current slot
+        if state_part.is_empty() {
+            return server.send_msg(
+                write_timeout,
+                BootstrapServerMessage::StateRangeFinished { slot: current_slot },
+            );
+        }
+        if let Some((new_last_key, _)) = state_part.new_elements.last_key_value() {
+            last_state_step = StreamingStep::Ongoing(new_last_key.clone());
+        }
+        last_slot = Some(current_slot);
+
+        let Some(write_timeout) = step_timeout_duration(bs_deadline, &write_timeout) else {
+            return Err(BootstrapError::Interupted(
+                "insufficient time left to provide next state range part".to_string(),
+            ));
+        };
+        server.send_msg(
+            write_timeout,
+            BootstrapServerMessage::StateRangePart {
+                slot: current_slot,
+                state_part,
+            },
+        )?;
+    }
+}
+
+/// Get the fingerprint of the final state at `slot`,
+/// waiting at most `timeout` for the slot to become final if it is not yet.
+/// Returns `None` if the slot is not in the change history.
+fn get_state_fingerprint(
+    final_state: &Arc<RwLock<FinalState>>,
+    slot: Slot,
+    timeout: Duration,
+) -> Option<massa_hash::Hash> {
+    let deadline = Instant::now() + timeout;
+    while final_state.read().get_slot() < slot && Instant::now() < deadline {
+        thread::sleep(FINGERPRINT_POLL_INTERVAL);
+    }
+    final_state.read().get_fingerprint_at(slot)
+}
+
 // derives the duration allowed for a step in the bootstrap process.
 // Returns None if the deadline for the entire bs-process has been reached
 fn step_timeout_duration(bs_deadline: &Instant, step_timeout: &Duration) -> Option<Duration> {
@@ -803,6 +887,40 @@ fn manage_bootstrap(
                         bootstrap_config.write_timeout.to_duration(),
                     )?;
                 }
+                BootstrapClientMessage::AskStateRangePart {
+                    range_start,
+                    range_end,
+                    last_slot,
+                    last_state_step,
+                } => {
+                    stream_state_range(
+                        server,
+                        final_state.clone(),
+                        range_start,
+                        range_end,
+                        last_slot,
+                        last_state_step,
+                        &deadline,
+                        bootstrap_config.write_timeout.to_duration(),
+                    )?;
+                }
+                BootstrapClientMessage::AskStateFingerprint { slot } => {
+                    let Some(write_timeout) = step_timeout_duration(
+                        &deadline,
+                        &bootstrap_config.write_timeout.to_duration(),
+                    ) else {
+                        return Err(BootstrapError::Interupted(
+                            "insufficient time left to respond to the request for the state fingerprint"
+                                .to_string(),
+                        ));
+                    };
+                    // the slot may not be final on this server yet: give it part of the time left
+                    let fingerprint = get_state_fingerprint(&final_state, slot, write_timeout / 2);
+                    server.send_msg(
+                        write_timeout / 2,
+                        BootstrapServerMessage::StateFingerprint { slot, fingerprint },
+                    )?;
+                }
                 BootstrapClientMessage::BootstrapSuccess => break Ok(()),
                 BootstrapClientMessage::BootstrapError { error } => {
                     break Err(BootstrapError::ReceivedError(error));
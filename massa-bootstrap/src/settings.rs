@@ -57,6 +57,8 @@ pub struct BootstrapConfig {
     pub keep_ledger: bool,
     /// Max simultaneous bootstraps
     pub max_simultaneous_bootstraps: u32,
+    /// Max number of servers the final state is streamed from in parallel, 1 to bootstrap from a single server
+    pub max_parallel_bootstrap_servers: usize,
     /// Minimum interval between two bootstrap attempts from a given IP
     pub per_ip_min_interval: MassaTime,
     /// Max size of the IP list
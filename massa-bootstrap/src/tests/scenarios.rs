@@ -105,6 +105,8 @@ fn mock_bootstrap_manager(
         max_history_length: 10,
         max_new_elements: 100,
         thread_count: 2,
+        enable_archive: false,
+        archive_retention_periods: 0,
     };
     let db = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -239,6 +241,8 @@ fn test_bootstrap_server() {
         max_history_length: 10,
         max_new_elements: 100,
         thread_count,
+        enable_archive: false,
+        archive_retention_periods: 0,
     };
     let db_server = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_server_config)) as Box<(dyn MassaDBController + 'static)>
@@ -249,6 +253,8 @@ fn test_bootstrap_server() {
         max_history_length: 10,
         max_new_elements: 100,
         thread_count,
+        enable_archive: false,
+        archive_retention_periods: 0,
     };
     let db_client = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_client_config)) as Box<(dyn MassaDBController + 'static)>
@@ -632,6 +638,8 @@ fn test_bootstrap_accept_err() {
         max_history_length: 10,
         max_new_elements: 100,
         thread_count,
+        enable_archive: false,
+        archive_retention_periods: 0,
     };
     let db_server = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_server_config)) as Box<(dyn MassaDBController + 'static)>
@@ -408,6 +408,7 @@ pub fn get_bootstrap_config(bootstrap_public_key: NodeId) -> BootstrapConfig {
         max_clock_delta: MassaTime::from_millis(1000),
         cache_duration: MassaTime::from_millis(10000),
         max_simultaneous_bootstraps: 2,
+        max_parallel_bootstrap_servers: 1,
         ip_list_max_size: 10,
         per_ip_min_interval: MassaTime::from_millis(10000),
         rate_limit: std::u64::MAX,
@@ -93,6 +93,19 @@ impl SharedWhiteBlackList<'_> {
         Ok(())
     }
 
+    /// Allow everyone to bootstrap by dropping the white list and deleting its file
+    pub fn allow_everyone(&self) -> Result<(), BootstrapError> {
+        let mut write_lock = self.inner.write();
+        if self.white_path.exists() {
+            std::fs::remove_file(&self.white_path).map_err(|e| {
+                warn!(error = ?e, "failed to delete whitelist file");
+                BootstrapError::IoError(e)
+            })?;
+        }
+        write_lock.white_list = None;
+        Ok(())
+    }
+
     /// write list to file
     fn write_to_file(
         &self,
@@ -15,8 +15,14 @@ pub const NONCE_SIZE: usize = 12;
 /// `PBKDF2` salt size.
 pub const SALT_SIZE: usize = 16;
 
+/// `PBKDF2` default number of rounds.
+pub const PBKDF2_ROUNDS: u32 = 600_000;
+
+/// AES-256-GCM key size, which is the `PBKDF2` output length.
+pub const KEY_SIZE: usize = 32;
+
 /// `PBKDF2` hash parameters.
 pub const HASH_PARAMS: Params = Params {
-    rounds: 600_000,
-    output_length: 32,
+    rounds: PBKDF2_ROUNDS,
+    output_length: KEY_SIZE,
 };
@@ -8,10 +8,10 @@ use aes_gcm::aead::Aead;
 use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use pbkdf2::{
     password_hash::{PasswordHasher, SaltString},
-    Pbkdf2,
+    Params, Pbkdf2,
 };
 
-use crate::constants::HASH_PARAMS;
+use crate::constants::{HASH_PARAMS, KEY_SIZE};
 use crate::encrypt::CipherData;
 use crate::error::CipherError;
 
@@ -19,13 +19,33 @@ use crate::error::CipherError;
 ///
 /// Read `lib.rs` module documentation for more information.
 pub fn decrypt(password: &str, data: CipherData) -> Result<Vec<u8>, CipherError> {
+    decrypt_with_rounds(password, data, HASH_PARAMS.rounds)
+}
+
+/// Decryption function using AES-GCM cipher, with a custom number of `PBKDF2` rounds.
+///
+/// Read `lib.rs` module documentation for more information.
+pub fn decrypt_with_rounds(
+    password: &str,
+    data: CipherData,
+    rounds: u32,
+) -> Result<Vec<u8>, CipherError> {
     // get PBKDF2 salt
     let salt = SaltString::encode_b64(&data.salt)
         .map_err(|e| CipherError::DecryptionError(e.to_string()))?;
 
     // compute PBKDF2 password hash
     let password_hash = Pbkdf2
-        .hash_password_customized(password.as_bytes(), None, None, HASH_PARAMS, &salt)
+        .hash_password_customized(
+            password.as_bytes(),
+            None,
+            None,
+            Params {
+                rounds,
+                output_length: KEY_SIZE,
+            },
+            &salt,
+        )
         .map_err(|e| CipherError::DecryptionError(e.to_string()))?
         .hash
         .expect("content is missing after a successful hash");
@@ -7,10 +7,10 @@
 use aes_gcm::aead::Aead;
 use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use pbkdf2::password_hash::{Salt, SaltString};
-use pbkdf2::{password_hash::PasswordHasher, Pbkdf2};
+use pbkdf2::{password_hash::PasswordHasher, Params, Pbkdf2};
 use rand::{thread_rng, RngCore};
 
-use crate::constants::{HASH_PARAMS, NONCE_SIZE, SALT_SIZE};
+use crate::constants::{HASH_PARAMS, KEY_SIZE, NONCE_SIZE, SALT_SIZE};
 use crate::error::CipherError;
 
 pub struct CipherData {
@@ -23,6 +23,17 @@ pub struct CipherData {
 ///
 /// Read `lib.rs` module documentation for more information.
 pub fn encrypt(password: &str, data: &[u8]) -> Result<CipherData, CipherError> {
+    encrypt_with_rounds(password, data, HASH_PARAMS.rounds)
+}
+
+/// Encryption function using AES-GCM cipher, with a custom number of `PBKDF2` rounds.
+///
+/// Read `lib.rs` module documentation for more information.
+pub fn encrypt_with_rounds(
+    password: &str,
+    data: &[u8],
+    rounds: u32,
+) -> Result<CipherData, CipherError> {
     // generate the PBKDF2 salt
     // Re-implementation of the SaltString::generate function (allowing to control the SALT_SIZE here)
     let mut rng = thread_rng();
@@ -37,7 +48,10 @@ pub fn encrypt(password: &str, data: &[u8]) -> Result<CipherData, CipherError> {
             password.as_bytes(),
             None,
             None,
-            HASH_PARAMS,
+            Params {
+                rounds,
+                output_length: KEY_SIZE,
+            },
             Salt::from(&salt),
         )
         .map_err(|e| CipherError::EncryptionError(e.to_string()))?
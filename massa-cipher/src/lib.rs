@@ -17,9 +17,10 @@ mod decrypt;
 mod encrypt;
 mod error;
 
-pub use decrypt::decrypt;
-pub use encrypt::encrypt;
+pub use constants::{KEY_SIZE, NONCE_SIZE, PBKDF2_ROUNDS, SALT_SIZE};
+pub use decrypt::{decrypt, decrypt_with_rounds};
 pub use encrypt::CipherData;
+pub use encrypt::{encrypt, encrypt_with_rounds};
 pub use error::CipherError;
 
 pub type Salt = [u8; constants::SALT_SIZE];
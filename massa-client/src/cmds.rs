@@ -4,12 +4,15 @@ use crate::display::Output;
 use crate::{client_warning, rpc_error};
 use anyhow::{anyhow, bail, Result};
 use console::style;
+use dialoguer::Password;
 use massa_api_exports::{
     address::{AddressInfo, CompactAddressInfo},
     datastore::DatastoreEntryInput,
     execution::{ReadOnlyBytecodeExecution, ReadOnlyCall},
+    node::{ConnectedPeersFilter, PeerState, TransportType},
     operation::OperationInput,
 };
+use massa_async_pool::{AsyncMessageFilter, AsyncMessageTrigger};
 use massa_models::node::NodeId;
 use massa_models::prehash::PreHashMap;
 use massa_models::timeslots::get_current_latest_block_slot;
@@ -23,15 +26,15 @@ use massa_models::{
     slot::Slot,
 };
 use massa_sdk::Client;
-use massa_signature::KeyPair;
+use massa_signature::{generate_mnemonic, KeyPair};
 use massa_time::MassaTime;
-use massa_wallet::Wallet;
+use massa_wallet::{Keystore, Wallet};
 
 use serde::Serialize;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write as _;
 use std::fmt::{Debug, Display};
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use strum::{EnumMessage, EnumProperty, IntoEnumIterator};
 use strum_macros::{Display, EnumIter, EnumString};
@@ -93,6 +96,13 @@ pub enum Command {
     )]
     node_stop,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "reload the API keys and rate limits of the public APIs from the node configuration"
+    )]
+    node_reload_api_access,
+
     #[strum(
         ascii_case_insensitive,
         props(pwd_not_needed = "true"),
@@ -100,6 +110,20 @@ pub enum Command {
     )]
     node_get_staking_addresses,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "count", pwd_not_needed = "true"),
+        message = "dev mode only: produce a block at each of the next count slots (1 by default)"
+    )]
+    node_dev_advance_slots,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "duration_ms", pwd_not_needed = "true"),
+        message = "dev mode only: move the node clock forward by duration_ms milliseconds without producing blocks"
+    )]
+    node_dev_advance_time,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address1 Address2 ..."),
@@ -142,6 +166,23 @@ pub enum Command {
     )]
     node_peers_whitelist,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "(add or remove) [ip:port[,tcp|quic]]", pwd_not_needed = "true"),
+        message = "Manage static peers, always kept connected (default transport is tcp). No args returns the static peers"
+    )]
+    node_static_peers,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "node_id=NodeId ip=IpAddr direction=in|out category=string state=banned|in_handshake|handshake_failed|trusted",
+            pwd_not_needed = "true"
+        ),
+        message = "show the connected peers (address, connection age, bandwidth, messages received, listeners, state) with optional filters"
+    )]
+    node_connected_peers,
+
     #[strum(
         ascii_case_insensitive,
         props(pwd_not_needed = "true"),
@@ -194,6 +235,16 @@ pub enum Command {
     )]
     get_filtered_sc_output_event,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "sender=Address destination=Address handler=string validity_start=slot_period,slot_thread validity_end=slot_period,slot_thread trigger_address=Address trigger_key=string can_be_executed=bool",
+            pwd_not_needed = "true"
+        ),
+        message = "show pending asynchronous messages with various filters"
+    )]
+    get_async_messages,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "show-all-keys"),
@@ -235,6 +286,39 @@ pub enum Command {
     )]
     wallet_remove_addresses,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToKeystore Address1 Address2 ..."),
+        message = "export the keys of the given addresses (all keys if none given) to a password protected JSON keystore file"
+    )]
+    wallet_export,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToKeystore"),
+        message = "import the keys of a password protected JSON keystore file into the wallet"
+    )]
+    wallet_import,
+
+    #[strum(
+        ascii_case_insensitive,
+        message = "change the wallet password, re-encrypting every wallet file"
+    )]
+    wallet_change_password,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "[AccountCount]"),
+        message = "set the HD seed of the wallet from a BIP-39 mnemonic (a new one is generated if none is entered) and derive its first accounts (1 by default)"
+    )]
+    wallet_create_from_mnemonic,
+
+    #[strum(
+        ascii_case_insensitive,
+        message = "derive the next account from the HD seed of the wallet and add it into the wallet"
+    )]
+    wallet_derive_next_account,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address string"),
@@ -528,6 +612,18 @@ impl Command {
                 Ok(Box::new(()))
             }
 
+            Command::node_reload_api_access => {
+                match client.private.node_reload_api_access().await {
+                    Ok(()) => {
+                        if !json {
+                            println!("API access configuration successfully reloaded")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                };
+                Ok(Box::new(()))
+            }
+
             Command::node_get_staking_addresses => {
                 match client.private.get_staking_addresses().await {
                     Ok(staking_addresses) => Ok(Box::new(staking_addresses)),
@@ -535,6 +631,31 @@ impl Command {
                 }
             }
 
+            Command::node_dev_advance_slots => {
+                if parameters.len() > 1 {
+                    bail!("wrong number of parameters");
+                }
+                let count = match parameters.first() {
+                    Some(count) => count.parse::<u64>()?,
+                    None => 1,
+                };
+                match client.private.node_dev_advance_slots(count).await {
+                    Ok(block_ids) => Ok(Box::new(block_ids)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_dev_advance_time => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let duration = MassaTime::from_millis(parameters[0].parse::<u64>()?);
+                match client.private.node_dev_advance_time(duration).await {
+                    Ok(time) => Ok(Box::new(time)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::node_testnet_rewards_program_ownership_proof => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
@@ -658,6 +779,52 @@ impl Command {
                 }
             }
 
+            Command::get_async_messages => {
+                let p_list: [&str; 8] = [
+                    "sender",
+                    "destination",
+                    "handler",
+                    "validity_start",
+                    "validity_end",
+                    "trigger_address",
+                    "trigger_key",
+                    "can_be_executed",
+                ];
+                let mut p: HashMap<&str, &str> = HashMap::new();
+                for v in parameters {
+                    let s: Vec<&str> = v.split('=').collect();
+                    if s.len() == 2 && p_list.contains(&s[0]) {
+                        p.insert(s[0], s[1]);
+                    } else {
+                        bail!("invalid parameter: {}, type \"help get_async_messages\" to get the list of valid parameters", v);
+                    }
+                }
+                let trigger_key: Option<String> = parse_key_value(&p, p_list[6])?;
+                let trigger = match parse_key_value::<Address>(&p, p_list[5])? {
+                    Some(address) => Some(AsyncMessageTrigger {
+                        address,
+                        datastore_key: trigger_key.map(String::into_bytes),
+                    }),
+                    None if trigger_key.is_some() => {
+                        bail!("trigger_key requires a trigger_address")
+                    }
+                    None => None,
+                };
+                let filter = AsyncMessageFilter {
+                    sender: parse_key_value(&p, p_list[0])?,
+                    destination: parse_key_value(&p, p_list[1])?,
+                    handler: parse_key_value(&p, p_list[2])?,
+                    validity_start: parse_key_value(&p, p_list[3])?,
+                    validity_end: parse_key_value(&p, p_list[4])?,
+                    trigger,
+                    can_be_executed: parse_key_value(&p, p_list[7])?,
+                };
+                match client.public.get_async_messages(filter, None).await {
+                    Ok(messages) => Ok(Box::new(messages)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::wallet_info => {
                 let show_keys = parameters.len() == 1 && parameters[0] == "show-all-keys";
 
@@ -847,6 +1014,126 @@ impl Command {
                 Ok(Box::new(()))
             }
 
+            Command::wallet_export => {
+                if parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let path = parameters[0].parse::<PathBuf>()?;
+                if path.exists() {
+                    bail!("file {} already exists", path.display());
+                }
+                let addresses = parse_vec::<Address>(&parameters[1..])?;
+                let password = ask_new_password("Enter new password for the keystore")?;
+                let keystore = wallet.export_keystore(&addresses, &password)?;
+                keystore.save(&path)?;
+                if !json {
+                    println!(
+                        "Exported {} key(s) to {}",
+                        keystore.keys.len(),
+                        path.display()
+                    );
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::wallet_import => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let path = parameters[0].parse::<PathBuf>()?;
+                let keystore = Keystore::load(&path)?;
+                let password = Password::new()
+                    .with_prompt("Enter keystore password")
+                    .interact()?;
+                let addresses = wallet.import_keystore(&keystore, &password)?;
+                if json {
+                    return Ok(Box::new(addresses));
+                } else {
+                    for address in addresses {
+                        println!("Imported address {} to the wallet.", address);
+                    }
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::wallet_change_password => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let password = ask_new_password("Enter new password for wallet")?;
+                wallet.change_password(password)?;
+                if !json {
+                    println!("Wallet password changed");
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::wallet_create_from_mnemonic => {
+                if parameters.len() > 1 {
+                    bail!("wrong number of parameters");
+                }
+                let wallet = wallet_opt.as_mut().unwrap();
+                if wallet.get_hd_account_count().is_some() {
+                    bail!("the wallet already has an HD seed");
+                }
+                let account_count = match parameters.first() {
+                    Some(count) => count.parse::<u32>()?,
+                    None => 1,
+                };
+
+                let mut mnemonic = Password::new()
+                    .with_prompt("Enter mnemonic (leave empty to generate a new one)")
+                    .allow_empty_password(true)
+                    .interact()?;
+                let generated = mnemonic.trim().is_empty();
+                if generated {
+                    if json {
+                        bail!("a mnemonic must be entered in JSON mode");
+                    }
+                    mnemonic = generate_mnemonic(24)?;
+                }
+                let passphrase = Password::new()
+                    .with_prompt("Enter mnemonic passphrase (optional)")
+                    .with_confirmation("Confirm passphrase", "Passphrases mismatching")
+                    .allow_empty_password(true)
+                    .interact()?;
+
+                // Note: keypair version is hardcoded here, see `wallet_generate_secret_key`
+                let keypair_version: u64 = 0;
+                let addresses = wallet.set_hd_seed_from_mnemonic(
+                    &mnemonic,
+                    &passphrase,
+                    keypair_version,
+                    account_count,
+                )?;
+                if json {
+                    return Ok(Box::new(addresses));
+                }
+                if generated {
+                    println!("Generated mnemonic, write it down and keep it safe, it is the only way to recover the derived accounts:");
+                    println!("{}\n", mnemonic);
+                }
+                for address in addresses {
+                    println!("Derived and added address {} to the wallet.", address);
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::wallet_derive_next_account => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let address = wallet.derive_next_account()?;
+                if json {
+                    Ok(Box::new(address.to_string()))
+                } else {
+                    println!("Derived and added address {} to the wallet.", address);
+                    Ok(Box::new(()))
+                }
+            }
+
             Command::buy_rolls => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
@@ -1320,6 +1607,46 @@ impl Command {
                     res
                 }
             }
+            Command::node_connected_peers => {
+                let p_list: [&str; 5] = ["node_id", "ip", "direction", "category", "state"];
+                let mut p: HashMap<&str, &str> = HashMap::new();
+                for v in parameters {
+                    let s: Vec<&str> = v.split('=').collect();
+                    if s.len() == 2 && p_list.contains(&s[0]) {
+                        p.insert(s[0], s[1]);
+                    } else {
+                        bail!("invalid parameter: {}, type \"help node_connected_peers\" to get the list of valid parameters", v);
+                    }
+                }
+                let is_outgoing = match p.get("direction") {
+                    Some(&"in") => Some(false),
+                    Some(&"out") => Some(true),
+                    Some(direction) => {
+                        bail!("invalid direction: {}, expected in or out", direction)
+                    }
+                    None => None,
+                };
+                let state = match p.get("state") {
+                    Some(&"banned") => Some(PeerState::Banned),
+                    Some(&"in_handshake") => Some(PeerState::InHandshake),
+                    Some(&"handshake_failed") => Some(PeerState::HandshakeFailed),
+                    Some(&"trusted") => Some(PeerState::Trusted),
+                    Some(state) => bail!("invalid state: {}, expected banned, in_handshake, handshake_failed or trusted", state),
+                    None => None,
+                };
+                let filter = ConnectedPeersFilter {
+                    node_ids: parse_key_value::<NodeId>(&p, p_list[0])?.map(|id| vec![id]),
+                    ip: parse_key_value(&p, p_list[1])?,
+                    is_outgoing,
+                    category: parse_key_value(&p, p_list[3])?,
+                    state,
+                };
+                match client.private.node_connected_peers(Some(filter)).await {
+                    Ok(peers) => Ok(Box::new(peers)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::node_peers_whitelist => {
                 if parameters.is_empty() {
                     match client.private.node_peers_whitelist().await {
@@ -1368,6 +1695,62 @@ impl Command {
                     res
                 }
             }
+            Command::node_static_peers => {
+                if parameters.is_empty() {
+                    match client.private.node_static_peers().await {
+                        Ok(peers) => Ok(Box::new(peers)),
+                        Err(e) => rpc_error!(e),
+                    }
+                } else {
+                    let cli_op = match parameters[0].parse::<ListOperation>() {
+                        Ok(op) => op,
+                        Err(_) => bail!(
+                            "failed to parse operation, supported operations are: [add, remove]"
+                        ),
+                    };
+                    let args = &parameters[1..];
+                    if args.is_empty() {
+                        bail!("[ip:port[,tcp|quic]] parameter shouldn't be empty");
+                    }
+                    let peers = args
+                        .iter()
+                        .map(|arg| parse_static_peer(arg))
+                        .collect::<Result<BTreeMap<_, _>>>()?;
+                    let res: Result<Box<dyn Output>> = match cli_op {
+                        ListOperation::Add => {
+                            match client.private.node_add_static_peers(peers).await {
+                                Ok(()) => {
+                                    if !json {
+                                        println!(
+                                            "Request of adding static peers successfully sent!"
+                                        )
+                                    }
+                                    Ok(Box::new(()))
+                                }
+                                Err(e) => rpc_error!(e),
+                            }
+                        }
+                        ListOperation::Remove => {
+                            let addrs = peers.into_keys().collect();
+                            match client.private.node_remove_static_peers(addrs).await {
+                                Ok(()) => {
+                                    if !json {
+                                        println!(
+                                            "Request of removing static peers successfully sent!"
+                                        )
+                                    }
+                                    Ok(Box::new(()))
+                                }
+                                Err(e) => rpc_error!(e),
+                            }
+                        }
+                        ListOperation::AllowAll => {
+                            bail!("\"allow-all\" command is not implemented")
+                        }
+                    };
+                    res
+                }
+            }
             Command::exit => {
                 std::process::exit(0);
             }
@@ -1439,6 +1822,31 @@ where
         .collect()
 }
 
+/// Parse a static peer given as `ip:port` or `ip:port,transport` (tcp or quic)
+fn parse_static_peer(arg: &str) -> Result<(SocketAddr, TransportType)> {
+    let (addr, transport) = match arg.split_once(',') {
+        Some((addr, transport)) => (addr, transport),
+        None => (arg, "tcp"),
+    };
+    let addr = addr
+        .parse::<SocketAddr>()
+        .map_err(|e| anyhow!("failed to parse \"{}\" due to: {}", addr, e))?;
+    let transport = match transport.to_lowercase().as_str() {
+        "tcp" => TransportType::Tcp,
+        "quic" => TransportType::Quic,
+        _ => bail!("unknown transport \"{}\", expected tcp or quic", transport),
+    };
+    Ok((addr, transport))
+}
+
+/// Ask for a new password, with confirmation
+fn ask_new_password(prompt: &str) -> Result<String> {
+    Ok(Password::new()
+        .with_prompt(prompt)
+        .with_confirmation("Confirm password", "Passwords mismatching")
+        .interact()?)
+}
+
 /// reads a file
 async fn get_file_as_byte_vec(filename: &std::path::Path) -> Result<Vec<u8>> {
     Ok(tokio::fs::read(filename).await?)
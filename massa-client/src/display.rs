@@ -4,18 +4,27 @@ use crate::cmds::ExtendedWallet;
 use console::style;
 use erased_serde::{Serialize, Serializer};
 use massa_api_exports::{
-    address::AddressInfo, block::BlockInfo, datastore::DatastoreEntryOutput,
-    endorsement::EndorsementInfo, execution::ExecuteReadOnlyResponse, node::NodeStatus,
+    address::AddressInfo,
+    async_message::AsyncMessageOutput,
+    block::BlockInfo,
+    datastore::DatastoreEntryOutput,
+    endorsement::EndorsementInfo,
+    execution::ExecuteReadOnlyResponse,
+    node::{ConnectedPeer, NodeStatus, TransportType},
     operation::OperationInfo,
 };
 use massa_models::composite::PubkeySig;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
-use massa_models::{address::Address, config::CompactConfig, operation::OperationId};
+use massa_models::{
+    address::Address, block_id::BlockId, config::CompactConfig, operation::OperationId,
+};
 use massa_signature::{KeyPair, PublicKey};
+use massa_time::MassaTime;
 use massa_wallet::Wallet;
-use std::net::IpAddr;
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
 use std::str;
 
 #[macro_export]
@@ -475,6 +484,14 @@ impl Output for Vec<DatastoreEntryOutput> {
     }
 }
 
+impl Output for Vec<AsyncMessageOutput> {
+    fn pretty_print(&self) {
+        for message in self {
+            println!("{}", message);
+        }
+    }
+}
+
 impl Output for Vec<EndorsementInfo> {
     fn pretty_print(&self) {
         for endorsement_info in self {
@@ -483,6 +500,14 @@ impl Output for Vec<EndorsementInfo> {
     }
 }
 
+impl Output for Vec<ConnectedPeer> {
+    fn pretty_print(&self) {
+        for peer in self {
+            println!("{}", peer);
+        }
+    }
+}
+
 impl Output for Vec<IpAddr> {
     fn pretty_print(&self) {
         for ips in self {
@@ -491,6 +516,18 @@ impl Output for Vec<IpAddr> {
     }
 }
 
+impl Output for BTreeMap<SocketAddr, TransportType> {
+    fn pretty_print(&self) {
+        for (addr, transport) in self {
+            let transport = match transport {
+                TransportType::Tcp => "tcp",
+                TransportType::Quic => "quic",
+            };
+            println!("{} ({})", addr, transport);
+        }
+    }
+}
+
 impl Output for Vec<OperationInfo> {
     fn pretty_print(&self) {
         for info in self {
@@ -568,6 +605,20 @@ impl Output for Vec<OperationId> {
     }
 }
 
+impl Output for Vec<BlockId> {
+    fn pretty_print(&self) {
+        for block_id in self {
+            println!("{}", block_id);
+        }
+    }
+}
+
+impl Output for MassaTime {
+    fn pretty_print(&self) {
+        println!("{}", self.format_instant());
+    }
+}
+
 impl Output for Vec<Address> {
     fn pretty_print(&self) {
         for addr in self {
@@ -1,3 +1,4 @@
+use massa_block_archive_exports::BlockArchiveController;
 use massa_channel::sender::MassaSender;
 use massa_execution_exports::ExecutionController;
 use massa_models::block::{FilledBlock, SecureShareBlock};
@@ -29,4 +30,6 @@ pub struct ConsensusChannels {
     pub block_header_sender: tokio::sync::broadcast::Sender<SecureShare<BlockHeader, BlockId>>,
     /// Channel use by Websocket (if they are enable) to broadcast a new block integrated
     pub filled_block_sender: tokio::sync::broadcast::Sender<FilledBlock>,
+    /// Interface to the block archive, if final blocks are archived
+    pub block_archive_controller: Option<Box<dyn BlockArchiveController>>,
 }
@@ -595,6 +595,24 @@ impl ConsensusState {
     ///
     /// # Arguments:
     /// * `finalized_blocks`: Block that became final and need to be send to execution
+    /// Sends newly finalized blocks with their operations to the block archive, if enabled
+    fn archive_final_blocks(&self, finalized_blocks: &HashMap<Slot, BlockId>) {
+        let Some(block_archive_controller) = &self.channels.block_archive_controller else {
+            return;
+        };
+        let blocks = finalized_blocks
+            .values()
+            .filter_map(|b_id| match self.blocks_state.get(b_id) {
+                Some(BlockStatus::Active {
+                    storage_or_block: StorageOrBlock::Storage(storage),
+                    ..
+                }) => Some((*b_id, storage.clone())),
+                _ => None,
+            })
+            .collect();
+        block_archive_controller.archive_final_blocks(blocks);
+    }
+
     fn notify_execution(&mut self, finalized_blocks: HashMap<Slot, BlockId>) {
         // List new block storage instances that Execution doesn't know about.
         // That's blocks that have not been sent to execution before, ie. in the previous blockclique).
@@ -749,6 +767,9 @@ impl ConsensusState {
             final_block_slots
         };
 
+        // archive final blocks
+        self.archive_final_blocks(&final_block_slots);
+
         // notify execution
         self.notify_execution(final_block_slots);
 
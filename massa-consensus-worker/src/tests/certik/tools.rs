@@ -80,6 +80,7 @@ where
             protocol_command_sender: protocol_command_sender.clone(),
             pool_command_sender: pool_controller,
             selector_controller: selector_controller.clone(),
+            block_archive_controller: None,
         },
         None,
         storage.clone(),
@@ -92,6 +92,7 @@ where
             protocol_controller: protocol_controller.clone_box(),
             pool_controller,
             selector_controller: selector_controller.clone(),
+            block_archive_controller: None,
         },
         None,
         storage.clone(),
@@ -2,17 +2,33 @@
 pub const METADATA_CF: &str = "metadata";
 pub const STATE_CF: &str = "state";
 pub const VERSIONING_CF: &str = "versioning";
+pub const MERKLE_CF: &str = "merkle";
+pub const ARCHIVE_CF: &str = "archive";
+pub const ARCHIVE_INDEX_CF: &str = "archive_index";
 
 // Hash
 pub const STATE_HASH_BYTES_LEN: usize = 512;
 pub const STATE_HASH_KEY: &[u8; 1] = b"h";
 pub const STATE_HASH_INITIAL_BYTES: &[u8; STATE_HASH_BYTES_LEN] = &[0; STATE_HASH_BYTES_LEN];
 
+// Merkle tree
+pub const MERKLE_ROOT_KEY: &[u8; 1] = b"m";
+pub const MERKLE_ROOT_ERROR: &str = "critical: saved merkle root is corrupted";
+pub const MERKLE_TREE_ERROR: &str = "critical: merkle tree is corrupted";
+
+// Archive
+pub const ARCHIVE_START_KEY: &[u8; 1] = b"a";
+pub const ARCHIVE_START_ERROR: &str = "critical: saved archive start slot is corrupted";
+
 // Change_id
 pub const CHANGE_ID_KEY: &[u8; 1] = b"c";
 pub const CHANGE_ID_DESER_ERROR: &str = "critical: change_id deserialization failed";
 pub const CHANGE_ID_SER_ERROR: &str = "critical: change_id serialization failed";
 
+// Bootstrap progress
+pub const BOOTSTRAP_PROGRESS_KEY: &[u8; 1] = b"b";
+pub const BOOTSTRAP_PROGRESS_SER_ERROR: &str = "critical: bootstrap progress serialization failed";
+
 // Errors
 pub const CF_ERROR: &str = "critical: rocksdb column family operation failed";
 pub const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
@@ -1,5 +1,5 @@
-use crate::{DBBatch, Key, MassaDBError, StreamBatch, Value};
-use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
+use crate::{BootstrapProgress, DBBatch, Key, MassaDBError, StateProof, StreamBatch, Value};
+use massa_hash::{Hash, HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::{error::ModelsError, slot::Slot, streaming_step::StreamingStep};
 use parking_lot::RwLock;
 use std::{fmt::Debug, sync::Arc};
@@ -55,16 +55,50 @@ pub trait MassaDBController: Send + Sync + Debug {
     /// Get the current extended state hash of the database
     fn get_xof_db_hash(&self) -> HashXof<HASH_XOF_SIZE_BYTES>;
 
+    /// Get the extended state hash of the database at the end of a recent slot,
+    /// `None` if the slot is not in the change history
+    fn get_xof_db_hash_at(&self, slot: Slot) -> Option<HashXof<HASH_XOF_SIZE_BYTES>>;
+
+    /// Get the current root of the state Merkle tree
+    fn get_merkle_root(&self) -> Hash;
+
+    /// Get the value of a key of STATE_CF along with its Merkle inclusion (or non-inclusion) proof,
+    /// at the current change_id
+    fn get_state_proof(&self, key: Key) -> Result<StateProof, MassaDBError>;
+
+    /// Get the oldest slot at which the state can be read from the archive,
+    /// `None` if the archive is disabled or empty
+    fn get_archive_start(&self) -> Option<Slot>;
+
+    /// Get the value of a key of STATE_CF as it was at the end of a past final slot.
+    /// Requires the archive to be enabled and to cover the slot.
+    fn get_state_value_at_slot(&self, key: Key, slot: Slot) -> Result<Option<Value>, MassaDBError>;
+
     /// Flushes the underlying db.
     fn flush(&self) -> Result<(), MassaDBError>;
 
-    /// Write a stream_batch of database entries received from a bootstrap server
+    /// Write a stream_batch of database entries received from a bootstrap server,
+    /// along with the resulting bootstrap progress
     fn write_batch_bootstrap_client(
         &mut self,
         stream_changes: StreamBatch<Slot>,
         stream_changes_versioning: StreamBatch<Slot>,
     ) -> Result<(StreamingStep<Key>, StreamingStep<Key>), MassaDBError>;
 
+    /// Get the progress of the bootstrap writing the database, `None` if no bootstrap was interrupted.
+    /// Fails if the saved progress cannot be read, in which case the bootstrap has to restart from scratch.
+    fn get_bootstrap_progress(&self) -> Result<Option<BootstrapProgress<Slot>>, MassaDBError>;
+
+    /// Forget the bootstrap progress, once the bootstrap is complete
+    fn clear_bootstrap_progress(&mut self) -> Result<(), MassaDBError>;
+
+    /// Write a stream_batch of STATE_CF entries of a key range received from one of several bootstrap servers.
+    /// The change_id is left unchanged, see `write_batch_bootstrap_client` for the batch bringing all ranges to the same slot
+    fn write_range_batch_bootstrap_client(
+        &mut self,
+        stream_changes: StreamBatch<Slot>,
+    ) -> Result<(), MassaDBError>;
+
     /// Used for bootstrap servers (get a new batch of data from STATE_CF to stream to the client)
     ///
     /// Returns a StreamBatch<Slot>
@@ -74,6 +108,18 @@ pub trait MassaDBController: Send + Sync + Debug {
         last_change_id: Option<Slot>,
     ) -> Result<StreamBatch<Slot>, MassaDBError>;
 
+    /// Used for bootstrap servers streaming only the keys of STATE_CF in `[range_start, range_end)`,
+    /// the client bootstrapping the other key ranges from other servers
+    ///
+    /// Returns a StreamBatch<Slot>
+    fn get_range_batch_to_stream(
+        &self,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<Slot>,
+        range_start: &[u8],
+        range_end: Option<&[u8]>,
+    ) -> Result<StreamBatch<Slot>, MassaDBError>;
+
     /// Used for bootstrap servers (get a new batch of data from VERSIONING_CF to stream to the client)
     ///
     /// Returns a StreamBatch<Slot>
@@ -1,3 +1,4 @@
+use massa_models::streaming_step::StreamingStep;
 use std::collections::BTreeMap;
 
 pub type Key = Vec<u8>;
@@ -27,3 +28,16 @@ impl<ChangeID: PartialOrd + Ord + PartialEq + Eq + Clone + std::fmt::Debug> Stre
         self.updates_on_previous_elements.is_empty() && self.new_elements.is_empty()
     }
 }
+
+/// Progress of a bootstrap client, persisted along with the partially bootstrapped state
+/// so that a restarted node resumes the bootstrap instead of starting it again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootstrapProgress<ChangeID: PartialOrd + Ord + PartialEq + Eq + Clone + std::fmt::Debug>
+{
+    /// Last received state key
+    pub last_state_step: StreamingStep<Key>,
+    /// Last received versioning key
+    pub last_versioning_step: StreamingStep<Key>,
+    /// ChangeID the received data is attached to
+    pub last_change_id: ChangeID,
+}
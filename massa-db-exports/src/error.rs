@@ -17,4 +17,10 @@ pub enum MassaDBError {
     RocksDBError(String),
     /// hash error: {0}
     HashError(String),
+    /// archive error: {0}
+    ArchiveError(String),
+    /// state export error: {0}
+    StateExportError(String),
+    /// bootstrap progress error: {0}
+    BootstrapProgressError(String),
 }
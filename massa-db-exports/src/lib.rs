@@ -2,10 +2,12 @@ mod constants;
 mod controller;
 mod db_batch;
 mod error;
+mod merkle;
 mod settings;
 
 pub use constants::*;
 pub use controller::*;
 pub use db_batch::*;
 pub use error::*;
+pub use merkle::*;
 pub use settings::*;
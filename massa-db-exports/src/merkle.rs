@@ -0,0 +1,206 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Sparse Merkle tree primitives authenticating the entries of `STATE_CF`.
+//!
+//! Every key is mapped to a leaf of a binary tree of depth `MERKLE_TREE_DEPTH`, following the bits of the hash of the key.
+//! A leaf holds the hash of its key/value pair and empty subtrees hash to a per-level default value,
+//! so that both the presence and the absence of a key can be proven against the root.
+//!
+//! The functions of this file do not need a database, a light client can use them to verify a `StateProof`.
+
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_models::slot::Slot;
+
+/// Depth of the sparse Merkle tree: one level per bit of the hashed key
+pub const MERKLE_TREE_DEPTH: usize = HASH_SIZE_BYTES * 8;
+
+/// Position of a leaf in the tree, obtained by hashing the key
+pub type MerklePath = [u8; HASH_SIZE_BYTES];
+
+const LEAF_DOMAIN: &[u8] = &[0];
+const NODE_DOMAIN: &[u8] = &[1];
+
+/// Value of a state entry along with the proof of its inclusion (or non-inclusion if `value` is `None`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateProof {
+    /// final slot at which the proof was generated
+    pub slot: Slot,
+    /// root of the state Merkle tree at that slot
+    pub root: Hash,
+    /// serialized key of the entry
+    pub key: Vec<u8>,
+    /// serialized value of the entry, `None` if the key is absent from the state
+    pub value: Option<Vec<u8>>,
+    /// siblings on the path from the leaf up to the root, leaf level first.
+    /// `None` stands for an empty subtree.
+    pub siblings: Vec<Option<Hash>>,
+}
+
+impl StateProof {
+    /// Check that the proof is consistent with `expected_root`.
+    /// The root must come from a trusted source, the one embedded in the proof is not checked.
+    pub fn verify(&self, expected_root: &Hash) -> bool {
+        verify_merkle_proof(
+            expected_root,
+            &self.key,
+            self.value.as_deref(),
+            &self.siblings,
+        )
+    }
+}
+
+/// Get the path of the leaf associated to a key
+pub fn merkle_key_path(key: &[u8]) -> MerklePath {
+    Hash::compute_from(key).into_bytes()
+}
+
+/// Get the direction taken at `depth` on the way to the leaf: `false` for left, `true` for right
+pub fn merkle_path_bit(path: &MerklePath, depth: usize) -> bool {
+    (path[depth / 8] >> (7 - depth % 8)) & 1 == 1
+}
+
+/// Hash of a leaf holding a key/value pair
+pub fn merkle_leaf_hash(key: &[u8], value: &[u8]) -> Hash {
+    Hash::compute_from_tuple(&[LEAF_DOMAIN, key, value])
+}
+
+/// Hash of an internal node
+pub fn merkle_node_hash(left: &Hash, right: &Hash) -> Hash {
+    Hash::compute_from_tuple(&[NODE_DOMAIN, left.to_bytes(), right.to_bytes()])
+}
+
+/// Hashes of the empty subtrees, indexed by depth (`MERKLE_TREE_DEPTH` being the leaf level)
+pub fn merkle_empty_hashes() -> Vec<Hash> {
+    let mut hashes = vec![Hash::zero(); MERKLE_TREE_DEPTH + 1];
+    for depth in (0..MERKLE_TREE_DEPTH).rev() {
+        hashes[depth] = merkle_node_hash(&hashes[depth + 1], &hashes[depth + 1]);
+    }
+    hashes
+}
+
+/// Check that `key` is bound to `value` (or absent if `value` is `None`) in the tree of root `root`
+pub fn verify_merkle_proof(
+    root: &Hash,
+    key: &[u8],
+    value: Option<&[u8]>,
+    siblings: &[Option<Hash>],
+) -> bool {
+    if siblings.len() != MERKLE_TREE_DEPTH {
+        return false;
+    }
+    let empty_hashes = merkle_empty_hashes();
+    let path = merkle_key_path(key);
+    let mut current = match value {
+        Some(value) => merkle_leaf_hash(key, value),
+        None => empty_hashes[MERKLE_TREE_DEPTH],
+    };
+    for (depth, sibling) in (0..MERKLE_TREE_DEPTH).rev().zip(siblings) {
+        let sibling = sibling.unwrap_or(empty_hashes[depth + 1]);
+        current = if merkle_path_bit(&path, depth) {
+            merkle_node_hash(&sibling, &current)
+        } else {
+            merkle_node_hash(&current, &sibling)
+        };
+    }
+    current == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build the proof of `key` in a tree containing only `entries`, without any storage
+    fn naive_proof(entries: &[(&[u8], &[u8])], key: &[u8]) -> (Hash, Vec<Option<Hash>>) {
+        let empty_hashes = merkle_empty_hashes();
+        let target = merkle_key_path(key);
+        let mut level: Vec<(MerklePath, Hash)> = entries
+            .iter()
+            .map(|(k, v)| (merkle_key_path(k), merkle_leaf_hash(k, v)))
+            .collect();
+        let mut siblings = Vec::new();
+        for depth in (0..MERKLE_TREE_DEPTH).rev() {
+            let prefix = |path: &MerklePath, len: usize| {
+                (0..len)
+                    .map(|d| merkle_path_bit(path, d))
+                    .collect::<Vec<_>>()
+            };
+            let sibling = level
+                .iter()
+                .find(|(path, _)| {
+                    prefix(path, depth) == prefix(&target, depth)
+                        && merkle_path_bit(path, depth) != merkle_path_bit(&target, depth)
+                })
+                .map(|(_, hash)| *hash);
+            siblings.push(sibling);
+            let mut parents: Vec<(MerklePath, Hash)> = Vec::new();
+            for (path, hash) in &level {
+                if parents
+                    .iter()
+                    .any(|(p, _)| prefix(p, depth) == prefix(path, depth))
+                {
+                    continue;
+                }
+                let other = level
+                    .iter()
+                    .find(|(p, _)| {
+                        prefix(p, depth) == prefix(path, depth)
+                            && merkle_path_bit(p, depth) != merkle_path_bit(path, depth)
+                    })
+                    .map(|(_, h)| *h)
+                    .unwrap_or(empty_hashes[depth + 1]);
+                let parent = if merkle_path_bit(path, depth) {
+                    merkle_node_hash(&other, hash)
+                } else {
+                    merkle_node_hash(hash, &other)
+                };
+                parents.push((*path, parent));
+            }
+            level = parents;
+        }
+        let root = level
+            .first()
+            .map(|(_, hash)| *hash)
+            .unwrap_or(empty_hashes[0]);
+        (root, siblings)
+    }
+
+    #[test]
+    fn test_merkle_proof_verification() {
+        let entries: Vec<(&[u8], &[u8])> = vec![
+            (b"ledger/a", b"1"),
+            (b"ledger/b", b"2"),
+            (b"async_pool/c", b"3"),
+        ];
+
+        // inclusion
+        let (root, siblings) = naive_proof(&entries, b"ledger/b");
+        assert!(verify_merkle_proof(
+            &root,
+            b"ledger/b",
+            Some(b"2"),
+            &siblings
+        ));
+        assert!(!verify_merkle_proof(
+            &root,
+            b"ledger/b",
+            Some(b"3"),
+            &siblings
+        ));
+        assert!(!verify_merkle_proof(&root, b"ledger/b", None, &siblings));
+
+        // non-inclusion
+        let (root, siblings) = naive_proof(&entries, b"ledger/d");
+        assert!(verify_merkle_proof(&root, b"ledger/d", None, &siblings));
+        assert!(!verify_merkle_proof(
+            &root,
+            b"ledger/d",
+            Some(b"4"),
+            &siblings
+        ));
+
+        // empty tree
+        let (root, siblings) = naive_proof(&[], b"ledger/a");
+        assert_eq!(root, merkle_empty_hashes()[0]);
+        assert!(verify_merkle_proof(&root, b"ledger/a", None, &siblings));
+    }
+}
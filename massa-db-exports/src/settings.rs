@@ -11,4 +11,9 @@ pub struct MassaDBConfig {
     pub max_new_elements: usize,
     /// Thread count for slot serialization
     pub thread_count: u8,
+    /// Keep the previous values of the keys of the state written at every slot,
+    /// to be able to read the state as it was at a past final slot
+    pub enable_archive: bool,
+    /// Number of periods for which the archived state is kept
+    pub archive_retention_periods: u64,
 }
@@ -0,0 +1,66 @@
+//! Time taken by a final state write, dominated by the update of the state Merkle tree,
+//! on a database already holding `STORED_ENTRIES` entries
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use massa_db_exports::{DBBatch, MassaDBConfig, MassaDBController};
+use massa_db_worker::MassaDB;
+use massa_models::slot::Slot;
+use tempfile::TempDir;
+
+const STORED_ENTRIES: u32 = 100_000;
+const BATCH_SIZES: [u32; 3] = [10, 1_000, 10_000];
+
+fn entries_batch(range: std::ops::Range<u32>, salt: u32) -> DBBatch {
+    range
+        .map(|i| {
+            (
+                format!("ledger/{}", i).into_bytes(),
+                Some((i ^ salt).to_be_bytes().to_vec()),
+            )
+        })
+        .collect()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let temp_dir = TempDir::new().unwrap();
+    let mut db = MassaDB::new(MassaDBConfig {
+        path: temp_dir.path().to_path_buf(),
+        max_history_length: 10,
+        max_new_elements: 100,
+        thread_count: 32,
+        enable_archive: false,
+        archive_retention_periods: 0,
+    });
+    for start in (0..STORED_ENTRIES).step_by(10_000) {
+        db.write_batch(
+            entries_batch(start..start + 10_000, 0),
+            DBBatch::new(),
+            None,
+        );
+    }
+
+    let mut period = 1;
+    for batch_size in BATCH_SIZES {
+        c.bench_function(&format!("write_batch of {} entries", batch_size), |b| {
+            b.iter_batched(
+                || {
+                    period += 1;
+                    // half updates of stored entries, half new entries
+                    let updated = (period * batch_size) % (STORED_ENTRIES - batch_size);
+                    let mut batch = entries_batch(updated..updated + batch_size / 2, period);
+                    let new = STORED_ENTRIES + period * batch_size;
+                    batch.extend(entries_batch(new..new + batch_size / 2, 0));
+                    (batch, Slot::new(period as u64, 0))
+                },
+                |(batch, slot)| db.write_batch(batch, DBBatch::new(), Some(slot)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = criterion_benchmark
+);
+criterion_main!(benches);
@@ -1,3 +1,6 @@
 mod massa_db;
+mod merkle;
+mod state_export;
 
 pub use crate::massa_db::*;
+pub use crate::state_export::*;
@@ -1,15 +1,18 @@
 use massa_db_exports::{
-    DBBatch, Key, MassaDBConfig, MassaDBController, MassaDBError, MassaDirection,
-    MassaIteratorMode, StreamBatch, Value, CF_ERROR, CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY,
-    CHANGE_ID_SER_ERROR, CRUD_ERROR, METADATA_CF, OPEN_ERROR, STATE_CF, STATE_HASH_ERROR,
-    STATE_HASH_INITIAL_BYTES, STATE_HASH_KEY, VERSIONING_CF,
+    merkle_empty_hashes, BootstrapProgress, DBBatch, Key, MassaDBConfig, MassaDBController,
+    MassaDBError, MassaDirection, MassaIteratorMode, StateProof, StreamBatch, Value, ARCHIVE_CF,
+    ARCHIVE_INDEX_CF, ARCHIVE_START_ERROR, ARCHIVE_START_KEY, BOOTSTRAP_PROGRESS_KEY,
+    BOOTSTRAP_PROGRESS_SER_ERROR, CF_ERROR, CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY,
+    CHANGE_ID_SER_ERROR, CRUD_ERROR, MERKLE_CF, MERKLE_ROOT_KEY, METADATA_CF, OPEN_ERROR, STATE_CF,
+    STATE_HASH_ERROR, STATE_HASH_INITIAL_BYTES, STATE_HASH_KEY, VERSIONING_CF,
 };
-use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
+use massa_hash::{Hash, HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::{
     config::MAX_BACKUPS_TO_KEEP,
     error::ModelsError,
-    slot::{Slot, SlotDeserializer, SlotSerializer},
-    streaming_step::StreamingStep,
+    serialization::{VecU8Deserializer, VecU8Serializer},
+    slot::{Slot, SlotDeserializer, SlotSerializer, SLOT_KEY_SIZE},
+    streaming_step::{StreamingStep, StreamingStepDeserializer, StreamingStepSerializer},
 };
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use parking_lot::Mutex;
@@ -33,7 +36,9 @@ pub type MassaDB = RawMassaDB<Slot, SlotSerializer, SlotDeserializer>;
 ///
 /// The added features are:
 /// - Hash tracking with Lsm-tree, a Sparse Merkle Tree implementation
+/// - A sparse Merkle tree over STATE_CF, to prove the presence or absence of an entry
 /// - Streaming the database while it is being actively updated
+/// - Optionally, an archive of the previous values of STATE_CF to read the state at past slots
 #[derive()]
 pub struct RawMassaDB<
     ChangeID: PartialOrd + Ord + PartialEq + Eq + Clone + std::fmt::Debug,
@@ -48,12 +53,16 @@ pub struct RawMassaDB<
     pub change_history: BTreeMap<ChangeID, BTreeMap<Key, Option<Value>>>,
     /// same as change_history but for versioning
     pub change_history_versioning: BTreeMap<ChangeID, BTreeMap<Key, Option<Value>>>,
+    /// The state hash at each change_id of change_history, letting bootstrap clients cross-check their state
+    pub hash_history: BTreeMap<ChangeID, HashXof<HASH_XOF_SIZE_BYTES>>,
     /// A serializer for the ChangeID type
     pub change_id_serializer: ChangeIDSerializer,
     /// A deserializer for the ChangeID type
     pub change_id_deserializer: ChangeIDDeserializer,
     /// The current RocksDB batch of the database, in a Mutex to share it
     pub current_batch: Arc<Mutex<WriteBatch>>,
+    /// Hashes of the empty subtrees of the state Merkle tree, indexed by depth
+    pub merkle_empty_hashes: Vec<Hash>,
 }
 
 impl<ChangeID, ChangeIDSerializer, ChangeIDDeserializer> std::fmt::Debug
@@ -87,9 +96,25 @@ where
         last_state_step: &StreamingStep<Vec<u8>>,
         last_change_id: Option<ChangeID>,
     ) -> Result<StreamBatch<ChangeID>, MassaDBError> {
-        let bound_key_for_changes = match &last_state_step {
-            StreamingStep::Ongoing(max_key) => Included(max_key.clone()),
-            _ => Unbounded,
+        self.get_range_batch_to_stream(last_state_step, last_change_id, &[], None)
+    }
+
+    /// Used for bootstrap servers streaming only the keys of STATE_CF in `[range_start, range_end)`,
+    /// the client bootstrapping the other key ranges from other servers.
+    /// The changes are also restricted to the range.
+    ///
+    /// Returns a StreamBatch<ChangeID>
+    pub fn get_range_batch_to_stream(
+        &self,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<ChangeID>,
+        range_start: &[u8],
+        range_end: Option<&[u8]>,
+    ) -> Result<StreamBatch<ChangeID>, MassaDBError> {
+        let bound_key_for_changes = match (&last_state_step, range_end) {
+            (StreamingStep::Ongoing(max_key), _) => Included(max_key.clone()),
+            (_, Some(range_end)) => Excluded(range_end.to_vec()),
+            (_, None) => Unbounded,
         };
 
         let updates_on_previous_elements = match (&last_state_step, last_change_id) {
@@ -135,7 +160,7 @@ where
                                     updates.extend(
                                         changes
                                             .range((
-                                                Bound::<Vec<u8>>::Unbounded,
+                                                Included(range_start.to_vec()),
                                                 bound_key_for_changes.clone(),
                                             ))
                                             .map(|(k, v)| (k.clone(), v.clone())),
@@ -161,7 +186,7 @@ where
         if !last_state_step.finished() {
             let handle = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
 
-            // Creates an iterator from the next element after the last if defined, otherwise initialize it at the first key.
+            // Creates an iterator from the next element after the last if defined, otherwise initialize it at the start of the range.
             let db_iterator = match &last_state_step {
                 StreamingStep::Ongoing(max_key) => {
                     let mut iter = self
@@ -170,10 +195,15 @@ where
                     iter.next();
                     iter
                 }
-                _ => self.db.iterator_cf(handle, IteratorMode::Start),
+                _ => self
+                    .db
+                    .iterator_cf(handle, IteratorMode::From(range_start, Direction::Forward)),
             };
 
             for (serialized_key, serialized_value) in db_iterator.flatten() {
+                if matches!(range_end, Some(range_end) if *serialized_key >= *range_end) {
+                    break;
+                }
                 if new_elements.len() < self.config.max_new_elements {
                     new_elements.insert(serialized_key.to_vec(), serialized_value.to_vec());
                 } else {
@@ -309,6 +339,24 @@ where
         versioning_changes: BTreeMap<Key, Option<Value>>,
         change_id: Option<ChangeID>,
         reset_history: bool,
+    ) -> Result<(), MassaDBError> {
+        self.write_changes_with_batch(
+            changes,
+            versioning_changes,
+            change_id,
+            reset_history,
+            WriteBatch::default(),
+        )
+    }
+
+    /// Same as `write_changes`, additionally applying the writes of `batch` atomically with the changes
+    fn write_changes_with_batch(
+        &mut self,
+        changes: BTreeMap<Key, Option<Value>>,
+        versioning_changes: BTreeMap<Key, Option<Value>>,
+        change_id: Option<ChangeID>,
+        reset_history: bool,
+        batch: WriteBatch,
     ) -> Result<(), MassaDBError> {
         if let Some(change_id) = change_id.clone() {
             if change_id < self.get_change_id().expect(CHANGE_ID_DESER_ERROR) {
@@ -324,7 +372,7 @@ where
 
         let mut current_xor_hash = self.get_xof_db_hash();
 
-        *self.current_batch.lock() = WriteBatch::default();
+        *self.current_batch.lock() = batch;
 
         for (key, value) in changes.iter() {
             if let Some(value) = value {
@@ -350,6 +398,15 @@ where
             }
         }
 
+        let merkle_root = self.update_merkle_tree(&changes, &mut self.current_batch.lock());
+        if let Some(merkle_root) = merkle_root {
+            self.current_batch.lock().put_cf(
+                handle_metadata,
+                MERKLE_ROOT_KEY,
+                merkle_root.to_bytes(),
+            );
+        }
+
         // in versioning_changes, we have the data that we do not want to include in hash
         // e.g everything that is not in 'Active' state (so hashes remain compatibles)
         for (key, value) in versioning_changes.iter() {
@@ -405,6 +462,11 @@ where
             }
         }
 
+        self.hash_history.insert(
+            self.get_change_id().expect(CHANGE_ID_DESER_ERROR),
+            current_xor_hash,
+        );
+
         if reset_history {
             self.change_history.clear();
         }
@@ -413,6 +475,10 @@ where
             self.change_history.pop_first();
         }
 
+        while self.hash_history.len() > self.config.max_history_length {
+            self.hash_history.pop_first();
+        }
+
         while self.change_history_versioning.len() > self.config.max_history_length {
             self.change_history_versioning.pop_first();
         }
@@ -506,16 +572,101 @@ where
                 .map(|(k, v)| (k.clone(), Some(v.clone()))),
         );
 
-        self.write_changes(
+        // the cursors are saved with the data they point to, to resume the bootstrap after a restart
+        let step_serializer = StreamingStepSerializer::new(VecU8Serializer::new());
+        let mut progress_bytes = Vec::new();
+        step_serializer
+            .serialize(&new_cursor, &mut progress_bytes)
+            .expect(BOOTSTRAP_PROGRESS_SER_ERROR);
+        step_serializer
+            .serialize(&new_cursor_versioning, &mut progress_bytes)
+            .expect(BOOTSTRAP_PROGRESS_SER_ERROR);
+        let mut progress_batch = WriteBatch::default();
+        progress_batch.put_cf(
+            self.db.cf_handle(METADATA_CF).expect(CF_ERROR),
+            BOOTSTRAP_PROGRESS_KEY,
+            progress_bytes,
+        );
+
+        self.write_changes_with_batch(
             changes,
             versioning_changes,
             Some(stream_changes.change_id),
             true,
+            progress_batch,
         )?;
 
         Ok((new_cursor, new_cursor_versioning))
     }
 
+    /// Get the progress saved by `write_batch_bootstrap_client`, `None` if no bootstrap is in progress.
+    /// Fails if the saved progress is corrupted.
+    pub fn get_bootstrap_progress(
+        &self,
+    ) -> Result<Option<BootstrapProgress<ChangeID>>, MassaDBError> {
+        let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        let Some(progress_bytes) = self
+            .db
+            .get_cf(handle_metadata, BOOTSTRAP_PROGRESS_KEY)
+            .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))?
+        else {
+            return Ok(None);
+        };
+
+        let step_deserializer = StreamingStepDeserializer::new(VecU8Deserializer::new(
+            Included(u64::MIN),
+            Included(u64::MAX),
+        ));
+        let (rest, last_state_step) = step_deserializer
+            .deserialize::<DeserializeError>(&progress_bytes)
+            .map_err(|err| MassaDBError::BootstrapProgressError(err.to_string()))?;
+        let (rest, last_versioning_step) = step_deserializer
+            .deserialize::<DeserializeError>(rest)
+            .map_err(|err| MassaDBError::BootstrapProgressError(err.to_string()))?;
+        if !rest.is_empty() {
+            return Err(MassaDBError::BootstrapProgressError(
+                "unexpected trailing bytes".to_string(),
+            ));
+        }
+
+        Ok(Some(BootstrapProgress {
+            last_state_step,
+            last_versioning_step,
+            last_change_id: self.get_change_id().expect(CHANGE_ID_DESER_ERROR),
+        }))
+    }
+
+    /// Remove the bootstrap progress
+    pub fn clear_bootstrap_progress(&self) -> Result<(), MassaDBError> {
+        let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        self.db
+            .delete_cf(handle_metadata, BOOTSTRAP_PROGRESS_KEY)
+            .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))
+    }
+
+    /// Write a stream_batch of STATE_CF entries received from one of the bootstrap servers each streaming a key range.
+    /// The change_id is left unchanged: the ranges are streamed at different change_ids,
+    /// and are then brought to the same one by a last batch written with `write_batch_bootstrap_client`.
+    pub fn write_range_batch_bootstrap_client(
+        &mut self,
+        stream_changes: StreamBatch<ChangeID>,
+    ) -> Result<(), MassaDBError> {
+        let mut changes = BTreeMap::new();
+        changes.extend(stream_changes.updates_on_previous_elements);
+        changes.extend(
+            stream_changes
+                .new_elements
+                .into_iter()
+                .map(|(k, v)| (k, Some(v))),
+        );
+        self.write_changes(changes, BTreeMap::new(), None, true)
+    }
+
+    /// Get the state hash at the end of a recent change_id, `None` if it is not in the history
+    pub fn get_xof_db_hash_at(&self, change_id: &ChangeID) -> Option<HashXof<HASH_XOF_SIZE_BYTES>> {
+        self.hash_history.get(change_id).copied()
+    }
+
     /// To be called just after bootstrap
     pub fn recompute_db_hash(&mut self) -> Result<(), MassaDBError> {
         let handle_state = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
@@ -586,6 +737,9 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
                 ColumnFamilyDescriptor::new(STATE_CF, Options::default()),
                 ColumnFamilyDescriptor::new(METADATA_CF, Options::default()),
                 ColumnFamilyDescriptor::new(VERSIONING_CF, Options::default()),
+                ColumnFamilyDescriptor::new(MERKLE_CF, Options::default()),
+                ColumnFamilyDescriptor::new(ARCHIVE_CF, Options::default()),
+                ColumnFamilyDescriptor::new(ARCHIVE_INDEX_CF, Options::default()),
             ],
         )
         .expect(OPEN_ERROR);
@@ -603,9 +757,11 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             config,
             change_history: BTreeMap::new(),
             change_history_versioning: BTreeMap::new(),
+            hash_history: BTreeMap::new(),
             change_id_serializer: SlotSerializer::new(),
             change_id_deserializer,
             current_batch,
+            merkle_empty_hashes: merkle_empty_hashes(),
         };
 
         if massa_db.get_change_id().is_err() {
@@ -615,8 +771,258 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             });
         }
 
+        // Databases created before the state Merkle tree existed have no root yet
+        let handle_metadata = massa_db.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        let handle_state = massa_db.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        if massa_db
+            .db
+            .get_cf(handle_metadata, MERKLE_ROOT_KEY)
+            .expect(CRUD_ERROR)
+            .is_none()
+            && massa_db
+                .db
+                .iterator_cf(handle_state, IteratorMode::Start)
+                .next()
+                .is_some()
+        {
+            massa_db.rebuild_merkle_tree().expect(CRUD_ERROR);
+        }
+
+        // An archive left by a previous run misses the changes written while it was disabled
+        if !massa_db.config.enable_archive && massa_db.get_archive_start().is_some() {
+            massa_db.clear_archive().expect(CRUD_ERROR);
+        }
+
         massa_db
     }
+
+    /// Get the oldest slot at which the state can be read from the archive
+    pub fn get_archive_start(&self) -> Option<Slot> {
+        let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+
+        self.db
+            .get_cf(handle_metadata, ARCHIVE_START_KEY)
+            .expect(CRUD_ERROR)
+            .map(|start_bytes| {
+                Slot::from_bytes_key(
+                    start_bytes
+                        .as_slice()
+                        .try_into()
+                        .expect(ARCHIVE_START_ERROR),
+                )
+            })
+    }
+
+    /// Build the writes archiving the values that the keys modified by `changes` had before slot `change_id`,
+    /// and pruning the slots that fall out of the retention window.
+    ///
+    /// The returned batch must be written atomically with the changes themselves,
+    /// so that the archive never diverges from the state after an interruption.
+    fn archive_changes(
+        &self,
+        changes: &DBBatch,
+        change_id: Slot,
+    ) -> Result<WriteBatch, MassaDBError> {
+        let handle_state = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        let handle_archive = self.db.cf_handle(ARCHIVE_CF).expect(CF_ERROR);
+        let handle_archive_index = self.db.cf_handle(ARCHIVE_INDEX_CF).expect(CF_ERROR);
+
+        let mut batch = WriteBatch::default();
+        let slot_key = change_id.to_bytes_key();
+
+        for key in changes.keys() {
+            let archive_key = archive_key(key, &slot_key);
+
+            // a key written several times during a slot keeps its value from before the slot
+            if self
+                .db
+                .get_cf(handle_archive, &archive_key)
+                .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))?
+                .is_some()
+            {
+                continue;
+            }
+
+            let previous_value = self
+                .db
+                .get_cf(handle_state, key)
+                .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))?;
+            batch.put_cf(
+                handle_archive,
+                &archive_key,
+                serialize_archived_value(previous_value.as_deref()),
+            );
+            batch.put_cf(
+                handle_archive_index,
+                [&slot_key[..], &archive_key[..]].concat(),
+                [],
+            );
+        }
+
+        let mut archive_start = match self.get_archive_start() {
+            Some(archive_start) => archive_start,
+            None => {
+                // the state is archived from the slot at which the archive was started
+                let archive_start = self.get_change_id().unwrap_or(change_id);
+                batch.put_cf(
+                    handle_metadata,
+                    ARCHIVE_START_KEY,
+                    archive_start.to_bytes_key(),
+                );
+                archive_start
+            }
+        };
+
+        // prune the slots that are older than the retention window
+        if let Some(cutoff_period) = change_id
+            .period
+            .checked_sub(self.config.archive_retention_periods)
+        {
+            let cutoff = Slot::new(cutoff_period, 0);
+            if archive_start < cutoff {
+                let cutoff_key = cutoff.to_bytes_key();
+                for (index_key, _) in self
+                    .db
+                    .iterator_cf(handle_archive_index, IteratorMode::Start)
+                    .flatten()
+                {
+                    if index_key[..SLOT_KEY_SIZE] >= cutoff_key[..] {
+                        break;
+                    }
+                    batch.delete_cf(handle_archive, &index_key[SLOT_KEY_SIZE..]);
+                    batch.delete_cf(handle_archive_index, &index_key);
+                }
+                archive_start = cutoff;
+                batch.put_cf(
+                    handle_metadata,
+                    ARCHIVE_START_KEY,
+                    archive_start.to_bytes_key(),
+                );
+            }
+        }
+
+        Ok(batch)
+    }
+
+    /// Remove every archived value, the archive restarts at the next archived write
+    fn clear_archive(&self) -> Result<(), MassaDBError> {
+        let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+
+        let mut batch = WriteBatch::default();
+        for handle_str in [ARCHIVE_CF, ARCHIVE_INDEX_CF] {
+            let handle = self.db.cf_handle(handle_str).expect(CF_ERROR);
+            for (key, _) in self.db.iterator_cf(handle, IteratorMode::Start).flatten() {
+                batch.delete_cf(handle, key);
+            }
+        }
+        batch.delete_cf(handle_metadata, ARCHIVE_START_KEY);
+
+        self.db
+            .write(batch)
+            .map_err(|e| MassaDBError::RocksDBError(format!("Can't write batch to disk: {}", e)))
+    }
+
+    /// Get the value of a key of STATE_CF as it was at the end of a past final slot
+    pub fn get_state_value_at_slot(
+        &self,
+        key: &[u8],
+        slot: Slot,
+    ) -> Result<Option<Value>, MassaDBError> {
+        if !self.config.enable_archive {
+            return Err(MassaDBError::ArchiveError(
+                "the archive is disabled on this node".to_string(),
+            ));
+        }
+        let change_id = self
+            .get_change_id()
+            .map_err(|e| MassaDBError::InvalidChangeID(e.to_string()))?;
+        if slot > change_id {
+            return Err(MassaDBError::ArchiveError(format!(
+                "slot {} is after the latest final slot {}",
+                slot, change_id
+            )));
+        }
+        match self.get_archive_start() {
+            Some(archive_start) if slot >= archive_start => {}
+            Some(archive_start) => {
+                return Err(MassaDBError::ArchiveError(format!(
+                    "slot {} is before the oldest archived slot {}",
+                    slot, archive_start
+                )))
+            }
+            // nothing was written since the archive was started
+            None if slot == change_id => {}
+            None => {
+                return Err(MassaDBError::ArchiveError(format!(
+                    "slot {} is before the oldest archived slot {}",
+                    slot, change_id
+                )))
+            }
+        }
+
+        // The value at the end of the slot is the one replaced by the first change after it,
+        // or the current value if the key was not changed since.
+        let next_slot = slot
+            .get_next_slot(self.config.thread_count)
+            .map_err(|e| MassaDBError::ArchiveError(e.to_string()))?;
+        let prefix = archive_key_prefix(key);
+        let seek_key = archive_key(key, &next_slot.to_bytes_key());
+
+        let handle_archive = self.db.cf_handle(ARCHIVE_CF).expect(CF_ERROR);
+        match self
+            .db
+            .iterator_cf(
+                handle_archive,
+                IteratorMode::From(&seek_key, Direction::Forward),
+            )
+            .next()
+        {
+            Some(Ok((archive_key, archived_value)))
+                if archive_key.starts_with(&prefix)
+                    && archive_key.len() == prefix.len() + SLOT_KEY_SIZE =>
+            {
+                deserialize_archived_value(&archived_value)
+            }
+            Some(Err(e)) => Err(MassaDBError::RocksDBError(format!("{:?}", e))),
+            _ => {
+                let handle_state = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+                self.db
+                    .get_cf(handle_state, key)
+                    .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))
+            }
+        }
+    }
+}
+
+/// Prefix of the archive keys of a key of STATE_CF: its length followed by the key itself,
+/// so that the archived slots of a key are contiguous and never mixed with those of a longer key
+fn archive_key_prefix(key: &[u8]) -> Vec<u8> {
+    [&(key.len() as u32).to_be_bytes()[..], key].concat()
+}
+
+/// Archive key of a key of STATE_CF changed at a given slot
+fn archive_key(key: &[u8], slot_key: &[u8; SLOT_KEY_SIZE]) -> Vec<u8> {
+    [&archive_key_prefix(key)[..], &slot_key[..]].concat()
+}
+
+/// Archived values are prefixed with 1 if the key was present, or are a single 0 if it was absent
+fn serialize_archived_value(value: Option<&[u8]>) -> Vec<u8> {
+    match value {
+        Some(value) => [&[1u8][..], value].concat(),
+        None => vec![0u8],
+    }
+}
+
+/// Inverse of `serialize_archived_value`
+fn deserialize_archived_value(bytes: &[u8]) -> Result<Option<Value>, MassaDBError> {
+    match bytes.split_first() {
+        Some((1, value)) => Ok(Some(value.to_vec())),
+        Some((0, [])) => Ok(None),
+        _ => Err(MassaDBError::ArchiveError(
+            "critical: archived value is corrupted".to_string(),
+        )),
+    }
 }
 
 impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
@@ -668,7 +1074,11 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
 
     /// Writes the batch to the DB
     fn write_batch(&mut self, batch: DBBatch, versioning_batch: DBBatch, change_id: Option<Slot>) {
-        self.write_changes(batch, versioning_batch, change_id, false)
+        let archive_batch = match (self.config.enable_archive, change_id) {
+            (true, Some(change_id)) => self.archive_changes(&batch, change_id).expect(CRUD_ERROR),
+            _ => WriteBatch::default(),
+        };
+        self.write_changes_with_batch(batch, versioning_batch, change_id, false, archive_batch)
             .expect(CRUD_ERROR);
     }
 
@@ -711,6 +1121,9 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
     fn reset(&mut self, slot: Slot) {
         self.set_initial_change_id(slot);
         self.change_history.clear();
+        self.hash_history.clear();
+        self.clear_archive().expect(CRUD_ERROR);
+        self.clear_bootstrap_progress().expect(CRUD_ERROR);
     }
 
     fn get_cf(&self, handle_cf: &str, key: Key) -> Result<Option<Value>, MassaDBError> {
@@ -784,16 +1197,51 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.get_xof_db_hash()
     }
 
+    /// Get the state hash at the end of a recent slot, `None` if it is not in the history
+    fn get_xof_db_hash_at(&self, slot: Slot) -> Option<HashXof<HASH_XOF_SIZE_BYTES>> {
+        self.get_xof_db_hash_at(&slot)
+    }
+
     /// Get the current change_id attached to the database.
     fn get_change_id(&self) -> Result<Slot, ModelsError> {
         self.get_change_id()
     }
 
+    /// Get the current root of the state Merkle tree
+    fn get_merkle_root(&self) -> Hash {
+        self.get_merkle_root()
+    }
+
+    /// Get the value of a key of STATE_CF along with its Merkle inclusion (or non-inclusion) proof,
+    /// at the current change_id
+    fn get_state_proof(&self, key: Key) -> Result<StateProof, MassaDBError> {
+        let (root, value, siblings) = self.get_merkle_proof(&key)?;
+        Ok(StateProof {
+            slot: self
+                .get_change_id()
+                .map_err(|e| MassaDBError::InvalidChangeID(e.to_string()))?,
+            root,
+            key,
+            value,
+            siblings,
+        })
+    }
+
     /// Set the initial change_id. This function should only be called at startup/reset, as it does not batch this set with other changes.
     fn set_initial_change_id(&self, change_id: Slot) {
         self.set_initial_change_id(change_id)
     }
 
+    /// Get the oldest slot at which the state can be read from the archive
+    fn get_archive_start(&self) -> Option<Slot> {
+        self.get_archive_start()
+    }
+
+    /// Get the value of a key of STATE_CF as it was at the end of a past final slot
+    fn get_state_value_at_slot(&self, key: Key, slot: Slot) -> Result<Option<Value>, MassaDBError> {
+        self.get_state_value_at_slot(&key, slot)
+    }
+
     /// Flushes the underlying db.
     fn flush(&self) -> Result<(), MassaDBError> {
         self.db
@@ -807,9 +1255,35 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         stream_changes: StreamBatch<Slot>,
         stream_changes_versioning: StreamBatch<Slot>,
     ) -> Result<(StreamingStep<Key>, StreamingStep<Key>), MassaDBError> {
+        // the bootstrapped state does not come with its history
+        if self.get_archive_start().is_some() {
+            self.clear_archive()?;
+        }
         self.write_batch_bootstrap_client(stream_changes, stream_changes_versioning)
     }
 
+    /// Get the progress of the bootstrap writing the database, `None` if no bootstrap was interrupted
+    fn get_bootstrap_progress(&self) -> Result<Option<BootstrapProgress<Slot>>, MassaDBError> {
+        self.get_bootstrap_progress()
+    }
+
+    /// Forget the bootstrap progress, once the bootstrap is complete
+    fn clear_bootstrap_progress(&mut self) -> Result<(), MassaDBError> {
+        RawMassaDB::clear_bootstrap_progress(self)
+    }
+
+    /// Write a stream_batch of STATE_CF entries of a key range received from one of several bootstrap servers
+    fn write_range_batch_bootstrap_client(
+        &mut self,
+        stream_changes: StreamBatch<Slot>,
+    ) -> Result<(), MassaDBError> {
+        // the bootstrapped state does not come with its history
+        if self.get_archive_start().is_some() {
+            self.clear_archive()?;
+        }
+        self.write_range_batch_bootstrap_client(stream_changes)
+    }
+
     /// Used for bootstrap servers (get a new batch of data from STATE_CF to stream to the client)
     ///
     /// Returns a StreamBatch<Slot>
@@ -821,6 +1295,19 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.get_batch_to_stream(last_state_step, last_change_id)
     }
 
+    /// Used for bootstrap servers streaming only the keys of STATE_CF in `[range_start, range_end)`
+    ///
+    /// Returns a StreamBatch<Slot>
+    fn get_range_batch_to_stream(
+        &self,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<Slot>,
+        range_start: &[u8],
+        range_end: Option<&[u8]>,
+    ) -> Result<StreamBatch<Slot>, MassaDBError> {
+        self.get_range_batch_to_stream(last_state_step, last_change_id, range_start, range_end)
+    }
+
     /// Used for bootstrap servers (get a new batch of data from VERSIONING_CF to stream to the client)
     ///
     /// Returns a StreamBatch<Slot>
@@ -837,3 +1324,188 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.recompute_db_hash()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::MassaDB;
+    use massa_db_exports::{
+        DBBatch, MassaDBConfig, MassaDBController, MassaDBError, StreamBatch,
+        BOOTSTRAP_PROGRESS_KEY, METADATA_CF,
+    };
+    use massa_models::{slot::Slot, streaming_step::StreamingStep};
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_state_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut db = MassaDB::new(MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_new_elements: 100,
+            thread_count: 2,
+            enable_archive: true,
+            archive_retention_periods: 2,
+        });
+        let key = b"ledger/a".to_vec();
+        let write = |db: &mut MassaDB, value: Option<u8>, slot: Slot| {
+            let mut batch = DBBatch::new();
+            batch.insert(key.clone(), value.map(|v| vec![v]));
+            db.write_batch(batch, DBBatch::new(), Some(slot));
+        };
+
+        write(&mut db, Some(1), Slot::new(1, 0));
+        write(&mut db, Some(2), Slot::new(2, 0));
+        write(&mut db, None, Slot::new(3, 0));
+        assert_eq!(db.get_archive_start(), Some(Slot::new(1, 0)));
+        let value_at = |db: &MassaDB, slot: Slot| db.get_state_value_at_slot(&key, slot);
+        assert_eq!(value_at(&db, Slot::new(1, 0)).unwrap(), Some(vec![1]));
+        assert_eq!(value_at(&db, Slot::new(1, 1)).unwrap(), Some(vec![1]));
+        assert_eq!(value_at(&db, Slot::new(2, 1)).unwrap(), Some(vec![2]));
+        assert_eq!(value_at(&db, Slot::new(3, 0)).unwrap(), None);
+        assert!(value_at(&db, Slot::new(3, 1)).is_err());
+
+        // slots older than the retention window are pruned
+        let mut batch = DBBatch::new();
+        batch.insert(b"ledger/b".to_vec(), Some(vec![0]));
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(4, 0)));
+        assert_eq!(db.get_archive_start(), Some(Slot::new(2, 0)));
+        assert!(value_at(&db, Slot::new(1, 1)).is_err());
+        assert_eq!(value_at(&db, Slot::new(2, 0)).unwrap(), Some(vec![2]));
+        assert_eq!(value_at(&db, Slot::new(4, 0)).unwrap(), None);
+
+        // a reset drops the archive
+        db.reset(Slot::new(5, 0));
+        assert_eq!(db.get_archive_start(), None);
+        assert!(value_at(&db, Slot::new(4, 0)).is_err());
+    }
+
+    #[test]
+    fn test_range_batch_to_stream() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_new_elements: 2,
+            thread_count: 2,
+            enable_archive: false,
+            archive_retention_periods: 0,
+        };
+        let mut db = MassaDB::new(config);
+        let mut batch = DBBatch::new();
+        for key in [b"a", b"b", b"c", b"d", b"e"] {
+            batch.insert(key.to_vec(), Some(vec![0]));
+        }
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(1, 0)));
+        let hash_at_1 = db.get_xof_db_hash();
+
+        // only the keys of the range are streamed
+        let part = db
+            .get_range_batch_to_stream(&StreamingStep::Started, None, b"b", Some(b"e"))
+            .unwrap();
+        assert_eq!(
+            part.new_elements.keys().cloned().collect::<Vec<_>>(),
+            vec![b"b".to_vec(), b"c".to_vec()]
+        );
+        let step = StreamingStep::Ongoing(b"c".to_vec());
+
+        // changes are only streamed for the keys of the range already sent
+        let mut batch = DBBatch::new();
+        for key in [b"a", b"b", b"d"] {
+            batch.insert(key.to_vec(), Some(vec![1]));
+        }
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(1, 1)));
+        let part = db
+            .get_range_batch_to_stream(&step, Some(Slot::new(1, 0)), b"b", Some(b"e"))
+            .unwrap();
+        assert_eq!(
+            part.updates_on_previous_elements
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>(),
+            vec![b"b".to_vec()]
+        );
+        assert_eq!(
+            part.new_elements.keys().cloned().collect::<Vec<_>>(),
+            vec![b"d".to_vec()]
+        );
+
+        // the state hash of recent slots is kept
+        assert_eq!(db.get_xof_db_hash_at(&Slot::new(1, 0)), Some(hash_at_1));
+        assert_eq!(
+            db.get_xof_db_hash_at(&Slot::new(1, 1)),
+            Some(db.get_xof_db_hash())
+        );
+        assert_eq!(db.get_xof_db_hash_at(&Slot::new(2, 0)), None);
+    }
+
+    #[test]
+    fn test_bootstrap_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_new_elements: 100,
+            thread_count: 2,
+            enable_archive: false,
+            archive_retention_periods: 0,
+        };
+        let mut db = MassaDB::new(config.clone());
+        assert_eq!(db.get_bootstrap_progress().unwrap(), None);
+
+        let state_part = StreamBatch {
+            new_elements: BTreeMap::from([(b"a".to_vec(), vec![0]), (b"b".to_vec(), vec![0])]),
+            updates_on_previous_elements: BTreeMap::new(),
+            change_id: Slot::new(3, 1),
+        };
+        let versioning_part = StreamBatch {
+            new_elements: BTreeMap::new(),
+            updates_on_previous_elements: BTreeMap::new(),
+            change_id: Slot::new(3, 1),
+        };
+        db.write_batch_bootstrap_client(state_part, versioning_part)
+            .unwrap();
+        drop(db);
+
+        // the progress survives a restart
+        let mut db = MassaDB::new(config);
+        let progress = db.get_bootstrap_progress().unwrap().unwrap();
+        assert_eq!(
+            progress.last_state_step,
+            StreamingStep::Ongoing(b"b".to_vec())
+        );
+        assert_eq!(progress.last_versioning_step, StreamingStep::Finished(None));
+        assert_eq!(progress.last_change_id, Slot::new(3, 1));
+
+        // a reset restarts the bootstrap from scratch
+        db.reset(Slot::new(0, 1));
+        assert_eq!(db.get_bootstrap_progress().unwrap(), None);
+    }
+
+    #[test]
+    fn test_corrupted_bootstrap_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_new_elements: 100,
+            thread_count: 2,
+            enable_archive: false,
+            archive_retention_periods: 0,
+        };
+        let mut db = MassaDB::new(config);
+
+        let handle_metadata = db.db.cf_handle(METADATA_CF).unwrap();
+        db.db
+            .put_cf(handle_metadata, BOOTSTRAP_PROGRESS_KEY, [0xff, 0xff])
+            .unwrap();
+        assert!(matches!(
+            db.get_bootstrap_progress(),
+            Err(MassaDBError::BootstrapProgressError(_))
+        ));
+
+        // a reset drops the corrupted progress
+        db.reset(Slot::new(0, 1));
+        assert_eq!(db.get_bootstrap_progress().unwrap(), None);
+    }
+}
@@ -0,0 +1,443 @@
+//! Sparse Merkle tree maintained next to STATE_CF, see `massa_db_exports::merkle` for the hashing rules.
+//!
+//! To keep the storage linear in the number of entries, MERKLE_CF only holds:
+//! - the leaves, keyed by `MERKLE_LEAF_PREFIX` + path
+//! - the internal nodes having at least two leaves below them, keyed by `MERKLE_NODE_PREFIX` + depth + truncated path
+//!
+//! The hash of a subtree that is not stored is either the empty hash of its level (no leaf below it),
+//! or is recomputed from the only leaf below it.
+
+use crate::massa_db::RawMassaDB;
+use massa_db_exports::{
+    merkle_key_path, merkle_leaf_hash, merkle_node_hash, merkle_path_bit, Key, MassaDBError,
+    MerklePath, Value, CF_ERROR, CRUD_ERROR, MERKLE_CF, MERKLE_ROOT_ERROR, MERKLE_ROOT_KEY,
+    MERKLE_TREE_DEPTH, MERKLE_TREE_ERROR, METADATA_CF, STATE_CF,
+};
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_serialization::{Deserializer, Serializer};
+use rocksdb::{Direction, IteratorMode, WriteBatch};
+use std::collections::BTreeMap;
+use std::ops::Bound::{Excluded, Unbounded};
+
+const MERKLE_LEAF_PREFIX: u8 = b'l';
+const MERKLE_NODE_PREFIX: u8 = b'n';
+
+/// Number of state entries hashed per batch when rebuilding the tree
+const MERKLE_REBUILD_CHUNK_SIZE: usize = 10_000;
+
+/// Keep only the first `depth` bits of a path
+fn merkle_truncate_path(path: &MerklePath, depth: usize) -> MerklePath {
+    let mut res = [0u8; HASH_SIZE_BYTES];
+    let full_bytes = depth / 8;
+    res[..full_bytes].copy_from_slice(&path[..full_bytes]);
+    if depth % 8 != 0 {
+        res[full_bytes] = path[full_bytes] & (0xFFu8 << (8 - depth % 8));
+    }
+    res
+}
+
+fn merkle_leaf_key(path: &MerklePath) -> Vec<u8> {
+    let mut key = Vec::with_capacity(1 + HASH_SIZE_BYTES);
+    key.push(MERKLE_LEAF_PREFIX);
+    key.extend(path);
+    key
+}
+
+fn merkle_node_key(depth: usize, path: &MerklePath) -> Vec<u8> {
+    let mut key = Vec::with_capacity(3 + HASH_SIZE_BYTES);
+    key.push(MERKLE_NODE_PREFIX);
+    key.extend((depth as u16).to_be_bytes());
+    key.extend(&merkle_truncate_path(path, depth)[..(depth + 7) / 8]);
+    key
+}
+
+/// Number of leading bits shared by two paths
+fn merkle_common_prefix_len(a: &MerklePath, b: &MerklePath) -> usize {
+    for (i, (byte_a, byte_b)) in a.iter().zip(b.iter()).enumerate() {
+        let diff = byte_a ^ byte_b;
+        if diff != 0 {
+            return i * 8 + diff.leading_zeros() as usize;
+        }
+    }
+    MERKLE_TREE_DEPTH
+}
+
+fn hash_from_db_bytes(bytes: &[u8]) -> Hash {
+    Hash::from_bytes(bytes.try_into().expect(MERKLE_TREE_ERROR))
+}
+
+impl<ChangeID, ChangeIDSerializer, ChangeIDDeserializer>
+    RawMassaDB<ChangeID, ChangeIDSerializer, ChangeIDDeserializer>
+where
+    ChangeID: PartialOrd + Ord + PartialEq + Eq + Clone + std::fmt::Debug,
+    ChangeIDSerializer: Serializer<ChangeID>,
+    ChangeIDDeserializer: Deserializer<ChangeID>,
+{
+    /// Get the current root of the state Merkle tree
+    pub fn get_merkle_root(&self) -> Hash {
+        let handle = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        match self.db.get_cf(handle, MERKLE_ROOT_KEY).expect(CRUD_ERROR) {
+            Some(bytes) => Hash::from_bytes(bytes.as_slice().try_into().expect(MERKLE_ROOT_ERROR)),
+            None => self.merkle_empty_hashes[0],
+        }
+    }
+
+    /// Get the current value of a STATE_CF key, the siblings of its Merkle path (leaf level first) and the root
+    pub fn get_merkle_proof(
+        &self,
+        key: &[u8],
+    ) -> Result<(Hash, Option<Value>, Vec<Option<Hash>>), MassaDBError> {
+        let handle_state = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        let value = self
+            .db
+            .get_cf(handle_state, key)
+            .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))?;
+        let path = merkle_key_path(key);
+        let siblings = (0..MERKLE_TREE_DEPTH)
+            .rev()
+            .map(|depth| {
+                let (hash, count) =
+                    self.get_merkle_subtree(depth + 1, &Self::merkle_sibling_path(&path, depth));
+                (count > 0).then_some(hash)
+            })
+            .collect();
+        Ok((self.get_merkle_root(), value, siblings))
+    }
+
+    /// Apply the changes of a STATE_CF batch to the Merkle tree.
+    /// The modified nodes are added to `batch` and the new root is returned, if anything changed.
+    pub(crate) fn update_merkle_tree(
+        &self,
+        changes: &BTreeMap<Key, Option<Value>>,
+        batch: &mut WriteBatch,
+    ) -> Option<Hash> {
+        let leaves: BTreeMap<MerklePath, (Hash, u8)> = changes
+            .iter()
+            .map(|(key, value)| {
+                let leaf = match value {
+                    Some(value) => (merkle_leaf_hash(key, value), 1),
+                    None => (self.merkle_empty_hashes[MERKLE_TREE_DEPTH], 0),
+                };
+                (merkle_key_path(key), leaf)
+            })
+            .collect();
+
+        // The longest prefix a leaf shares with any other leaf, stored or in the batch,
+        // is the one shared with its closest neighbours in path order
+        let leaves = leaves
+            .iter()
+            .map(|(path, (hash, count))| {
+                let batch_neighbours = leaves
+                    .range(..path)
+                    .next_back()
+                    .into_iter()
+                    .chain(leaves.range((Excluded(path), Unbounded)).next())
+                    .map(|(neighbour, _)| *neighbour);
+                let shared_depth = batch_neighbours
+                    .chain(self.merkle_stored_neighbours(path))
+                    .map(|neighbour| merkle_common_prefix_len(path, &neighbour))
+                    .max()
+                    .unwrap_or(0);
+                (*path, (*hash, *count, shared_depth))
+            })
+            .collect();
+        self.update_merkle_leaves(leaves, batch)
+    }
+
+    /// Paths of the stored leaves right before and right after `path`, not including `path` itself
+    fn merkle_stored_neighbours(&self, path: &MerklePath) -> Vec<MerklePath> {
+        let handle_merkle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+        let leaf_key = merkle_leaf_key(path);
+        [Direction::Forward, Direction::Reverse]
+            .into_iter()
+            .filter_map(|direction| {
+                self.db
+                    .iterator_cf(handle_merkle, IteratorMode::From(&leaf_key, direction))
+                    .flatten()
+                    .take(2)
+                    .map(|(key, _)| key)
+                    .find(|key| key.as_ref() != leaf_key.as_slice())
+                    .filter(|key| key.len() == 1 + HASH_SIZE_BYTES && key[0] == MERKLE_LEAF_PREFIX)
+                    .map(|key| key[1..].try_into().expect(MERKLE_TREE_ERROR))
+            })
+            .collect()
+    }
+
+    /// Rebuild the whole Merkle tree from the content of STATE_CF.
+    /// The root is only written at the end, so that an interrupted rebuild is started again on next startup.
+    pub(crate) fn rebuild_merkle_tree(&self) -> Result<(), MassaDBError> {
+        let handle_state = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        let handle_merkle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+        let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+
+        let mut batch = WriteBatch::default();
+        for (key, _) in self
+            .db
+            .iterator_cf(handle_merkle, IteratorMode::Start)
+            .flatten()
+        {
+            batch.delete_cf(handle_merkle, key);
+        }
+        batch.delete_cf(handle_metadata, MERKLE_ROOT_KEY);
+        self.write_merkle_batch(batch)?;
+
+        let mut root = None;
+        let mut chunk = BTreeMap::new();
+        let mut entries = self
+            .db
+            .iterator_cf(handle_state, IteratorMode::Start)
+            .flatten()
+            .peekable();
+        while let Some((key, value)) = entries.next() {
+            chunk.insert(key.to_vec(), Some(value.to_vec()));
+            if chunk.len() >= MERKLE_REBUILD_CHUNK_SIZE || entries.peek().is_none() {
+                let mut batch = WriteBatch::default();
+                root = self.update_merkle_tree(&chunk, &mut batch);
+                self.write_merkle_batch(batch)?;
+                chunk.clear();
+            }
+        }
+
+        if let Some(root) = root {
+            let mut batch = WriteBatch::default();
+            batch.put_cf(handle_metadata, MERKLE_ROOT_KEY, root.to_bytes());
+            self.write_merkle_batch(batch)?;
+        }
+        Ok(())
+    }
+
+    fn write_merkle_batch(&self, batch: WriteBatch) -> Result<(), MassaDBError> {
+        self.db
+            .write(batch)
+            .map_err(|e| MassaDBError::RocksDBError(format!("Can't write batch to disk: {}", e)))
+    }
+
+    /// Path of the sibling, at `depth + 1`, of the node at `depth + 1` on the way to `path`
+    fn merkle_sibling_path(path: &MerklePath, depth: usize) -> MerklePath {
+        let mut sibling = merkle_truncate_path(path, depth + 1);
+        sibling[depth / 8] ^= 1 << (7 - depth % 8);
+        sibling
+    }
+
+    /// Recompute the tree from updated leaves, level by level.
+    /// Each node is associated to the number of leaves below it, capped to 2,
+    /// and to the longest prefix its leaves share with the other leaves of the tree.
+    /// Deeper than that prefix, a node has no sibling and was not stored before the batch, so the DB is not read.
+    fn update_merkle_leaves(
+        &self,
+        mut level: BTreeMap<MerklePath, (Hash, u8, usize)>,
+        batch: &mut WriteBatch,
+    ) -> Option<Hash> {
+        if level.is_empty() {
+            return None;
+        }
+        let handle_merkle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+
+        for depth in (0..MERKLE_TREE_DEPTH).rev() {
+            // `level` holds the modified nodes at depth + 1
+            let mut parents: BTreeMap<MerklePath, (Hash, u8, usize)> = BTreeMap::new();
+            for (path, (hash, count, shared_depth)) in level.iter() {
+                if depth + 1 == MERKLE_TREE_DEPTH {
+                    if *count > 0 {
+                        batch.put_cf(handle_merkle, merkle_leaf_key(path), hash.to_bytes());
+                    } else {
+                        batch.delete_cf(handle_merkle, merkle_leaf_key(path));
+                    }
+                } else if depth < *shared_depth {
+                    if *count > 1 {
+                        batch.put_cf(
+                            handle_merkle,
+                            merkle_node_key(depth + 1, path),
+                            hash.to_bytes(),
+                        );
+                    } else {
+                        batch.delete_cf(handle_merkle, merkle_node_key(depth + 1, path));
+                    }
+                }
+
+                let parent_path = merkle_truncate_path(path, depth);
+                if parents.contains_key(&parent_path) {
+                    continue;
+                }
+                let sibling_path = Self::merkle_sibling_path(path, depth);
+                let (sibling_hash, sibling_count, sibling_shared_depth) = if depth > *shared_depth {
+                    (self.merkle_empty_hashes[depth + 1], 0, 0)
+                } else {
+                    match level.get(&sibling_path) {
+                        Some(sibling) => *sibling,
+                        None => {
+                            let (hash, count) = self.get_merkle_subtree(depth + 1, &sibling_path);
+                            (hash, count, 0)
+                        }
+                    }
+                };
+                let parent_hash = if merkle_path_bit(path, depth) {
+                    merkle_node_hash(&sibling_hash, hash)
+                } else {
+                    merkle_node_hash(hash, &sibling_hash)
+                };
+                parents.insert(
+                    parent_path,
+                    (
+                        parent_hash,
+                        (count + sibling_count).min(2),
+                        (*shared_depth).max(sibling_shared_depth),
+                    ),
+                );
+            }
+            level = parents;
+        }
+
+        level.get(&[0u8; HASH_SIZE_BYTES]).map(|(root, _, _)| *root)
+    }
+
+    /// Get the hash of the stored subtree at `depth` on the way to `path`,
+    /// with the number of leaves below it, capped to 2.
+    fn get_merkle_subtree(&self, depth: usize, path: &MerklePath) -> (Hash, u8) {
+        let handle_merkle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+        if depth == MERKLE_TREE_DEPTH {
+            return match self
+                .db
+                .get_cf(handle_merkle, merkle_leaf_key(path))
+                .expect(CRUD_ERROR)
+            {
+                Some(bytes) => (hash_from_db_bytes(&bytes), 1),
+                None => (self.merkle_empty_hashes[depth], 0),
+            };
+        }
+        if let Some(bytes) = self
+            .db
+            .get_cf(handle_merkle, merkle_node_key(depth, path))
+            .expect(CRUD_ERROR)
+        {
+            return (hash_from_db_bytes(&bytes), 2);
+        }
+
+        // Not stored: there is at most one leaf below this node
+        let prefix = merkle_truncate_path(path, depth);
+        let start_key = merkle_leaf_key(&prefix);
+        let mut leaves = self
+            .db
+            .iterator_cf(
+                handle_merkle,
+                IteratorMode::From(&start_key, Direction::Forward),
+            )
+            .flatten()
+            .take_while(|(key, _)| key.len() == 1 + HASH_SIZE_BYTES && key[0] == MERKLE_LEAF_PREFIX)
+            .map(|(key, value)| {
+                let leaf_path: MerklePath = key[1..].try_into().expect(MERKLE_TREE_ERROR);
+                (leaf_path, hash_from_db_bytes(&value))
+            })
+            .take_while(|(leaf_path, _)| merkle_truncate_path(leaf_path, depth) == prefix);
+        match (leaves.next(), leaves.next()) {
+            (None, _) => (self.merkle_empty_hashes[depth], 0),
+            (Some((leaf_path, leaf_hash)), None) => {
+                let mut current = leaf_hash;
+                for d in (depth..MERKLE_TREE_DEPTH).rev() {
+                    let empty = &self.merkle_empty_hashes[d + 1];
+                    current = if merkle_path_bit(&leaf_path, d) {
+                        merkle_node_hash(empty, &current)
+                    } else {
+                        merkle_node_hash(&current, empty)
+                    };
+                }
+                (current, 1)
+            }
+            (Some(_), Some(_)) => panic!("{}", MERKLE_TREE_ERROR),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MassaDB;
+    use massa_db_exports::{DBBatch, MassaDBConfig, MassaDBController};
+    use massa_models::slot::Slot;
+    use tempfile::TempDir;
+
+    fn create_db(temp_dir: &TempDir) -> MassaDB {
+        MassaDB::new(MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_new_elements: 100,
+            thread_count: 2,
+            enable_archive: false,
+            archive_retention_periods: 0,
+        })
+    }
+
+    #[test]
+    fn test_state_proofs() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut db = create_db(&temp_dir);
+        let root = db.get_merkle_root();
+        let proof = db.get_state_proof(b"ledger/a".to_vec()).unwrap();
+        assert!(proof.value.is_none());
+        assert!(proof.verify(&root));
+
+        let mut batch = DBBatch::new();
+        for i in 0..200u32 {
+            batch.insert(
+                format!("ledger/{}", i).into_bytes(),
+                Some(i.to_be_bytes().to_vec()),
+            );
+        }
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(1, 0)));
+        let root = db.get_merkle_root();
+
+        let proof = db.get_state_proof(b"ledger/42".to_vec()).unwrap();
+        assert_eq!(proof.slot, Slot::new(1, 0));
+        assert_eq!(proof.value, Some(42u32.to_be_bytes().to_vec()));
+        assert!(proof.verify(&root));
+        let mut forged = proof.clone();
+        forged.value = Some(43u32.to_be_bytes().to_vec());
+        assert!(!forged.verify(&root));
+
+        let proof = db.get_state_proof(b"ledger/missing".to_vec()).unwrap();
+        assert!(proof.value.is_none());
+        assert!(proof.verify(&root));
+
+        // deleting an entry updates the root and the proofs
+        let mut batch = DBBatch::new();
+        batch.insert(b"ledger/42".to_vec(), None);
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(1, 1)));
+        let new_root = db.get_merkle_root();
+        assert_ne!(root, new_root);
+        let proof = db.get_state_proof(b"ledger/42".to_vec()).unwrap();
+        assert!(proof.value.is_none());
+        assert!(proof.verify(&new_root));
+        assert!(!proof.verify(&root));
+
+        // a rebuild from scratch gives the same tree
+        db.rebuild_merkle_tree().unwrap();
+        assert_eq!(db.get_merkle_root(), new_root);
+        let proof = db.get_state_proof(b"ledger/7".to_vec()).unwrap();
+        assert!(proof.verify(&new_root));
+    }
+
+    #[test]
+    fn test_incremental_updates_match_rebuild() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut db = create_db(&temp_dir);
+        // inserts, updates and deletes over batches sharing more and more of the tree
+        for round in 0..6u32 {
+            let mut batch = DBBatch::new();
+            for i in 0..(50 * (round + 1)) {
+                let key = format!("ledger/{}", i * 7 % 300).into_bytes();
+                let value = (i % 5 != round % 5).then(|| (i + round).to_be_bytes().to_vec());
+                batch.insert(key, value);
+            }
+            db.write_batch(batch, DBBatch::new(), Some(Slot::new(round as u64 + 1, 0)));
+
+            let root = db.get_merkle_root();
+            for i in (0..300u32).step_by(13) {
+                let proof = db
+                    .get_state_proof(format!("ledger/{}", i).into_bytes())
+                    .unwrap();
+                assert!(proof.verify(&root));
+            }
+            db.rebuild_merkle_tree().unwrap();
+            assert_eq!(db.get_merkle_root(), root);
+        }
+    }
+}
@@ -0,0 +1,412 @@
+//! Portable export of the final state database.
+//!
+//! Unlike RocksDB checkpoints, an export does not depend on the RocksDB version or layout.
+//! A file is made of an uncompressed header describing its format, followed by a gzip-compressed body:
+//! - the slot of the exported state, its XOF hash and the hash of its VERSIONING_CF entries
+//! - every entry of STATE_CF, then every entry of VERSIONING_CF (which holds the MIP store)
+//! - an end record with the number of entries of each column family
+
+use crate::MassaDB;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use massa_db_exports::{
+    Key, MassaDBController, MassaDBError, StreamBatch, Value, CF_ERROR, STATE_CF, VERSIONING_CF,
+};
+use massa_hash::{Hash, HashXof, HASH_SIZE_BYTES, HASH_XOF_SIZE_BYTES};
+use massa_models::{
+    slot::{Slot, SLOT_KEY_SIZE},
+    streaming_step::StreamingStep,
+};
+use rocksdb::IteratorMode;
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+};
+
+/// Magic bytes at the start of every state export
+const STATE_EXPORT_MAGIC: &[u8; 12] = b"MASSA_STATE\0";
+/// Version of the export format written by this software
+pub const STATE_EXPORT_FORMAT_VERSION: u32 = 1;
+/// Compression of the body: gzip
+const STATE_EXPORT_COMPRESSION_GZIP: u8 = 1;
+
+/// Record holding an entry of STATE_CF
+const RECORD_STATE: u8 = 0;
+/// Record holding an entry of VERSIONING_CF
+const RECORD_VERSIONING: u8 = 1;
+/// Last record of the body
+const RECORD_END: u8 = 2;
+
+/// Description of an exported state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateExportInfo {
+    /// slot of the exported state
+    pub slot: Slot,
+    /// XOF hash of the exported state
+    pub state_hash: HashXof<HASH_XOF_SIZE_BYTES>,
+    /// hash of the exported entries of VERSIONING_CF, which are not covered by `state_hash`
+    pub versioning_hash: Hash,
+    /// number of exported entries of STATE_CF
+    pub state_entry_count: u64,
+    /// number of exported entries of VERSIONING_CF
+    pub versioning_entry_count: u64,
+}
+
+fn io_error(err: std::io::Error) -> MassaDBError {
+    MassaDBError::StateExportError(err.to_string())
+}
+
+/// Write the whole database to `writer`.
+///
+/// The database must not be modified while it is exported.
+pub fn export_state<W: Write>(
+    db: &MassaDB,
+    mut writer: W,
+) -> Result<StateExportInfo, MassaDBError> {
+    let slot = db
+        .get_change_id()
+        .map_err(|e| MassaDBError::InvalidChangeID(e.to_string()))?;
+    let state_hash = db.get_xof_db_hash();
+    let versioning_hash = compute_versioning_hash(db)?;
+
+    writer.write_all(STATE_EXPORT_MAGIC).map_err(io_error)?;
+    writer
+        .write_all(&STATE_EXPORT_FORMAT_VERSION.to_be_bytes())
+        .map_err(io_error)?;
+    writer
+        .write_all(&[STATE_EXPORT_COMPRESSION_GZIP])
+        .map_err(io_error)?;
+
+    let mut encoder = GzEncoder::new(writer, Compression::default());
+    encoder.write_all(&slot.to_bytes_key()).map_err(io_error)?;
+    encoder.write_all(&state_hash.0).map_err(io_error)?;
+    encoder
+        .write_all(versioning_hash.to_bytes())
+        .map_err(io_error)?;
+
+    let state_entry_count = export_column(&mut encoder, RECORD_STATE, |step| {
+        db.get_batch_to_stream(step, Some(slot))
+    })?;
+    let versioning_entry_count = export_column(&mut encoder, RECORD_VERSIONING, |step| {
+        db.get_versioning_batch_to_stream(step, Some(slot))
+    })?;
+
+    encoder.write_all(&[RECORD_END]).map_err(io_error)?;
+    encoder
+        .write_all(&state_entry_count.to_be_bytes())
+        .map_err(io_error)?;
+    encoder
+        .write_all(&versioning_entry_count.to_be_bytes())
+        .map_err(io_error)?;
+    encoder
+        .finish()
+        .map_err(io_error)?
+        .flush()
+        .map_err(io_error)?;
+
+    Ok(StateExportInfo {
+        slot,
+        state_hash,
+        versioning_hash,
+        state_entry_count,
+        versioning_entry_count,
+    })
+}
+
+/// Hash every entry of VERSIONING_CF, in key order
+fn compute_versioning_hash(db: &MassaDB) -> Result<Hash, MassaDBError> {
+    let handle_versioning = db.db.cf_handle(VERSIONING_CF).expect(CF_ERROR);
+    let mut entries = Vec::new();
+    for entry in db.db.iterator_cf(handle_versioning, IteratorMode::Start) {
+        let (key, value) = entry.map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))?;
+        entries.push(key);
+        entries.push(value);
+    }
+    let entries: Vec<&[u8]> = entries.iter().map(|entry| entry.as_ref()).collect();
+    Ok(Hash::compute_from_tuple(&entries))
+}
+
+/// Write every entry streamed by `get_batch` as records of type `record`, returns the number of entries
+fn export_column<W, F>(writer: &mut W, record: u8, get_batch: F) -> Result<u64, MassaDBError>
+where
+    W: Write,
+    F: Fn(&StreamingStep<Key>) -> Result<StreamBatch<Slot>, MassaDBError>,
+{
+    let mut step = StreamingStep::Started;
+    let mut count = 0;
+    loop {
+        let batch = get_batch(&step)?;
+        if !batch.updates_on_previous_elements.is_empty() {
+            return Err(MassaDBError::StateExportError(
+                "the database was modified during the export".to_string(),
+            ));
+        }
+        let Some((last_key, _)) = batch.new_elements.last_key_value() else {
+            return Ok(count);
+        };
+        step = StreamingStep::Ongoing(last_key.clone());
+        for (key, value) in batch.new_elements.iter() {
+            writer.write_all(&[record]).map_err(io_error)?;
+            write_bytes(writer, key)?;
+            write_bytes(writer, value)?;
+            count += 1;
+        }
+    }
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<(), MassaDBError> {
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| MassaDBError::StateExportError("entry too large".to_string()))?;
+    writer.write_all(&len.to_be_bytes()).map_err(io_error)?;
+    writer.write_all(bytes).map_err(io_error)
+}
+
+fn read_array<R: Read, const N: usize>(reader: &mut R) -> Result<[u8; N], MassaDBError> {
+    let mut bytes = [0u8; N];
+    reader.read_exact(&mut bytes).map_err(io_error)?;
+    Ok(bytes)
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>, MassaDBError> {
+    let len = u32::from_be_bytes(read_array(reader)?);
+    let mut bytes = Vec::new();
+    reader
+        .by_ref()
+        .take(len as u64)
+        .read_to_end(&mut bytes)
+        .map_err(io_error)?;
+    if bytes.len() != len as usize {
+        return Err(MassaDBError::StateExportError(
+            "unexpected end of file".to_string(),
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Read the header and the fingerprint of an export, without importing it:
+/// the slot of the exported state, its XOF hash and the hash of its VERSIONING_CF entries
+pub fn read_state_export_info<R: Read>(
+    reader: R,
+) -> Result<(Slot, HashXof<HASH_XOF_SIZE_BYTES>, Hash), MassaDBError> {
+    let mut decoder = open_export(reader)?;
+    read_export_fingerprint(&mut decoder)
+}
+
+/// Check the header of an export and return a reader on its decompressed body
+fn open_export<R: Read>(mut reader: R) -> Result<GzDecoder<R>, MassaDBError> {
+    let magic: [u8; 12] = read_array(&mut reader)?;
+    if &magic != STATE_EXPORT_MAGIC {
+        return Err(MassaDBError::StateExportError(
+            "not a state export file".to_string(),
+        ));
+    }
+    let version = u32::from_be_bytes(read_array(&mut reader)?);
+    if version != STATE_EXPORT_FORMAT_VERSION {
+        return Err(MassaDBError::StateExportError(format!(
+            "unsupported export format version {} (supported: {})",
+            version, STATE_EXPORT_FORMAT_VERSION
+        )));
+    }
+    let [compression] = read_array(&mut reader)?;
+    if compression != STATE_EXPORT_COMPRESSION_GZIP {
+        return Err(MassaDBError::StateExportError(format!(
+            "unsupported compression {}",
+            compression
+        )));
+    }
+    Ok(GzDecoder::new(reader))
+}
+
+fn read_export_fingerprint<R: Read>(
+    reader: &mut R,
+) -> Result<(Slot, HashXof<HASH_XOF_SIZE_BYTES>, Hash), MassaDBError> {
+    let slot = Slot::from_bytes_key(&read_array::<_, SLOT_KEY_SIZE>(reader)?);
+    let state_hash = HashXof(read_array(reader)?);
+    let versioning_hash = Hash::from_bytes(&read_array::<_, HASH_SIZE_BYTES>(reader)?);
+    Ok((slot, state_hash, versioning_hash))
+}
+
+/// Import an export into `db`, which must be empty.
+///
+/// Fails if the hashes of the imported state and of its VERSIONING_CF entries
+/// do not match the ones recorded in the export, in which case the database must be discarded.
+/// On success, the bootstrap progress possibly left in the database is cleared.
+pub fn import_state<R: Read>(db: &mut MassaDB, reader: R) -> Result<StateExportInfo, MassaDBError> {
+    let handle_state = db.db.cf_handle(STATE_CF).expect(CF_ERROR);
+    if db
+        .db
+        .iterator_cf(handle_state, IteratorMode::Start)
+        .next()
+        .is_some()
+    {
+        return Err(MassaDBError::StateExportError(
+            "the state can only be imported into an empty database".to_string(),
+        ));
+    }
+
+    let mut decoder = open_export(reader)?;
+    let (slot, state_hash, versioning_hash) = read_export_fingerprint(&mut decoder)?;
+
+    let mut state_entry_count = 0u64;
+    let mut versioning_entry_count = 0u64;
+    let mut state_batch: BTreeMap<Key, Value> = BTreeMap::new();
+    let mut versioning_batch: BTreeMap<Key, Value> = BTreeMap::new();
+    loop {
+        let [record] = read_array(&mut decoder)?;
+        match record {
+            RECORD_STATE => {
+                state_batch.insert(read_bytes(&mut decoder)?, read_bytes(&mut decoder)?);
+                state_entry_count += 1;
+            }
+            RECORD_VERSIONING => {
+                versioning_batch.insert(read_bytes(&mut decoder)?, read_bytes(&mut decoder)?);
+                versioning_entry_count += 1;
+            }
+            RECORD_END => break,
+            _ => {
+                return Err(MassaDBError::StateExportError(format!(
+                    "unknown record type {}",
+                    record
+                )))
+            }
+        }
+        if state_batch.len() + versioning_batch.len() >= db.config.max_new_elements {
+            write_import_batch(db, &mut state_batch, &mut versioning_batch, slot)?;
+        }
+    }
+    write_import_batch(db, &mut state_batch, &mut versioning_batch, slot)?;
+
+    if u64::from_be_bytes(read_array(&mut decoder)?) != state_entry_count
+        || u64::from_be_bytes(read_array(&mut decoder)?) != versioning_entry_count
+    {
+        return Err(MassaDBError::StateExportError(
+            "the number of imported entries does not match the export".to_string(),
+        ));
+    }
+    if db.get_xof_db_hash() != state_hash {
+        return Err(MassaDBError::HashError(
+            "the hash of the imported state does not match the export".to_string(),
+        ));
+    }
+    if compute_versioning_hash(db)? != versioning_hash {
+        return Err(MassaDBError::HashError(
+            "the hash of the imported versioning entries does not match the export".to_string(),
+        ));
+    }
+    // the imported state is complete: it must not be taken for an interrupted bootstrap
+    db.clear_bootstrap_progress()?;
+    db.flush()?;
+
+    Ok(StateExportInfo {
+        slot,
+        state_hash,
+        versioning_hash,
+        state_entry_count,
+        versioning_entry_count,
+    })
+}
+
+fn write_import_batch(
+    db: &mut MassaDB,
+    state_batch: &mut BTreeMap<Key, Value>,
+    versioning_batch: &mut BTreeMap<Key, Value>,
+    slot: Slot,
+) -> Result<(), MassaDBError> {
+    db.write_batch_bootstrap_client(
+        StreamBatch {
+            new_elements: std::mem::take(state_batch),
+            updates_on_previous_elements: BTreeMap::new(),
+            change_id: slot,
+        },
+        StreamBatch {
+            new_elements: std::mem::take(versioning_batch),
+            updates_on_previous_elements: BTreeMap::new(),
+            change_id: slot,
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_state, import_state, read_state_export_info};
+    use crate::MassaDB;
+    use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+    use massa_db_exports::{DBBatch, MassaDBConfig, MassaDBController, VERSIONING_CF};
+    use massa_hash::HASH_XOF_SIZE_BYTES;
+    use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+    use std::io::{Read, Write};
+    use tempfile::TempDir;
+
+    fn create_db(temp_dir: &TempDir) -> MassaDB {
+        MassaDB::new(MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_new_elements: 7,
+            thread_count: 2,
+            enable_archive: false,
+            archive_retention_periods: 0,
+        })
+    }
+
+    #[test]
+    fn test_state_export_import() {
+        let source_dir = TempDir::new().unwrap();
+        let mut source = create_db(&source_dir);
+        let mut batch = DBBatch::new();
+        let mut versioning_batch = DBBatch::new();
+        for i in 0..50u32 {
+            batch.insert(
+                format!("ledger/{}", i).into_bytes(),
+                Some(i.to_be_bytes().to_vec()),
+            );
+        }
+        versioning_batch.insert(b"versioning/0".to_vec(), Some(vec![1, 2, 3]));
+        source.write_batch(batch, versioning_batch, Some(Slot::new(3, 1)));
+
+        let mut export = Vec::new();
+        let info = export_state(&source, &mut export).unwrap();
+        assert_eq!(info.slot, Slot::new(3, 1));
+        assert_eq!(info.state_entry_count, 50);
+        assert_eq!(info.versioning_entry_count, 1);
+        assert_eq!(
+            read_state_export_info(export.as_slice()).unwrap(),
+            (info.slot, info.state_hash, info.versioning_hash)
+        );
+
+        let target_dir = TempDir::new().unwrap();
+        let mut target = create_db(&target_dir);
+        assert_eq!(import_state(&mut target, export.as_slice()).unwrap(), info);
+        assert_eq!(target.get_change_id().unwrap(), Slot::new(3, 1));
+        assert_eq!(target.get_xof_db_hash(), source.get_xof_db_hash());
+        assert_eq!(target.get_merkle_root(), source.get_merkle_root());
+        assert_eq!(
+            target
+                .get_cf(VERSIONING_CF, b"versioning/0".to_vec())
+                .unwrap(),
+            Some(vec![1, 2, 3])
+        );
+        assert!(target.get_bootstrap_progress().unwrap().is_none());
+
+        // the target is no longer empty
+        assert!(import_state(&mut target, export.as_slice()).is_err());
+
+        // exports of another format version are rejected
+        let mut other_version = export.clone();
+        other_version[12] = 2;
+        let other_dir = TempDir::new().unwrap();
+        assert!(import_state(&mut create_db(&other_dir), other_version.as_slice()).is_err());
+
+        // exports whose versioning entries do not match their fingerprint are rejected
+        let header_len = 17;
+        let mut body = Vec::new();
+        GzDecoder::new(&export[header_len..])
+            .read_to_end(&mut body)
+            .unwrap();
+        body[SLOT_KEY_SIZE + HASH_XOF_SIZE_BYTES] ^= 1;
+        let mut encoder = GzEncoder::new(export[..header_len].to_vec(), Compression::default());
+        encoder.write_all(&body).unwrap();
+        let tampered = encoder.finish().unwrap();
+        let tampered_dir = TempDir::new().unwrap();
+        assert!(import_state(&mut create_db(&tampered_dir), tampered.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{address::Address, amount::Amount};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Devnet settings
+#[derive(Debug, Clone)]
+pub struct DevnetConfig {
+    /// settings of each node of the devnet
+    pub nodes: Vec<DevnetNodeConfig>,
+    /// path to the `massa-node` binary.
+    /// Defaults to `$MASSA_DEVNET_NODE_BIN`, or to the debug build of the workspace
+    pub node_binary: PathBuf,
+    /// directory of the node base configuration, copied in the directory of each node
+    pub base_config_dir: PathBuf,
+    /// directory where the genesis and the node directories are written.
+    /// A temporary directory removed with the devnet is used if not set
+    pub root_dir: Option<PathBuf>,
+    /// number of threads of the chain
+    pub thread_count: u8,
+    /// time between two periods of a thread
+    pub t0: MassaTime,
+    /// number of periods of a cycle
+    pub periods_per_cycle: u64,
+    /// delay between the start of the devnet and the genesis, leaving the nodes time to start
+    pub genesis_delay: MassaTime,
+    /// rolls of each staking address at genesis
+    pub initial_rolls: u64,
+    /// balance of each staking address at genesis
+    pub initial_balance: Amount,
+    /// other balances of the genesis ledger
+    pub initial_ledger: BTreeMap<Address, Amount>,
+    /// password of the node staking wallets
+    pub wallet_password: String,
+    /// log level of the nodes (0: ERROR to 4: TRACE)
+    pub log_level: usize,
+    /// max time to wait for a node API to answer after a (re)start
+    pub startup_timeout: MassaTime,
+    /// interval at which the nodes are polled while waiting for a condition
+    pub poll_interval: MassaTime,
+}
+
+/// Settings of one devnet node
+#[derive(Debug, Clone)]
+pub struct DevnetNodeConfig {
+    /// node key (not the staking key), defining the node id
+    pub node_keypair: KeyPair,
+    /// staking keys of the node. Each staking address gets `initial_rolls` rolls at genesis
+    pub staking_keys: Vec<KeyPair>,
+}
+
+impl DevnetNodeConfig {
+    /// node settings with a random node key and a random staking key
+    pub fn random() -> Self {
+        Self {
+            node_keypair: KeyPair::generate(0).unwrap(),
+            staking_keys: vec![KeyPair::generate(0).unwrap()],
+        }
+    }
+}
+
+impl DevnetConfig {
+    /// devnet of `node_count` nodes staking with one random key each
+    pub fn with_node_count(node_count: usize) -> Self {
+        Self {
+            nodes: (0..node_count)
+                .map(|_| DevnetNodeConfig::random())
+                .collect(),
+            node_binary: std::env::var_os("MASSA_DEVNET_NODE_BIN")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| {
+                    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!(
+                        "../target/debug/massa-node{}",
+                        std::env::consts::EXE_SUFFIX
+                    ))
+                }),
+            base_config_dir: PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../massa-node/base_config"),
+            root_dir: None,
+            thread_count: 4,
+            t0: MassaTime::from_millis(2000),
+            periods_per_cycle: 16,
+            genesis_delay: MassaTime::from_millis(20_000),
+            initial_rolls: 100,
+            initial_balance: Amount::from_str("1000000").unwrap(),
+            initial_ledger: BTreeMap::new(),
+            wallet_password: "massa-devnet".to_string(),
+            log_level: 2,
+            startup_timeout: MassaTime::from_millis(60_000),
+            poll_interval: MassaTime::from_millis(250),
+        }
+    }
+}
+
+impl Default for DevnetConfig {
+    fn default() -> Self {
+        Self::with_node_count(2)
+    }
+}
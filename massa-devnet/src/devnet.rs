@@ -0,0 +1,209 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::config::DevnetConfig;
+use crate::error::DevnetError;
+use crate::genesis::write_genesis;
+use crate::node::{DevnetNode, NodePorts};
+use massa_models::node::NodeId;
+use massa_models::slot::Slot;
+use massa_protocol_exports::PeerId;
+use massa_time::MassaTime;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Nodes of a local devnet sharing the same genesis
+pub struct Devnet {
+    config: DevnetConfig,
+    nodes: Vec<DevnetNode>,
+    chain_spec_path: PathBuf,
+    root_dir: PathBuf,
+    // removed with the devnet if no root directory was given
+    _temp_dir: Option<TempDir>,
+}
+
+impl Devnet {
+    /// Generate the genesis and the directories of the nodes, then start all the nodes.
+    /// Returns once the API of every node answers.
+    pub async fn start(config: DevnetConfig) -> Result<Devnet, DevnetError> {
+        if config.nodes.is_empty() {
+            return Err(DevnetError::InvalidConfig(
+                "the devnet needs at least one node".to_string(),
+            ));
+        }
+        let (root_dir, temp_dir) = match &config.root_dir {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)?;
+                (dir.clone(), None)
+            }
+            None => {
+                let temp_dir = tempfile::Builder::new().prefix("massa-devnet").tempdir()?;
+                (temp_dir.path().to_path_buf(), Some(temp_dir))
+            }
+        };
+        let chain_spec_path = write_genesis(&config, &root_dir)?;
+
+        let ports = NodePorts::allocate(config.nodes.len())?;
+        let mut nodes = Vec::with_capacity(config.nodes.len());
+        for (index, (node_config, ports)) in config.nodes.iter().zip(ports).enumerate() {
+            nodes.push(DevnetNode::new(
+                index,
+                node_config.clone(),
+                ports,
+                root_dir.join(format!("node_{}", index)),
+                &config,
+            )?);
+        }
+        let peers: Vec<(NodeId, PeerId, NodePorts)> = nodes
+            .iter()
+            .map(|node| {
+                (
+                    node.node_id(),
+                    PeerId::from_public_key(node.node_keypair().get_public_key()),
+                    node.ports(),
+                )
+            })
+            .collect();
+        for node in &nodes {
+            node.write_config(&peers, &config)?;
+        }
+
+        let mut devnet = Devnet {
+            config,
+            nodes,
+            chain_spec_path,
+            root_dir,
+            _temp_dir: temp_dir,
+        };
+        for index in 0..devnet.nodes.len() {
+            devnet.start_node(index, false).await?;
+        }
+        Ok(devnet)
+    }
+
+    /// Nodes of the devnet
+    pub fn nodes(&self) -> &[DevnetNode] {
+        &self.nodes
+    }
+
+    /// Node at `index`
+    pub fn node(&self, index: usize) -> Result<&DevnetNode, DevnetError> {
+        self.nodes.get(index).ok_or(DevnetError::UnknownNode(index))
+    }
+
+    /// Settings of the devnet
+    pub fn config(&self) -> &DevnetConfig {
+        &self.config
+    }
+
+    /// Directory holding the genesis files and the node directories
+    pub fn root_dir(&self) -> &Path {
+        &self.root_dir
+    }
+
+    /// Chain spec shared by the nodes
+    pub fn chain_spec_path(&self) -> &Path {
+        &self.chain_spec_path
+    }
+
+    /// Start a stopped node, from its previous ledger if `keep_ledger` is set.
+    /// Returns once the API of the node answers.
+    pub async fn start_node(&mut self, index: usize, keep_ledger: bool) -> Result<(), DevnetError> {
+        let node = self
+            .nodes
+            .get_mut(index)
+            .ok_or(DevnetError::UnknownNode(index))?;
+        node.start(&self.chain_spec_path, keep_ledger, &self.config)
+            .await
+    }
+
+    /// Kill a node without letting it shut down
+    pub fn kill_node(&mut self, index: usize) -> Result<(), DevnetError> {
+        self.nodes
+            .get_mut(index)
+            .ok_or(DevnetError::UnknownNode(index))?
+            .kill()
+    }
+
+    /// Kill a node and restart it with `--keep-ledger`.
+    /// After genesis, the restarted node bootstraps from the other nodes.
+    pub async fn restart_node(&mut self, index: usize) -> Result<(), DevnetError> {
+        self.kill_node(index)?;
+        self.start_node(index, true).await
+    }
+
+    /// Wait until `slot` is final on all the running nodes
+    pub async fn wait_final_slot(
+        &mut self,
+        slot: Slot,
+        timeout: MassaTime,
+    ) -> Result<(), DevnetError> {
+        let deadline = MassaTime::now()?.saturating_add(timeout);
+        loop {
+            let mut all_final = true;
+            for node in self.nodes.iter_mut().filter(|node| node.is_running()) {
+                node.check_alive()?;
+                // a node may not answer for a short while, for example when it is overloaded
+                let final_cursor = match node.status().await {
+                    Ok(status) => Some(status.execution_stats.final_cursor),
+                    Err(_) => None,
+                };
+                if !matches!(final_cursor, Some(cursor) if cursor >= slot) {
+                    all_final = false;
+                    break;
+                }
+            }
+            if all_final {
+                return Ok(());
+            }
+            if MassaTime::now()? > deadline {
+                return Err(DevnetError::Timeout(format!(
+                    "slot {} is not final on all the nodes",
+                    slot
+                )));
+            }
+            tokio::time::sleep(self.config.poll_interval.to_duration()).await;
+        }
+    }
+
+    /// Split the nodes in two groups that can't reach each other,
+    /// by making each node of a group ban the nodes of the other group.
+    /// Both groups must be running.
+    pub async fn partition(&self, group_a: &[usize], group_b: &[usize]) -> Result<(), DevnetError> {
+        let ids_a = self.node_ids(group_a)?;
+        let ids_b = self.node_ids(group_b)?;
+        for (group, other_ids) in [(group_a, &ids_b), (group_b, &ids_a)] {
+            for index in group {
+                self.node(*index)?
+                    .private()?
+                    .node_ban_by_id(other_ids.clone())
+                    .await
+                    .map_err(|err| DevnetError::ApiError(*index, err.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Undo the partitions: every running node unbans all the other nodes
+    pub async fn heal(&self) -> Result<(), DevnetError> {
+        let ids: Vec<NodeId> = self.nodes.iter().map(|node| node.node_id()).collect();
+        for node in self.nodes.iter().filter(|node| node.is_running()) {
+            let others = ids
+                .iter()
+                .filter(|id| **id != node.node_id())
+                .copied()
+                .collect();
+            node.private()?
+                .node_unban_by_id(others)
+                .await
+                .map_err(|err| DevnetError::ApiError(node.index(), err.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn node_ids(&self, indexes: &[usize]) -> Result<Vec<NodeId>, DevnetError> {
+        indexes
+            .iter()
+            .map(|index| self.node(*index).map(|node| node.node_id()))
+            .collect()
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use displaydoc::Display;
+use thiserror::Error;
+
+/// devnet error
+#[non_exhaustive]
+#[derive(Display, Error, Debug)]
+pub enum DevnetError {
+    /// IO error: {0}
+    IOError(#[from] std::io::Error),
+    /// serde_json error: {0}
+    SerdeJsonError(#[from] serde_json::Error),
+    /// signature error: {0}
+    SignatureError(#[from] massa_signature::MassaSignatureError),
+    /// wallet error: {0}
+    WalletError(#[from] massa_wallet::WalletError),
+    /// time error: {0}
+    TimeError(#[from] massa_time::TimeError),
+    /// invalid devnet config: {0}
+    InvalidConfig(String),
+    /// no node at index {0}
+    UnknownNode(usize),
+    /// node {0} is not running
+    NodeNotRunning(usize),
+    /// node {0} exited, see its logs in {1}
+    NodeExited(usize, String),
+    /// API error on node {0}: {1}
+    ApiError(usize, String),
+    /// timeout: {0}
+    Timeout(String),
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Genesis shared by the devnet nodes
+
+use crate::config::DevnetConfig;
+use crate::error::DevnetError;
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::config::ChainSpec;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub(crate) const CHAIN_SPEC_FILE: &str = "chain_spec.json";
+pub(crate) const INITIAL_LEDGER_FILE: &str = "initial_ledger.json";
+pub(crate) const INITIAL_ROLLS_FILE: &str = "initial_rolls.json";
+pub(crate) const INITIAL_DEFERRED_CREDITS_FILE: &str = "deferred_credits.json";
+
+/// Write the chain spec and the genesis files of the devnet in `dir`,
+/// with a genesis `config.genesis_delay` after now.
+/// Returns the path of the chain spec.
+pub(crate) fn write_genesis(config: &DevnetConfig, dir: &Path) -> Result<PathBuf, DevnetError> {
+    let staking_addresses: Vec<Address> = config
+        .nodes
+        .iter()
+        .flat_map(|node| node.staking_keys.iter())
+        .map(|keypair| Address::from_public_key(&keypair.get_public_key()))
+        .collect();
+    if staking_addresses.is_empty() {
+        return Err(DevnetError::InvalidConfig(
+            "the devnet needs at least one staking key".to_string(),
+        ));
+    }
+
+    // every staking address gets rolls and coins, on top of the extra balances of the config
+    let mut balances: BTreeMap<Address, Amount> = config.initial_ledger.clone();
+    let mut rolls: BTreeMap<Address, u64> = BTreeMap::new();
+    for addr in staking_addresses {
+        let balance = balances.entry(addr).or_default();
+        *balance = balance.saturating_add(config.initial_balance);
+        rolls.insert(addr, config.initial_rolls);
+    }
+
+    let ledger: Map<String, Value> = balances
+        .iter()
+        .map(|(addr, balance)| {
+            (
+                addr.to_string(),
+                json!({
+                    "balance": balance.to_string(),
+                    "datastore": {},
+                    "bytecode": []
+                }),
+            )
+        })
+        .collect();
+    std::fs::write(
+        dir.join(INITIAL_LEDGER_FILE),
+        serde_json::to_string_pretty(&ledger)?,
+    )?;
+    let rolls: Map<String, Value> = rolls
+        .iter()
+        .map(|(addr, count)| (addr.to_string(), json!(count)))
+        .collect();
+    std::fs::write(
+        dir.join(INITIAL_ROLLS_FILE),
+        serde_json::to_string_pretty(&rolls)?,
+    )?;
+    std::fs::write(dir.join(INITIAL_DEFERRED_CREDITS_FILE), "{}")?;
+
+    let default = ChainSpec::default();
+    let chain_spec = ChainSpec {
+        genesis_timestamp: MassaTime::now()?.saturating_add(config.genesis_delay),
+        end_timestamp: None,
+        genesis_key: KeyPair::generate(0)?,
+        thread_count: config.thread_count,
+        t0: config.t0,
+        periods_per_cycle: config.periods_per_cycle,
+        initial_ledger_path: Some(PathBuf::from(INITIAL_LEDGER_FILE)),
+        initial_rolls_path: Some(PathBuf::from(INITIAL_ROLLS_FILE)),
+        initial_deferred_credits_path: Some(PathBuf::from(INITIAL_DEFERRED_CREDITS_FILE)),
+        ..default
+    };
+    chain_spec
+        .check()
+        .map_err(|err| DevnetError::InvalidConfig(err.to_string()))?;
+    let chain_spec_path = dir.join(CHAIN_SPEC_FILE);
+    std::fs::write(&chain_spec_path, serde_json::to_string_pretty(&chain_spec)?)?;
+    Ok(chain_spec_path)
+}
@@ -0,0 +1,47 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Local multi-node devnet for end-to-end tests
+//!
+//! A [`Devnet`] starts N `massa-node` processes listening on loopback. The nodes
+//! share a genesis generated for the test (chain spec, initial ledger, rolls and
+//! deferred credits), and each node stakes with the keys given in its [`DevnetNodeConfig`].
+//!
+//! Each node is driven through its JSON-RPC APIs (see [`DevnetNode::public`] and
+//! [`DevnetNode::private`]), and the devnet provides helpers for the usual scenarios:
+//! * wait until a slot is final on all the running nodes ([`Devnet::wait_final_slot`])
+//! * split the nodes in two groups that can't reach each other ([`Devnet::partition`])
+//! * kill a node and restart it from its ledger ([`Devnet::kill_node`], [`Devnet::restart_node`])
+//!
+//! The nodes run as child processes: the node settings are read once per process,
+//! so several nodes can't share one. The `massa-node` binary must be built beforehand,
+//! see [`DevnetConfig::node_binary`].
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), massa_devnet::DevnetError> {
+//! use massa_devnet::{Devnet, DevnetConfig};
+//! use massa_models::slot::Slot;
+//! use massa_time::MassaTime;
+//!
+//! let mut devnet = Devnet::start(DevnetConfig::with_node_count(3)).await?;
+//! devnet.wait_final_slot(Slot::new(2, 0), MassaTime::from_millis(60_000)).await?;
+//! devnet.restart_node(1).await?;
+//! devnet.wait_final_slot(Slot::new(6, 0), MassaTime::from_millis(60_000)).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+#![warn(missing_docs)]
+#![warn(unused_crate_dependencies)]
+
+mod config;
+mod devnet;
+mod error;
+mod genesis;
+mod node;
+
+pub use config::{DevnetConfig, DevnetNodeConfig};
+pub use devnet::Devnet;
+pub use error::DevnetError;
+pub use node::{DevnetNode, NodePorts};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,411 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::config::{DevnetConfig, DevnetNodeConfig};
+use crate::error::DevnetError;
+use massa_api_exports::node::NodeStatus;
+use massa_models::address::Address;
+use massa_models::node::NodeId;
+use massa_protocol_exports::PeerId;
+use massa_sdk::{ClientConfig, HttpConfig, RpcClient};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use massa_wallet::Wallet;
+use serde_json::{json, Map, Value};
+use std::fs::OpenOptions;
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use tracing::{info, warn};
+
+const LOG_FILE: &str = "node.log";
+
+/// Loopback ports of a devnet node
+#[derive(Debug, Clone, Copy)]
+pub struct NodePorts {
+    /// protocol listener
+    pub protocol: u16,
+    /// bootstrap server
+    pub bootstrap: u16,
+    /// private JSON-RPC API
+    pub api_private: u16,
+    /// public JSON-RPC API
+    pub api_public: u16,
+    /// JSON-RPC API v2
+    pub api: u16,
+}
+
+impl NodePorts {
+    /// pick `count` sets of currently free loopback ports
+    pub(crate) fn allocate(count: usize) -> Result<Vec<NodePorts>, DevnetError> {
+        // all the listeners are kept open until every port is picked, so that no port is picked twice
+        let listeners = (0..count * 5)
+            .map(|_| TcpListener::bind((Ipv4Addr::LOCALHOST, 0)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ports = listeners
+            .iter()
+            .map(|listener| listener.local_addr().map(|addr| addr.port()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ports
+            .chunks(5)
+            .map(|chunk| NodePorts {
+                protocol: chunk[0],
+                bootstrap: chunk[1],
+                api_private: chunk[2],
+                api_public: chunk[3],
+                api: chunk[4],
+            })
+            .collect())
+    }
+}
+
+/// A node of the devnet
+pub struct DevnetNode {
+    index: usize,
+    config: DevnetNodeConfig,
+    node_id: NodeId,
+    ports: NodePorts,
+    dir: PathBuf,
+    process: Option<Child>,
+    public: Option<RpcClient>,
+    private: Option<RpcClient>,
+}
+
+impl DevnetNode {
+    /// Create the directory of the node: a copy of the base configuration,
+    /// the settings overriding it, the node key and the staking wallet.
+    pub(crate) fn new(
+        index: usize,
+        config: DevnetNodeConfig,
+        ports: NodePorts,
+        dir: PathBuf,
+        devnet_config: &DevnetConfig,
+    ) -> Result<Self, DevnetError> {
+        copy_dir(&devnet_config.base_config_dir, &dir.join("base_config"))?;
+        std::fs::create_dir_all(dir.join("config"))?;
+        std::fs::write(
+            dir.join("config/node_privkey.key"),
+            serde_json::to_string(&config.node_keypair)?,
+        )?;
+        let mut wallet = Wallet::new(
+            dir.join("config/staking_wallets"),
+            devnet_config.wallet_password.clone(),
+        )?;
+        wallet.add_keypairs(config.staking_keys.clone())?;
+        Ok(DevnetNode {
+            index,
+            node_id: NodeId::new(config.node_keypair.get_public_key()),
+            config,
+            ports,
+            dir,
+            process: None,
+            public: None,
+            private: None,
+        })
+    }
+
+    /// Write the settings of the node overriding its base configuration,
+    /// with all the other nodes as bootstrap servers and initial peers.
+    pub(crate) fn write_config(
+        &self,
+        peers: &[(NodeId, PeerId, NodePorts)],
+        devnet_config: &DevnetConfig,
+    ) -> Result<(), DevnetError> {
+        let others = peers
+            .iter()
+            .filter(|(node_id, _, _)| node_id != &self.node_id);
+
+        let initial_peers: Map<String, Value> = others
+            .clone()
+            .map(|(_, peer_id, ports)| {
+                (
+                    peer_id.to_string(),
+                    json!({
+                        "listeners": { format!("127.0.0.1:{}", ports.protocol): "Tcp" },
+                        "category": "Bootstrap"
+                    }),
+                )
+            })
+            .collect();
+        std::fs::write(
+            self.dir.join("config/initial_peers.json"),
+            serde_json::to_string_pretty(&initial_peers)?,
+        )?;
+        std::fs::write(
+            self.dir.join("config/bootstrap_whitelist.json"),
+            r#"["127.0.0.1"]"#,
+        )?;
+        std::fs::write(self.dir.join("config/bootstrap_blacklist.json"), "[]")?;
+
+        let bootstrap_list = others
+            .map(|(node_id, _, ports)| {
+                format!("[\"127.0.0.1:{}\", \"{}\"]", ports.bootstrap, node_id)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        // every node may connect to all the others, all from the same IP
+        let category = format!(
+            "{{ target_out_connections = {0}, max_in_connections_per_ip = {0}, max_in_connections = {0}, allow_local_peers = true }}",
+            peers.len()
+        );
+        let settings = format!(
+            r#"[logging]
+    level = {log_level}
+
+[api]
+    bind_private = "127.0.0.1:{api_private}"
+    bind_public = "127.0.0.1:{api_public}"
+    bind_api = "127.0.0.1:{api}"
+
+[grpc]
+    [grpc.public]
+        enabled = false
+    [grpc.private]
+        enabled = false
+
+[protocol]
+    bind = "127.0.0.1:{protocol}"
+    initial_peers_file = "config/initial_peers.json"
+    default_category_info = {category}
+    [protocol.peers_categories]
+    Bootstrap = {category}
+
+[metrics]
+    enabled = false
+
+[bootstrap]
+    bootstrap_list = [{bootstrap_list}]
+    bootstrap_protocol = "IPv4"
+    bootstrap_whitelist_path = "config/bootstrap_whitelist.json"
+    bootstrap_blacklist_path = "config/bootstrap_blacklist.json"
+    bind = "127.0.0.1:{bootstrap}"
+    retry_delay = 1000
+    per_ip_min_interval = 0
+    max_simultaneous_bootstraps = {node_count}
+
+[factory]
+    stop_production_when_zero_connections = false
+"#,
+            log_level = devnet_config.log_level,
+            api_private = self.ports.api_private,
+            api_public = self.ports.api_public,
+            api = self.ports.api,
+            protocol = self.ports.protocol,
+            category = category,
+            bootstrap_list = bootstrap_list,
+            bootstrap = self.ports.bootstrap,
+            node_count = peers.len(),
+        );
+        std::fs::write(self.dir.join("config/config.toml"), settings)?;
+        Ok(())
+    }
+
+    /// Start the node process, keeping its previous ledger if `keep_ledger` is set,
+    /// and wait for its APIs to answer.
+    pub(crate) async fn start(
+        &mut self,
+        chain_spec_path: &Path,
+        keep_ledger: bool,
+        devnet_config: &DevnetConfig,
+    ) -> Result<(), DevnetError> {
+        if self.is_running() {
+            return Ok(());
+        }
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(LOG_FILE))?;
+        let mut command = Command::new(&devnet_config.node_binary);
+        command
+            .current_dir(&self.dir)
+            .env(
+                "MASSA_CONFIG_PATH",
+                self.dir.join("base_config/config.toml"),
+            )
+            .env(
+                "MASSA_CONFIG_OVERRIDE_PATH",
+                self.dir.join("config/config.toml"),
+            )
+            .arg("--chain-spec")
+            .arg(chain_spec_path)
+            .arg("--pwd")
+            .arg(&devnet_config.wallet_password)
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log);
+        if keep_ledger {
+            command.arg("--keep-ledger");
+        }
+        info!("starting devnet node {} ({})", self.index, self.node_id);
+        self.process = Some(command.spawn()?);
+
+        let http_config = http_config();
+        let public = RpcClient::from_url(
+            &format!("http://127.0.0.1:{}", self.ports.api_public),
+            &http_config,
+        )
+        .await;
+        let private = RpcClient::from_url(
+            &format!("http://127.0.0.1:{}", self.ports.api_private),
+            &http_config,
+        )
+        .await;
+
+        // the APIs are started once the node is bootstrapped
+        let deadline = MassaTime::now()?.saturating_add(devnet_config.startup_timeout);
+        while public.get_status().await.is_err() {
+            self.check_alive()?;
+            if MassaTime::now()? > deadline {
+                self.kill()?;
+                return Err(DevnetError::Timeout(format!(
+                    "the API of node {} did not answer, see its logs in {}",
+                    self.index,
+                    self.log_path().display()
+                )));
+            }
+            tokio::time::sleep(devnet_config.poll_interval.to_duration()).await;
+        }
+        self.public = Some(public);
+        self.private = Some(private);
+        Ok(())
+    }
+
+    /// Kill the node process without letting it shut down
+    pub(crate) fn kill(&mut self) -> Result<(), DevnetError> {
+        self.public = None;
+        self.private = None;
+        if let Some(mut process) = self.process.take() {
+            info!("killing devnet node {} ({})", self.index, self.node_id);
+            if let Err(err) = process.kill() {
+                warn!("could not kill devnet node {}: {}", self.index, err);
+            }
+            process.wait()?;
+        }
+        Ok(())
+    }
+
+    /// Error if the node process exited
+    pub(crate) fn check_alive(&mut self) -> Result<(), DevnetError> {
+        let exited = match self.process.as_mut() {
+            Some(process) => process.try_wait()?.is_some(),
+            None => return Err(DevnetError::NodeNotRunning(self.index)),
+        };
+        if exited {
+            self.process = None;
+            self.public = None;
+            self.private = None;
+            return Err(DevnetError::NodeExited(
+                self.index,
+                self.log_path().display().to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Index of the node in the devnet
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Node id
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    /// Node key (not the staking key)
+    pub fn node_keypair(&self) -> &KeyPair {
+        &self.config.node_keypair
+    }
+
+    /// Staking keys of the node
+    pub fn staking_keys(&self) -> &[KeyPair] {
+        &self.config.staking_keys
+    }
+
+    /// Staking addresses of the node
+    pub fn staking_addresses(&self) -> Vec<Address> {
+        self.config
+            .staking_keys
+            .iter()
+            .map(|keypair| Address::from_public_key(&keypair.get_public_key()))
+            .collect()
+    }
+
+    /// Loopback ports of the node
+    pub fn ports(&self) -> NodePorts {
+        self.ports
+    }
+
+    /// Directory of the node, from where the node is run
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File where the output of the node is written
+    pub fn log_path(&self) -> PathBuf {
+        self.dir.join(LOG_FILE)
+    }
+
+    /// Whether the node process was started and not killed
+    pub fn is_running(&self) -> bool {
+        self.process.is_some()
+    }
+
+    /// Public JSON-RPC API of the node
+    pub fn public(&self) -> Result<&RpcClient, DevnetError> {
+        self.public
+            .as_ref()
+            .ok_or(DevnetError::NodeNotRunning(self.index))
+    }
+
+    /// Private JSON-RPC API of the node
+    pub fn private(&self) -> Result<&RpcClient, DevnetError> {
+        self.private
+            .as_ref()
+            .ok_or(DevnetError::NodeNotRunning(self.index))
+    }
+
+    /// Status of the node
+    pub async fn status(&self) -> Result<NodeStatus, DevnetError> {
+        self.public()?
+            .get_status()
+            .await
+            .map_err(|err| DevnetError::ApiError(self.index, err.to_string()))
+    }
+}
+
+impl Drop for DevnetNode {
+    fn drop(&mut self) {
+        if let Err(err) = self.kill() {
+            warn!("could not stop devnet node {}: {}", self.index, err);
+        }
+    }
+}
+
+fn http_config() -> HttpConfig {
+    HttpConfig {
+        client_config: ClientConfig {
+            max_request_body_size: 52428800,
+            request_timeout: MassaTime::from_millis(10_000),
+            max_concurrent_requests: 100,
+            certificate_store: "Native".to_string(),
+            id_kind: "Number".to_string(),
+            max_log_length: 4096,
+            headers: Vec::new(),
+        },
+        enabled: true,
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), DevnetError> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
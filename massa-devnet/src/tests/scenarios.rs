@@ -0,0 +1,68 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::genesis::{write_genesis, INITIAL_LEDGER_FILE, INITIAL_ROLLS_FILE};
+use crate::{Devnet, DevnetConfig};
+use massa_models::address::Address;
+use massa_models::config::ChainSpec;
+use massa_models::slot::Slot;
+use massa_time::MassaTime;
+use std::collections::BTreeMap;
+
+#[test]
+fn test_write_genesis() {
+    let config = DevnetConfig::with_node_count(3);
+    let dir = tempfile::tempdir().unwrap();
+    let chain_spec_path = write_genesis(&config, dir.path()).unwrap();
+
+    let chain_spec = ChainSpec::from_file(&chain_spec_path).unwrap();
+    assert_eq!(chain_spec.thread_count, config.thread_count);
+    assert_eq!(chain_spec.t0, config.t0);
+    assert_eq!(
+        chain_spec.initial_ledger_path,
+        Some(dir.path().join(INITIAL_LEDGER_FILE))
+    );
+
+    // every staking address of every node has rolls and coins at genesis
+    let rolls: BTreeMap<Address, u64> = serde_json::from_str(
+        &std::fs::read_to_string(dir.path().join(INITIAL_ROLLS_FILE)).unwrap(),
+    )
+    .unwrap();
+    let ledger: BTreeMap<Address, serde_json::Value> = serde_json::from_str(
+        &std::fs::read_to_string(dir.path().join(INITIAL_LEDGER_FILE)).unwrap(),
+    )
+    .unwrap();
+    for node in &config.nodes {
+        for keypair in &node.staking_keys {
+            let addr = Address::from_public_key(&keypair.get_public_key());
+            assert_eq!(rolls.get(&addr), Some(&config.initial_rolls));
+            assert_eq!(ledger[&addr]["balance"], config.initial_balance.to_string());
+        }
+    }
+}
+
+/// Needs a built `massa-node` binary, see `DevnetConfig::node_binary`
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_devnet_partition_and_restart() {
+    let timeout = MassaTime::from_millis(120_000);
+    let mut devnet = Devnet::start(DevnetConfig::with_node_count(3))
+        .await
+        .unwrap();
+    devnet
+        .wait_final_slot(Slot::new(2, 0), timeout)
+        .await
+        .unwrap();
+
+    devnet.partition(&[0, 1], &[2]).await.unwrap();
+    devnet.heal().await.unwrap();
+    devnet
+        .wait_final_slot(Slot::new(6, 0), timeout)
+        .await
+        .unwrap();
+
+    devnet.restart_node(1).await.unwrap();
+    devnet
+        .wait_final_slot(Slot::new(10, 0), timeout)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,62 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Items of the history of an address, indexed from final execution outputs
+
+use massa_async_pool::AsyncMessageId;
+use massa_models::{address::Address, amount::Amount, operation::OperationId, slot::Slot};
+use std::collections::BTreeSet;
+
+/// What affected an address during the execution of a slot
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AddressHistoryItem {
+    /// operation created by the address, sending coins to it, calling it,
+    /// or moving its coins during the execution of a smart contract
+    Operation(OperationId),
+    /// asynchronous message sent by the address, targeting it,
+    /// or moving its coins during the execution of its handler
+    AsyncMessage(AsyncMessageId),
+    /// new roll count of the address
+    RollCount(u64),
+    /// deferred credit of the address: scheduled if `slot` is after the slot of the entry, paid otherwise.
+    /// An amount of zero means that a scheduled credit was cancelled.
+    DeferredCredit {
+        /// slot at which the credit is paid
+        slot: Slot,
+        /// credited amount
+        amount: Amount,
+    },
+}
+
+/// Addresses affected during the execution of a slot, with what affected them
+pub type AddressHistoryChanges = BTreeSet<(Address, AddressHistoryItem)>;
+
+/// Entry of the history of an address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressHistoryEntry {
+    /// final slot at which the address was affected
+    pub slot: Slot,
+    /// index of the entry among the entries of the address at this slot
+    pub index: u32,
+    /// what affected the address
+    pub item: AddressHistoryItem,
+}
+
+/// Position in the history of an address.
+/// A page of history starting at a cursor only contains entries strictly older than it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AddressHistoryCursor {
+    /// slot of the entry
+    pub slot: Slot,
+    /// index of the entry at this slot
+    pub index: u32,
+}
+
+impl AddressHistoryEntry {
+    /// Cursor pointing at this entry, used to request the next (older) page of history
+    pub fn cursor(&self) -> AddressHistoryCursor {
+        AddressHistoryCursor {
+            slot: self.slot,
+            index: self.index,
+        }
+    }
+}
@@ -3,9 +3,11 @@
 //! This module exports generic traits representing interfaces for interacting with the Execution worker
 
 use crate::types::{
-    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse, ReadOnlyExecutionRequest,
+    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse,
+    OperationSimulationOutput, OperationSimulationRequest, ReadOnlyExecutionRequest,
 };
 use crate::ExecutionError;
+use crate::{AddressHistoryCursor, AddressHistoryEntry};
 use crate::{ExecutionAddressInfo, ReadOnlyExecutionOutput};
 use massa_models::address::Address;
 use massa_models::amount::Amount;
@@ -94,6 +96,21 @@ pub trait ExecutionController: Send + Sync {
         req: ReadOnlyExecutionRequest,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError>;
 
+    /// Simulate the execution of an operation on top of the candidate state,
+    /// as if it was included in a block at the next slot of the sender's thread,
+    /// without causing modifications to the consensus state
+    ///
+    /// # arguments
+    /// * `req`: an instance of `OperationSimulationRequest` describing the operation and its sender
+    ///
+    /// # returns
+    /// An instance of `OperationSimulationOutput` describing the effects of the operation,
+    /// or an error if the operation could not be included in a block at all.
+    fn simulate_operation(
+        &self,
+        req: OperationSimulationRequest,
+    ) -> Result<OperationSimulationOutput, ExecutionError>;
+
     /// Check if a denunciation has been executed given a `DenunciationIndex`
     /// (speculative, final)
     fn get_denunciation_execution_status(
@@ -107,6 +124,22 @@ pub trait ExecutionController: Send + Sync {
     /// Get execution statistics
     fn get_stats(&self) -> ExecutionStats;
 
+    /// Get the history of an address, from the newest final entries to the oldest ones.
+    ///
+    /// # Arguments
+    /// * `address`: address whose history is requested
+    /// * `cursor`: only return entries older than this cursor, from the newest entry if `None`
+    /// * `limit`: maximum number of returned entries
+    ///
+    /// # Returns
+    /// The entries of the page, or an error if the address history is disabled or could not be read
+    fn get_address_history(
+        &self,
+        address: &Address,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> Result<Vec<AddressHistoryEntry>, ExecutionError>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ExecutionController>`.
     fn clone_box(&self) -> Box<dyn ExecutionController>;
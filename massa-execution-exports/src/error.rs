@@ -67,6 +67,9 @@ pub enum ExecutionError {
 
     /// Factory error: {0}
     FactoryError(#[from] FactoryError),
+
+    /// Address history error: {0}
+    AddressHistoryError(String),
 }
 
 /// Execution query errors
@@ -74,4 +77,6 @@ pub enum ExecutionError {
 pub enum ExecutionQueryError {
     /// Not found: {0}
     NotFound(String),
+    /// Unavailable: {0}
+    Unavailable(String),
 }
@@ -0,0 +1,161 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module defines the execution traces recorded for operations and asynchronous messages,
+//! and a store keeping the traces of a config-limited number of final slots
+
+use massa_async_pool::AsyncMessageId;
+use massa_models::{address::Address, amount::Amount, operation::OperationId, slot::Slot};
+use std::collections::VecDeque;
+
+/// What caused an execution trace to be recorded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionTraceOrigin {
+    /// execution of an operation
+    Operation(OperationId),
+    /// execution of an asynchronous message
+    AsyncMessage(AsyncMessageId),
+}
+
+/// Coins moved from one address to another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoinTransferTrace {
+    /// spending address, `None` for coin creation
+    pub from: Option<Address>,
+    /// credited address, `None` for coin destruction
+    pub to: Option<Address>,
+    /// amount of coins transferred
+    pub amount: Amount,
+}
+
+/// Write to the ledger performed during a call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerWriteTrace {
+    /// balance of an address after a coin transfer
+    Balance {
+        /// address of the ledger entry
+        address: Address,
+        /// new balance
+        balance: Amount,
+    },
+    /// bytecode of an address was replaced
+    Bytecode {
+        /// address of the ledger entry
+        address: Address,
+    },
+    /// datastore entry set (or appended to)
+    DatastoreSet {
+        /// address of the ledger entry
+        address: Address,
+        /// datastore key
+        key: Vec<u8>,
+        /// full value of the entry after the write
+        value: Vec<u8>,
+    },
+    /// datastore entry deleted
+    DatastoreDelete {
+        /// address of the ledger entry
+        address: Address,
+        /// datastore key
+        key: Vec<u8>,
+    },
+}
+
+/// A call and the effects it caused, including the ones of its sub-calls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallTrace {
+    /// calling address
+    pub caller: Address,
+    /// called address
+    pub callee: Address,
+    /// called function, `None` when no function is called or when the runtime does not report it
+    pub function: Option<String>,
+    /// coins sent along with the call
+    pub coins: Amount,
+    /// gas used by the call, `None` when it could not be measured.
+    /// For a sub-call, it is measured up to the next remaining gas measurement after it returned
+    /// and includes the gas spent by its callers in between.
+    pub gas_used: Option<u64>,
+    /// coin transfers performed by the call, in execution order
+    pub transfers: Vec<CoinTransferTrace>,
+    /// ledger writes performed by the call, in execution order
+    pub ledger_writes: Vec<LedgerWriteTrace>,
+    /// reason why the call failed, `None` if it completed.
+    /// The effects of a completed call are discarded as well if one of its parents failed.
+    pub revert_reason: Option<String>,
+    /// calls made from this one, in execution order
+    pub sub_calls: Vec<CallTrace>,
+}
+
+impl CallTrace {
+    /// Create a new call trace with no recorded effects
+    pub fn new(caller: Address, callee: Address, function: Option<String>, coins: Amount) -> Self {
+        CallTrace {
+            caller,
+            callee,
+            function,
+            coins,
+            gas_used: None,
+            transfers: Vec::new(),
+            ledger_writes: Vec::new(),
+            revert_reason: None,
+            sub_calls: Vec::new(),
+        }
+    }
+}
+
+/// Trace of the execution of an operation or asynchronous message.
+///
+/// Operation fees and the reimbursement of failed asynchronous messages are not part of the trace:
+/// they are applied outside of the traced execution and can be derived from the operation or message itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionTrace {
+    /// slot at which the execution happened
+    pub slot: Slot,
+    /// operation or asynchronous message that was executed
+    pub origin: ExecutionTraceOrigin,
+    /// root call of the execution
+    pub call: CallTrace,
+}
+
+impl ExecutionTrace {
+    /// Whether the execution succeeded, in which case all its effects were kept
+    pub fn is_success(&self) -> bool {
+        self.call.revert_reason.is_none()
+    }
+}
+
+/// Store for the execution traces of the latest final slots
+#[derive(Default, Debug, Clone)]
+pub struct ExecutionTraceStore(pub VecDeque<(Slot, Vec<ExecutionTrace>)>);
+
+impl ExecutionTraceStore {
+    /// Push the traces of a newly final slot to the store
+    pub fn push(&mut self, slot: Slot, traces: Vec<ExecutionTrace>) {
+        self.0.push_back((slot, traces));
+    }
+
+    /// Prune the store if it holds more than `max_slots` slots
+    pub fn prune(&mut self, max_slots: usize) {
+        while self.0.len() > max_slots {
+            self.0.pop_front();
+        }
+    }
+
+    /// Get the traces recorded at a given slot, `None` if the slot is not in the store
+    pub fn get_slot_traces(&self, slot: &Slot) -> Option<&Vec<ExecutionTrace>> {
+        self.0
+            .iter()
+            .rev()
+            .find(|(s, _)| s == slot)
+            .map(|(_, traces)| traces)
+    }
+
+    /// Get the trace of the execution of an operation
+    pub fn get_operation_trace(&self, operation_id: &OperationId) -> Option<&ExecutionTrace> {
+        self.0
+            .iter()
+            .rev()
+            .flat_map(|(_, traces)| traces.iter())
+            .find(|trace| trace.origin == ExecutionTraceOrigin::Operation(*operation_id))
+    }
+}
@@ -22,6 +22,9 @@
 //!
 //! # Architecture
 //!
+//! ## `address_history.rs`
+//! Defines the items of the history of an address, indexed from final execution outputs.
+//!
 //! ## `config.rs`
 //! Contains configuration parameters for the execution system.
 //!
@@ -34,6 +37,9 @@
 //! ## `event_store.rs`
 //! Defines an indexed, finite-size storage system for execution events.
 //!
+//! ## `execution_trace.rs`
+//! Defines the call traces recorded for operations and asynchronous messages, and their finite-size store.
+//!
 //! ## `types.rs`
 //! Defines useful shared structures.
 //!
@@ -44,28 +50,38 @@
 
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
+mod address_history;
 mod channels;
 mod controller_traits;
 mod error;
 mod event_store;
+mod execution_trace;
 /// mapping grpc
 pub mod mapping_grpc;
 mod settings;
 mod types;
 
+pub use address_history::{
+    AddressHistoryChanges, AddressHistoryCursor, AddressHistoryEntry, AddressHistoryItem,
+};
 pub use channels::ExecutionChannels;
 #[cfg(any(test, feature = "testing"))]
 pub use controller_traits::MockExecutionController;
 pub use controller_traits::{ExecutionController, ExecutionManager};
 pub use error::{ExecutionError, ExecutionQueryError};
 pub use event_store::EventStore;
+pub use execution_trace::{
+    CallTrace, CoinTransferTrace, ExecutionTrace, ExecutionTraceOrigin, ExecutionTraceStore,
+    LedgerWriteTrace,
+};
 pub use massa_sc_runtime::GasCosts;
 pub use settings::{ExecutionConfig, StorageCostsConstants};
 pub use types::{
     ExecutedBlockInfo, ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionOutput,
     ExecutionQueryCycleInfos, ExecutionQueryExecutionStatus, ExecutionQueryRequest,
     ExecutionQueryRequestItem, ExecutionQueryResponse, ExecutionQueryResponseItem,
-    ExecutionQueryStakerInfo, ExecutionStackElement, ReadOnlyCallRequest, ReadOnlyExecutionOutput,
+    ExecutionQueryStakerInfo, ExecutionStackElement, OperationSimulationOutput,
+    OperationSimulationRequest, ReadOnlyCallRequest, ReadOnlyExecutionOutput,
     ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlotExecutionOutput,
 };
 
@@ -266,6 +266,15 @@ fn to_execution_query_result(
                 },
             )
         }
+        // asynchronous messages, state proofs and execution traces can not be queried through gRPC
+        ExecutionQueryResponseItem::AsyncMessages(_)
+        | ExecutionQueryResponseItem::AsyncMessage(_)
+        | ExecutionQueryResponseItem::StateProof(_)
+        | ExecutionQueryResponseItem::ExecutionTraces(_) => {
+            return grpc_api::ExecutionQueryResponseItem {
+                response_item: None,
+            };
+        }
     };
 
     grpc_api::ExecutionQueryResponseItem {
@@ -349,6 +358,11 @@ impl From<ExecutionQueryError> for grpc_model::Error {
                 code: 404,
                 message: error,
             },
+            ExecutionQueryError::Unavailable(error) => grpc_model::Error {
+                //TODO to be defined
+                code: 400,
+                message: error,
+            },
         }
     }
 }
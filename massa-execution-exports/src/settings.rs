@@ -88,4 +88,12 @@ pub struct ExecutionConfig {
     pub broadcast_slot_execution_output_channel_capacity: usize,
     /// max size of event data, in bytes
     pub max_event_size: usize,
+    /// whether call traces are recorded for executed operations and asynchronous messages
+    pub execution_traces_enabled: bool,
+    /// number of final slots for which execution traces are kept
+    pub execution_traces_slot_limit: usize,
+    /// whether the history of the addresses affected by final slots is indexed
+    pub address_history_enabled: bool,
+    /// path to the address history database
+    pub address_history_path: PathBuf,
 }
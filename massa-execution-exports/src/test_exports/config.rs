@@ -64,6 +64,10 @@ impl Default for ExecutionConfig {
             broadcast_enabled: true,
             broadcast_slot_execution_output_channel_capacity: 5000,
             max_event_size: 50_000,
+            execution_traces_enabled: true,
+            execution_traces_slot_limit: 320,
+            address_history_enabled: true,
+            address_history_path: TempDir::new().unwrap().path().to_path_buf(),
             max_function_length: 1000,
             max_parameter_length: 1000,
         }
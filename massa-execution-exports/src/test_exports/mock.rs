@@ -4,7 +4,8 @@
 
 use crate::types::{ExecutionQueryRequest, ExecutionQueryResponse};
 use crate::{
-    ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionController, ExecutionError,
+    AddressHistoryCursor, AddressHistoryEntry, ExecutionAddressInfo, ExecutionBlockMetadata,
+    ExecutionController, ExecutionError, OperationSimulationOutput, OperationSimulationRequest,
     ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
 };
 use massa_ledger_exports::LedgerEntry;
@@ -68,6 +69,13 @@ pub enum MockExecutionControllerMessage {
         /// response channel
         response_tx: mpsc::Sender<Result<ReadOnlyExecutionOutput, ExecutionError>>,
     },
+    /// operation simulation request
+    SimulateOperation {
+        /// operation simulation request
+        req: OperationSimulationRequest,
+        /// response channel
+        response_tx: mpsc::Sender<Result<OperationSimulationOutput, ExecutionError>>,
+    },
     /// Not executed operation among call
     UnexecutedOpsAmong {
         /// operation ids
@@ -199,6 +207,15 @@ impl ExecutionController for MockExecutionController {
         BTreeMap::default()
     }
 
+    fn get_address_history(
+        &self,
+        _address: &Address,
+        _cursor: Option<AddressHistoryCursor>,
+        _limit: usize,
+    ) -> Result<Vec<AddressHistoryEntry>, ExecutionError> {
+        Ok(Vec::default())
+    }
+
     fn execute_readonly_request(
         &self,
         req: ReadOnlyExecutionRequest,
@@ -211,6 +228,18 @@ impl ExecutionController for MockExecutionController {
         response_rx.recv().unwrap()
     }
 
+    fn simulate_operation(
+        &self,
+        req: OperationSimulationRequest,
+    ) -> Result<OperationSimulationOutput, ExecutionError> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.0
+            .lock()
+            .send(MockExecutionControllerMessage::SimulateOperation { req, response_tx })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
     fn get_denunciation_execution_status(
         &self,
         denunciation_index: &DenunciationIndex,
@@ -2,8 +2,12 @@
 
 //! This file exports useful types used to interact with the execution worker
 
-use crate::error::ExecutionQueryError;
+use crate::address_history::AddressHistoryChanges;
+use crate::error::{ExecutionError, ExecutionQueryError};
 use crate::event_store::EventStore;
+use crate::execution_trace::ExecutionTrace;
+use massa_async_pool::{AsyncMessage, AsyncMessageFilter, AsyncMessageId};
+use massa_db_exports::StateProof;
 use massa_final_state::StateChanges;
 use massa_hash::Hash;
 use massa_models::block_id::BlockId;
@@ -11,7 +15,7 @@ use massa_models::bytecode::Bytecode;
 use massa_models::datastore::Datastore;
 use massa_models::denunciation::DenunciationIndex;
 use massa_models::execution::EventFilter;
-use massa_models::operation::OperationId;
+use massa_models::operation::{OperationId, OperationType};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::{
@@ -90,6 +94,58 @@ pub enum ExecutionQueryRequestItem {
         /// Key of the entry
         key: Vec<u8>,
     },
+    /// gets the serialized balance (final) of an address along with its Merkle inclusion or non-inclusion proof,
+    /// returns ExecutionQueryResponseItem::StateProof(proof) or an error if `slot` is provided and is not the last final slot
+    AddressBalanceProofFinal {
+        /// Address for which to query the balance
+        addr: Address,
+        /// Slot at which the proof is expected, must be the last final slot if provided
+        slot: Option<Slot>,
+    },
+    /// gets a datastore value (final) for an address along with its Merkle inclusion or non-inclusion proof,
+    /// returns ExecutionQueryResponseItem::StateProof(proof) or an error if `slot` is provided and is not the last final slot
+    AddressDatastoreValueProofFinal {
+        /// Address for which to query the datastore
+        addr: Address,
+        /// Key of the entry
+        key: Vec<u8>,
+        /// Slot at which the proof is expected, must be the last final slot if provided
+        slot: Option<Slot>,
+    },
+    /// gets the balance of an address as it was at the end of a past final slot (requires the state archive),
+    /// returns ExecutionQueryResponseItem::Amount(balance) or an error if the address is not found or the slot is not archived
+    AddressBalanceAtSlot {
+        /// Address for which to query the balance
+        addr: Address,
+        /// Final slot at the end of which the balance is read
+        slot: Slot,
+    },
+    /// gets the bytecode of an address as it was at the end of a past final slot (requires the state archive),
+    /// returns ExecutionQueryResponseItem::Bytecode(bytecode) or an error if the address is not found or the slot is not archived
+    AddressBytecodeAtSlot {
+        /// Address for which to query the bytecode
+        addr: Address,
+        /// Final slot at the end of which the bytecode is read
+        slot: Slot,
+    },
+    /// gets a datastore value of an address as it was at the end of a past final slot (requires the state archive),
+    /// returns ExecutionQueryResponseItem::DatastoreValue(value) or an error if the address or key is not found or the slot is not archived
+    AddressDatastoreValueAtSlot {
+        /// Address for which to query the datastore
+        addr: Address,
+        /// Key of the entry
+        key: Vec<u8>,
+        /// Final slot at the end of which the value is read
+        slot: Slot,
+    },
+    /// gets the roll count of an address as it was at the end of a past final slot (requires the state archive),
+    /// returns ExecutionQueryResponseItem::RollCount(rolls) or an error if the slot is not archived
+    AddressRollsAtSlot {
+        /// Address for which to query the roll count
+        addr: Address,
+        /// Final slot at the end of which the roll count is read
+        slot: Slot,
+    },
 
     /// gets the execution status (candidate) for an operation, returns ExecutionQueryResponseItem::ExecutionStatus(status)
     OpExecutionStatusCandidate(OperationId),
@@ -119,6 +175,38 @@ pub enum ExecutionQueryRequestItem {
 
     /// get filtered events. Returns ExecutionQueryResponseItem::Events
     Events(EventFilter),
+
+    /// gets at most `limit` asynchronous messages (candidate) matching a filter, sorted by id and starting after `start_after` if provided,
+    /// returns ExecutionQueryResponseItem::AsyncMessages(messages)
+    AsyncMessagesCandidate {
+        /// filter on the messages
+        filter: AsyncMessageFilter,
+        /// optional id after which messages are returned
+        start_after: Option<AsyncMessageId>,
+        /// maximum number of messages to return
+        limit: usize,
+    },
+    /// gets at most `limit` asynchronous messages (final) matching a filter, sorted by id and starting after `start_after` if provided,
+    /// returns ExecutionQueryResponseItem::AsyncMessages(messages)
+    AsyncMessagesFinal {
+        /// filter on the messages
+        filter: AsyncMessageFilter,
+        /// optional id after which messages are returned
+        start_after: Option<AsyncMessageId>,
+        /// maximum number of messages to return
+        limit: usize,
+    },
+    /// gets an asynchronous message (candidate) by id, returns ExecutionQueryResponseItem::AsyncMessage(message) or an error if the message is not in the pool
+    AsyncMessageCandidate(AsyncMessageId),
+    /// gets an asynchronous message (final) by id, returns ExecutionQueryResponseItem::AsyncMessage(message) or an error if the message is not in the pool
+    AsyncMessageFinal(AsyncMessageId),
+
+    /// gets the execution trace (final) of an operation, returns ExecutionQueryResponseItem::ExecutionTraces(vec![trace])
+    /// or an error if tracing is disabled or if no trace is kept for the operation
+    OpExecutionTraceFinal(OperationId),
+    /// gets the execution traces (final) of a slot, returns ExecutionQueryResponseItem::ExecutionTraces(traces)
+    /// or an error if tracing is disabled or if the traces of the slot are not kept
+    SlotExecutionTracesFinal(Slot),
 }
 
 /// Execution state query response item
@@ -143,6 +231,14 @@ pub enum ExecutionQueryResponseItem {
     CycleInfos(ExecutionQueryCycleInfos),
     /// Events
     Events(Vec<SCOutputEvent>),
+    /// asynchronous messages, sorted by id
+    AsyncMessages(Vec<(AsyncMessageId, AsyncMessage)>),
+    /// asynchronous message
+    AsyncMessage(AsyncMessage),
+    /// final state entry with its Merkle proof
+    StateProof(StateProof),
+    /// execution traces, in execution order
+    ExecutionTraces(Vec<ExecutionTrace>),
 }
 
 /// Execution status of an operation or denunciation
@@ -235,6 +331,10 @@ pub struct ExecutionOutput {
     pub state_changes: StateChanges,
     /// events emitted by the execution step
     pub events: EventStore,
+    /// call traces of the operations and asynchronous messages executed during the step, empty if tracing is disabled
+    pub traces: Vec<ExecutionTrace>,
+    /// addresses affected during the execution step, empty if the address history is disabled
+    pub address_history: AddressHistoryChanges,
 }
 
 /// structure describing the output of a read only execution
@@ -299,6 +399,39 @@ pub struct ReadOnlyCallRequest {
     pub is_final: bool,
 }
 
+/// structure describing an operation to simulate on top of the candidate state
+#[derive(Debug, Clone)]
+pub struct OperationSimulationRequest {
+    /// address of the operation sender
+    pub sender: Address,
+    /// fee of the operation
+    pub fee: Amount,
+    /// expiration period of the operation, defaults to the period of the simulation slot
+    pub expire_period: Option<u64>,
+    /// operation to simulate
+    pub op: OperationType,
+}
+
+/// structure describing the output of an operation simulation
+#[derive(Debug, Clone)]
+pub struct OperationSimulationOutput {
+    /// slot at which the operation was simulated
+    pub slot: Slot,
+    /// VM gas used by the operation, up to its failure if it failed, zero for operations that do not run bytecode.
+    /// `None` if the operation could not be executed because its fee could not be spent.
+    pub gas_used: Option<u64>,
+    /// `max_gas` to set in the operation, with a safety margin over `gas_used`
+    pub suggested_max_gas: Option<u64>,
+    /// fee debited from the sender, zero if the fee could not be spent
+    pub fee: Amount,
+    /// `(balance_before, balance_after)` of every address whose balance changed, fee included
+    pub balance_changes: BTreeMap<Address, (Amount, Amount)>,
+    /// events emitted by the execution
+    pub events: Vec<SCOutputEvent>,
+    /// error that the execution of the operation would produce, `None` if it succeeds
+    pub error: Option<ExecutionError>,
+}
+
 /// Structure describing an element of the execution stack.
 /// Every time a function is called from bytecode,
 /// a new `ExecutionStackElement` is pushed at the top of the execution stack
@@ -0,0 +1,274 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Persistent index of the history of addresses, built from final execution outputs.
+//!
+//! Keys are made of the serialized address, the slot key and the big-endian index of the entry
+//! among the entries of the address at this slot, so that the history of an address
+//! is a contiguous range of keys sorted by slot. Values are serialized `AddressHistoryItem`s.
+
+use massa_async_pool::{AsyncMessageIdDeserializer, AsyncMessageIdSerializer};
+use massa_execution_exports::{
+    AddressHistoryChanges, AddressHistoryCursor, AddressHistoryEntry, AddressHistoryItem,
+};
+use massa_models::{
+    address::{Address, AddressSerializer},
+    amount::{Amount, AmountDeserializer, AmountSerializer},
+    operation::{OperationIdDeserializer, OperationIdSerializer},
+    slot::{Slot, SlotDeserializer, SlotSerializer, SLOT_KEY_SIZE},
+};
+use massa_serialization::{
+    DeserializeError, Deserializer, SerializeError, Serializer, U64VarIntDeserializer,
+    U64VarIntSerializer,
+};
+use nom::{
+    error::{context, ContextError, ParseError},
+    IResult,
+};
+use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use std::ops::Bound::{Excluded, Included};
+use std::path::Path;
+
+const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
+const ITEM_SER_ERROR: &str = "critical: address history item serialization failed";
+const ITEM_DESER_ERROR: &str = "critical: address history item deserialization failed";
+const ADDRESS_SER_ERROR: &str = "critical: address serialization failed";
+
+/// Size of the part of a key following the serialized address
+const ENTRY_KEY_SUFFIX_SIZE: usize = SLOT_KEY_SIZE + 4;
+
+/// Persistent index of the history of addresses
+pub(crate) struct AddressHistoryDB {
+    /// RocksDB database
+    db: DB,
+    /// Address serializer, used to build keys
+    address_serializer: AddressSerializer,
+    /// Item serializer
+    item_serializer: AddressHistoryItemSerializer,
+    /// Item deserializer
+    item_deserializer: AddressHistoryItemDeserializer,
+}
+
+impl AddressHistoryDB {
+    /// Open the address history database, creating it if needed
+    ///
+    /// # Arguments
+    /// * `path`: where to store the db
+    /// * `thread_count`: number of threads, used to deserialize slots
+    pub fn new(path: &Path, thread_count: u8) -> Self {
+        let db = DB::open_default(path).expect(OPEN_ERROR);
+        Self {
+            db,
+            address_serializer: AddressSerializer::new(),
+            item_serializer: AddressHistoryItemSerializer::new(),
+            item_deserializer: AddressHistoryItemDeserializer::new(thread_count),
+        }
+    }
+
+    /// Index the addresses affected at a final slot.
+    /// Indexing the same slot again overwrites its entries.
+    ///
+    /// # Arguments
+    /// * `slot`: final slot
+    /// * `changes`: addresses affected at this slot, sorted by address
+    pub fn apply_slot(&self, slot: &Slot, changes: &AddressHistoryChanges) {
+        let mut batch = WriteBatch::default();
+        let mut previous_address = None;
+        let mut index = 0u32;
+        for (address, item) in changes {
+            if previous_address != Some(address) {
+                previous_address = Some(address);
+                index = 0;
+            }
+            let mut value = Vec::new();
+            self.item_serializer
+                .serialize(item, &mut value)
+                .expect(ITEM_SER_ERROR);
+            batch.put(self.entry_key(address, slot, index), value);
+            index = index.saturating_add(1);
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Get a page of the history of an address, from the newest entries to the oldest ones
+    ///
+    /// # Arguments
+    /// * `address`: address whose history is requested
+    /// * `cursor`: only return entries older than this cursor, from the newest entry if `None`
+    /// * `limit`: maximum number of returned entries
+    pub fn get_history(
+        &self,
+        address: &Address,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> Vec<AddressHistoryEntry> {
+        let prefix = self.address_prefix(address);
+        let start_key = match &cursor {
+            Some(cursor) => self.entry_key(address, &cursor.slot, cursor.index),
+            None => [prefix.as_slice(), &[u8::MAX; ENTRY_KEY_SUFFIX_SIZE]].concat(),
+        };
+
+        let mut entries = Vec::new();
+        for entry in self
+            .db
+            .iterator(IteratorMode::From(&start_key, Direction::Reverse))
+        {
+            if entries.len() >= limit {
+                break;
+            }
+            let (key, value) = entry.expect(CRUD_ERROR);
+            let Some(suffix) = key.strip_prefix(prefix.as_slice()) else {
+                break;
+            };
+            if cursor.is_some() && *key == start_key[..] {
+                // the entry at the cursor was returned in the previous page
+                continue;
+            }
+            let (slot_key, index) = suffix.split_at(SLOT_KEY_SIZE);
+            let (_, item) = self
+                .item_deserializer
+                .deserialize::<DeserializeError>(&value)
+                .expect(ITEM_DESER_ERROR);
+            entries.push(AddressHistoryEntry {
+                slot: Slot::from_bytes_key(slot_key.try_into().expect(ITEM_DESER_ERROR)),
+                index: u32::from_be_bytes(index.try_into().expect(ITEM_DESER_ERROR)),
+                item,
+            });
+        }
+        entries
+    }
+
+    /// Serialized address, common prefix of all the keys of its history
+    fn address_prefix(&self, address: &Address) -> Vec<u8> {
+        let mut key = Vec::new();
+        self.address_serializer
+            .serialize(address, &mut key)
+            .expect(ADDRESS_SER_ERROR);
+        key
+    }
+
+    /// Key of an entry of the history of an address
+    fn entry_key(&self, address: &Address, slot: &Slot, index: u32) -> Vec<u8> {
+        let mut key = self.address_prefix(address);
+        key.extend(slot.to_bytes_key());
+        key.extend(index.to_be_bytes());
+        key
+    }
+}
+
+/// Serializer for `AddressHistoryItem`
+struct AddressHistoryItemSerializer {
+    u64_serializer: U64VarIntSerializer,
+    operation_id_serializer: OperationIdSerializer,
+    message_id_serializer: AsyncMessageIdSerializer,
+    slot_serializer: SlotSerializer,
+    amount_serializer: AmountSerializer,
+}
+
+impl AddressHistoryItemSerializer {
+    fn new() -> Self {
+        Self {
+            u64_serializer: U64VarIntSerializer::new(),
+            operation_id_serializer: OperationIdSerializer::new(),
+            message_id_serializer: AsyncMessageIdSerializer::new(),
+            slot_serializer: SlotSerializer::new(),
+            amount_serializer: AmountSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<AddressHistoryItem> for AddressHistoryItemSerializer {
+    fn serialize(
+        &self,
+        value: &AddressHistoryItem,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        match value {
+            AddressHistoryItem::Operation(operation_id) => {
+                buffer.push(0);
+                self.operation_id_serializer.serialize(operation_id, buffer)
+            }
+            AddressHistoryItem::AsyncMessage(message_id) => {
+                buffer.push(1);
+                self.message_id_serializer.serialize(message_id, buffer)
+            }
+            AddressHistoryItem::RollCount(roll_count) => {
+                buffer.push(2);
+                self.u64_serializer.serialize(roll_count, buffer)
+            }
+            AddressHistoryItem::DeferredCredit { slot, amount } => {
+                buffer.push(3);
+                self.slot_serializer.serialize(slot, buffer)?;
+                self.amount_serializer.serialize(amount, buffer)
+            }
+        }
+    }
+}
+
+/// Deserializer for `AddressHistoryItem`
+struct AddressHistoryItemDeserializer {
+    u64_deserializer: U64VarIntDeserializer,
+    operation_id_deserializer: OperationIdDeserializer,
+    message_id_deserializer: AsyncMessageIdDeserializer,
+    slot_deserializer: SlotDeserializer,
+    amount_deserializer: AmountDeserializer,
+}
+
+impl AddressHistoryItemDeserializer {
+    fn new(thread_count: u8) -> Self {
+        Self {
+            u64_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+            operation_id_deserializer: OperationIdDeserializer::new(),
+            message_id_deserializer: AsyncMessageIdDeserializer::new(thread_count),
+            slot_deserializer: SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            ),
+            amount_deserializer: AmountDeserializer::new(
+                Included(Amount::MIN),
+                Included(Amount::MAX),
+            ),
+        }
+    }
+}
+
+impl Deserializer<AddressHistoryItem> for AddressHistoryItemDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], AddressHistoryItem, E> {
+        let Some((tag, rest)) = buffer.split_first() else {
+            return Err(nom::Err::Error(ParseError::from_error_kind(
+                buffer,
+                nom::error::ErrorKind::Eof,
+            )));
+        };
+        match tag {
+            0 => context("Failed OperationId deserialization", |input| {
+                self.operation_id_deserializer.deserialize(input)
+            })(rest)
+            .map(|(rest, id)| (rest, AddressHistoryItem::Operation(id))),
+            1 => context("Failed AsyncMessageId deserialization", |input| {
+                self.message_id_deserializer.deserialize(input)
+            })(rest)
+            .map(|(rest, id)| (rest, AddressHistoryItem::AsyncMessage(id))),
+            2 => context("Failed roll count deserialization", |input| {
+                self.u64_deserializer.deserialize(input)
+            })(rest)
+            .map(|(rest, roll_count)| (rest, AddressHistoryItem::RollCount(roll_count))),
+            3 => {
+                let (rest, slot) = context("Failed slot deserialization", |input| {
+                    self.slot_deserializer.deserialize(input)
+                })(rest)?;
+                let (rest, amount) = context("Failed amount deserialization", |input| {
+                    self.amount_deserializer.deserialize(input)
+                })(rest)?;
+                Ok((rest, AddressHistoryItem::DeferredCredit { slot, amount }))
+            }
+            _ => Err(nom::Err::Error(ParseError::from_error_kind(
+                buffer,
+                nom::error::ErrorKind::Digit,
+            ))),
+        }
+    }
+}
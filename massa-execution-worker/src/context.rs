@@ -12,13 +12,15 @@ use crate::speculative_async_pool::SpeculativeAsyncPool;
 use crate::speculative_executed_denunciations::SpeculativeExecutedDenunciations;
 use crate::speculative_executed_ops::SpeculativeExecutedOps;
 use crate::speculative_ledger::SpeculativeLedger;
+use crate::trace_recorder::TraceRecorder;
 use crate::{active_history::ActiveHistory, speculative_roll_state::SpeculativeRollState};
 use massa_async_pool::{AsyncMessage, AsyncPoolChanges};
 use massa_async_pool::{AsyncMessageId, AsyncMessageInfo};
 use massa_executed_ops::{ExecutedDenunciationsChanges, ExecutedOpsChanges};
 use massa_execution_exports::{
-    EventStore, ExecutedBlockInfo, ExecutionConfig, ExecutionError, ExecutionOutput,
-    ExecutionStackElement,
+    AddressHistoryChanges, AddressHistoryItem, CallTrace, EventStore, ExecutedBlockInfo,
+    ExecutionConfig, ExecutionError, ExecutionOutput, ExecutionStackElement, ExecutionTrace,
+    ExecutionTraceOrigin, LedgerWriteTrace,
 };
 use massa_final_state::{FinalState, StateChanges};
 use massa_hash::Hash;
@@ -46,6 +48,7 @@ use parking_lot::RwLock;
 use rand::SeedableRng;
 use rand_xoshiro::Xoshiro256PlusPlus;
 use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound::{Excluded, Unbounded};
 use std::sync::Arc;
 use tracing::{debug, warn};
 
@@ -87,6 +90,9 @@ pub struct ExecutionContextSnapshot {
 
     /// Unsafe random state
     pub unsafe_rng: Xoshiro256PlusPlus,
+
+    /// addresses affected so far during this execution
+    pub address_history: AddressHistoryChanges,
 }
 
 /// An execution context that needs to be initialized before executing bytecode,
@@ -128,6 +134,9 @@ pub struct ExecutionContext {
     /// max gas for this execution
     pub max_gas: u64,
 
+    /// remaining gas last reported by the runtime during the current execution, if any
+    pub last_remaining_gas: Option<u64>,
+
     /// minimal balance allowed for the creator of the operation after its execution
     pub creator_min_balance: Option<Amount>,
 
@@ -172,6 +181,18 @@ pub struct ExecutionContext {
 
     /// Address factory
     pub address_factory: AddressFactory,
+
+    /// call tree of the operation or async message being executed, if tracing is enabled
+    trace_recorder: Option<TraceRecorder>,
+
+    /// execution traces recorded so far at this slot
+    traces: Vec<ExecutionTrace>,
+
+    /// operation or async message to which coin transfers are attributed in the address history, if any
+    address_history_origin: Option<AddressHistoryItem>,
+
+    /// addresses affected so far at this slot, if the address history is enabled
+    address_history: AddressHistoryChanges,
 }
 
 impl ExecutionContext {
@@ -219,6 +240,7 @@ impl ExecutionContext {
                 active_history,
             ),
             max_gas: Default::default(),
+            last_remaining_gas: Default::default(),
             creator_min_balance: Default::default(),
             slot: Slot::new(0, 0),
             created_addr_index: Default::default(),
@@ -235,6 +257,10 @@ impl ExecutionContext {
             config,
             address_factory: AddressFactory { mip_store },
             execution_trail_hash,
+            trace_recorder: None,
+            traces: Default::default(),
+            address_history_origin: None,
+            address_history: Default::default(),
         }
     }
 
@@ -255,6 +281,7 @@ impl ExecutionContext {
             stack: self.stack.clone(),
             events: self.events.clone(),
             unsafe_rng: self.unsafe_rng.clone(),
+            address_history: self.address_history.clone(),
         }
     }
 
@@ -282,6 +309,7 @@ impl ExecutionContext {
         self.created_message_index = snapshot.created_message_index;
         self.stack = snapshot.stack;
         self.unsafe_rng = snapshot.unsafe_rng;
+        self.address_history = snapshot.address_history;
 
         // For events, set snapshot delta to error events.
         // Start iterating from snapshot events length because we are dealing with a VecDeque.
@@ -575,8 +603,22 @@ impl ExecutionContext {
         }
 
         // set data entry
-        self.speculative_ledger
-            .set_data_entry(&self.get_current_address()?, address, key, data)
+        let value = self.trace_recorder.as_ref().map(|_| data.clone());
+        self.speculative_ledger.set_data_entry(
+            &self.get_current_address()?,
+            address,
+            key.clone(),
+            data,
+        )?;
+
+        if let (Some(recorder), Some(value)) = (&mut self.trace_recorder, value) {
+            recorder.record_ledger_write(LedgerWriteTrace::DatastoreSet {
+                address: *address,
+                key,
+                value,
+            });
+        }
+        Ok(())
     }
 
     /// Appends data to a datastore entry for an address in the speculative ledger.
@@ -616,8 +658,22 @@ impl ExecutionContext {
         res_data.extend(data);
 
         // set data entry
-        self.speculative_ledger
-            .set_data_entry(&self.get_current_address()?, address, key, res_data)
+        let value = self.trace_recorder.as_ref().map(|_| res_data.clone());
+        self.speculative_ledger.set_data_entry(
+            &self.get_current_address()?,
+            address,
+            key.clone(),
+            res_data,
+        )?;
+
+        if let (Some(recorder), Some(value)) = (&mut self.trace_recorder, value) {
+            recorder.record_ledger_write(LedgerWriteTrace::DatastoreSet {
+                address: *address,
+                key,
+                value,
+            });
+        }
+        Ok(())
     }
 
     /// Deletes a datastore entry for an address.
@@ -641,7 +697,15 @@ impl ExecutionContext {
 
         // delete entry
         self.speculative_ledger
-            .delete_data_entry(&self.get_current_address()?, address, key)
+            .delete_data_entry(&self.get_current_address()?, address, key)?;
+
+        if let Some(recorder) = &mut self.trace_recorder {
+            recorder.record_ledger_write(LedgerWriteTrace::DatastoreDelete {
+                address: *address,
+                key: key.to_vec(),
+            });
+        }
+        Ok(())
     }
 
     /// Transfers coins from one address to another.
@@ -685,7 +749,28 @@ impl ExecutionContext {
 
         // do the transfer
         self.speculative_ledger
-            .transfer_coins(from_addr, to_addr, amount)
+            .transfer_coins(from_addr, to_addr, amount)?;
+
+        if self.trace_recorder.is_some() {
+            let balances: Vec<_> = [from_addr, to_addr]
+                .into_iter()
+                .flatten()
+                .map(|address| (address, self.get_balance(&address).unwrap_or_default()))
+                .collect();
+            if let Some(recorder) = &mut self.trace_recorder {
+                recorder.record_transfer(from_addr, to_addr, amount);
+                for (address, balance) in balances {
+                    recorder.record_ledger_write(LedgerWriteTrace::Balance { address, balance });
+                }
+            }
+        }
+
+        if let Some(origin) = self.address_history_origin.clone() {
+            for address in [from_addr, to_addr].into_iter().flatten() {
+                self.record_address_history(address, origin.clone());
+            }
+        }
+        Ok(())
     }
 
     /// Add a new asynchronous message to speculative pool
@@ -835,7 +920,15 @@ impl ExecutionContext {
                         "could not credit {} deferred coins to {} at slot {}: {}",
                         amount, address, slot, e
                     );
+                    continue;
                 }
+                self.record_address_history(
+                    address,
+                    AddressHistoryItem::DeferredCredit {
+                        slot: *slot,
+                        amount,
+                    },
+                );
             }
         }
     }
@@ -887,11 +980,32 @@ impl ExecutionContext {
             );
         }
 
+        // record the roll count and scheduled deferred credit changes in the address history
+        let pos_changes = self.speculative_roll_state.take();
+        for (address, roll_count) in &pos_changes.roll_changes {
+            self.record_address_history(*address, AddressHistoryItem::RollCount(*roll_count));
+        }
+        for (credit_slot, credits) in pos_changes
+            .deferred_credits
+            .credits
+            .range((Excluded(slot), Unbounded))
+        {
+            for (address, amount) in credits {
+                self.record_address_history(
+                    *address,
+                    AddressHistoryItem::DeferredCredit {
+                        slot: *credit_slot,
+                        amount: *amount,
+                    },
+                );
+            }
+        }
+
         // generate the execution output
         let state_changes = StateChanges {
             ledger_changes,
             async_pool_changes: self.speculative_async_pool.take(),
-            pos_changes: self.speculative_roll_state.take(),
+            pos_changes,
             executed_ops_changes: self.speculative_executed_ops.take(),
             executed_denunciations_changes: self.speculative_executed_denunciations.take(),
             execution_trail_hash_change: SetOrKeep::Set(self.execution_trail_hash),
@@ -903,6 +1017,8 @@ impl ExecutionContext {
             block_info,
             state_changes,
             events: std::mem::take(&mut self.events),
+            traces: std::mem::take(&mut self.traces),
+            address_history: std::mem::take(&mut self.address_history),
         }
     }
 
@@ -925,9 +1041,9 @@ impl ExecutionContext {
             )));
         }
 
-        // Do not allow user addresses to store bytecode.
+        // Do not allow user and multisig addresses to store bytecode.
         // See: https://github.com/massalabs/massa/discussions/2952
-        if let Address::User(_) = address {
+        if let Address::User(_) | Address::Multisig(_) = address {
             return Err(ExecutionError::RuntimeError(format!(
                 "can't set the bytecode of address {} because this is not a smart contract address",
                 address
@@ -936,7 +1052,12 @@ impl ExecutionContext {
 
         // set data entry
         self.speculative_ledger
-            .set_bytecode(&self.get_current_address()?, address, bytecode)
+            .set_bytecode(&self.get_current_address()?, address, bytecode)?;
+
+        if let Some(recorder) = &mut self.trace_recorder {
+            recorder.record_ledger_write(LedgerWriteTrace::Bytecode { address: *address });
+        }
+        Ok(())
     }
 
     /// Creates a new event but does not emit it.
@@ -974,6 +1095,110 @@ impl ExecutionContext {
         self.events.push(event);
     }
 
+    /// Starts recording the call tree of an operation or async message execution, if tracing is enabled.
+    /// Read-only executions are never traced.
+    ///
+    /// # Arguments
+    /// * `root`: root call of the execution
+    pub fn start_trace(&mut self, root: CallTrace) {
+        if self.config.execution_traces_enabled && !self.read_only {
+            self.trace_recorder = Some(TraceRecorder::new(root));
+        }
+    }
+
+    /// Records the start of a sub-call in the current execution trace, if any
+    ///
+    /// # Arguments
+    /// * `caller`: calling address
+    /// * `callee`: called address
+    /// * `function`: called function, if known
+    /// * `coins`: coins sent along with the call
+    pub fn trace_call_start(
+        &mut self,
+        caller: Address,
+        callee: Address,
+        function: Option<String>,
+        coins: Amount,
+    ) {
+        if let Some(recorder) = &mut self.trace_recorder {
+            recorder.start_call(caller, callee, function, coins);
+        }
+    }
+
+    /// Records the end of a sub-call in the current execution trace, if any
+    pub fn trace_call_finish(&mut self) {
+        if let Some(recorder) = &mut self.trace_recorder {
+            recorder.finish_call();
+        }
+    }
+
+    /// Records the remaining gas reported by the runtime during the current execution.
+    /// It measures the gas used by the sub-calls of the current execution trace, if any,
+    /// and the gas used by the execution if it fails.
+    ///
+    /// # Arguments
+    /// * `remaining_gas`: gas left to the execution
+    pub fn record_remaining_gas(&mut self, remaining_gas: u64) {
+        self.last_remaining_gas = Some(remaining_gas);
+        if let Some(recorder) = &mut self.trace_recorder {
+            recorder.record_remaining_gas(remaining_gas);
+        }
+    }
+
+    /// Stops recording the current execution trace and stores it with the traces of the slot.
+    /// Does nothing if no trace is being recorded.
+    ///
+    /// # Arguments
+    /// * `origin`: operation or async message that was executed
+    /// * `gas_used`: gas used by the execution, if known
+    /// * `error`: error that caused the execution to be reverted, if any
+    pub fn finish_trace(
+        &mut self,
+        origin: ExecutionTraceOrigin,
+        gas_used: Option<u64>,
+        error: Option<&ExecutionError>,
+    ) {
+        if let Some(recorder) = self.trace_recorder.take() {
+            let remaining_gas = gas_used.map(|gas_used| self.max_gas.saturating_sub(gas_used));
+            self.traces.push(ExecutionTrace {
+                slot: self.slot,
+                origin,
+                call: recorder.finish(gas_used, remaining_gas, error.map(|err| err.to_string())),
+            });
+        }
+    }
+
+    /// Records that an operation or async message affects the given addresses,
+    /// and attributes the coin transfers that follow to it until `finish_address_history` is called.
+    /// Does nothing if the address history is disabled.
+    ///
+    /// # Arguments
+    /// * `origin`: operation or async message being executed
+    /// * `addresses`: addresses affected by the execution whatever its outcome
+    pub fn start_address_history(
+        &mut self,
+        origin: AddressHistoryItem,
+        addresses: impl IntoIterator<Item = Address>,
+    ) {
+        for address in addresses {
+            self.record_address_history(address, origin.clone());
+        }
+        self.address_history_origin = Some(origin);
+    }
+
+    /// Stops attributing coin transfers to the operation or async message being executed
+    pub fn finish_address_history(&mut self) {
+        self.address_history_origin = None;
+    }
+
+    /// Records that an address was affected at this slot, if the address history is enabled.
+    /// Read-only executions are never recorded.
+    fn record_address_history(&mut self, address: Address, item: AddressHistoryItem) {
+        if self.config.address_history_enabled && !self.read_only {
+            self.address_history.insert((address, item));
+        }
+    }
+
     /// Check if an operation was previously executed (to prevent reuse)
     pub fn is_op_executed(&self, op_id: &OperationId) -> bool {
         self.speculative_executed_ops.is_op_executed(op_id)
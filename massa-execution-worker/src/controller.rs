@@ -7,11 +7,13 @@ use crate::execution::ExecutionState;
 use crate::request_queue::{RequestQueue, RequestWithResponseSender};
 use massa_channel::MassaChannel;
 use massa_execution_exports::{
-    ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, ExecutionQueryError, ExecutionQueryExecutionStatus,
-    ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponse,
-    ExecutionQueryResponseItem, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    AddressHistoryCursor, AddressHistoryEntry, ExecutionAddressInfo, ExecutionBlockMetadata,
+    ExecutionConfig, ExecutionController, ExecutionError, ExecutionManager, ExecutionQueryError,
+    ExecutionQueryExecutionStatus, ExecutionQueryRequest, ExecutionQueryRequestItem,
+    ExecutionQueryResponse, ExecutionQueryResponseItem, OperationSimulationOutput,
+    OperationSimulationRequest, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
 };
+use massa_ledger_exports::{Key as LedgerKey, KeyType};
 use massa_models::denunciation::DenunciationIndex;
 use massa_models::execution::EventFilter;
 use massa_models::output_event::SCOutputEvent;
@@ -37,13 +39,15 @@ pub(crate) struct ExecutionInputData {
     pub block_metadata: PreHashMap<BlockId, ExecutionBlockMetadata>,
     /// queue for read-only execution requests and response MPSCs to send back their outputs
     pub readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+    /// queue for operation simulation requests and response MPSCs to send back their outputs
+    pub simulation_requests: RequestQueue<OperationSimulationRequest, OperationSimulationOutput>,
 }
 
 impl Display for ExecutionInputData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "stop={:?}, finalized={:?}, blockclique={:?}, readonly={:?}, storage={:?}",
+            "stop={:?}, finalized={:?}, blockclique={:?}, readonly={:?}, simulation={:?}, storage={:?}",
             self.stop,
             self.finalized_blocks
                 .iter()
@@ -54,6 +58,7 @@ impl Display for ExecutionInputData {
                 .map(|(slot, id)| (*slot, *id))
                 .collect::<BTreeMap<Slot, BlockId>>()),
             self.readonly_requests,
+            self.simulation_requests,
             self.block_metadata.keys().collect::<Vec<&BlockId>>(),
         )
     }
@@ -68,6 +73,7 @@ impl ExecutionInputData {
             new_blockclique: Default::default(),
             block_metadata: Default::default(),
             readonly_requests: RequestQueue::new(config.max_final_events),
+            simulation_requests: RequestQueue::new(config.readonly_queue_length),
         }
     }
 
@@ -75,6 +81,7 @@ impl ExecutionInputData {
     /// and resets self.
     pub fn take(&mut self) -> Self {
         let max_final_events = self.readonly_requests.capacity();
+        let readonly_queue_length = self.simulation_requests.capacity();
         ExecutionInputData {
             stop: std::mem::take(&mut self.stop),
             finalized_blocks: std::mem::take(&mut self.finalized_blocks),
@@ -84,6 +91,10 @@ impl ExecutionInputData {
                 &mut self.readonly_requests,
                 RequestQueue::new(max_final_events),
             ),
+            simulation_requests: std::mem::replace(
+                &mut self.simulation_requests,
+                RequestQueue::new(readonly_queue_length),
+            ),
         }
     }
 }
@@ -226,6 +237,52 @@ impl ExecutionController for ExecutionControllerImpl {
                         ))),
                     }
                 }
+                ExecutionQueryRequestItem::AddressBalanceProofFinal { addr, slot } => {
+                    execution_lock
+                        .get_final_ledger_proof(&LedgerKey::new(&addr, KeyType::BALANCE), slot)
+                        .map(ExecutionQueryResponseItem::StateProof)
+                }
+                ExecutionQueryRequestItem::AddressDatastoreValueProofFinal { addr, key, slot } => {
+                    execution_lock
+                        .get_final_ledger_proof(
+                            &LedgerKey::new(&addr, KeyType::DATASTORE(key)),
+                            slot,
+                        )
+                        .map(ExecutionQueryResponseItem::StateProof)
+                }
+                ExecutionQueryRequestItem::AddressBalanceAtSlot { addr, slot } => {
+                    match execution_lock.get_balance_at_slot(&addr, slot) {
+                        Ok(Some(balance)) => Ok(ExecutionQueryResponseItem::Amount(balance)),
+                        Ok(None) => Err(ExecutionQueryError::NotFound(format!(
+                            "Account {} at slot {}",
+                            addr, slot
+                        ))),
+                        Err(err) => Err(err),
+                    }
+                }
+                ExecutionQueryRequestItem::AddressBytecodeAtSlot { addr, slot } => {
+                    match execution_lock.get_bytecode_at_slot(&addr, slot) {
+                        Ok(Some(bytecode)) => Ok(ExecutionQueryResponseItem::Bytecode(bytecode)),
+                        Ok(None) => Err(ExecutionQueryError::NotFound(format!(
+                            "Account {} at slot {}",
+                            addr, slot
+                        ))),
+                        Err(err) => Err(err),
+                    }
+                }
+                ExecutionQueryRequestItem::AddressDatastoreValueAtSlot { addr, key, slot } => {
+                    match execution_lock.get_data_entry_at_slot(&addr, &key, slot) {
+                        Ok(Some(value)) => Ok(ExecutionQueryResponseItem::DatastoreValue(value)),
+                        Ok(None) => Err(ExecutionQueryError::NotFound(format!(
+                            "Account {} datastore entry {:?} at slot {}",
+                            addr, key, slot
+                        ))),
+                        Err(err) => Err(err),
+                    }
+                }
+                ExecutionQueryRequestItem::AddressRollsAtSlot { addr, slot } => execution_lock
+                    .get_rolls_at_slot(&addr, slot)
+                    .map(ExecutionQueryResponseItem::RollCount),
                 ExecutionQueryRequestItem::OpExecutionStatusCandidate(id) => {
                     let (speculative_v, _final_v) = execution_lock
                         .get_ops_exec_status(&[id])
@@ -324,6 +381,52 @@ impl ExecutionController for ExecutionControllerImpl {
                         execution_lock.get_filtered_sc_output_event(filter),
                     ))
                 }
+                ExecutionQueryRequestItem::AsyncMessagesCandidate {
+                    filter,
+                    start_after,
+                    limit,
+                } => Ok(ExecutionQueryResponseItem::AsyncMessages(
+                    execution_lock.get_candidate_async_messages(
+                        &filter,
+                        start_after.as_ref(),
+                        limit,
+                    ),
+                )),
+                ExecutionQueryRequestItem::AsyncMessagesFinal {
+                    filter,
+                    start_after,
+                    limit,
+                } => Ok(ExecutionQueryResponseItem::AsyncMessages(
+                    execution_lock.get_final_async_messages(&filter, start_after.as_ref(), limit),
+                )),
+                ExecutionQueryRequestItem::AsyncMessageCandidate(id) => {
+                    let (_final_v, speculative_v) =
+                        execution_lock.get_final_and_candidate_async_message(&id);
+                    match speculative_v {
+                        Some(message) => Ok(ExecutionQueryResponseItem::AsyncMessage(message)),
+                        None => Err(ExecutionQueryError::NotFound(format!(
+                            "Async message {:?}",
+                            id
+                        ))),
+                    }
+                }
+                ExecutionQueryRequestItem::AsyncMessageFinal(id) => {
+                    let (final_v, _speculative_v) =
+                        execution_lock.get_final_and_candidate_async_message(&id);
+                    match final_v {
+                        Some(message) => Ok(ExecutionQueryResponseItem::AsyncMessage(message)),
+                        None => Err(ExecutionQueryError::NotFound(format!(
+                            "Async message {:?}",
+                            id
+                        ))),
+                    }
+                }
+                ExecutionQueryRequestItem::OpExecutionTraceFinal(id) => execution_lock
+                    .get_final_operation_trace(&id)
+                    .map(|trace| ExecutionQueryResponseItem::ExecutionTraces(vec![trace])),
+                ExecutionQueryRequestItem::SlotExecutionTracesFinal(slot) => execution_lock
+                    .get_final_slot_traces(&slot)
+                    .map(ExecutionQueryResponseItem::ExecutionTraces),
             };
             resp.responses.push(resp_item);
         }
@@ -419,6 +522,47 @@ impl ExecutionController for ExecutionControllerImpl {
         }
     }
 
+    /// Simulates the execution of an operation on top of the candidate state
+    /// Simulations do not modify consensus state
+    fn simulate_operation(
+        &self,
+        req: OperationSimulationRequest,
+    ) -> Result<OperationSimulationOutput, ExecutionError> {
+        let resp_rx = {
+            let mut input_data = self.input_data.1.lock();
+
+            // if the simulation queue is already full, return an error
+            if input_data.simulation_requests.is_full() {
+                return Err(ExecutionError::ChannelError(
+                    "too many queued operation simulation requests".into(),
+                ));
+            }
+
+            // prepare the channel to send back the result of the simulation
+            let (resp_tx, resp_rx) =
+                MassaChannel::new("operation_simulation_request".to_string(), None);
+
+            // append the request to the queue of input simulation requests
+            input_data
+                .simulation_requests
+                .push(RequestWithResponseSender::new(req, resp_tx));
+
+            // wake up the execution main loop
+            self.input_data.0.notify_one();
+
+            resp_rx
+        };
+
+        // Wait for the result of the simulation
+        match resp_rx.recv() {
+            Ok(result) => result,
+            Err(err) => Err(ExecutionError::ChannelError(format!(
+                "operation simulation response channel readout failed: {}",
+                err
+            ))),
+        }
+    }
+
     /// Check if a denunciation has been executed given a `DenunciationIndex`
     /// Returns a tuple of booleans: `(speculative_execution_status, final_execution_status)`
     fn get_denunciation_execution_status(
@@ -460,6 +604,18 @@ impl ExecutionController for ExecutionControllerImpl {
         self.execution_state.read().get_stats()
     }
 
+    /// See trait definition
+    fn get_address_history(
+        &self,
+        address: &Address,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> Result<Vec<AddressHistoryEntry>, ExecutionError> {
+        self.execution_state
+            .read()
+            .get_address_history(address, cursor, limit)
+    }
+
     /// Returns a boxed clone of self.
     /// Allows cloning `Box<dyn ExecutionController>`,
     /// see `massa-execution-exports/controller_traits.rs`
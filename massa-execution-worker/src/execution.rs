@@ -9,18 +9,25 @@
 //! * the output of the execution is extracted from the context
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
+use crate::address_history::AddressHistoryDB;
 use crate::context::{ExecutionContext, ExecutionContextSnapshot};
 use crate::interface_impl::InterfaceImpl;
 use crate::stats::ExecutionStatsCounter;
-use massa_async_pool::AsyncMessage;
+use massa_async_pool::{AsyncMessage, AsyncMessageFilter, AsyncMessageId, AsyncPoolChanges};
+use massa_db_exports::StateProof;
 use massa_execution_exports::{
-    EventStore, ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig,
-    ExecutionError, ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryStakerInfo,
-    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
-    ReadOnlyExecutionTarget, SlotExecutionOutput,
+    AddressHistoryCursor, AddressHistoryEntry, AddressHistoryItem, CallTrace, EventStore,
+    ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig, ExecutionError,
+    ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryError, ExecutionQueryStakerInfo,
+    ExecutionStackElement, ExecutionTrace, ExecutionTraceOrigin, ExecutionTraceStore,
+    OperationSimulationOutput, OperationSimulationRequest, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlotExecutionOutput,
 };
 use massa_final_state::FinalState;
-use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
+use massa_hash::Hash;
+use massa_ledger_exports::{
+    Applicable, Key as LedgerKey, KeySerializer, SetOrDelete, SetOrKeep, SetUpdateOrDelete,
+};
 use massa_metrics::MassaMetrics;
 use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::bytecode::Bytecode;
@@ -29,22 +36,28 @@ use massa_models::denunciation::{Denunciation, DenunciationIndex};
 use massa_models::execution::EventFilter;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
+use massa_models::secure_share::Id;
 use massa_models::stats::ExecutionStats;
 use massa_models::timeslots::get_block_slot_timestamp;
 use massa_models::{
     address::Address,
     block_id::BlockId,
-    operation::{OperationId, OperationType, SecureShareOperation},
+    operation::{
+        Operation, OperationId, OperationSerializer, OperationType, SecureShareOperation,
+        SupersededOperation,
+    },
 };
 use massa_models::{amount::Amount, slot::Slot};
 use massa_module_cache::config::ModuleCacheConfig;
 use massa_module_cache::controller::ModuleCache;
 use massa_pos_exports::SelectorController;
 use massa_sc_runtime::{Interface, Response, VMError};
-use massa_versioning::versioning::MipStore;
+use massa_serialization::Serializer;
+use massa_versioning::versioning::{MipComponent, MipStore};
 use massa_wallet::Wallet;
 use parking_lot::{Mutex, RwLock};
 use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound;
 use std::sync::Arc;
 use tracing::{debug, info, trace, warn};
 
@@ -72,6 +85,10 @@ pub(crate) struct ExecutionState {
     pub final_cursor: Slot,
     // store containing execution events that became final
     final_events: EventStore,
+    // store containing the execution traces of the latest final slots
+    final_traces: ExecutionTraceStore,
+    // index of the history of the addresses affected by final slots, if enabled
+    address_history: Option<AddressHistoryDB>,
     // final state with atomic R/W access
     final_state: Arc<RwLock<FinalState>>,
     // execution context (see documentation in context.rs)
@@ -152,6 +169,11 @@ impl ExecutionState {
             execution_context.clone(),
         ));
 
+        // Open the address history index, kept across restarts
+        let address_history = config
+            .address_history_enabled
+            .then(|| AddressHistoryDB::new(&config.address_history_path, config.thread_count));
+
         // build the execution state
         ExecutionState {
             final_state,
@@ -161,6 +183,9 @@ impl ExecutionState {
             active_history,
             // empty final event store: it is not recovered through bootstrap
             final_events: Default::default(),
+            // empty final trace store: it is not recovered through bootstrap
+            final_traces: Default::default(),
+            address_history,
             // no active slots executed yet: set active_cursor to the last final block
             active_cursor: last_final_slot,
             final_cursor: last_final_slot,
@@ -186,6 +211,26 @@ impl ExecutionState {
             .get_stats(self.active_cursor, self.final_cursor)
     }
 
+    /// Get a page of the history of an address, from the newest final entries to the oldest ones
+    ///
+    /// # Arguments
+    /// * `address`: address whose history is requested
+    /// * `cursor`: only return entries older than this cursor, from the newest entry if `None`
+    /// * `limit`: maximum number of returned entries
+    pub fn get_address_history(
+        &self,
+        address: &Address,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> Result<Vec<AddressHistoryEntry>, ExecutionError> {
+        match &self.address_history {
+            Some(address_history) => Ok(address_history.get_history(address, cursor, limit)),
+            None => Err(ExecutionError::AddressHistoryError(
+                "the address history is disabled on this node".to_string(),
+            )),
+        }
+    }
+
     /// Applies the output of an execution to the final execution state.
     /// The newly applied final output should be from the slot just after the last executed final slot
     ///
@@ -227,11 +272,24 @@ impl ExecutionState {
             self.active_cursor = self.final_cursor;
         }
 
+        // index the addresses affected at this slot
+        if let Some(address_history) = &self.address_history {
+            address_history.apply_slot(&exec_out.slot, &exec_out.address_history);
+        }
+
         // append generated events to the final event store
         exec_out.events.finalize();
         self.final_events.extend(exec_out.events);
         self.final_events.prune(self.config.max_final_events);
 
+        // append recorded execution traces to the final trace store
+        if self.config.execution_traces_enabled {
+            self.final_traces
+                .push(exec_out.slot, std::mem::take(&mut exec_out.traces));
+            self.final_traces
+                .prune(self.config.execution_traces_slot_limit);
+        }
+
         // update the prometheus metrics
         self.massa_metrics
             .set_active_cursor(self.active_cursor.period, self.active_cursor.thread);
@@ -301,15 +359,15 @@ impl ExecutionState {
     /// - if not yet executed then transfer fee and add the operation to the context then return a context snapshot
     ///
     /// # Arguments
-    /// * `operation`: operation to be schedule
+    /// * `operation_id`: ID of the operation
+    /// * `operation`: content of the operation to be scheduled
     /// * `sender_addr`: sender address for the operation (for fee transfer)
     fn prepare_operation_for_execution(
         &self,
-        operation: &SecureShareOperation,
+        operation_id: OperationId,
+        operation: &Operation,
         sender_addr: Address,
     ) -> Result<ExecutionContextSnapshot, ExecutionError> {
-        let operation_id = operation.id;
-
         // lock execution context
         let mut context = context_guard!(self);
 
@@ -320,6 +378,18 @@ impl ExecutionState {
             ));
         }
 
+        // ignore the operation if an operation of its sender superseded it
+        let supersession_id = SupersededOperation {
+            id: operation_id,
+            expire_period: operation.expire_period,
+        }
+        .get_supersession_id(&sender_addr);
+        if context.is_op_executed(&supersession_id) {
+            return Err(ExecutionError::IncludeOperationError(
+                "operation was superseded".to_string(),
+            ));
+        }
+
         // Compute the minimal amount of coins the sender is allowed to have after the execution of this op based on `op.max_spending`.
         // Note that the max spending might exceed the sender's balance.
         let creator_initial_balance = context
@@ -331,9 +401,7 @@ impl ExecutionState {
         );
 
         // debit the fee from the operation sender
-        if let Err(err) =
-            context.transfer_coins(Some(sender_addr), None, operation.content.fee, false)
-        {
+        if let Err(err) = context.transfer_coins(Some(sender_addr), None, operation.fee, false) {
             let error = format!("could not spend fees: {}", err);
             let event = context.event_create(error.clone(), true);
             context.event_emit(event);
@@ -343,6 +411,12 @@ impl ExecutionState {
         // from here, fees have been transferred.
         // Op will be executed just after in the context of a snapshot.
 
+        // record the addresses involved in the operation, whatever the outcome of its execution
+        context.start_address_history(
+            AddressHistoryItem::Operation(operation_id),
+            operation.get_ledger_involved_addresses(sender_addr),
+        );
+
         // save a snapshot of the context to revert any further changes on error
         let context_snapshot = context.get_snapshot();
 
@@ -350,7 +424,7 @@ impl ExecutionState {
         context.max_gas = operation.get_gas_usage();
 
         // set the creator address
-        context.creator_address = Some(operation.content_creator_address);
+        context.creator_address = Some(sender_addr);
 
         // set the context origin operation ID
         context.origin_operation_id = Some(operation_id);
@@ -402,13 +476,101 @@ impl ExecutionState {
             ));
         }
 
+        // multisig operations are only allowed once enabled by versioning,
+        // cannot buy or sell rolls,
+        // and need enough valid signatures from the co-signers of the multisig address
+        if let OperationType::Multisig { op, .. } = &operation.content.op {
+            let slot_ts = get_block_slot_timestamp(
+                self.config.thread_count,
+                self.config.t0,
+                self.config.genesis_timestamp,
+                block_slot,
+            )?;
+            if self
+                .mip_store
+                .get_latest_component_version_at(&MipComponent::Multisig, slot_ts)
+                == 0
+            {
+                return Err(ExecutionError::IncludeOperationError(
+                    "multisig operations are not enabled yet".to_string(),
+                ));
+            }
+            if matches!(
+                op.as_ref(),
+                OperationType::RollBuy { .. } | OperationType::RollSell { .. }
+            ) {
+                return Err(ExecutionError::IncludeOperationError(
+                    "multisig operations cannot buy or sell rolls".to_string(),
+                ));
+            }
+            operation.verify_multisig_signatures().map_err(|err| {
+                ExecutionError::IncludeOperationError(format!(
+                    "invalid multisig signatures: {}",
+                    err
+                ))
+            })?;
+        }
+
+        // sequenced operations are only allowed once enabled by versioning,
+        // after the operation they depend on and before the operation they supersede.
+        // The superseded operation has to expire within the validity period of operations,
+        // as it is recorded until then.
+        if let OperationType::Sequenced {
+            supersedes, after, ..
+        } = &operation.content.op
+        {
+            let slot_ts = get_block_slot_timestamp(
+                self.config.thread_count,
+                self.config.t0,
+                self.config.genesis_timestamp,
+                block_slot,
+            )?;
+            if self
+                .mip_store
+                .get_latest_component_version_at(&MipComponent::SequencedOperations, slot_ts)
+                == 0
+            {
+                return Err(ExecutionError::IncludeOperationError(
+                    "sequenced operations are not enabled yet".to_string(),
+                ));
+            }
+            let context = context_guard!(self);
+            if let Some(after) = after {
+                if !context.is_op_executed(after) {
+                    return Err(ExecutionError::IncludeOperationError(format!(
+                        "operation {} has to be executed first",
+                        after
+                    )));
+                }
+            }
+            if let Some(supersedes) = supersedes {
+                if supersedes.expire_period
+                    > block_slot
+                        .period
+                        .saturating_add(self.config.operation_validity_period)
+                {
+                    return Err(ExecutionError::IncludeOperationError(format!(
+                        "superseded operation {} expires too late",
+                        supersedes
+                    )));
+                }
+                if context.is_op_executed(&supersedes.id) {
+                    return Err(ExecutionError::IncludeOperationError(format!(
+                        "superseded operation {} was already executed",
+                        supersedes.id
+                    )));
+                }
+            }
+        }
+
         // get operation ID
         let operation_id = operation.id;
 
         // Add fee from operation.
         let new_block_credits = block_credits.saturating_add(operation.content.fee);
 
-        let context_snapshot = self.prepare_operation_for_execution(operation, sender_addr)?;
+        let context_snapshot =
+            self.prepare_operation_for_execution(operation_id, &operation.content, sender_addr)?;
 
         // update block gas
         *remaining_block_gas = new_remaining_block_gas;
@@ -416,73 +578,250 @@ impl ExecutionState {
         // update block credits
         *block_credits = new_block_credits;
 
-        // Call the execution process specific to the operation type.
-        let mut execution_result = match &operation.content.op {
-            OperationType::ExecuteSC { .. } => {
-                self.execute_executesc_op(&operation.content.op, sender_addr)
+        // execute the operation, errors are recorded in the context
+        self.execute_prepared_operation(
+            operation_id,
+            &operation.content,
+            sender_addr,
+            context_snapshot,
+        );
+        let mut context = context_guard!(self);
+        // the superseded operation can no longer be executed if it was created by the same sender,
+        // until it expires
+        if let Some(superseded) = operation.content.op.get_superseded_op() {
+            context.insert_executed_op(
+                superseded.get_supersession_id(&sender_addr),
+                false,
+                Slot::new(superseded.expire_period, op_thread),
+            );
+        }
+        context.finish_address_history();
+
+        Ok(())
+    }
+
+    /// Execute an operation for which `prepare_operation_for_execution` succeeded.
+    /// On failure, the context is reset to the snapshot taken after the fees were spent.
+    /// In all cases, the operation is marked as executed.
+    ///
+    /// # Arguments
+    /// * `operation_id`: ID of the operation
+    /// * `operation`: content of the operation
+    /// * `sender_addr`: address of the sender
+    /// * `context_snapshot`: snapshot returned by `prepare_operation_for_execution`
+    ///
+    /// # Returns
+    /// The VM gas used by operations running bytecode, even if they failed, `None` for other operations,
+    /// and the error that made the execution fail, if any
+    fn execute_prepared_operation(
+        &self,
+        operation_id: OperationId,
+        operation: &Operation,
+        sender_addr: Address,
+        context_snapshot: ExecutionContextSnapshot,
+    ) -> (Option<u64>, Result<(), ExecutionError>) {
+        // get the thread to which the operation belongs
+        let op_thread = sender_addr.get_thread(self.config.thread_count);
+
+        // multisig operations execute their inner operation with the multisig address as sender,
+        // which is then subject to the max spending limit instead of the operation creator
+        let (executed_op, sender_addr) = match &operation.op {
+            OperationType::Multisig {
+                multisig_public_key,
+                op,
+                ..
+            } => {
+                let multisig_addr = Address::from_multisig_public_key(multisig_public_key);
+                let mut context = context_guard!(self);
+                let multisig_initial_balance = context
+                    .get_balance(&multisig_addr)
+                    .unwrap_or_else(Amount::zero);
+                context.creator_min_balance = Some(
+                    multisig_initial_balance
+                        .saturating_sub(op.get_max_non_fee_spending(self.config.roll_price)),
+                );
+                context.creator_address = Some(multisig_addr);
+                (op.as_ref(), multisig_addr)
             }
+            OperationType::Sequenced { op, .. } => (op.as_ref(), sender_addr),
+            op => (op, sender_addr),
+        };
+
+        // start recording the execution trace of the operation
+        {
+            let mut context = context_guard!(self);
+            context.last_remaining_gas = None;
+            context.start_trace(Self::operation_root_call(executed_op, sender_addr));
+        }
+
+        // Call the execution process specific to the operation type.
+        // The VM gas used by the execution is returned for operations running bytecode.
+        let execution_result = match executed_op {
+            OperationType::ExecuteSC { .. } => self
+                .execute_executesc_op(executed_op, sender_addr)
+                .map(Some),
             OperationType::CallSC { .. } => {
-                self.execute_callsc_op(&operation.content.op, sender_addr)
-            }
-            OperationType::RollBuy { .. } => {
-                self.execute_roll_buy_op(&operation.content.op, sender_addr)
+                self.execute_callsc_op(executed_op, sender_addr).map(Some)
             }
-            OperationType::RollSell { .. } => {
-                self.execute_roll_sell_op(&operation.content.op, sender_addr)
-            }
-            OperationType::Transaction { .. } => {
-                self.execute_transaction_op(&operation.content.op, sender_addr)
+            OperationType::RollBuy { .. } => self
+                .execute_roll_buy_op(executed_op, sender_addr)
+                .map(|_| None),
+            OperationType::RollSell { .. } => self
+                .execute_roll_sell_op(executed_op, sender_addr)
+                .map(|_| None),
+            OperationType::Transaction { .. } => self
+                .execute_transaction_op(executed_op, sender_addr)
+                .map(|_| None),
+            OperationType::Multisig { .. } | OperationType::Sequenced { .. } => {
+                Err(ExecutionError::RuntimeError(
+                    "multisig and sequenced operations cannot be nested".to_string(),
+                ))
             }
         };
+        let (gas_used, mut execution_result) = match execution_result {
+            Ok(gas_used) => (gas_used, Ok(())),
+            Err(err) => (self.failed_execution_gas(executed_op, &err), Err(err)),
+        };
 
-        {
-            // lock execution context
-            let mut context = context_guard!(self);
+        // lock execution context
+        let mut context = context_guard!(self);
 
-            if execution_result.is_ok() {
-                // check that the `max_coins` spending limit was respected by the sender
-                if let Some(creator_min_balance) = &context.creator_min_balance {
-                    let creator_balance = context
-                        .get_balance(&sender_addr)
-                        .unwrap_or_else(Amount::zero);
-                    if &creator_balance < creator_min_balance {
-                        execution_result = Err(ExecutionError::RuntimeError(format!(
-                            "at the end of the execution of the operation, the sender {} was expected to have at least {} coins according to the operation's max spending, but has only {}.",
-                            sender_addr, creator_min_balance, creator_balance
-                        )));
-                    }
+        if execution_result.is_ok() {
+            // check that the `max_coins` spending limit was respected by the sender
+            if let Some(creator_min_balance) = &context.creator_min_balance {
+                let creator_balance = context
+                    .get_balance(&sender_addr)
+                    .unwrap_or_else(Amount::zero);
+                if &creator_balance < creator_min_balance {
+                    execution_result = Err(ExecutionError::RuntimeError(format!(
+                        "at the end of the execution of the operation, the sender {} was expected to have at least {} coins according to the operation's max spending, but has only {}.",
+                        sender_addr, creator_min_balance, creator_balance
+                    )));
                 }
             }
+        }
 
-            // check execution results
-            match execution_result {
-                Ok(_) => {
-                    context.insert_executed_op(
-                        operation_id,
-                        true,
-                        Slot::new(operation.content.expire_period, op_thread),
-                    );
-                }
-                Err(err) => {
-                    // an error occurred: emit error event and reset context to snapshot
-                    let err = ExecutionError::RuntimeError(format!(
-                        "runtime error when executing operation {}: {}",
-                        operation_id, &err
-                    ));
-                    debug!("{}", &err);
-                    context.reset_to_snapshot(context_snapshot, err);
+        // check execution results
+        match execution_result {
+            Ok(_) => {
+                context.finish_trace(
+                    ExecutionTraceOrigin::Operation(operation_id),
+                    gas_used,
+                    None,
+                );
+                context.insert_executed_op(
+                    operation_id,
+                    true,
+                    Slot::new(operation.expire_period, op_thread),
+                );
+                (gas_used, Ok(()))
+            }
+            Err(err) => {
+                // an error occurred: emit error event and reset context to snapshot
+                let err = ExecutionError::RuntimeError(format!(
+                    "runtime error when executing operation {}: {}",
+                    operation_id, &err
+                ));
+                debug!("{}", &err);
+                context.finish_trace(
+                    ExecutionTraceOrigin::Operation(operation_id),
+                    gas_used,
+                    Some(&err),
+                );
+                context.reset_to_snapshot(context_snapshot, err.clone());
 
-                    // Insert op AFTER the context has been restored (otherwise it would be overwritten)
-                    context.insert_executed_op(
-                        operation_id,
-                        false,
-                        Slot::new(operation.content.expire_period, op_thread),
-                    )
-                }
+                // Insert op AFTER the context has been restored (otherwise it would be overwritten)
+                context.insert_executed_op(
+                    operation_id,
+                    false,
+                    Slot::new(operation.expire_period, op_thread),
+                );
+                (gas_used, Err(err))
             }
         }
+    }
 
-        Ok(())
+    /// Gas used by an operation whose execution failed, `None` for operations that do not run bytecode.
+    ///
+    /// The runtime does not report the gas left when the VM fails. A VM failure is counted
+    /// up to the last remaining gas the runtime reported, when a sub-call loaded its bytecode,
+    /// and at least up to the instantiation of the module.
+    /// Other failures happen before the VM runs and use no gas,
+    /// except when `max_gas` cannot pay for the compilation of an `ExecuteSC` bytecode.
+    ///
+    /// # Arguments
+    /// * `operation`: the executed operation
+    /// * `err`: the error that made the execution fail
+    fn failed_execution_gas(&self, operation: &OperationType, err: &ExecutionError) -> Option<u64> {
+        let (max_gas, compilation_cost) = match operation {
+            OperationType::ExecuteSC { max_gas, .. } => {
+                (*max_gas, self.config.gas_costs.sp_compilation_cost)
+            }
+            OperationType::CallSC { max_gas, .. } => (*max_gas, 0),
+            _ => return None,
+        };
+        match err {
+            ExecutionError::VMError { error, .. } => {
+                let init_gas_cost = if let VMError::ExecutionError { init_gas_cost, .. } = error {
+                    *init_gas_cost
+                } else {
+                    0
+                };
+                let measured_gas = context_guard!(self)
+                    .last_remaining_gas
+                    .map_or(0, |remaining_gas| max_gas.saturating_sub(remaining_gas));
+                Some(
+                    measured_gas
+                        .max(compilation_cost.saturating_add(init_gas_cost))
+                        .min(max_gas),
+                )
+            }
+            _ if max_gas < compilation_cost => Some(max_gas),
+            _ => Some(0),
+        }
+    }
+
+    /// Builds the root call of the execution trace of an operation
+    ///
+    /// # Arguments
+    /// * `operation`: the operation to be executed
+    /// * `sender_addr`: address of the sender
+    fn operation_root_call(operation: &OperationType, sender_addr: Address) -> CallTrace {
+        match operation {
+            OperationType::ExecuteSC { .. } => CallTrace::new(
+                sender_addr,
+                sender_addr,
+                Some("main".to_string()),
+                Amount::zero(),
+            ),
+            OperationType::CallSC {
+                target_addr,
+                target_func,
+                coins,
+                ..
+            } => CallTrace::new(
+                sender_addr,
+                *target_addr,
+                (!target_func.is_empty()).then(|| target_func.clone()),
+                *coins,
+            ),
+            OperationType::Transaction {
+                recipient_address,
+                amount,
+            } => CallTrace::new(sender_addr, *recipient_address, None, *amount),
+            OperationType::RollBuy { .. } | OperationType::RollSell { .. } => {
+                CallTrace::new(sender_addr, sender_addr, None, Amount::zero())
+            }
+            OperationType::Multisig {
+                multisig_public_key,
+                op,
+                ..
+            } => Self::operation_root_call(
+                op,
+                Address::from_multisig_public_key(multisig_public_key),
+            ),
+            OperationType::Sequenced { op, .. } => Self::operation_root_call(op, sender_addr),
+        }
     }
 
     /// Execute a denunciation in the context of a block.
@@ -772,11 +1111,14 @@ impl ExecutionState {
     /// # Arguments
     /// * `operation`: the `WrappedOperation` to process, must be an `ExecuteSC`
     /// * `sender_addr`: address of the sender
+    ///
+    /// # Returns
+    /// The gas used by the execution, compilation included
     pub fn execute_executesc_op(
         &self,
         operation: &OperationType,
         sender_addr: Address,
-    ) -> Result<(), ExecutionError> {
+    ) -> Result<u64, ExecutionError> {
         // process ExecuteSC operations only
         let (bytecode, max_gas, datastore) = match &operation {
             OperationType::ExecuteSC {
@@ -816,7 +1158,7 @@ impl ExecutionState {
                 "not enough gas to pay for singlepass compilation".to_string(),
             ))?;
         // run the VM
-        massa_sc_runtime::run_main(
+        let response = massa_sc_runtime::run_main(
             &*self.execution_interface,
             module,
             remaining_gas,
@@ -827,7 +1169,7 @@ impl ExecutionState {
             error,
         })?;
 
-        Ok(())
+        Ok(max_gas.saturating_sub(response.remaining_gas))
     }
 
     /// Execute an operation of type `CallSC`
@@ -838,11 +1180,14 @@ impl ExecutionState {
     /// * `block_creator_addr`: address of the block creator
     /// * `operation_id`: ID of the operation
     /// * `sender_addr`: address of the sender
+    ///
+    /// # Returns
+    /// The gas used by the execution
     pub fn execute_callsc_op(
         &self,
         operation: &OperationType,
         sender_addr: Address,
-    ) -> Result<(), ExecutionError> {
+    ) -> Result<u64, ExecutionError> {
         // process CallSC operations only
         let (max_gas, target_addr, target_func, param, coins) = match &operation {
             OperationType::CallSC {
@@ -899,7 +1244,7 @@ impl ExecutionState {
 
             // quit if there is no function to be called
             if target_func.is_empty() {
-                return Ok(());
+                return Ok(0);
             }
 
             // Load bytecode. Assume empty bytecode if not found.
@@ -926,11 +1271,11 @@ impl ExecutionState {
             }
             _ => (),
         }
-        response.map_err(|error| ExecutionError::VMError {
+        let response = response.map_err(|error| ExecutionError::VMError {
             context: "CallSC".to_string(),
             error,
         })?;
-        Ok(())
+        Ok(max_gas.saturating_sub(response.remaining_gas))
     }
 
     /// Tries to execute an asynchronous message
@@ -946,9 +1291,22 @@ impl ExecutionState {
     ) -> Result<(), ExecutionError> {
         // prepare execution context
         let context_snapshot;
+        let message_id = message.compute_id();
+        let trace_origin = ExecutionTraceOrigin::AsyncMessage(message_id);
         let bytecode = {
             let mut context = context_guard!(self);
+            // record the sender and destination of the message, whatever the outcome of its execution
+            context.start_address_history(
+                AddressHistoryItem::AsyncMessage(message_id),
+                [message.sender, message.destination],
+            );
             context_snapshot = context.get_snapshot();
+            context.start_trace(CallTrace::new(
+                message.sender,
+                message.destination,
+                Some(message.handler.clone()),
+                message.coins,
+            ));
             context.max_gas = message.max_gas;
             context.creator_address = None;
             context.creator_min_balance = None;
@@ -972,6 +1330,7 @@ impl ExecutionState {
                 let err = ExecutionError::RuntimeError(
                     "the target address is not a smart contract address".into(),
                 );
+                context.finish_trace(trace_origin, None, Some(&err));
                 context.reset_to_snapshot(context_snapshot, err.clone());
                 context.cancel_async_message(&message);
                 return Err(err);
@@ -982,6 +1341,7 @@ impl ExecutionState {
                 Some(bytecode) => bytecode,
                 None => {
                     let err = ExecutionError::RuntimeError("no target bytecode found".into());
+                    context.finish_trace(trace_origin, None, Some(&err));
                     context.reset_to_snapshot(context_snapshot, err.clone());
                     context.cancel_async_message(&message);
                     return Err(err);
@@ -997,6 +1357,7 @@ impl ExecutionState {
                     "could not credit coins to target of async execution: {}",
                     err
                 ));
+                context.finish_trace(trace_origin, None, Some(&err));
                 context.reset_to_snapshot(context_snapshot, err.clone());
                 context.cancel_async_message(&message);
                 return Err(err);
@@ -1010,7 +1371,15 @@ impl ExecutionState {
         let module = self
             .module_cache
             .write()
-            .load_module(&bytecode, message.max_gas)?;
+            .load_module(&bytecode, message.max_gas);
+        let module = match module {
+            Ok(module) => module,
+            Err(err) => {
+                let err = ExecutionError::from(err);
+                context_guard!(self).finish_trace(trace_origin, None, Some(&err));
+                return Err(err);
+            }
+        };
         let response = massa_sc_runtime::run_function(
             &*self.execution_interface,
             module,
@@ -1020,10 +1389,19 @@ impl ExecutionState {
             self.config.gas_costs.clone(),
         );
         match response {
-            Ok(Response { init_gas_cost, .. }) => {
+            Ok(Response {
+                init_gas_cost,
+                remaining_gas,
+                ..
+            }) => {
                 self.module_cache
                     .write()
                     .set_init_cost(&bytecode, init_gas_cost);
+                context_guard!(self).finish_trace(
+                    trace_origin,
+                    Some(message.max_gas.saturating_sub(remaining_gas)),
+                    None,
+                );
                 Ok(())
             }
             Err(error) => {
@@ -1038,6 +1416,7 @@ impl ExecutionState {
                     error,
                 };
                 let mut context = context_guard!(self);
+                context.finish_trace(trace_origin, None, Some(&err));
                 context.reset_to_snapshot(context_snapshot, err.clone());
                 context.cancel_async_message(&message);
                 Err(err)
@@ -1085,6 +1464,7 @@ impl ExecutionState {
             if let Err(err) = self.execute_async_message(message, opt_bytecode) {
                 debug!("failed executing async message: {}", err);
             }
+            context_guard!(self).finish_address_history();
         }
 
         let mut block_info: Option<ExecutedBlockInfo> = None;
@@ -1493,6 +1873,158 @@ impl ExecutionState {
         })
     }
 
+    /// Simulates the execution of an operation on top of the candidate state,
+    /// as if it was included in a block at the next slot of the sender's thread.
+    /// The operation goes through the same checks, fee spending and execution as in a block,
+    /// but all accumulated changes are discarded.
+    ///
+    /// Note that the simulated operation is not signed: its ID is derived from its content and sender.
+    ///
+    /// # Arguments
+    /// * `req`: the operation to simulate and its sender
+    ///
+    /// # Returns
+    ///  `OperationSimulationOutput` describing the effects of the operation,
+    ///  or an error if the operation could not be included in a block at all
+    pub(crate) fn simulate_operation(
+        &self,
+        req: OperationSimulationRequest,
+    ) -> Result<OperationSimulationOutput, ExecutionError> {
+        // simulate at the first slot of the sender's thread after the latest executed active slot
+        let op_thread = req.sender.get_thread(self.config.thread_count);
+        let mut slot = self
+            .active_cursor
+            .get_next_slot(self.config.thread_count)
+            .expect("slot overflow in operation simulation from active slot");
+        while slot.thread != op_thread {
+            slot = slot
+                .get_next_slot(self.config.thread_count)
+                .expect("slot overflow in operation simulation from active slot");
+        }
+
+        let operation = Operation {
+            fee: req.fee,
+            expire_period: req.expire_period.unwrap_or(slot.period),
+            op: req.op,
+        };
+
+        // check validity period
+        if !(operation
+            .get_validity_range(self.config.operation_validity_period)
+            .contains(&slot.period))
+        {
+            return Err(ExecutionError::InvalidSlotRange);
+        }
+
+        // check that the operation gas is acceptable for a simulation and fits in a block
+        let op_gas = operation.get_gas_usage();
+        if op_gas > self.config.max_read_only_gas {
+            return Err(ExecutionError::TooMuchGas(format!(
+                "execution gas for operation simulation is {} which is above the maximum allowed {}",
+                op_gas, self.config.max_read_only_gas
+            )));
+        }
+        if op_gas > self.config.max_gas_per_block {
+            return Err(ExecutionError::NotEnoughGas(
+                "not enough remaining block gas to execute operation".to_string(),
+            ));
+        }
+
+        // derive an operation ID from the content of the operation and its sender
+        let mut content_bytes = Vec::new();
+        OperationSerializer::new()
+            .serialize(&operation, &mut content_bytes)
+            .map_err(|err| {
+                ExecutionError::RuntimeError(format!(
+                    "could not serialize the simulated operation: {}",
+                    err
+                ))
+            })?;
+        let operation_id = OperationId::new(Hash::compute_from_tuple(&[
+            &req.sender.to_prefixed_bytes(),
+            &content_bytes,
+        ]));
+
+        // set a readonly execution context
+        *context_guard!(self) = ExecutionContext::readonly(
+            self.config.clone(),
+            slot,
+            op_gas,
+            vec![],
+            self.final_state.clone(),
+            self.active_history.clone(),
+            self.module_cache.clone(),
+            self.mip_store.clone(),
+        );
+
+        // spend the fee and execute the operation as block execution would
+        let (fee, gas_used, error) =
+            match self.prepare_operation_for_execution(operation_id, &operation, req.sender) {
+                Ok(context_snapshot) => {
+                    match self.execute_prepared_operation(
+                        operation_id,
+                        &operation,
+                        req.sender,
+                        context_snapshot,
+                    ) {
+                        (gas_used, Ok(())) => (operation.fee, Some(gas_used.unwrap_or(0)), None),
+                        (gas_used, Err(err)) => {
+                            (operation.fee, Some(gas_used.unwrap_or(0)), Some(err))
+                        }
+                    }
+                }
+                Err(err) => (Amount::zero(), None, Some(err)),
+            };
+
+        // operations that do not run bytecode use no gas, even on failure
+        let executed_op = match &operation.op {
+            OperationType::Multisig { op, .. } | OperationType::Sequenced { op, .. } => op.as_ref(),
+            op => op,
+        };
+        let gas_used = match executed_op {
+            OperationType::ExecuteSC { .. } | OperationType::CallSC { .. } => gas_used,
+            _ => Some(0),
+        };
+
+        // leave a 20% margin over the measured gas usage, within the block gas limit
+        let suggested_max_gas = gas_used.map(|gas| {
+            gas.saturating_add(gas / 5)
+                .min(self.config.max_gas_per_block)
+        });
+
+        let execution_output = context_guard!(self).settle_slot(None);
+
+        // compare the balances of the addresses touched by the simulation with their candidate values
+        let mut balance_changes = BTreeMap::new();
+        for (address, change) in execution_output.state_changes.ledger_changes.0.iter() {
+            let balance_after = match change {
+                SetUpdateOrDelete::Set(entry) => entry.balance,
+                SetUpdateOrDelete::Update(update) => match update.balance {
+                    SetOrKeep::Set(balance) => balance,
+                    SetOrKeep::Keep => continue,
+                },
+                SetUpdateOrDelete::Delete => Amount::zero(),
+            };
+            let balance_before = self
+                .get_final_and_candidate_balance(address)
+                .1
+                .unwrap_or_default();
+            if balance_before != balance_after {
+                balance_changes.insert(*address, (balance_before, balance_after));
+            }
+        }
+
+        Ok(OperationSimulationOutput {
+            slot,
+            gas_used,
+            suggested_max_gas,
+            fee,
+            balance_changes,
+            events: execution_output.events.0.into_iter().collect(),
+            error,
+        })
+    }
+
     /// Gets a balance both at the latest final and candidate executed slots
     pub fn get_final_and_candidate_balance(
         &self,
@@ -1557,6 +2089,88 @@ impl ExecutionState {
         )
     }
 
+    /// Get a final ledger entry along with its Merkle inclusion or non-inclusion proof.
+    /// The Merkle tree is only kept for the latest final state:
+    /// any `slot` other than the last final slot is rejected.
+    pub fn get_final_ledger_proof(
+        &self,
+        key: &LedgerKey,
+        slot: Option<Slot>,
+    ) -> Result<StateProof, ExecutionQueryError> {
+        let mut serialized_key = Vec::new();
+        KeySerializer::new(false)
+            .serialize(key, &mut serialized_key)
+            .map_err(|err| ExecutionQueryError::Unavailable(err.to_string()))?;
+        let final_state = self.final_state.read();
+        let db = final_state.db.read();
+        if let Some(slot) = slot {
+            let last_final_slot = db
+                .get_change_id()
+                .map_err(|err| ExecutionQueryError::Unavailable(err.to_string()))?;
+            if slot != last_final_slot {
+                return Err(ExecutionQueryError::Unavailable(format!(
+                    "state proofs are only available for the last final slot {}, requested {}",
+                    last_final_slot, slot
+                )));
+            }
+        }
+        db.get_state_proof(serialized_key)
+            .map_err(|err| ExecutionQueryError::Unavailable(err.to_string()))
+    }
+
+    /// Gets the balance of an address as it was at the end of a past final slot, read from the state archive
+    pub fn get_balance_at_slot(
+        &self,
+        address: &Address,
+        slot: Slot,
+    ) -> Result<Option<Amount>, ExecutionQueryError> {
+        self.final_state
+            .read()
+            .ledger
+            .get_balance_at_slot(address, slot)
+            .map_err(|err| ExecutionQueryError::Unavailable(err.to_string()))
+    }
+
+    /// Gets the bytecode of an address as it was at the end of a past final slot, read from the state archive
+    pub fn get_bytecode_at_slot(
+        &self,
+        address: &Address,
+        slot: Slot,
+    ) -> Result<Option<Bytecode>, ExecutionQueryError> {
+        self.final_state
+            .read()
+            .ledger
+            .get_bytecode_at_slot(address, slot)
+            .map_err(|err| ExecutionQueryError::Unavailable(err.to_string()))
+    }
+
+    /// Gets a datastore value of an address as it was at the end of a past final slot, read from the state archive
+    pub fn get_data_entry_at_slot(
+        &self,
+        address: &Address,
+        key: &[u8],
+        slot: Slot,
+    ) -> Result<Option<Vec<u8>>, ExecutionQueryError> {
+        self.final_state
+            .read()
+            .ledger
+            .get_data_entry_at_slot(address, key, slot)
+            .map_err(|err| ExecutionQueryError::Unavailable(err.to_string()))
+    }
+
+    /// Gets the roll count of an address as it was at the end of a past final slot, read from the state archive
+    pub fn get_rolls_at_slot(
+        &self,
+        address: &Address,
+        slot: Slot,
+    ) -> Result<u64, ExecutionQueryError> {
+        self.final_state
+            .read()
+            .pos_state
+            .get_rolls_for_at_slot(address, slot)
+            .map_err(|err| ExecutionQueryError::Unavailable(err.to_string()))
+    }
+
     /// Get every final and active datastore key of the given address
     #[allow(clippy::type_complexity)]
     pub fn get_final_and_candidate_datastore_keys(
@@ -1633,6 +2247,45 @@ impl ExecutionState {
             .get_all_active_rolls(cycle)
     }
 
+    /// Gets the final execution trace of an operation
+    pub fn get_final_operation_trace(
+        &self,
+        operation_id: &OperationId,
+    ) -> Result<ExecutionTrace, ExecutionQueryError> {
+        if !self.config.execution_traces_enabled {
+            return Err(ExecutionQueryError::Unavailable(
+                "execution traces are disabled on this node".to_string(),
+            ));
+        }
+        self.final_traces
+            .get_operation_trace(operation_id)
+            .cloned()
+            .ok_or_else(|| {
+                ExecutionQueryError::NotFound(format!(
+                    "Execution trace of operation {}",
+                    operation_id
+                ))
+            })
+    }
+
+    /// Gets the final execution traces recorded at a slot
+    pub fn get_final_slot_traces(
+        &self,
+        slot: &Slot,
+    ) -> Result<Vec<ExecutionTrace>, ExecutionQueryError> {
+        if !self.config.execution_traces_enabled {
+            return Err(ExecutionQueryError::Unavailable(
+                "execution traces are disabled on this node".to_string(),
+            ));
+        }
+        self.final_traces
+            .get_slot_traces(slot)
+            .cloned()
+            .ok_or_else(|| {
+                ExecutionQueryError::NotFound(format!("Execution traces of slot {}", slot))
+            })
+    }
+
     /// Gets execution events optionally filtered by:
     /// * start slot
     /// * end slot
@@ -1669,6 +2322,100 @@ impl ExecutionState {
         }
     }
 
+    /// Gets at most `limit` asynchronous messages of the latest final pool matching a filter,
+    /// sorted by id, starting after the id `start_after` if provided
+    pub fn get_final_async_messages(
+        &self,
+        filter: &AsyncMessageFilter,
+        start_after: Option<&AsyncMessageId>,
+        limit: usize,
+    ) -> Vec<(AsyncMessageId, AsyncMessage)> {
+        let final_state = self.final_state.read();
+        final_state
+            .async_pool
+            .message_info_cache
+            .range((async_message_lower_bound(start_after), Bound::Unbounded))
+            .filter_map(|(id, _info)| {
+                final_state
+                    .async_pool
+                    .fetch_message(id)
+                    .map(|message| (*id, message))
+            })
+            .filter(|(_id, message)| filter.matches(message))
+            .take(limit)
+            .collect()
+    }
+
+    /// Gets at most `limit` asynchronous messages of the candidate pool matching a filter,
+    /// sorted by id, starting after the id `start_after` if provided.
+    ///
+    /// Each candidate message is rebuilt by applying the async pool changes of the active history,
+    /// from oldest to newest, on top of its final version.
+    /// Messages are fetched one by one until `limit` of them match the filter.
+    pub fn get_candidate_async_messages(
+        &self,
+        filter: &AsyncMessageFilter,
+        start_after: Option<&AsyncMessageId>,
+        limit: usize,
+    ) -> Vec<(AsyncMessageId, AsyncMessage)> {
+        // copy the changes first so that both locks are never held at the same time
+        let history_changes: Vec<AsyncPoolChanges> = self
+            .active_history
+            .read()
+            .0
+            .iter()
+            .map(|output| output.state_changes.async_pool_changes.clone())
+            .collect();
+        let lower_bound = async_message_lower_bound(start_after);
+
+        let final_state = self.final_state.read();
+        // ids of the candidate pool: the final ones and the ones added by the active history
+        let mut ids: BTreeSet<AsyncMessageId> = history_changes
+            .iter()
+            .flat_map(|changes| changes.0.range((lower_bound, Bound::Unbounded)))
+            .filter(|(_id, change)| matches!(change, SetUpdateOrDelete::Set(_)))
+            .map(|(id, _change)| *id)
+            .collect();
+        ids.extend(
+            final_state
+                .async_pool
+                .message_info_cache
+                .range((lower_bound, Bound::Unbounded))
+                .map(|(id, _info)| *id),
+        );
+
+        ids.into_iter()
+            .filter_map(|id| {
+                let final_message = final_state.async_pool.fetch_message(&id);
+                apply_async_message_changes(&id, final_message, &history_changes)
+                    .map(|message| (id, message))
+            })
+            .filter(|(_id, message)| filter.matches(message))
+            .take(limit)
+            .collect()
+    }
+
+    /// Gets an asynchronous message both in the latest final and candidate pools
+    ///
+    /// # Return value
+    /// * `(final_message, candidate_message)`
+    pub fn get_final_and_candidate_async_message(
+        &self,
+        id: &AsyncMessageId,
+    ) -> (Option<AsyncMessage>, Option<AsyncMessage>) {
+        let history_changes: Vec<AsyncPoolChanges> = self
+            .active_history
+            .read()
+            .0
+            .iter()
+            .map(|output| output.state_changes.async_pool_changes.clone())
+            .collect();
+        let final_message = self.final_state.read().async_pool.fetch_message(id);
+        let candidate_message =
+            apply_async_message_changes(id, final_message.clone(), &history_changes);
+        (final_message, candidate_message)
+    }
+
     /// Check if a denunciation has been executed given a `DenunciationIndex`
     /// Returns a tuple of booleans:
     /// * first boolean is true if the denunciation has been executed speculatively
@@ -1846,3 +2593,33 @@ impl ExecutionState {
         );
     }
 }
+
+/// Lower bound of an asynchronous message id range starting after `start_after` if provided
+fn async_message_lower_bound(start_after: Option<&AsyncMessageId>) -> Bound<AsyncMessageId> {
+    match start_after {
+        Some(id) => Bound::Excluded(*id),
+        None => Bound::Unbounded,
+    }
+}
+
+/// Applies the changes of the active history, from oldest to newest, on the final version of a message
+fn apply_async_message_changes(
+    id: &AsyncMessageId,
+    final_message: Option<AsyncMessage>,
+    history_changes: &[AsyncPoolChanges],
+) -> Option<AsyncMessage> {
+    let mut message = final_message;
+    for changes in history_changes {
+        match changes.0.get(id) {
+            Some(SetUpdateOrDelete::Set(new_message)) => message = Some(new_message.clone()),
+            Some(SetUpdateOrDelete::Update(update)) => {
+                if let Some(message) = message.as_mut() {
+                    message.apply(update.clone());
+                }
+            }
+            Some(SetUpdateOrDelete::Delete) => message = None,
+            None => (),
+        }
+    }
+    message
+}
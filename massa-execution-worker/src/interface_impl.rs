@@ -14,7 +14,7 @@ use massa_models::bytecode::Bytecode;
 use massa_models::config::MAX_DATASTORE_KEY_LENGTH;
 use massa_models::datastore::get_prefix_bounds;
 use massa_models::{
-    address::{Address, SCAddress, UserAddress},
+    address::{Address, MultisigAddress, SCAddress, UserAddress},
     amount::Amount,
     slot::Slot,
     timeslots::get_block_slot_timestamp,
@@ -247,6 +247,10 @@ impl Interface for InterfaceImpl {
             );
         }
 
+        // open the sub-call trace once the call cannot fail here anymore, so that `finish_call` always closes it.
+        // The runtime does not tell which function is called: it is not part of the sub-call trace.
+        context.trace_call_start(from_address, to_address, None, coins);
+
         // push a new call stack element on top of the current call stack
         context.stack.push(ExecutionStackElement {
             address: to_address,
@@ -267,6 +271,8 @@ impl Interface for InterfaceImpl {
         if context.stack.pop().is_none() {
             bail!("call stack out of bounds")
         }
+        // the gas used by the sub-call is measured at the next remaining gas measurement
+        context.trace_call_finish();
 
         Ok(())
     }
@@ -276,7 +282,9 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// A `massa-sc-runtime` compiled module
     fn get_module(&self, bytecode: &[u8], limit: u64) -> Result<RuntimeModule> {
-        let context = context_guard!(self);
+        let mut context = context_guard!(self);
+        // `limit` is the gas left to the execution when a sub-call loads its bytecode
+        context.record_remaining_gas(limit);
         let module = context.module_cache.write().load_module(bytecode, limit)?;
         Ok(module)
     }
@@ -916,10 +924,10 @@ impl Interface for InterfaceImpl {
         Ok(recovered.serialize().to_vec())
     }
 
-    // Return true if the address is a User address, false if it is an SC address.
+    // Return true if the address is a User or Multisig address, false if it is an SC address.
     fn is_address_eoa(&self, address_: &str) -> Result<bool> {
         let address = Address::from_str(address_)?;
-        Ok(matches!(address, Address::User(..)))
+        Ok(matches!(address, Address::User(..) | Address::Multisig(..)))
     }
 
     /// Transfer coins from the current address (top of the call stack) towards a target address.
@@ -1331,6 +1339,10 @@ impl Interface for InterfaceImpl {
             );
         }
 
+        // open the sub-call trace once the call cannot fail here anymore, so that `finish_call` always closes it.
+        // The runtime does not tell which function is called: it is not part of the sub-call trace.
+        context.trace_call_start(from_address, to_address, None, coins);
+
         // push a new call stack element on top of the current call stack
         context.stack.push(ExecutionStackElement {
             address: to_address,
@@ -1479,6 +1491,7 @@ impl Interface for InterfaceImpl {
             // Address::User(UserAddress::UserAddressV1(_)) => Ok(1),
             Address::SC(SCAddress::SCAddressV0(_)) => Ok(0),
             // Address::SC(SCAddress::SCAddressV1(_)) => Ok(1),
+            Address::Multisig(MultisigAddress::MultisigAddressV0(_)) => Ok(0),
             #[allow(unreachable_patterns)]
             _ => bail!("Unknown address version"),
         }
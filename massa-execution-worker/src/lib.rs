@@ -69,6 +69,13 @@
 //! ## `speculative_executed_ops.rs`
 //! A speculative (non-final) list of previously executed operations to prevent reuse.
 //!
+//! ## `address_history.rs`
+//! Persistent index of the operations, asynchronous messages and proof-of-stake changes
+//! that affected each address, built from final execution outputs.
+//!
+//! ## `trace_recorder.rs`
+//! Records the call tree of the operation or asynchronous message being executed.
+//!
 //! ## `request_queue.rs`
 //! This module contains the implementation of a generic finite-size execution request queue.
 //! It handles requests that come with an MPSC to send back the result of their execution once it's done.
@@ -80,6 +87,7 @@
 #![warn(unused_crate_dependencies)]
 
 mod active_history;
+mod address_history;
 mod context;
 mod controller;
 mod execution;
@@ -92,6 +100,7 @@ mod speculative_executed_ops;
 mod speculative_ledger;
 mod speculative_roll_state;
 mod stats;
+mod trace_recorder;
 mod worker;
 
 use massa_db_exports as _;
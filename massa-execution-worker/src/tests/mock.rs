@@ -84,6 +84,8 @@ pub fn get_sample_state(
         max_history_length: 10,
         max_new_elements: 100,
         thread_count: THREAD_COUNT,
+        enable_archive: false,
+        archive_retention_periods: 0,
     };
     let db = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -16,6 +16,8 @@ mod tests_active_history;
 
 mod interface;
 
+mod trace_recorder;
+
 #[cfg(any(
     feature = "gas_calibration",
     feature = "benchmarking",
@@ -8,13 +8,17 @@ mod tests {
     use massa_async_pool::AsyncMessage;
     use massa_db_exports::DBBatch;
     use massa_execution_exports::{
-        ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig, ExecutionController,
-        ExecutionError, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+        AddressHistoryEntry, AddressHistoryItem, CoinTransferTrace, ExecutionBlockMetadata,
+        ExecutionChannels, ExecutionConfig, ExecutionController, ExecutionError,
+        ExecutionQueryError, ExecutionQueryRequest, ExecutionQueryRequestItem,
+        ExecutionQueryResponseItem, ExecutionTraceOrigin, LedgerWriteTrace,
+        OperationSimulationRequest, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
     };
     use massa_hash::Hash;
     use massa_metrics::MassaMetrics;
     use massa_models::config::{
         LEDGER_ENTRY_BASE_COST, LEDGER_ENTRY_DATASTORE_BASE_SIZE, MIP_STORE_STATS_BLOCK_CONSIDERED,
+        THREAD_COUNT,
     };
     use massa_models::prehash::PreHashMap;
     use massa_models::test_exports::gen_endorsements_for_denunciation;
@@ -126,6 +130,71 @@ mod tests {
         manager.stop();
     }
 
+    #[test]
+    #[serial]
+    fn test_final_ledger_proof_slot() {
+        let config = ExecutionConfig::default();
+        // init the MIP store
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
+
+        let slot_execution_output_sender = broadcast::channel(5000).0;
+
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+        };
+
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+        let (mut manager, controller) = start_execution_worker(
+            config,
+            sample_state.clone(),
+            sample_state.read().pos_state.selector.clone(),
+            mip_store,
+            channels,
+            Arc::new(RwLock::new(create_test_wallet(Some(PreHashMap::default())))),
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
+        );
+        let addr = get_random_address();
+        let last_final_slot = sample_state.read().db.read().get_change_id().unwrap();
+        let mut response = controller.query_state(ExecutionQueryRequest {
+            requests: vec![
+                ExecutionQueryRequestItem::AddressBalanceProofFinal { addr, slot: None },
+                ExecutionQueryRequestItem::AddressBalanceProofFinal {
+                    addr,
+                    slot: Some(last_final_slot),
+                },
+                ExecutionQueryRequestItem::AddressBalanceProofFinal {
+                    addr,
+                    slot: Some(last_final_slot.get_next_slot(THREAD_COUNT).unwrap()),
+                },
+            ],
+        });
+        // proofs are only served for the last final slot
+        assert!(matches!(
+            response.responses.pop(),
+            Some(Err(ExecutionQueryError::Unavailable(_)))
+        ));
+        for response_item in response.responses {
+            match response_item {
+                Ok(ExecutionQueryResponseItem::StateProof(proof)) => {
+                    assert_eq!(proof.slot, last_final_slot);
+                    assert!(proof.value.is_none());
+                }
+                _ => panic!("expected a state proof"),
+            }
+        }
+        manager.stop();
+    }
+
     #[test]
     #[serial]
     fn test_readonly_execution() {
@@ -1387,6 +1456,420 @@ mod tests {
         manager.stop();
     }
 
+    #[test]
+    #[serial]
+    pub fn simulate_transaction_operation() {
+        // setup the period duration
+        let exec_cfg = ExecutionConfig {
+            t0: MassaTime::from_millis(100),
+            cursor_delay: MassaTime::from_millis(0),
+            ..ExecutionConfig::default()
+        };
+        // get a sample final state
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+
+        // init the MIP store
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
+
+        // init the storage
+        let storage = Storage::create_root();
+
+        let slot_execution_output_sender = broadcast::channel(5000).0;
+
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+        };
+
+        // start the execution worker
+        let (mut manager, controller) = start_execution_worker(
+            exec_cfg.clone(),
+            sample_state.clone(),
+            sample_state.read().pos_state.selector.clone(),
+            mip_store,
+            channels,
+            Arc::new(RwLock::new(create_test_wallet(Some(PreHashMap::default())))),
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
+        );
+        // initialize the execution system with genesis blocks
+        init_execution_worker(&exec_cfg, &storage, controller.clone());
+
+        let sender_keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+        let sender_address = Address::from_public_key(&sender_keypair.get_public_key());
+        let (recipient_address, _keypair) = get_random_address_full();
+        let sender_balance = controller.get_final_and_candidate_balance(&[sender_address])[0]
+            .1
+            .unwrap();
+
+        // simulate a transaction without including it in a block
+        let output = controller
+            .simulate_operation(OperationSimulationRequest {
+                sender: sender_address,
+                fee: Amount::from_str("10").unwrap(),
+                expire_period: None,
+                op: OperationType::Transaction {
+                    recipient_address,
+                    amount: Amount::from_str("100").unwrap(),
+                },
+            })
+            .expect("operation simulation failed");
+
+        assert!(output.error.is_none(), "{:?}", output.error);
+        assert_eq!(
+            output.slot.thread,
+            sender_address.get_thread(exec_cfg.thread_count)
+        );
+        assert_eq!(output.gas_used, Some(0));
+        assert_eq!(output.fee, Amount::from_str("10").unwrap());
+        assert_eq!(
+            output.balance_changes.get(&sender_address),
+            Some(&(
+                sender_balance,
+                sender_balance.saturating_sub(Amount::from_str("110").unwrap())
+            ))
+        );
+        assert_eq!(
+            output.balance_changes.get(&recipient_address),
+            Some(&(
+                Amount::zero(),
+                // Storage cost applied
+                Amount::from_str("100")
+                    .unwrap()
+                    .saturating_sub(LEDGER_ENTRY_BASE_COST)
+            ))
+        );
+
+        // the simulation did not change the state
+        assert_eq!(
+            controller.get_final_and_candidate_balance(&[sender_address, recipient_address]),
+            vec![(Some(sender_balance), Some(sender_balance)), (None, None)]
+        );
+
+        // an operation that cannot be paid for is reported with its exact error
+        let output = controller
+            .simulate_operation(OperationSimulationRequest {
+                sender: recipient_address,
+                fee: Amount::from_str("10").unwrap(),
+                expire_period: None,
+                op: OperationType::RollBuy { roll_count: 1 },
+            })
+            .expect("operation simulation failed");
+        assert!(output.error.is_some());
+        assert_eq!(output.fee, Amount::zero());
+        assert!(output.balance_changes.is_empty());
+
+        // a failing bytecode execution reports the gas it used until its failure
+        let output = controller
+            .simulate_operation(OperationSimulationRequest {
+                sender: sender_address,
+                fee: Amount::from_str("10").unwrap(),
+                expire_period: None,
+                op: OperationType::ExecuteSC {
+                    max_coins: Amount::zero(),
+                    data: include_bytes!("./wasm/execution_error.wasm").to_vec(),
+                    max_gas: 1_000_000,
+                    datastore: BTreeMap::default(),
+                },
+            })
+            .expect("operation simulation failed");
+        assert!(output.error.is_some());
+        assert_eq!(output.fee, Amount::from_str("10").unwrap());
+        let gas_used = output.gas_used.expect("expected the gas used by the failure");
+        assert!(gas_used >= exec_cfg.gas_costs.sp_compilation_cost);
+        assert!(gas_used < 1_000_000);
+
+        // a call failing before running any bytecode uses no gas
+        let output = controller
+            .simulate_operation(OperationSimulationRequest {
+                sender: sender_address,
+                fee: Amount::from_str("10").unwrap(),
+                expire_period: None,
+                op: OperationType::CallSC {
+                    max_gas: 1_000_000,
+                    target_addr: recipient_address,
+                    coins: Amount::zero(),
+                    target_func: "main".to_string(),
+                    param: vec![],
+                },
+            })
+            .expect("operation simulation failed");
+        assert!(output.error.is_some());
+        assert_eq!(output.gas_used, Some(0));
+
+        // stop the execution controller
+        manager.stop();
+    }
+
+    #[test]
+    #[serial]
+    pub fn transaction_execution_trace() {
+        // setup the period duration
+        let exec_cfg = ExecutionConfig {
+            t0: MassaTime::from_millis(100),
+            cursor_delay: MassaTime::from_millis(0),
+            ..ExecutionConfig::default()
+        };
+        // get a sample final state
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+
+        // init the MIP store
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
+
+        // init the storage
+        let mut storage = Storage::create_root();
+
+        let slot_execution_output_sender = broadcast::channel(5000).0;
+
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+        };
+
+        // start the execution worker
+        let (mut manager, controller) = start_execution_worker(
+            exec_cfg.clone(),
+            sample_state.clone(),
+            sample_state.read().pos_state.selector.clone(),
+            mip_store,
+            channels,
+            Arc::new(RwLock::new(create_test_wallet(Some(PreHashMap::default())))),
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
+        );
+        // initialize the execution system with genesis blocks
+        init_execution_worker(&exec_cfg, &storage, controller.clone());
+        // generate the sender_keypair and recipient_address
+
+        let sender_keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+        let (recipient_address, _keypair) = get_random_address_full();
+
+        // create the operation
+        let operation = Operation::new_verifiable(
+            Operation {
+                fee: Amount::zero(),
+                expire_period: 10,
+                op: OperationType::Transaction {
+                    recipient_address,
+                    amount: Amount::from_str("100").unwrap(),
+                },
+            },
+            OperationSerializer::new(),
+            &sender_keypair,
+        )
+        .unwrap();
+        let operation_id = operation.id;
+        // create the block containing the transaction operation
+        storage.store_operations(vec![operation.clone()]);
+        let block = create_block(
+            KeyPair::generate(0).unwrap(),
+            vec![operation],
+            vec![],
+            Slot::new(1, 0),
+        )
+        .unwrap();
+        // store the block in storage
+        storage.store_block(block.clone());
+        // set our block as a final block so the transaction is processed
+        let mut finalized_blocks: HashMap<Slot, BlockId> = Default::default();
+        finalized_blocks.insert(block.content.header.content.slot, block.id);
+        let mut block_metadata: PreHashMap<BlockId, ExecutionBlockMetadata> = Default::default();
+        block_metadata.insert(
+            block.id,
+            ExecutionBlockMetadata {
+                same_thread_parent_creator: Some(get_random_address()),
+                storage: Some(storage),
+            },
+        );
+        controller.update_blockclique_status(
+            finalized_blocks,
+            Default::default(),
+            block_metadata.clone(),
+        );
+        std::thread::sleep(Duration::from_millis(10));
+        // check the trace of the transaction
+        let sender_address = Address::from_public_key(&sender_keypair.get_public_key());
+        let mut response = controller.query_state(ExecutionQueryRequest {
+            requests: vec![
+                ExecutionQueryRequestItem::OpExecutionTraceFinal(operation_id),
+                ExecutionQueryRequestItem::SlotExecutionTracesFinal(Slot::new(1, 0)),
+            ],
+        });
+        let slot_traces = match response.responses.pop() {
+            Some(Ok(ExecutionQueryResponseItem::ExecutionTraces(traces))) => traces,
+            _ => panic!("expected the execution traces of the slot"),
+        };
+        let trace = match response.responses.pop() {
+            Some(Ok(ExecutionQueryResponseItem::ExecutionTraces(mut traces))) => {
+                traces.pop().expect("expected a trace")
+            }
+            _ => panic!("expected the execution trace of the operation"),
+        };
+        assert_eq!(slot_traces, vec![trace.clone()]);
+        assert_eq!(trace.origin, ExecutionTraceOrigin::Operation(operation_id));
+        assert!(trace.is_success());
+        assert_eq!(trace.call.caller, sender_address);
+        assert_eq!(trace.call.callee, recipient_address);
+        assert_eq!(trace.call.coins, Amount::from_str("100").unwrap());
+        assert!(trace.call.sub_calls.is_empty());
+        assert_eq!(
+            trace.call.transfers,
+            vec![CoinTransferTrace {
+                from: Some(sender_address),
+                to: Some(recipient_address),
+                amount: Amount::from_str("100").unwrap(),
+            }]
+        );
+        assert!(trace
+            .call
+            .ledger_writes
+            .contains(&LedgerWriteTrace::Balance {
+                address: recipient_address,
+                balance: Amount::from_str("100")
+                    .unwrap()
+                    .saturating_sub(LEDGER_ENTRY_BASE_COST),
+            }));
+        // stop the execution controller
+        manager.stop();
+    }
+
+    #[test]
+    #[serial]
+    pub fn transaction_address_history() {
+        // setup the period duration
+        let exec_cfg = ExecutionConfig {
+            t0: MassaTime::from_millis(100),
+            cursor_delay: MassaTime::from_millis(0),
+            ..ExecutionConfig::default()
+        };
+        // get a sample final state
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+
+        // init the MIP store
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
+
+        // init the storage
+        let mut storage = Storage::create_root();
+
+        let slot_execution_output_sender = broadcast::channel(5000).0;
+
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+        };
+
+        // start the execution worker
+        let (mut manager, controller) = start_execution_worker(
+            exec_cfg.clone(),
+            sample_state.clone(),
+            sample_state.read().pos_state.selector.clone(),
+            mip_store,
+            channels,
+            Arc::new(RwLock::new(create_test_wallet(Some(PreHashMap::default())))),
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
+        );
+        // initialize the execution system with genesis blocks
+        init_execution_worker(&exec_cfg, &storage, controller.clone());
+        // generate the sender_keypair and recipient_address
+
+        let sender_keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+        let (recipient_address, _keypair) = get_random_address_full();
+
+        // create the operation
+        let operation = Operation::new_verifiable(
+            Operation {
+                fee: Amount::zero(),
+                expire_period: 10,
+                op: OperationType::Transaction {
+                    recipient_address,
+                    amount: Amount::from_str("100").unwrap(),
+                },
+            },
+            OperationSerializer::new(),
+            &sender_keypair,
+        )
+        .unwrap();
+        let operation_id = operation.id;
+        // create the block containing the transaction operation
+        storage.store_operations(vec![operation.clone()]);
+        let block = create_block(
+            KeyPair::generate(0).unwrap(),
+            vec![operation],
+            vec![],
+            Slot::new(1, 0),
+        )
+        .unwrap();
+        // store the block in storage
+        storage.store_block(block.clone());
+        // set our block as a final block so the transaction is processed
+        let mut finalized_blocks: HashMap<Slot, BlockId> = Default::default();
+        finalized_blocks.insert(block.content.header.content.slot, block.id);
+        let mut block_metadata: PreHashMap<BlockId, ExecutionBlockMetadata> = Default::default();
+        block_metadata.insert(
+            block.id,
+            ExecutionBlockMetadata {
+                same_thread_parent_creator: Some(get_random_address()),
+                storage: Some(storage),
+            },
+        );
+        controller.update_blockclique_status(
+            finalized_blocks,
+            Default::default(),
+            block_metadata.clone(),
+        );
+        std::thread::sleep(Duration::from_millis(10));
+        // check the history of the sender and of the recipient
+        let sender_address = Address::from_public_key(&sender_keypair.get_public_key());
+        for address in [sender_address, recipient_address] {
+            let history = controller.get_address_history(&address, None, 10).unwrap();
+            assert_eq!(
+                history,
+                vec![AddressHistoryEntry {
+                    slot: Slot::new(1, 0),
+                    index: 0,
+                    item: AddressHistoryItem::Operation(operation_id),
+                }]
+            );
+            // the page following the last entry is empty
+            assert!(controller
+                .get_address_history(&address, Some(history[0].cursor()), 10)
+                .unwrap()
+                .is_empty());
+        }
+        // unrelated addresses have no history
+        assert!(controller
+            .get_address_history(&get_random_address(), None, 10)
+            .unwrap()
+            .is_empty());
+        // stop the execution controller
+        manager.stop();
+    }
+
     #[test]
     #[serial]
     pub fn roll_buy() {
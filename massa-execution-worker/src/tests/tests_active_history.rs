@@ -58,6 +58,8 @@ mod tests {
                 execution_trail_hash_change: Default::default(),
             },
             events: Default::default(),
+            traces: Default::default(),
+            address_history: Default::default(),
         };
 
         let active_history = ActiveHistory {
@@ -0,0 +1,98 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+#[cfg(test)]
+mod tests {
+    use massa_execution_exports::CallTrace;
+    use massa_models::{address::Address, amount::Amount};
+    use std::str::FromStr;
+
+    use crate::trace_recorder::TraceRecorder;
+
+    #[test]
+    fn test_nested_calls_trace() {
+        let user =
+            Address::from_str("AU12cMW9zRKFDS43Z2W88VCmdQFxmHjAo54XvuVV34UzJeXRLXW9M").unwrap();
+        let sc_a =
+            Address::from_str("AS12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+        let sc_b =
+            Address::from_str("AS12DDxjqtBVshdQ4nLqYg6GwRddY5LzEC7bnatVxB5SFtpbCFj8E").unwrap();
+
+        let mut recorder = TraceRecorder::new(CallTrace::new(
+            user,
+            sc_a,
+            Some("main".to_string()),
+            Amount::zero(),
+        ));
+        recorder.start_call(
+            sc_a,
+            sc_b,
+            Some("forward".to_string()),
+            Amount::from_str("2").unwrap(),
+        );
+        recorder.record_remaining_gas(90_000);
+        recorder.start_call(sc_b, sc_a, Some("callback".to_string()), Amount::zero());
+        recorder.record_remaining_gas(80_000);
+        recorder.finish_call();
+        recorder.finish_call();
+        recorder.start_call(sc_a, sc_b, None, Amount::zero());
+        recorder.record_remaining_gas(60_000);
+        recorder.finish_call();
+        recorder.start_call(sc_a, sc_b, None, Amount::zero());
+        recorder.finish_call();
+        let root = recorder.finish(Some(50_000), Some(50_000), None);
+
+        assert_eq!(root.function.as_deref(), Some("main"));
+        assert_eq!(root.gas_used, Some(50_000));
+        assert_eq!(root.sub_calls.len(), 3);
+
+        // measured up to the start of the next sub-call
+        let forward = &root.sub_calls[0];
+        assert_eq!((forward.caller, forward.callee), (sc_a, sc_b));
+        assert_eq!(forward.function.as_deref(), Some("forward"));
+        assert_eq!(forward.gas_used, Some(30_000));
+        assert_eq!(forward.coins, Amount::from_str("2").unwrap());
+        assert_eq!(forward.sub_calls.len(), 1);
+
+        let callback = &forward.sub_calls[0];
+        assert_eq!((callback.caller, callback.callee), (sc_b, sc_a));
+        assert_eq!(callback.function.as_deref(), Some("callback"));
+        assert_eq!(callback.gas_used, Some(20_000));
+        assert!(callback.sub_calls.is_empty());
+
+        // measured up to the end of the execution
+        let unnamed = &root.sub_calls[1];
+        assert_eq!(unnamed.function, None);
+        assert_eq!(unnamed.gas_used, Some(10_000));
+
+        // never measured at its start
+        assert_eq!(root.sub_calls[2].gas_used, None);
+    }
+
+    #[test]
+    fn test_nested_calls_trace_revert() {
+        let user =
+            Address::from_str("AU12cMW9zRKFDS43Z2W88VCmdQFxmHjAo54XvuVV34UzJeXRLXW9M").unwrap();
+        let sc_a =
+            Address::from_str("AS12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+
+        let mut recorder = TraceRecorder::new(CallTrace::new(
+            user,
+            sc_a,
+            Some("main".to_string()),
+            Amount::zero(),
+        ));
+        recorder.start_call(sc_a, sc_a, Some("inner".to_string()), Amount::zero());
+        recorder.record_remaining_gas(7_000);
+        let root = recorder.finish(None, None, Some("out of gas".to_string()));
+
+        assert_eq!(root.revert_reason.as_deref(), Some("out of gas"));
+        assert_eq!(root.gas_used, None);
+        assert_eq!(root.sub_calls.len(), 1);
+        assert_eq!(root.sub_calls[0].function.as_deref(), Some("inner"));
+        assert_eq!(root.sub_calls[0].gas_used, None);
+        assert_eq!(
+            root.sub_calls[0].revert_reason.as_deref(),
+            Some("out of gas")
+        );
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Records the call tree of the operation or asynchronous message being executed.
+//!
+//! The recorder lives in the execution context outside of its snapshots,
+//! so that the effects of a reverted execution remain visible in its trace.
+//!
+//! The runtime only reports the remaining gas when a sub-call loads its bytecode,
+//! not when it returns. The gas used by a sub-call is thus measured from the remaining gas
+//! at its start to the next remaining gas measured after it returned:
+//! the start of the next sub-call, or the end of the execution.
+//! It includes the gas spent by its callers in between.
+
+use massa_execution_exports::{CallTrace, CoinTransferTrace, LedgerWriteTrace};
+use massa_models::{address::Address, amount::Amount};
+
+/// Call that has started but not finished yet
+struct OpenCall {
+    /// call trace recorded so far
+    call: CallTrace,
+    /// remaining gas when the call started, if measured
+    remaining_gas_at_start: Option<u64>,
+}
+
+/// Call tree under construction, the root call is at the bottom of the stack of open calls
+pub(crate) struct TraceRecorder {
+    /// calls that have started but not finished yet, most recent at the back
+    open_calls: Vec<OpenCall>,
+    /// finished sub-calls waiting for the next gas measurement, with their remaining gas at start.
+    /// Each one is identified by its path of sub-call indices from the root call.
+    unmeasured_calls: Vec<(Vec<usize>, u64)>,
+}
+
+impl TraceRecorder {
+    /// Start recording with the root call of the execution
+    pub fn new(root: CallTrace) -> Self {
+        TraceRecorder {
+            open_calls: vec![OpenCall {
+                call: root,
+                remaining_gas_at_start: None,
+            }],
+            unmeasured_calls: Vec::new(),
+        }
+    }
+
+    /// Open a sub-call of the current call
+    pub fn start_call(
+        &mut self,
+        caller: Address,
+        callee: Address,
+        function: Option<String>,
+        coins: Amount,
+    ) {
+        self.open_calls.push(OpenCall {
+            call: CallTrace::new(caller, callee, function, coins),
+            remaining_gas_at_start: None,
+        });
+    }
+
+    /// Record the remaining gas of the execution.
+    /// It sets the gas used by the sub-calls that finished since the previous measurement,
+    /// and the remaining gas at the start of the current sub-call if it was not measured yet.
+    pub fn record_remaining_gas(&mut self, remaining_gas: u64) {
+        self.measure_finished_calls(remaining_gas);
+        if self.open_calls.len() < 2 {
+            return;
+        }
+        if let Some(open_call) = self.open_calls.last_mut() {
+            open_call.remaining_gas_at_start.get_or_insert(remaining_gas);
+        }
+    }
+
+    /// Close the current call and attach it to its parent.
+    /// The root call is only closed by `finish`.
+    pub fn finish_call(&mut self) {
+        if self.open_calls.len() < 2 {
+            return;
+        }
+        let Some(open_call) = self.open_calls.pop() else {
+            return;
+        };
+        let mut path = self.open_call_path();
+        if let Some(parent) = self.open_calls.last_mut() {
+            path.push(parent.call.sub_calls.len());
+            parent.call.sub_calls.push(open_call.call);
+        }
+        if let Some(remaining_gas_at_start) = open_call.remaining_gas_at_start {
+            self.unmeasured_calls.push((path, remaining_gas_at_start));
+        }
+    }
+
+    /// Record a coin transfer in the current call
+    pub fn record_transfer(&mut self, from: Option<Address>, to: Option<Address>, amount: Amount) {
+        if let Some(open_call) = self.open_calls.last_mut() {
+            open_call
+                .call
+                .transfers
+                .push(CoinTransferTrace { from, to, amount });
+        }
+    }
+
+    /// Record a ledger write in the current call
+    pub fn record_ledger_write(&mut self, write: LedgerWriteTrace) {
+        if let Some(open_call) = self.open_calls.last_mut() {
+            open_call.call.ledger_writes.push(write);
+        }
+    }
+
+    /// Close all the open calls and return the root call.
+    /// If the execution failed, `revert_reason` is set on the root call
+    /// and on every call that was still running when the failure happened.
+    ///
+    /// # Arguments
+    /// * `gas_used`: gas used by the whole execution, if known
+    /// * `remaining_gas`: gas left at the end of the execution, if known
+    /// * `revert_reason`: error that caused the execution to be reverted, if any
+    pub fn finish(
+        mut self,
+        gas_used: Option<u64>,
+        remaining_gas: Option<u64>,
+        revert_reason: Option<String>,
+    ) -> CallTrace {
+        while self.open_calls.len() > 1 {
+            if let Some(open_call) = self.open_calls.last_mut() {
+                open_call.call.revert_reason = revert_reason.clone();
+            }
+            self.finish_call();
+        }
+        if let Some(remaining_gas) = remaining_gas {
+            self.measure_finished_calls(remaining_gas);
+        }
+        let mut root = self
+            .open_calls
+            .pop()
+            .expect("the root call is only removed when finishing the recorder")
+            .call;
+        root.gas_used = gas_used;
+        root.revert_reason = revert_reason;
+        root
+    }
+
+    /// Set the gas used by the sub-calls that finished since the previous measurement
+    fn measure_finished_calls(&mut self, remaining_gas: u64) {
+        for (path, remaining_gas_at_start) in std::mem::take(&mut self.unmeasured_calls) {
+            if let Some(call) = self.call_at_path_mut(&path) {
+                call.gas_used = Some(remaining_gas_at_start.saturating_sub(remaining_gas));
+            }
+        }
+    }
+
+    /// Path of sub-call indices from the root call to the current call.
+    /// An open call gets the next index among the sub-calls of its parent once finished.
+    fn open_call_path(&self) -> Vec<usize> {
+        self.open_calls
+            .iter()
+            .take(self.open_calls.len().saturating_sub(1))
+            .map(|open_call| open_call.call.sub_calls.len())
+            .collect()
+    }
+
+    /// Get a call of the tree from its path of sub-call indices from the root call
+    fn call_at_path_mut(&mut self, path: &[usize]) -> Option<&mut CallTrace> {
+        let open_path = self.open_call_path();
+        // follow the open calls as long as the path goes through them
+        let open_depth = path
+            .iter()
+            .zip(open_path.iter())
+            .take_while(|(index, open_index)| index == open_index)
+            .count();
+        let mut call = &mut self.open_calls.get_mut(open_depth)?.call;
+        for index in &path[open_depth..] {
+            call = call.sub_calls.get_mut(*index)?;
+        }
+        Some(call)
+    }
+}
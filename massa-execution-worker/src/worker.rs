@@ -11,7 +11,8 @@ use crate::request_queue::RequestQueue;
 use crate::slot_sequencer::SlotSequencer;
 use massa_execution_exports::{
     ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ExecutionError, ExecutionManager, OperationSimulationOutput, OperationSimulationRequest,
+    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
 };
 use massa_final_state::FinalState;
 use massa_metrics::MassaMetrics;
@@ -36,6 +37,8 @@ pub(crate) struct ExecutionThread {
     execution_state: Arc<RwLock<ExecutionState>>,
     /// queue for read-only requests and response MPSCs to send back their outputs
     readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+    /// queue for operation simulation requests and response MPSCs to send back their outputs
+    simulation_requests: RequestQueue<OperationSimulationRequest, OperationSimulationOutput>,
     /// Selector controller
     selector: Box<dyn SelectorController>,
 }
@@ -69,6 +72,7 @@ impl ExecutionThread {
         ExecutionThread {
             input_data,
             readonly_requests: RequestQueue::new(config.readonly_queue_length),
+            simulation_requests: RequestQueue::new(config.readonly_queue_length),
             execution_state,
             slot_sequencer: SlotSequencer::new(config, final_cursor),
             selector,
@@ -108,6 +112,37 @@ impl ExecutionThread {
         false
     }
 
+    /// Append incoming operation simulation requests to the relevant queue,
+    /// Cancel those that are in excess if there are too many.
+    fn update_simulation_requests(
+        &mut self,
+        new_requests: RequestQueue<OperationSimulationRequest, OperationSimulationOutput>,
+    ) {
+        self.simulation_requests.extend(new_requests);
+    }
+
+    /// Executes an operation simulation request from the queue, if any.
+    /// The result of the simulation is sent asynchronously through the response channel provided with the request.
+    ///
+    /// # Returns
+    /// true if a request was executed, false otherwise
+    fn execute_one_simulation_request(&mut self) -> bool {
+        if let Some(req_resp) = self.simulation_requests.pop() {
+            let (req, resp_tx) = req_resp.into_request_sender_pair();
+
+            // Acquire write access to the execution state (for cache updates) and simulate the operation
+            let outcome = self.execution_state.write().simulate_operation(req);
+
+            // Send the simulation output through resp_tx.
+            // Ignore errors because they just mean that the request emitter dropped the received
+            // because it doesn't need the response anymore.
+            let _ = resp_tx.send(outcome);
+
+            return true;
+        }
+        false
+    }
+
     /// Waits for an event to trigger a new iteration in the execution main loop.
     ///
     /// # Returns
@@ -131,6 +166,7 @@ impl ExecutionThread {
                 || !input_data.finalized_blocks.is_empty()
                 || !input_data.block_metadata.is_empty()
                 || !input_data.readonly_requests.is_empty()
+                || !input_data.simulation_requests.is_empty()
             {
                 return (input_data, false);
             }
@@ -145,6 +181,11 @@ impl ExecutionThread {
                 return (input_data, false);
             }
 
+            // there are operation simulation requests ready
+            if !self.simulation_requests.is_empty() {
+                return (input_data, false);
+            }
+
             // Compute when the next slot will be
             // This is useful to wait for the next speculative miss to append to active slots.
             let wakeup_deadline = self.slot_sequencer.get_next_slot_deadline();
@@ -171,7 +212,7 @@ impl ExecutionThread {
         // It also prioritizes executions in the following order:
         // 1 - final executions
         // 2 - speculative executions
-        // 3 - read-only executions
+        // 3 - read-only executions and operation simulations
         loop {
             let (input_data, stop) = self.wait_loop_event();
             debug!("Execution loop triggered, input_data = {}", input_data);
@@ -179,6 +220,9 @@ impl ExecutionThread {
             // update the sequence of read-only requests
             self.update_readonly_requests(input_data.readonly_requests);
 
+            // update the sequence of operation simulation requests
+            self.update_simulation_requests(input_data.simulation_requests);
+
             if stop {
                 // we need to stop
                 break;
@@ -218,6 +262,9 @@ impl ExecutionThread {
 
             // low priority: execute a read-only request (note that the queue is of finite length), if there is one ready.
             self.execute_one_readonly_request();
+
+            // low priority: simulate an operation (note that the queue is of finite length), if there is one ready.
+            self.execute_one_simulation_request();
         }
 
         // We are quitting the loop.
@@ -226,12 +273,13 @@ impl ExecutionThread {
         let cancel_err = ExecutionError::ChannelError(
             "readonly execution cancelled because the execution worker is closing".into(),
         );
-        self.input_data
-            .1
-            .lock()
-            .take()
-            .readonly_requests
-            .cancel(cancel_err);
+        let mut input_data = self.input_data.1.lock().take();
+        input_data.readonly_requests.cancel(cancel_err);
+        input_data
+            .simulation_requests
+            .cancel(ExecutionError::ChannelError(
+                "operation simulation cancelled because the execution worker is closing".into(),
+            ));
     }
 }
 
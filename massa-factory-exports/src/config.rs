@@ -3,6 +3,7 @@
 //! This file defines the factory settings
 
 use massa_time::MassaTime;
+use std::path::PathBuf;
 
 /// Structure defining the settings of the factory
 #[derive(Debug, Clone)]
@@ -29,4 +30,10 @@ pub struct FactoryConfig {
     pub denunciation_expire_periods: u64,
     /// choose whether to stop production when zero connections on protocol
     pub stop_production_when_zero_connections: bool,
+    /// path of the journal of signed blocks and endorsements
+    pub signing_journal_path: PathBuf,
+    /// dev mode: blocks are produced on demand or when operations arrive, instead of at each slot
+    pub dev_mode: bool,
+    /// interval at which the pool is checked for new operations in dev mode
+    pub dev_operations_check_interval: MassaTime,
 }
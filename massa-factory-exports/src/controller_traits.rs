@@ -3,6 +3,10 @@
 //! This module exports generic traits representing interfaces for interacting
 //! with the factory worker.
 
+use crate::FactoryResult;
+use massa_models::block_id::BlockId;
+use massa_time::MassaTime;
+
 /// Factory manager used to stop the factory thread
 pub trait FactoryManager {
     /// Stop the factory thread
@@ -11,3 +15,28 @@ pub trait FactoryManager {
     /// This will improve if the `unsized_fn_params` feature stabilizes enough to be safely usable.
     fn stop(&mut self);
 }
+
+/// Factory controller, driving block production when the node runs in dev mode
+pub trait FactoryController: Send + Sync {
+    /// Dev mode only: move the clock forward slot by slot, producing a block at each of the next `count` slots.
+    /// A block is final as soon as the next one is produced.
+    /// Returns the ids of the produced blocks.
+    fn advance_slots(&self, count: u64) -> FactoryResult<Vec<BlockId>>;
+
+    /// Dev mode only: move the clock forward by `duration` without producing blocks.
+    /// The slots skipped this way are missed.
+    /// Returns the new time.
+    fn advance_time(&self, duration: MassaTime) -> FactoryResult<MassaTime>;
+
+    /// Returns a boxed clone of self.
+    /// Useful to allow cloning `Box<dyn FactoryController>`.
+    fn clone_box(&self) -> Box<dyn FactoryController>;
+}
+
+/// Allow cloning `Box<dyn FactoryController>`
+/// Uses `FactoryController::clone_box` internally
+impl Clone for Box<dyn FactoryController> {
+    fn clone(&self) -> Box<dyn FactoryController> {
+        self.clone_box()
+    }
+}
@@ -10,4 +10,8 @@ pub type FactoryResult<T, E = FactoryError> = core::result::Result<T, E>;
 pub enum FactoryError {
     /// Generic error: {0}
     GenericError(String),
+    /// Signing journal error: {0}
+    SigningJournalError(String),
+    /// Dev mode error: {0}
+    DevModeError(String),
 }
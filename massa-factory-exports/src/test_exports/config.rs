@@ -2,6 +2,7 @@
 
 use crate::FactoryConfig;
 use massa_time::MassaTime;
+use tempfile::TempDir;
 
 impl Default for FactoryConfig {
     fn default() -> Self {
@@ -18,6 +19,12 @@ impl Default for FactoryConfig {
             periods_per_cycle: PERIODS_PER_CYCLE,
             denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
             stop_production_when_zero_connections: false,
+            signing_journal_path: TempDir::new()
+                .expect("cannot create temp directory")
+                .path()
+                .join("signing_journal.jsonl"),
+            dev_mode: false,
+            dev_operations_check_interval: MassaTime::from_millis(100),
         }
     }
 }
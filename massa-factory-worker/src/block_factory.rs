@@ -1,32 +1,48 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crossbeam_channel::{never, select, Receiver};
 use massa_channel::receiver::MassaReceiver;
-use massa_factory_exports::{FactoryChannels, FactoryConfig};
+use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryError, FactoryResult};
 use massa_models::{
     block::{Block, BlockSerializer},
     block_header::{BlockHeader, BlockHeaderSerializer, SecuredHeader},
     block_id::BlockId,
     endorsement::SecureShareEndorsement,
     operation::{compute_operations_hash, OperationIdSerializer},
-    secure_share::SecureShareContent,
+    secure_share::{Id, SecureShareContent},
     slot::Slot,
-    timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
+    timeslots::{
+        get_block_slot_timestamp, get_closest_slot_to_timestamp, get_latest_block_slot_at_timestamp,
+    },
 };
+use massa_signature::MassaSignatureError;
 use massa_time::MassaTime;
 use massa_versioning::versioning::MipStore;
-use massa_wallet::Wallet;
-use parking_lot::RwLock;
-use std::{sync::Arc, thread, time::Instant};
+use massa_wallet::{Signer, SigningPayload};
+use parking_lot::Mutex;
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
 use tracing::{info, warn};
 
+use crate::controller::DevCommand;
+use crate::signing_journal::{SigningJournal, SigningKind, SigningRecord};
+
+/// Max time to wait in dev mode for consensus to take a produced block as best parent
+const DEV_BLOCK_PROCESSING_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Structure gathering all elements needed by the factory thread
 pub(crate) struct BlockFactoryWorker {
     cfg: FactoryConfig,
-    wallet: Arc<RwLock<Wallet>>,
+    signer: Arc<dyn Signer>,
     channels: FactoryChannels,
     factory_receiver: MassaReceiver<()>,
+    dev_command_receiver: Receiver<DevCommand>,
     mip_store: MipStore,
     op_id_serializer: OperationIdSerializer,
+    signing_journal: Arc<Mutex<SigningJournal>>,
 }
 
 impl BlockFactoryWorker {
@@ -34,21 +50,25 @@ impl BlockFactoryWorker {
     /// needed by the factory worker thread.
     pub(crate) fn spawn(
         cfg: FactoryConfig,
-        wallet: Arc<RwLock<Wallet>>,
+        signer: Arc<dyn Signer>,
         channels: FactoryChannels,
         factory_receiver: MassaReceiver<()>,
+        dev_command_receiver: Receiver<DevCommand>,
         mip_store: MipStore,
+        signing_journal: Arc<Mutex<SigningJournal>>,
     ) -> thread::JoinHandle<()> {
         thread::Builder::new()
             .name("block-factory".into())
             .spawn(|| {
                 let mut this = Self {
                     cfg,
-                    wallet,
+                    signer,
                     channels,
                     factory_receiver,
+                    dev_command_receiver,
                     mip_store,
                     op_id_serializer: OperationIdSerializer::new(),
+                    signing_journal,
                 };
                 this.run();
             })
@@ -121,7 +141,8 @@ impl BlockFactoryWorker {
     }
 
     /// Process a slot: produce a block at that slot if one of the managed keys is drawn.
-    fn process_slot(&mut self, slot: Slot) {
+    /// Returns the id of the produced block, if any.
+    fn process_slot(&mut self, slot: Slot) -> Option<BlockId> {
         // get block producer address for that slot
         let block_producer_addr = match self.channels.selector.get_producer(slot) {
             Ok(addr) => addr,
@@ -130,20 +151,23 @@ impl BlockFactoryWorker {
                     "block factory could not get selector draws for slot {}: {}",
                     slot, err
                 );
-                return;
+                return None;
             }
         };
 
-        // check if the block producer address is handled by the wallet
-        let block_producer_keypair_ref = self.wallet.read();
-        let block_producer_keypair = if let Some(kp) =
-            block_producer_keypair_ref.find_associated_keypair(&block_producer_addr)
-        {
+        // check if the block producer address is handled by the signer
+        let block_producer_public_key = match self.signer.get_public_key(&block_producer_addr) {
             // the selected block producer is managed locally => continue to attempt block production
-            kp
-        } else {
+            Ok(Some(public_key)) => public_key,
             // the selected block producer is not managed locally => quit
-            return;
+            Ok(None) => return,
+            Err(err) => {
+                warn!(
+                    "block factory could not get the key of address {} for slot {}: {}",
+                    block_producer_addr, slot, err
+                );
+                return None;
+            }
         };
         let mut block_storage = self.channels.storage.clone_without_refs();
         {
@@ -165,7 +189,7 @@ impl BlockFactoryWorker {
             if let Ok(stats) = self.channels.protocol.get_stats() {
                 if stats.1.is_empty() {
                     warn!("block factory could not produce block for slot {} because there are no connections", slot);
-                    return;
+                    return None;
                 }
             }
         }
@@ -203,7 +227,7 @@ impl BlockFactoryWorker {
         let (op_ids, op_storage) = self.channels.pool.get_block_operations(&slot);
         if op_ids.len() > self.cfg.max_operations_per_block as usize {
             warn!("Too many operations returned");
-            return;
+            return None;
         }
 
         block_storage.extend(op_storage);
@@ -211,7 +235,7 @@ impl BlockFactoryWorker {
         // create header
         let current_version = self.mip_store.get_network_version_current();
         let announced_version = self.mip_store.get_network_version_to_announce();
-        let header: SecuredHeader = BlockHeader::new_verifiable::<BlockHeaderSerializer, BlockId>(
+        let header: SecuredHeader = match BlockHeader::new_verifiable_with_signer(
             BlockHeader {
                 current_version,
                 announced_version,
@@ -222,19 +246,56 @@ impl BlockFactoryWorker {
                 denunciations: self.channels.pool.get_block_denunciations(&slot),
             },
             BlockHeaderSerializer::new(), // TODO reuse self.block_header_serializer
-            block_producer_keypair,
-        )
-        .expect("error while producing block header");
+            &block_producer_public_key,
+            |hash| {
+                self.signer
+                    .sign(&block_producer_addr, &SigningPayload::Block { slot }, hash)
+                    .map_err(|err| MassaSignatureError::SignatureError(err.to_string()).into())
+            },
+        ) {
+            Ok(header) => header,
+            Err(err) => {
+                warn!(
+                    "block factory could not sign block header for slot {} with address {}: {}",
+                    slot, block_producer_addr, err
+                );
+                return None;
+            }
+        };
+
+        // record the header in the signing journal before releasing it
+        let record = SigningRecord {
+            address: block_producer_addr,
+            kind: SigningKind::Block,
+            slot,
+            index: 0,
+            content_hash: *header.id.get_hash(),
+        };
+        match self.signing_journal.lock().record(&[record]) {
+            Ok(allowed) if allowed[0] => {}
+            // a different block was already signed for that slot (logged by the journal)
+            Ok(_) => return None,
+            Err(err) => {
+                warn!(
+                    "block factory could not record block for slot {} in the signing journal: {}",
+                    slot, err
+                );
+                return None;
+            }
+        }
+
         // create block
         let block_ = Block {
             header,
             operations: op_ids.into_iter().collect(),
         };
 
-        let block = Block::new_verifiable(
+        // the block is signed through its header
+        let block = Block::new_verifiable_with_signer(
             block_,
             BlockSerializer::new(), // TODO reuse self.block_serializer
-            block_producer_keypair,
+            &block_producer_public_key,
+            |_| unreachable!("blocks are signed through their header"),
         )
         .expect("error while producing block");
         let block_id = block.id;
@@ -251,10 +312,107 @@ impl BlockFactoryWorker {
         self.channels
             .consensus
             .register_block(block_id, slot, block_storage, true);
+
+        Some(block_id)
+    }
+
+    /// Dev mode: produce a block at each of the next `count` slots, moving the clock to each slot.
+    /// Waits for consensus to take each block as best parent, so that the next block is its descendant.
+    fn dev_advance_slots(&mut self, count: u64) -> FactoryResult<Vec<BlockId>> {
+        let mut block_ids = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let now =
+                MassaTime::now().map_err(|err| FactoryError::DevModeError(err.to_string()))?;
+            let next_slot = match get_latest_block_slot_at_timestamp(
+                self.cfg.thread_count,
+                self.cfg.t0,
+                self.cfg.genesis_timestamp,
+                now,
+            ) {
+                Ok(Some(slot)) => slot.get_next_slot(self.cfg.thread_count),
+                Ok(None) => Ok(Slot::new(0, 0)),
+                Err(err) => Err(err),
+            }
+            .map_err(|err| FactoryError::DevModeError(err.to_string()))?;
+            // ignore genesis
+            let slot = std::cmp::max(next_slot, Slot::new(self.cfg.last_start_period + 1, 0));
+            let slot_timestamp = get_block_slot_timestamp(
+                self.cfg.thread_count,
+                self.cfg.t0,
+                self.cfg.genesis_timestamp,
+                slot,
+            )
+            .map_err(|err| FactoryError::DevModeError(err.to_string()))?;
+            MassaTime::freeze_clock(slot_timestamp);
+
+            let block_id = self.process_slot(slot).ok_or_else(|| {
+                FactoryError::DevModeError(format!("no block was produced at slot {}", slot))
+            })?;
+            let deadline = Instant::now() + DEV_BLOCK_PROCESSING_TIMEOUT;
+            while self.channels.consensus.get_best_parents()[slot.thread as usize].0 != block_id {
+                if Instant::now() > deadline {
+                    return Err(FactoryError::DevModeError(format!(
+                        "block {} was not processed by consensus",
+                        block_id
+                    )));
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+            block_ids.push(block_id);
+        }
+        Ok(block_ids)
+    }
+
+    /// Dev mode: move the clock forward without producing blocks
+    fn dev_advance_time(&self, duration: MassaTime) -> FactoryResult<MassaTime> {
+        let now = MassaTime::now().map_err(|err| FactoryError::DevModeError(err.to_string()))?;
+        MassaTime::freeze_clock(now.saturating_add(duration));
+        Ok(now.saturating_add(duration))
+    }
+
+    /// main run loop of the block creator thread in dev mode:
+    /// blocks are produced on demand, or when new operations reach the pool
+    fn run_dev(&mut self) {
+        // local receivers: the worker is borrowed mutably while handling messages
+        let factory_receiver = self.factory_receiver.clone();
+        let dev_command_receiver = self.dev_command_receiver.clone();
+        let no_command = never();
+        let mut controllers_dropped = false;
+        let check_interval = self.cfg.dev_operations_check_interval.to_duration();
+        let mut operation_count = self.channels.pool.get_operation_count();
+        loop {
+            select! {
+                // stop signal or factory manager dropped => quit main loop
+                recv(factory_receiver) -> _ => break,
+                recv(if controllers_dropped { &no_command } else { &dev_command_receiver }) -> command => match command {
+                    Ok(DevCommand::AdvanceSlots { count, response_tx }) => {
+                        let _ = response_tx.send(self.dev_advance_slots(count));
+                    }
+                    Ok(DevCommand::AdvanceTime { duration, response_tx }) => {
+                        let _ = response_tx.send(self.dev_advance_time(duration));
+                    }
+                    // all the controllers were dropped, only wait for the stop signal
+                    Err(_) => controllers_dropped = true,
+                },
+                default(check_interval) => {
+                    // new operations: include them in a block, made final by the next one
+                    if self.channels.pool.get_operation_count() > operation_count {
+                        if let Err(err) = self.dev_advance_slots(2) {
+                            warn!("block factory could not include new operations in dev mode: {}", err);
+                        }
+                    }
+                }
+            }
+            operation_count = self.channels.pool.get_operation_count();
+        }
     }
 
     /// main run loop of the block creator thread
     fn run(&mut self) {
+        if self.cfg.dev_mode {
+            self.run_dev();
+            return;
+        }
         let mut prev_slot = None;
         loop {
             // get next slot
@@ -0,0 +1,74 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module implements a factory controller.
+//! See `massa-factory-exports/controller_traits.rs` for functional details.
+
+use crossbeam_channel::{bounded, Sender};
+use massa_factory_exports::{FactoryController, FactoryError, FactoryResult};
+use massa_models::block_id::BlockId;
+use massa_time::MassaTime;
+
+/// Commands sent to the block factory worker in dev mode
+pub(crate) enum DevCommand {
+    /// produce a block at each of the next `count` slots
+    AdvanceSlots {
+        count: u64,
+        response_tx: Sender<FactoryResult<Vec<BlockId>>>,
+    },
+    /// move the clock forward without producing blocks
+    AdvanceTime {
+        duration: MassaTime,
+        response_tx: Sender<FactoryResult<MassaTime>>,
+    },
+}
+
+/// Implementation of the factory controller
+#[derive(Clone)]
+pub struct FactoryControllerImpl {
+    /// whether the node runs in dev mode
+    pub(crate) dev_mode: bool,
+    /// channel to send dev mode commands to the block factory worker
+    pub(crate) command_sender: Sender<DevCommand>,
+}
+
+impl FactoryControllerImpl {
+    fn check_dev_mode(&self) -> FactoryResult<()> {
+        if !self.dev_mode {
+            return Err(FactoryError::DevModeError(
+                "the node is not running in dev mode".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn worker_stopped() -> FactoryError {
+    FactoryError::DevModeError("the block factory is stopped".to_string())
+}
+
+impl FactoryController for FactoryControllerImpl {
+    fn advance_slots(&self, count: u64) -> FactoryResult<Vec<BlockId>> {
+        self.check_dev_mode()?;
+        let (response_tx, response_rx) = bounded(1);
+        self.command_sender
+            .send(DevCommand::AdvanceSlots { count, response_tx })
+            .map_err(|_| worker_stopped())?;
+        response_rx.recv().map_err(|_| worker_stopped())?
+    }
+
+    fn advance_time(&self, duration: MassaTime) -> FactoryResult<MassaTime> {
+        self.check_dev_mode()?;
+        let (response_tx, response_rx) = bounded(1);
+        self.command_sender
+            .send(DevCommand::AdvanceTime {
+                duration,
+                response_tx,
+            })
+            .map_err(|_| worker_stopped())?;
+        response_rx.recv().map_err(|_| worker_stopped())?
+    }
+
+    fn clone_box(&self) -> Box<dyn FactoryController> {
+        Box::new(self.clone())
+    }
+}
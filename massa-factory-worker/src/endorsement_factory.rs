@@ -3,27 +3,31 @@
 use massa_channel::receiver::MassaReceiver;
 use massa_factory_exports::{FactoryChannels, FactoryConfig};
 use massa_models::{
+    address::Address,
     block_id::BlockId,
     endorsement::{Endorsement, EndorsementSerializer, SecureShareEndorsement},
-    secure_share::SecureShareContent,
+    secure_share::{Id, SecureShareContent},
     slot::Slot,
     timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
 };
-use massa_signature::KeyPair;
+use massa_signature::{MassaSignatureError, PublicKey};
 use massa_time::MassaTime;
-use massa_wallet::Wallet;
-use parking_lot::RwLock;
+use massa_wallet::{Signer, SigningPayload};
+use parking_lot::Mutex;
 use std::{sync::Arc, thread, time::Instant};
 use tracing::{debug, warn};
 
+use crate::signing_journal::{SigningJournal, SigningKind, SigningRecord};
+
 /// Structure gathering all elements needed by the factory thread
 pub(crate) struct EndorsementFactoryWorker {
     cfg: FactoryConfig,
-    wallet: Arc<RwLock<Wallet>>,
+    signer: Arc<dyn Signer>,
     channels: FactoryChannels,
     factory_receiver: MassaReceiver<()>,
     half_t0: MassaTime,
     endorsement_serializer: EndorsementSerializer,
+    signing_journal: Arc<Mutex<SigningJournal>>,
 }
 
 impl EndorsementFactoryWorker {
@@ -31,9 +35,10 @@ impl EndorsementFactoryWorker {
     /// needed by the factory worker thread.
     pub(crate) fn spawn(
         cfg: FactoryConfig,
-        wallet: Arc<RwLock<Wallet>>,
+        signer: Arc<dyn Signer>,
         channels: FactoryChannels,
         factory_receiver: MassaReceiver<()>,
+        signing_journal: Arc<Mutex<SigningJournal>>,
     ) -> thread::JoinHandle<()> {
         thread::Builder::new()
             .name("endorsement-factory".into())
@@ -44,10 +49,11 @@ impl EndorsementFactoryWorker {
                         .checked_div_u64(2)
                         .expect("could not compute half_t0"),
                     cfg,
-                    wallet,
+                    signer,
                     channels,
                     factory_receiver,
                     endorsement_serializer: EndorsementSerializer::new(),
+                    signing_journal,
                 };
                 this.run();
             })
@@ -134,21 +140,22 @@ impl EndorsementFactoryWorker {
             }
         };
 
-        // get creators if they are managed by our wallet
-        let mut producers_indices: Vec<(KeyPair, usize)> = Vec::new();
-        {
-            let wallet = self.wallet.read();
-            for (index, producer_addr) in producer_addrs.into_iter().enumerate() {
-                // check if the block producer address is handled by the wallet
-                let producer_keypair =
-                    if let Some(kp) = wallet.find_associated_keypair(&producer_addr) {
-                        // the selected block producer is managed locally => continue to attempt endorsement production
-                        kp.clone()
-                    } else {
-                        // the selected block producer is not managed locally => continue
-                        continue;
-                    };
-                producers_indices.push((producer_keypair, index));
+        // get creators if they are managed by our signer
+        let mut producers_indices: Vec<(Address, PublicKey, usize)> = Vec::new();
+        for (index, producer_addr) in producer_addrs.into_iter().enumerate() {
+            // check if the endorsement producer address is handled by the signer
+            match self.signer.get_public_key(&producer_addr) {
+                // the selected endorsement producer is managed locally => continue to attempt endorsement production
+                Ok(Some(public_key)) => producers_indices.push((producer_addr, public_key, index)),
+                // the selected endorsement producer is not managed locally => continue
+                Ok(None) => continue,
+                Err(err) => {
+                    warn!(
+                        "endorsement factory could not get the key of address {} for slot {}: {}",
+                        producer_addr, slot, err
+                    );
+                    continue;
+                }
             }
         }
 
@@ -177,17 +184,35 @@ impl EndorsementFactoryWorker {
         // produce endorsements
         let mut endorsements: Vec<SecureShareEndorsement> =
             Vec::with_capacity(producers_indices.len());
-        for (keypair, index) in producers_indices {
-            let endorsement = Endorsement::new_verifiable(
+        for (producer_addr, public_key, index) in producers_indices {
+            let index = index as u32;
+            let endorsement: SecureShareEndorsement = match Endorsement::new_verifiable_with_signer(
                 Endorsement {
                     slot,
-                    index: index as u32,
+                    index,
                     endorsed_block,
                 },
                 self.endorsement_serializer.clone(),
-                &keypair,
-            )
-            .expect("could not create endorsement");
+                &public_key,
+                |hash| {
+                    self.signer
+                        .sign(
+                            &producer_addr,
+                            &SigningPayload::Endorsement { slot, index },
+                            hash,
+                        )
+                        .map_err(|err| MassaSignatureError::SignatureError(err.to_string()).into())
+                },
+            ) {
+                Ok(endorsement) => endorsement,
+                Err(err) => {
+                    warn!(
+                        "endorsement factory could not sign endorsement {} of slot {} with address {}: {}",
+                        index, slot, producer_addr, err
+                    );
+                    continue;
+                }
+            };
 
             // log endorsement creation
             debug!(
@@ -198,6 +223,37 @@ impl EndorsementFactoryWorker {
             endorsements.push(endorsement);
         }
 
+        // record the endorsements in the signing journal before releasing them
+        let records: Vec<SigningRecord> = endorsements
+            .iter()
+            .map(|endorsement| SigningRecord {
+                address: endorsement.content_creator_address,
+                kind: SigningKind::Endorsement,
+                slot,
+                index: endorsement.content.index,
+                content_hash: *endorsement.id.get_hash(),
+            })
+            .collect();
+        let allowed = match self.signing_journal.lock().record(&records) {
+            Ok(allowed) => allowed,
+            Err(err) => {
+                warn!(
+                    "endorsement factory could not record endorsements for slot {} in the signing journal: {}",
+                    slot, err
+                );
+                return;
+            }
+        };
+        // endorsements conflicting with already signed ones are dropped (logged by the journal)
+        let endorsements: Vec<SecureShareEndorsement> = endorsements
+            .into_iter()
+            .zip(allowed)
+            .filter_map(|(endorsement, allowed)| allowed.then_some(endorsement))
+            .collect();
+        if endorsements.is_empty() {
+            return;
+        }
+
         // store endorsements
         let mut endo_storage = self.channels.storage.clone_without_refs();
         endo_storage.store_endorsements(endorsements);
@@ -212,6 +268,11 @@ impl EndorsementFactoryWorker {
 
     /// main run loop of the endorsement creator thread
     fn run(&mut self) {
+        // blocks are produced on demand in dev mode, not at each slot: no endorsements
+        if self.cfg.dev_mode {
+            let _ = self.factory_receiver.recv();
+            return;
+        }
         let mut prev_slot = None;
         loop {
             // get next slot
@@ -1,11 +1,15 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 mod block_factory;
+mod controller;
 mod endorsement_factory;
 mod manager;
 mod run;
+mod signing_journal;
 
+pub use controller::FactoryControllerImpl;
 pub use run::start_factory;
+pub use signing_journal::{SigningJournal, SigningKind, SigningRecord};
 
 #[cfg(test)]
 mod tests;
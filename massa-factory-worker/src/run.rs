@@ -2,35 +2,47 @@
 
 use massa_channel::MassaChannel;
 use massa_versioning::versioning::MipStore;
-use parking_lot::RwLock;
+use parking_lot::Mutex;
 use std::sync::Arc;
 
 use crate::{
-    block_factory::BlockFactoryWorker, endorsement_factory::EndorsementFactoryWorker,
-    manager::FactoryManagerImpl,
+    block_factory::BlockFactoryWorker, controller::FactoryControllerImpl,
+    endorsement_factory::EndorsementFactoryWorker, manager::FactoryManagerImpl,
+    signing_journal::SigningJournal,
 };
-use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
-use massa_wallet::Wallet;
+use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryController, FactoryManager};
+use massa_wallet::Signer;
 
 /// Start factory
 ///
 /// # Arguments
 /// * `cfg`: factory configuration
-/// * `wallet`: atomic reference to the node wallet
+/// * `signer`: holder of the staking keys
 /// * `channels`: channels to communicate with other modules
+/// * `mip_store`: versioning store
 ///
 /// # Return value
-/// Returns a factory manager allowing to stop the workers cleanly.
+/// Returns a factory manager allowing to stop the workers cleanly,
+/// and a factory controller driving block production in dev mode.
 pub fn start_factory(
     cfg: FactoryConfig,
-    wallet: Arc<RwLock<Wallet>>,
+    signer: Arc<dyn Signer>,
     channels: FactoryChannels,
     mip_store: MipStore,
-) -> Box<dyn FactoryManager> {
+) -> (Box<dyn FactoryManager>, Box<dyn FactoryController>) {
+    // open the journal of signed blocks and endorsements, shared by both workers
+    let signing_journal = Arc::new(Mutex::new(
+        SigningJournal::open(&cfg.signing_journal_path, cfg.denunciation_expire_periods)
+            .expect("could not open the signing journal"),
+    ));
+
     // create block factory channel
     let (block_worker_tx, block_worker_rx) =
         MassaChannel::new("factory_block_worker".to_string(), None);
 
+    // create the channel of the dev mode commands
+    let (dev_command_tx, dev_command_rx) = crossbeam_channel::unbounded();
+
     // create endorsement factory channel
     let (endorsement_worker_tx, endorsement_worker_rx) =
         MassaChannel::new("factory_endorsement_worker".to_string(), None);
@@ -38,15 +50,27 @@ pub fn start_factory(
     // start block factory worker
     let block_worker_handle = BlockFactoryWorker::spawn(
         cfg.clone(),
-        wallet.clone(),
+        signer.clone(),
         channels.clone(),
         block_worker_rx,
+        dev_command_rx,
         mip_store,
+        signing_journal.clone(),
     );
 
+    let controller = FactoryControllerImpl {
+        dev_mode: cfg.dev_mode,
+        command_sender: dev_command_tx,
+    };
+
     // start endorsement factory worker
-    let endorsement_worker_handle =
-        EndorsementFactoryWorker::spawn(cfg, wallet, channels, endorsement_worker_rx);
+    let endorsement_worker_handle = EndorsementFactoryWorker::spawn(
+        cfg,
+        signer,
+        channels,
+        endorsement_worker_rx,
+        signing_journal,
+    );
 
     // create factory manager
     let manager = FactoryManagerImpl {
@@ -54,5 +78,5 @@ pub fn start_factory(
         endorsement_worker: Some((endorsement_worker_tx, endorsement_worker_handle)),
     };
 
-    Box::new(manager)
+    (Box::new(manager), Box::new(controller))
 }
@@ -0,0 +1,293 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! On-disk journal of the blocks and endorsements signed by the factory.
+//!
+//! Before a block or an endorsement produced by the factory is released, the (slot, index)
+//! pair it was signed for is appended to the journal and the journal file is synced to disk.
+//! Signing a different content for a pair that is already in the journal is refused, which
+//! protects against double-staking after a restart or when keys are moved to another node.
+//!
+//! The journal is a file of JSON lines, one `SigningRecord` per line.
+
+use massa_factory_exports::{FactoryError, FactoryResult};
+use massa_hash::Hash;
+use massa_models::{address::Address, prehash::PreHashMap, slot::Slot};
+use serde::{Deserialize, Serialize};
+use std::collections::{btree_map::Entry, BTreeMap};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Kind of signed content
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningKind {
+    /// block header
+    Block,
+    /// endorsement
+    Endorsement,
+}
+
+/// A content signed by an address at a given slot and index
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningRecord {
+    /// signer address
+    pub address: Address,
+    /// kind of signed content
+    pub kind: SigningKind,
+    /// slot of the signed content
+    pub slot: Slot,
+    /// index of the endorsement, 0 for blocks
+    pub index: u32,
+    /// hash identifying the signed content (block or endorsement id)
+    pub content_hash: Hash,
+}
+
+/// Journal of the contents signed by the factory
+pub struct SigningJournal {
+    /// path of the journal file
+    path: PathBuf,
+    /// journal file opened in append mode
+    file: File,
+    /// signed content hashes per address, indexed by (slot, kind, index)
+    records: PreHashMap<Address, BTreeMap<(Slot, SigningKind, u32), Hash>>,
+}
+
+impl SigningJournal {
+    /// Opens the journal at the given path, creating it if needed.
+    ///
+    /// Records more than `keep_periods` periods older than the latest recorded slot can no longer
+    /// be denounced, they are dropped and the file is compacted.
+    pub fn open(path: &Path, keep_periods: u64) -> FactoryResult<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| journal_error(path, err))?;
+        }
+        let mut records = PreHashMap::default();
+        let (loaded, torn) = if path.exists() {
+            read_records(path, true)?
+        } else {
+            (Vec::new(), false)
+        };
+        let loaded_count = loaded.len();
+        for record in loaded {
+            // conflicting records can only come from a corrupted journal, the first one is kept
+            let _ = insert_record(&mut records, &record);
+        }
+
+        // prune old records
+        if let Some(max_period) = records
+            .values()
+            .filter_map(|signed| signed.keys().next_back())
+            .map(|(slot, _, _)| slot.period)
+            .max()
+        {
+            let min_period = max_period.saturating_sub(keep_periods);
+            for signed in records.values_mut() {
+                *signed = signed.split_off(&(Slot::new(min_period, 0), SigningKind::Block, 0));
+            }
+            records.retain(|_, signed| !signed.is_empty());
+        }
+
+        // rewrite the journal if records were pruned or if its last line is incomplete
+        if torn || records.values().map(BTreeMap::len).sum::<usize>() != loaded_count {
+            let tmp_path = path.with_extension("tmp");
+            let mut tmp_file = File::create(&tmp_path).map_err(|err| journal_error(path, err))?;
+            write_records(&mut tmp_file, iter_records(&records))
+                .and_then(|_| tmp_file.sync_all())
+                .and_then(|_| std::fs::rename(&tmp_path, path))
+                .map_err(|err| journal_error(path, err))?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|err| journal_error(path, err))?;
+        Ok(SigningJournal {
+            path: path.to_path_buf(),
+            file,
+            records,
+        })
+    }
+
+    /// Checks the given records against the journal and persists the new ones.
+    ///
+    /// Returns, for each record, whether its content can be released: it is the case if the
+    /// record is new or if the exact same content was already signed. The journal is synced to
+    /// disk before returning, so that released signatures are never lost on a crash.
+    pub fn record(&mut self, records: &[SigningRecord]) -> FactoryResult<Vec<bool>> {
+        let mut allowed = Vec::with_capacity(records.len());
+        let mut new_records = Vec::new();
+        for record in records {
+            match insert_record(&mut self.records, record) {
+                Ok(true) => {
+                    new_records.push(record);
+                    allowed.push(true);
+                }
+                Ok(false) => allowed.push(true),
+                Err(existing) => {
+                    warn!(
+                        "refusing to sign {:?} at slot {} index {} with address {}: content {} was already signed",
+                        record.kind, record.slot, record.index, record.address, existing
+                    );
+                    allowed.push(false);
+                }
+            }
+        }
+        if let Err(err) = self.append(new_records.iter().copied()) {
+            // nothing is released if the records could not be persisted
+            for record in new_records {
+                remove_record(&mut self.records, record);
+            }
+            return Err(err);
+        }
+        Ok(allowed)
+    }
+
+    /// Writes the records of the given addresses (all the records if `addresses` is empty)
+    /// to a new file at `path`, returns the number of exported records
+    pub fn export(&self, addresses: &[Address], path: &Path) -> FactoryResult<usize> {
+        let records = iter_records(&self.records)
+            .filter(|record| addresses.is_empty() || addresses.contains(&record.address))
+            .collect::<Vec<_>>();
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(|err| journal_error(path, err))?;
+        write_records(&mut file, records.iter())
+            .and_then(|_| file.sync_all())
+            .map_err(|err| journal_error(path, err))?;
+        Ok(records.len())
+    }
+
+    /// Merges the records of a file written by `export` into the journal,
+    /// returns the number of new records.
+    /// Nothing is imported if one of the records conflicts with the journal.
+    pub fn import(&mut self, path: &Path) -> FactoryResult<usize> {
+        let (records, _) = read_records(path, false)?;
+        let mut imported = self.records.clone();
+        let mut new_records = Vec::new();
+        for record in &records {
+            match insert_record(&mut imported, record) {
+                Ok(true) => new_records.push(record),
+                Ok(false) => {}
+                Err(existing) => {
+                    return Err(FactoryError::SigningJournalError(format!(
+                        "imported {:?} record at slot {} index {} for address {} conflicts with already signed content {}",
+                        record.kind, record.slot, record.index, record.address, existing
+                    )))
+                }
+            }
+        }
+        self.append(new_records.iter().copied())?;
+        self.records = imported;
+        Ok(new_records.len())
+    }
+
+    /// Appends records to the journal file and syncs it
+    fn append<'a>(
+        &mut self,
+        records: impl Iterator<Item = &'a SigningRecord>,
+    ) -> FactoryResult<()> {
+        let mut records = records.peekable();
+        if records.peek().is_none() {
+            return Ok(());
+        }
+        write_records(&mut self.file, records)
+            .and_then(|_| self.file.sync_data())
+            .map_err(|err| journal_error(&self.path, err))
+    }
+}
+
+/// Inserts a record in the journal map.
+/// Returns `Ok(true)` if it is new, `Ok(false)` if it was already there,
+/// or the hash of the already signed content if it conflicts.
+fn insert_record(
+    records: &mut PreHashMap<Address, BTreeMap<(Slot, SigningKind, u32), Hash>>,
+    record: &SigningRecord,
+) -> Result<bool, Hash> {
+    match records
+        .entry(record.address)
+        .or_default()
+        .entry((record.slot, record.kind, record.index))
+    {
+        Entry::Vacant(entry) => {
+            entry.insert(record.content_hash);
+            Ok(true)
+        }
+        Entry::Occupied(entry) if *entry.get() == record.content_hash => Ok(false),
+        Entry::Occupied(entry) => Err(*entry.get()),
+    }
+}
+
+fn remove_record(
+    records: &mut PreHashMap<Address, BTreeMap<(Slot, SigningKind, u32), Hash>>,
+    record: &SigningRecord,
+) {
+    if let Some(signed) = records.get_mut(&record.address) {
+        signed.remove(&(record.slot, record.kind, record.index));
+    }
+}
+
+fn iter_records(
+    records: &PreHashMap<Address, BTreeMap<(Slot, SigningKind, u32), Hash>>,
+) -> impl Iterator<Item = SigningRecord> + '_ {
+    records.iter().flat_map(|(address, signed)| {
+        signed
+            .iter()
+            .map(|((slot, kind, index), content_hash)| SigningRecord {
+                address: *address,
+                kind: *kind,
+                slot: *slot,
+                index: *index,
+                content_hash: *content_hash,
+            })
+    })
+}
+
+fn write_records<R: std::borrow::Borrow<SigningRecord>>(
+    file: &mut File,
+    records: impl Iterator<Item = R>,
+) -> std::io::Result<()> {
+    let mut buffer = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut buffer, record.borrow())?;
+        buffer.push(b'\n');
+    }
+    file.write_all(&buffer)
+}
+
+/// Reads the records of a journal file, and whether its last line was ignored.
+/// If `allow_torn_tail` is set, an incomplete last line left by a crash during a write is ignored:
+/// the corresponding content was never released.
+fn read_records(path: &Path, allow_torn_tail: bool) -> FactoryResult<(Vec<SigningRecord>, bool)> {
+    let file = File::open(path).map_err(|err| journal_error(path, err))?;
+    let mut records = Vec::new();
+    let mut torn = false;
+    let mut lines = BufReader::new(file).split(b'\n').peekable();
+    while let Some(line) = lines.next() {
+        let line = line.map_err(|err| journal_error(path, err))?;
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        match serde_json::from_slice(&line) {
+            Ok(record) => records.push(record),
+            Err(err) if allow_torn_tail && lines.peek().is_none() => {
+                warn!(
+                    "ignoring incomplete last record of signing journal {}: {}",
+                    path.display(),
+                    err
+                );
+                torn = true;
+            }
+            Err(err) => return Err(journal_error(path, err)),
+        }
+    }
+    Ok((records, torn))
+}
+
+fn journal_error(path: &Path, err: impl std::fmt::Display) -> FactoryError {
+    FactoryError::SigningJournalError(format!("{}: {}", path.display(), err))
+}
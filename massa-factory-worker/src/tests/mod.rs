@@ -1,4 +1,5 @@
 mod scenarios;
+mod signing_journal;
 mod tools;
 
 pub use tools::*;
@@ -0,0 +1,90 @@
+use crate::{SigningJournal, SigningKind, SigningRecord};
+use massa_hash::Hash;
+use massa_models::{address::Address, slot::Slot};
+use massa_signature::KeyPair;
+use tempfile::TempDir;
+
+fn endorsement_record(address: Address, period: u64, content: &[u8]) -> SigningRecord {
+    SigningRecord {
+        address,
+        kind: SigningKind::Endorsement,
+        slot: Slot::new(period, 0),
+        index: 3,
+        content_hash: Hash::compute_from(content),
+    }
+}
+
+/// Checks that conflicting contents are refused, including after reopening the journal.
+#[test]
+fn test_signing_journal_conflicts() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("signing_journal.jsonl");
+    let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+    let mut journal = SigningJournal::open(&path, 10).unwrap();
+    let first = endorsement_record(address, 5, b"first");
+    let second = endorsement_record(address, 5, b"second");
+    assert_eq!(
+        journal.record(&[first.clone(), second.clone()]).unwrap(),
+        vec![true, false]
+    );
+    // signing the same content again is allowed
+    assert_eq!(journal.record(&[first.clone()]).unwrap(), vec![true]);
+    drop(journal);
+
+    let mut journal = SigningJournal::open(&path, 10).unwrap();
+    assert_eq!(
+        journal.record(&[second, first.clone()]).unwrap(),
+        vec![false, true]
+    );
+    // a block at the same slot and index is not a conflict
+    let block = SigningRecord {
+        kind: SigningKind::Block,
+        ..endorsement_record(address, 5, b"block")
+    };
+    assert_eq!(journal.record(&[block]).unwrap(), vec![true]);
+}
+
+/// Checks that old records are pruned and that records can be moved to another journal.
+#[test]
+fn test_signing_journal_export_import() {
+    let dir = TempDir::new().unwrap();
+    let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let other_address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+    let path = dir.path().join("signing_journal.jsonl");
+    let mut journal = SigningJournal::open(&path, 10).unwrap();
+    journal
+        .record(&[
+            endorsement_record(address, 1, b"old"),
+            endorsement_record(address, 20, b"recent"),
+            endorsement_record(other_address, 20, b"other"),
+        ])
+        .unwrap();
+    drop(journal);
+    let journal = SigningJournal::open(&path, 10).unwrap();
+
+    let export_path = dir.path().join("export.jsonl");
+    assert_eq!(journal.export(&[address], &export_path).unwrap(), 1);
+    assert!(journal.export(&[address], &export_path).is_err());
+
+    let mut other_journal = SigningJournal::open(&dir.path().join("other.jsonl"), 10).unwrap();
+    assert_eq!(other_journal.import(&export_path).unwrap(), 1);
+    assert_eq!(other_journal.import(&export_path).unwrap(), 0);
+    assert_eq!(
+        other_journal
+            .record(&[
+                endorsement_record(address, 20, b"conflict"),
+                endorsement_record(address, 1, b"pruned"),
+            ])
+            .unwrap(),
+        vec![false, true]
+    );
+
+    let conflicting_path = dir.path().join("conflicting.jsonl");
+    let mut conflicting_journal = SigningJournal::open(&conflicting_path, 10).unwrap();
+    conflicting_journal
+        .record(&[endorsement_record(address, 20, b"conflict")])
+        .unwrap();
+    assert!(conflicting_journal.import(&export_path).is_err());
+}
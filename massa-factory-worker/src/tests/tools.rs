@@ -30,7 +30,7 @@ use massa_storage::Storage;
 use massa_time::MassaTime;
 
 use crate::start_factory;
-use massa_wallet::test_exports::create_test_wallet;
+use massa_wallet::{test_exports::create_test_wallet, LocalSigner};
 
 /// This structure store all information and links to creates tests for the factory.
 /// The factory will ask that to the the pool, consensus and factory and then will send the block to the consensus.
@@ -90,9 +90,9 @@ impl TestFactory {
             MipStore::try_from(([], mip_stats_config)).expect("Cannot create an empty MIP store");
 
         let wallet = create_test_wallet(Some(accounts));
-        let factory_manager = start_factory(
+        let (factory_manager, _factory_controller) = start_factory(
             factory_config.clone(),
-            Arc::new(RwLock::new(wallet)),
+            Arc::new(LocalSigner::new(Arc::new(RwLock::new(wallet)))),
             FactoryChannels {
                 selector: selector_controller.clone(),
                 consensus: consensus_controller,
@@ -148,6 +148,13 @@ impl FinalState {
         massa_hash::Hash::compute_from(internal_hash.to_bytes())
     }
 
+    /// Get the fingerprint of the final state at the end of a recent final slot,
+    /// `None` if the slot is not in the change history
+    pub fn get_fingerprint_at(&self, slot: Slot) -> Option<massa_hash::Hash> {
+        let internal_hash = self.db.read().get_xof_db_hash_at(slot)?;
+        Some(massa_hash::Hash::compute_from(internal_hash.to_bytes()))
+    }
+
     /// Get the slot at the end of which the final state is attached
     pub fn get_slot(&self) -> Slot {
         self.db
@@ -38,6 +38,8 @@ fn create_final_state(temp_dir: &TempDir, reset_final_state: bool) -> Arc<RwLock
         max_history_length: 10,
         max_new_elements: 100,
         thread_count,
+        enable_archive: false,
+        archive_retention_periods: 0,
     };
     let db = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -1,5 +1,6 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use massa_models::config::CompactConfig;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use serde::Deserialize;
@@ -95,6 +96,8 @@ pub struct GrpcConfig {
     pub t0: MassaTime,
     /// periods per cycle
     pub periods_per_cycle: u64,
+    /// network parameters shown by the status endpoints
+    pub compact_config: CompactConfig,
     /// keypair file
     pub keypair: KeyPair,
     /// limits the maximum size of streaming channel
@@ -7,7 +7,7 @@ use crate::error::GrpcError;
 use crate::server::MassaPrivateGrpc;
 use massa_execution_exports::ExecutionQueryRequest;
 use massa_hash::Hash;
-use massa_models::config::CompactConfig;
+use massa_models::address::Address;
 use massa_models::node::NodeId;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
@@ -74,14 +74,16 @@ pub(crate) fn add_to_bootstrap_whitelist(
     Ok(grpc_api::AddToBootstrapWhitelistResponse {})
 }
 /// Add IP addresses to node peers whitelist. No confirmation to expect.
-/// Note: If the ip was unknown it adds it to the known peers, otherwise it updates the peer type
+/// Note: A whitelisted ip is unbanned, can't be banned by ip and is connected to first
 pub(crate) fn add_to_peers_whitelist(
-    _grpc: &MassaPrivateGrpc,
-    _request: tonic::Request<grpc_api::AddToPeersWhitelistRequest>,
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::AddToPeersWhitelistRequest>,
 ) -> Result<grpc_api::AddToPeersWhitelistResponse, GrpcError> {
-    Err(GrpcError::Unimplemented(
-        "add_to_peers_whitelist".to_string(),
-    ))
+    let ips = parse_ips(grpc, request.into_inner().ips)?;
+
+    grpc.protocol_controller.add_to_peers_whitelist(ips)?;
+
+    Ok(grpc_api::AddToPeersWhitelistResponse {})
 }
 /// Add staking secret keys to wallet
 pub(crate) fn add_staking_secret_keys(
@@ -148,10 +150,14 @@ pub(crate) fn ban_nodes_by_ids(
 
 /// Ban multiple nodes by their individual IP addresses
 pub(crate) fn ban_nodes_by_ips(
-    _grpc: &MassaPrivateGrpc,
-    _request: tonic::Request<grpc_api::BanNodesByIpsRequest>,
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::BanNodesByIpsRequest>,
 ) -> Result<grpc_api::BanNodesByIpsResponse, GrpcError> {
-    Err(GrpcError::Unimplemented("ban_nodes_by_ips".to_string()))
+    let ips = parse_ips(grpc, request.into_inner().node_ips)?;
+
+    grpc.protocol_controller.ban_ips(ips)?;
+
+    Ok(grpc_api::BanNodesByIpsResponse {})
 }
 
 /// Get node bootstrap blacklist IP addresses
@@ -215,19 +221,26 @@ pub(crate) fn get_mip_status(
 
 /// Allow everyone to bootstrap from the node by removing bootstrap whitelist configuration file
 pub(crate) fn allow_everyone_to_bootstrap(
-    _grpc: &MassaPrivateGrpc,
+    grpc: &MassaPrivateGrpc,
     _request: tonic::Request<grpc_api::AllowEveryoneToBootstrapRequest>,
 ) -> Result<grpc_api::AllowEveryoneToBootstrapResponse, GrpcError> {
-    Err(GrpcError::Unimplemented(
-        "allow_everyone_to_bootstrap".to_string(),
-    ))
+    if let Some(bs_list) = &grpc.bs_white_black_list {
+        bs_list.allow_everyone().map_err(|e| {
+            GrpcError::InternalServerError(format!(
+                "failed to delete bootstrap whitelist configuration file: {}",
+                e
+            ))
+        })?;
+    }
+
+    Ok(grpc_api::AllowEveryoneToBootstrapResponse {})
 }
 /// Get node status
 pub(crate) fn get_node_status(
     grpc: &MassaPrivateGrpc,
     _request: tonic::Request<grpc_api::GetNodeStatusRequest>,
 ) -> Result<grpc_api::GetNodeStatusResponse, GrpcError> {
-    let config = CompactConfig::default();
+    let config = grpc.grpc_config.compact_config;
     let now = MassaTime::now()?;
     let last_slot = get_latest_block_slot_at_timestamp(
         grpc.grpc_config.thread_count,
@@ -312,10 +325,17 @@ pub(crate) fn get_node_status(
 }
 /// Get node peers whitelist IP addresses
 pub(crate) fn get_peers_whitelist(
-    _grpc: &MassaPrivateGrpc,
+    grpc: &MassaPrivateGrpc,
     _request: tonic::Request<grpc_api::GetPeersWhitelistRequest>,
 ) -> Result<grpc_api::GetPeersWhitelistResponse, GrpcError> {
-    Err(GrpcError::Unimplemented("get_peers_whitelist".to_string()))
+    let ips = grpc
+        .protocol_controller
+        .get_peers_whitelist()?
+        .into_iter()
+        .map(|ip| ip.to_string())
+        .collect();
+
+    Ok(grpc_api::GetPeersWhitelistResponse { ips })
 }
 /// Remove from bootstrap blacklist given IP addresses
 pub(crate) fn remove_from_bootstrap_blacklist(
@@ -371,21 +391,46 @@ pub(crate) fn remove_from_bootstrap_whitelist(
 }
 /// Remove from peers whitelist given IP addresses
 pub(crate) fn remove_from_peers_whitelist(
-    _grpc: &MassaPrivateGrpc,
-    _request: tonic::Request<grpc_api::RemoveFromPeersWhitelistRequest>,
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::RemoveFromPeersWhitelistRequest>,
 ) -> Result<grpc_api::RemoveFromPeersWhitelistResponse, GrpcError> {
-    Err(GrpcError::Unimplemented(
-        "remove_from_peers_whitelist".to_string(),
-    ))
+    let ips = parse_ips(grpc, request.into_inner().ips)?;
+
+    grpc.protocol_controller.remove_from_peers_whitelist(ips)?;
+
+    Ok(grpc_api::RemoveFromPeersWhitelistResponse {})
 }
 /// Remove addresses from staking
 pub(crate) fn remove_staking_addresses(
-    _grpc: &MassaPrivateGrpc,
-    _request: tonic::Request<grpc_api::RemoveStakingAddressesRequest>,
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::RemoveStakingAddressesRequest>,
 ) -> Result<grpc_api::RemoveStakingAddressesResponse, GrpcError> {
-    Err(GrpcError::Unimplemented(
-        "remove_staking_addresses".to_string(),
-    ))
+    let addresses = request.into_inner().addresses;
+
+    if addresses.is_empty() {
+        return Err(GrpcError::InvalidArgument(
+            "no address received".to_string(),
+        ));
+    }
+
+    if addresses.len() as u64 > grpc.grpc_config.max_arguments {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many addresses received. Only a maximum of {} addresses are accepted per request",
+            grpc.grpc_config.max_arguments
+        )));
+    }
+
+    let addresses = addresses
+        .iter()
+        .map(|address| Address::from_str(address))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let changed = grpc.node_wallet.write().remove_addresses(&addresses)?;
+    if changed {
+        grpc.node_wallet.read().save()?;
+    }
+
+    Ok(grpc_api::RemoveStakingAddressesResponse {})
 }
 /// Sign messages with node's key
 pub(crate) fn sign_messages(
@@ -424,10 +469,17 @@ pub(crate) fn sign_messages(
 }
 /// Shutdown the node gracefully
 pub(crate) fn shutdown_gracefully(
-    _grpc: &MassaPrivateGrpc,
+    grpc: &MassaPrivateGrpc,
     _request: tonic::Request<grpc_api::ShutdownGracefullyRequest>,
 ) -> Result<grpc_api::ShutdownGracefullyResponse, GrpcError> {
-    Err(GrpcError::Unimplemented("shutdown_gracefully".to_string()))
+    *grpc
+        .stop_cv
+        .0
+        .lock()
+        .map_err(|e| GrpcError::InternalServerError(e.to_string()))? = true;
+    grpc.stop_cv.1.notify_all();
+
+    Ok(grpc_api::ShutdownGracefullyResponse {})
 }
 
 /// Unban multiple nodes by their individual ids
@@ -465,8 +517,33 @@ pub(crate) fn unban_nodes_by_ids(
 
 /// Unban multiple nodes by their individual IP addresses
 pub(crate) fn unban_nodes_by_ips(
-    _grpc: &MassaPrivateGrpc,
-    _request: tonic::Request<grpc_api::UnbanNodesByIpsRequest>,
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::UnbanNodesByIpsRequest>,
 ) -> Result<grpc_api::UnbanNodesByIpsResponse, GrpcError> {
-    Err(GrpcError::Unimplemented("unban_nodes_by_ips".to_string()))
+    let ips = parse_ips(grpc, request.into_inner().node_ips)?;
+
+    grpc.protocol_controller.unban_ips(ips)?;
+
+    Ok(grpc_api::UnbanNodesByIpsResponse {})
+}
+
+/// Parse a list of IP addresses, rejecting empty, oversized or malformed lists
+fn parse_ips(grpc: &MassaPrivateGrpc, ips: Vec<String>) -> Result<Vec<IpAddr>, GrpcError> {
+    if ips.is_empty() {
+        return Err(GrpcError::InvalidArgument("no ip received".to_string()));
+    }
+
+    if ips.len() as u64 > grpc.grpc_config.max_arguments {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many ips received. Only a maximum of {} ips are accepted per request",
+            grpc.grpc_config.max_arguments
+        )));
+    }
+
+    ips.iter()
+        .map(|ip| {
+            IpAddr::from_str(ip)
+                .map_err(|e| GrpcError::InvalidArgument(format!("invalid ip {}: {}", ip, e)))
+        })
+        .collect()
 }
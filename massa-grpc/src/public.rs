@@ -5,6 +5,7 @@ use crate::server::MassaPublicGrpc;
 use crate::{EndorsementDraw, SlotDraw, SlotRange};
 
 use itertools::{izip, Itertools};
+use massa_block_archive_exports::{ArchivedItemId, ArchivedItemKind};
 use massa_execution_exports::mapping_grpc::{
     to_event_filter, to_execution_query_response, to_querystate_filter,
 };
@@ -14,7 +15,6 @@ use massa_execution_exports::{
 use massa_models::address::Address;
 use massa_models::block::{Block, BlockGraphStatus};
 use massa_models::block_id::BlockId;
-use massa_models::config::CompactConfig;
 use massa_models::datastore::DatastoreDeserializer;
 use massa_models::endorsement::{EndorsementId, SecureShareEndorsement};
 use massa_models::operation::{OperationId, SecureShareOperation};
@@ -161,6 +161,26 @@ pub(crate) fn get_blocks(
         .collect::<Result<_, _>>()?;
 
     let read_blocks = grpc.storage.read_blocks();
+
+    // blocks pruned from storage may be found in the block archive, they are final
+    let archived_blocks: Vec<Block> = match &grpc.consensus_channels.block_archive_controller {
+        Some(block_archive_controller) => {
+            let missing: Vec<BlockId> = block_ids
+                .iter()
+                .filter(|id| !read_blocks.contains(id))
+                .copied()
+                .collect();
+            block_archive_controller
+                .get_blocks(&missing)
+                .map_err(|err| GrpcError::InternalServerError(err.to_string()))?
+                .into_iter()
+                .flatten()
+                .map(|block| block.content)
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
     let blocks = block_ids
         .into_iter()
         .filter_map(|id| {
@@ -184,6 +204,11 @@ pub(crate) fn get_blocks(
     let result = blocks
         .iter()
         .zip(blocks_status)
+        .chain(
+            archived_blocks
+                .iter()
+                .map(|block| (block, BlockGraphStatus::Final)),
+        )
         .map(|(block, block_graph_status)| grpc_model::BlockWrapper {
             block: Some(block.clone().into()),
             status: block_graph_status.into(),
@@ -275,7 +300,7 @@ pub(crate) fn get_endorsements(
         })
         .collect::<Result<_, _>>()?;
 
-    let storage_info: Vec<(SecureShareEndorsement, PreHashSet<BlockId>)> = {
+    let mut storage_info: Vec<(SecureShareEndorsement, PreHashSet<BlockId>)> = {
         let read_blocks = grpc.storage.read_blocks();
         let read_endos = grpc.storage.read_endorsements();
         endorsement_ids
@@ -294,7 +319,27 @@ pub(crate) fn get_endorsements(
             .collect()
     };
 
-    // keep only the endorsements found in storage
+    // endorsements pruned from storage may be found in the block archive, they are final
+    let mut archived_eds: PreHashSet<EndorsementId> = PreHashSet::default();
+    if let Some(block_archive_controller) = &grpc.consensus_channels.block_archive_controller {
+        let found: PreHashSet<EndorsementId> = storage_info.iter().map(|(ed, _)| ed.id).collect();
+        let missing: Vec<EndorsementId> = endorsement_ids
+            .iter()
+            .filter(|id| !found.contains(id))
+            .copied()
+            .collect();
+        for (ed, block_id) in block_archive_controller
+            .get_endorsements(&missing)
+            .map_err(|err| GrpcError::InternalServerError(err.to_string()))?
+            .into_iter()
+            .flatten()
+        {
+            archived_eds.insert(ed.id);
+            storage_info.push((ed, std::iter::once(block_id).collect()));
+        }
+    }
+
+    // keep only the endorsements found in storage or in the archive
     let eds: Vec<EndorsementId> = storage_info.iter().map(|(ed, _)| ed.id).collect();
 
     // ask pool whether it carries the endorsements
@@ -319,9 +364,11 @@ pub(crate) fn get_endorsements(
             .collect();
         storage_info
             .iter()
-            .map(|(_ed, bs)| {
-                bs.iter()
-                    .any(|b| block_statuses.get(b) == Some(&BlockGraphStatus::Final))
+            .map(|(ed, bs)| {
+                archived_eds.contains(&ed.id)
+                    || bs
+                        .iter()
+                        .any(|b| block_statuses.get(b) == Some(&BlockGraphStatus::Final))
             })
             .collect()
     };
@@ -487,7 +534,8 @@ pub(crate) fn get_operations(
 
     // Get the operations and the list of blocks that contain them from storage
     let storage_info: Vec<(&SecureShareOperation, HashSet<BlockId>)> = operation_ids
-        .into_iter()
+        .iter()
+        .copied()
         .filter_map(|ope_id| {
             read_ops.get(&ope_id).map(|secure_share| {
                 let block_ids = read_blocks
@@ -500,8 +548,32 @@ pub(crate) fn get_operations(
         })
         .collect();
 
+    // operations pruned from storage may be found in the block archive
+    let archived_ops: Vec<(SecureShareOperation, BlockId)> =
+        match &grpc.consensus_channels.block_archive_controller {
+            Some(block_archive_controller) => {
+                let missing: Vec<OperationId> = operation_ids
+                    .iter()
+                    .filter(|id| !read_ops.contains(id))
+                    .copied()
+                    .collect();
+                block_archive_controller
+                    .get_operations(&missing)
+                    .map_err(|err| GrpcError::InternalServerError(err.to_string()))?
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
     let operations: Vec<grpc_model::OperationWrapper> = storage_info
         .into_iter()
+        .chain(
+            archived_ops
+                .iter()
+                .map(|(op, block_id)| (op, std::iter::once(*block_id).collect())),
+        )
         .map(|secure_share| {
             let (secure_share, block_ids) = secure_share;
             grpc_model::OperationWrapper {
@@ -665,7 +737,7 @@ pub(crate) fn get_status(
     grpc: &MassaPublicGrpc,
     _request: tonic::Request<grpc_api::GetStatusRequest>,
 ) -> Result<grpc_api::GetStatusResponse, GrpcError> {
-    let config = CompactConfig::default();
+    let config = grpc.grpc_config.compact_config;
     let now = MassaTime::now()?;
     let last_slot = get_latest_block_slot_at_timestamp(
         grpc.grpc_config.thread_count,
@@ -840,29 +912,86 @@ pub(crate) fn search_blocks(
         return Err(GrpcError::InvalidArgument("no filter provided".to_string()));
     }
 
+    // bounds of the intersection of the slot ranges
+    let slot_bounds = slot_ranges_filter.map(|slot_ranges| {
+        let mut start_slot = Slot::new(0, 0); // inclusive
+        let mut end_slot = Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1); // exclusive
+        for slot_range in &slot_ranges {
+            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(|| Slot::new(0, 0)));
+            end_slot = end_slot.min(
+                slot_range
+                    .end_slot
+                    .unwrap_or_else(|| Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1)),
+            );
+        }
+        (start_slot, end_slot.max(start_slot))
+    });
+
+    // blocks pruned from storage may be found in the block archive, they are final
+    let block_archive_controller = grpc.consensus_channels.block_archive_controller.as_ref();
+    let archive_search_limit = grpc.grpc_config.max_block_ids_per_request as usize;
+    let mut archived_block_ids: PreHashSet<BlockId> = PreHashSet::default();
+
     let mut res: Option<PreHashSet<BlockId>> = None;
 
     // filter by block ids
     if let Some(mut b_ids) = block_ids_filter {
-        let read_lock = grpc.storage.read_blocks();
-        b_ids.retain(|id: &BlockId| read_lock.contains(id));
+        let missing: Vec<BlockId> = {
+            let read_lock = grpc.storage.read_blocks();
+            b_ids
+                .iter()
+                .filter(|id| !read_lock.contains(id))
+                .copied()
+                .collect()
+        };
+        if let Some(block_archive_controller) = block_archive_controller {
+            let archived = block_archive_controller
+                .get_blocks(&missing)
+                .map_err(|err| GrpcError::InternalServerError(err.to_string()))?;
+            archived_block_ids.extend(archived.into_iter().flatten().map(|block| block.id));
+        }
+        b_ids.retain(|id: &BlockId| !missing.contains(id) || archived_block_ids.contains(id));
 
         res = Some(b_ids);
     }
 
     // filter by addresses
     if let Some(addrs) = addresses_filter {
-        let b_ids: PreHashSet<BlockId> = {
+        let mut b_ids: PreHashSet<BlockId> = {
             let read_lock = grpc.storage.read_blocks();
             let mut b_ids: PreHashSet<BlockId> = PreHashSet::default();
-            for addr in addrs {
-                if let Some(addr_b_ids) = read_lock.get_blocks_created_by(&addr) {
+            for addr in &addrs {
+                if let Some(addr_b_ids) = read_lock.get_blocks_created_by(addr) {
                     b_ids.extend(addr_b_ids.clone());
                 }
             }
 
             b_ids
         };
+        if let Some(block_archive_controller) = block_archive_controller {
+            let (start_slot, end_slot) = slot_bounds.unwrap_or((
+                Slot::new(0, 0),
+                Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1),
+            ));
+            for addr in &addrs {
+                for (_slot, id) in block_archive_controller
+                    .get_address_history(
+                        addr,
+                        ArchivedItemKind::Block,
+                        start_slot,
+                        end_slot,
+                        archive_search_limit,
+                    )
+                    .map_err(|err| GrpcError::InternalServerError(err.to_string()))?
+                {
+                    if let ArchivedItemId::Block(block_id) = id {
+                        if b_ids.insert(block_id) {
+                            archived_block_ids.insert(block_id);
+                        }
+                    }
+                }
+            }
+        }
         if let Some(block_ids) = res.as_mut() {
             block_ids.retain(|id: &BlockId| b_ids.contains(id));
         } else {
@@ -871,22 +1000,20 @@ pub(crate) fn search_blocks(
     }
 
     // filter by slot ranges
-    if let Some(slot_ranges) = slot_ranges_filter {
-        let mut start_slot = Slot::new(0, 0); // inclusive
-        let mut end_slot = Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1); // exclusive
-        for slot_range in &slot_ranges {
-            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(|| Slot::new(0, 0)));
-            end_slot = end_slot.min(
-                slot_range
-                    .end_slot
-                    .unwrap_or_else(|| Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1)),
-            );
-        }
-        end_slot = end_slot.max(start_slot);
-
+    if let Some((start_slot, end_slot)) = slot_bounds {
         let read_lock = grpc.storage.read_blocks();
-        let b_ids: PreHashSet<BlockId> =
+        let mut b_ids: PreHashSet<BlockId> =
             read_lock.aggregate_blocks_by_slot_range(start_slot..end_slot);
+        if let Some(block_archive_controller) = block_archive_controller {
+            for block_id in block_archive_controller
+                .get_block_ids_by_slot_range(start_slot, end_slot, archive_search_limit)
+                .map_err(|err| GrpcError::InternalServerError(err.to_string()))?
+            {
+                if b_ids.insert(block_id) {
+                    archived_block_ids.insert(block_id);
+                }
+            }
+        }
 
         if let Some(block_ids) = res.as_mut() {
             block_ids.retain(|id: &BlockId| b_ids.contains(id));
@@ -910,7 +1037,11 @@ pub(crate) fn search_blocks(
         .zip(blocks_status)
         .map(|(block_id, block_graph_status)| grpc_model::BlockInfo {
             block_id: block_id.to_string(),
-            status: block_graph_status.into(),
+            status: if archived_block_ids.contains(block_id) {
+                BlockGraphStatus::Final.into()
+            } else {
+                block_graph_status.into()
+            },
         })
         .collect();
 
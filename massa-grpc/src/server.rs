@@ -1,5 +1,6 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use massa_api_access::{AccessController, GrpcAccessService, API_KEY_HEADER};
 use massa_bootstrap::white_black_list::SharedWhiteBlackList;
 use massa_models::node::NodeId;
 use massa_versioning::keypair_factory::KeyPairFactory;
@@ -122,11 +123,14 @@ pub struct MassaPublicGrpc {
     pub version: massa_models::version::Version,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// authentication and rate limiting of the public APIs
+    pub access_controller: AccessController,
 }
 
 impl MassaPublicGrpc {
     /// Start the gRPC PUBLIC API
     pub async fn serve(self, config: &GrpcConfig) -> Result<StopHandle, GrpcError> {
+        let access_controller = self.access_controller.clone();
         let mut service = PublicServiceServer::new(self)
             .max_decoding_message_size(config.max_decoding_message_size)
             .max_encoding_message_size(config.max_encoding_message_size);
@@ -143,7 +147,7 @@ impl MassaPublicGrpc {
             };
         }
 
-        serve(service, config).await
+        serve(GrpcAccessService::new(service, access_controller), config).await
     }
 }
 
@@ -268,7 +272,11 @@ where
                 .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
                 // Allow requests from any origin
                 .allow_origin(Any)
-                .allow_headers([hyper::header::CONTENT_TYPE]);
+                .allow_headers([
+                    hyper::header::CONTENT_TYPE,
+                    hyper::header::AUTHORIZATION,
+                    hyper::header::HeaderName::from_static(API_KEY_HEADER),
+                ]);
 
             let router_with_http1 = server_builder
                 .accept_http1(true)
@@ -3,7 +3,7 @@
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
 use futures_util::StreamExt;
-use massa_models::operation::{OperationDeserializer, OperationType, SecureShareOperation};
+use massa_models::operation::{OperationDeserializer, SecureShareOperation};
 use massa_models::secure_share::SecureShareDeserializer;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_proto_rs::massa::api::v1 as grpc_api;
@@ -104,14 +104,9 @@ pub(crate) async fn send_operations(
                                     let verified_op_res = match operation_deserializer.deserialize::<DeserializeError>(&proto_operation) {
                                         Ok(tuple) => {
                                             let (rest, res_operation): (&[u8], SecureShareOperation) = tuple;
-                                            match res_operation.content.op {
-                                                OperationType::CallSC { max_gas, .. } | OperationType::ExecuteSC { max_gas, .. } => {
-                                                    if max_gas > config.max_gas_per_block {
-                                                        return Err(GrpcError::InvalidArgument("Gas limit of the operation is higher than the block gas limit. Your operation will never be included in a block.".into()));
-                                                    }
-                                                },
-                                                _ => {}
-                                            };
+                                            if res_operation.content.get_gas_usage() > config.max_gas_per_block {
+                                                return Err(GrpcError::InvalidArgument("Gas limit of the operation is higher than the block gas limit. Your operation will never be included in a block.".into()));
+                                            }
                                             if let Some(slot) = last_slot {
                                                 if res_operation.content.expire_period < slot.period {
                                                     return Err(GrpcError::InvalidArgument("Operation expire_period is lower than the current period of this node. Your operation will never be included in a block.".into()));
@@ -119,6 +114,7 @@ pub(crate) async fn send_operations(
                                             }
                                             if rest.is_empty() {
                                                 res_operation.verify_signature()
+                                                    .and_then(|_| res_operation.verify_multisig_signatures())
                                                     .map(|_| (res_operation.id.to_string(), res_operation))
                                                     .map_err(|e| e.into())
                                             } else {
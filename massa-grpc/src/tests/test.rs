@@ -2,13 +2,15 @@
 
 use crate::config::{GrpcConfig, ServiceName};
 use crate::server::MassaPublicGrpc;
+use massa_api_access::{AccessConfig, AccessController};
 use massa_channel::MassaChannel;
 use massa_consensus_exports::test_exports::MockConsensusControllerImpl;
 use massa_consensus_exports::ConsensusChannels;
 use massa_execution_exports::{test_exports::MockExecutionController, ExecutionChannels};
+use massa_metrics::MassaMetrics;
 use massa_models::{
     config::{
-        ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, MAX_DATASTORE_VALUE_LENGTH,
+        CompactConfig, ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, MAX_DATASTORE_VALUE_LENGTH,
         MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_FUNCTION_NAME_LENGTH,
         MAX_OPERATIONS_PER_BLOCK, MAX_OPERATIONS_PER_MESSAGE, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
         MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
@@ -52,6 +54,7 @@ async fn test_start_grpc_server() {
         block_sender: tokio::sync::broadcast::channel(100).0,
         block_header_sender: tokio::sync::broadcast::channel(100).0,
         filled_block_sender: tokio::sync::broadcast::channel(100).0,
+        block_archive_controller: None,
     };
 
     let endorsement_sender = tokio::sync::broadcast::channel(2000).0;
@@ -102,6 +105,7 @@ async fn test_start_grpc_server() {
         genesis_timestamp: *GENESIS_TIMESTAMP,
         t0: T0,
         periods_per_cycle: PERIODS_PER_CYCLE,
+        compact_config: CompactConfig::default(),
         keypair: keypair.clone(),
         max_channel_size: 128,
         draw_lookahead_period_count: 10,
@@ -152,6 +156,17 @@ async fn test_start_grpc_server() {
         keypair_factory: KeyPairFactory {
             mip_store: mip_store.clone(),
         },
+        access_controller: AccessController::new(
+            AccessConfig::default(),
+            None,
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                THREAD_COUNT,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
+        ),
     };
 
     let stop_handle = service.serve(&grpc_config).await.unwrap();
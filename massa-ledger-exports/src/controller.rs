@@ -1,9 +1,9 @@
-use massa_models::{address::Address, amount::Amount, bytecode::Bytecode};
+use massa_models::{address::Address, amount::Amount, bytecode::Bytecode, slot::Slot};
 use std::collections::BTreeSet;
 use std::fmt::Debug;
 
 use crate::{LedgerChanges, LedgerError};
-use massa_db_exports::DBBatch;
+use massa_db_exports::{DBBatch, MassaDBError};
 
 pub trait LedgerController: Send + Sync + Debug {
     /// Loads ledger from file
@@ -37,6 +37,37 @@ pub trait LedgerController: Send + Sync + Debug {
     /// A copy of the datastore value, or `None` if the ledger entry or datastore entry was not found
     fn get_data_entry(&self, addr: &Address, key: &[u8]) -> Option<Vec<u8>>;
 
+    /// Gets the balance of a ledger entry as it was at the end of a past final slot.
+    /// Fails if the archive of the state does not cover that slot.
+    ///
+    /// # Returns
+    /// The balance, or None if the ledger entry did not exist at that slot
+    fn get_balance_at_slot(&self, addr: &Address, slot: Slot)
+        -> Result<Option<Amount>, MassaDBError>;
+
+    /// Gets a copy of the bytecode of a ledger entry as it was at the end of a past final slot.
+    /// Fails if the archive of the state does not cover that slot.
+    ///
+    /// # Returns
+    /// A copy of the bytecode, or None if the ledger entry did not exist at that slot
+    fn get_bytecode_at_slot(
+        &self,
+        addr: &Address,
+        slot: Slot,
+    ) -> Result<Option<Bytecode>, MassaDBError>;
+
+    /// Gets a copy of the value of a datastore entry as it was at the end of a past final slot.
+    /// Fails if the archive of the state does not cover that slot.
+    ///
+    /// # Returns
+    /// A copy of the datastore value, or `None` if the datastore entry did not exist at that slot
+    fn get_data_entry_at_slot(
+        &self,
+        addr: &Address,
+        key: &[u8],
+        slot: Slot,
+    ) -> Result<Option<Vec<u8>>, MassaDBError>;
+
     /// Get every key of the datastore for a given address.
     ///
     /// # Returns
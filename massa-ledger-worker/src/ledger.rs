@@ -3,7 +3,7 @@
 //! This file defines the final ledger associating addresses to their balances, bytecode and data.
 
 use crate::ledger_db::{LedgerDB, LedgerSubEntry};
-use massa_db_exports::{DBBatch, ShareableMassaDBController};
+use massa_db_exports::{DBBatch, MassaDBError, ShareableMassaDBController};
 use massa_ledger_exports::{
     LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerError,
 };
@@ -11,6 +11,7 @@ use massa_models::{
     address::Address,
     amount::{Amount, AmountDeserializer},
     bytecode::{Bytecode, BytecodeDeserializer},
+    slot::Slot,
 };
 use massa_serialization::{DeserializeError, Deserializer};
 use std::collections::{BTreeSet, HashMap};
@@ -134,6 +135,64 @@ impl LedgerController for FinalLedger {
             .get_sub_entry(addr, LedgerSubEntry::Datastore(key.to_owned()))
     }
 
+    /// Gets the balance of a ledger entry as it was at the end of a past final slot
+    ///
+    /// # Returns
+    /// The balance, or None if the ledger entry did not exist at that slot
+    fn get_balance_at_slot(
+        &self,
+        addr: &Address,
+        slot: Slot,
+    ) -> Result<Option<Amount>, MassaDBError> {
+        let amount_deserializer =
+            AmountDeserializer::new(Included(Amount::MIN), Included(Amount::MAX));
+        Ok(self
+            .sorted_ledger
+            .get_sub_entry_at_slot(addr, LedgerSubEntry::Balance, slot)?
+            .map(|bytes| {
+                amount_deserializer
+                    .deserialize::<DeserializeError>(&bytes)
+                    .expect("critical: invalid balance format")
+                    .1
+            }))
+    }
+
+    /// Gets a copy of the bytecode of a ledger entry as it was at the end of a past final slot
+    ///
+    /// # Returns
+    /// A copy of the bytecode, or None if the ledger entry did not exist at that slot
+    fn get_bytecode_at_slot(
+        &self,
+        addr: &Address,
+        slot: Slot,
+    ) -> Result<Option<Bytecode>, MassaDBError> {
+        let bytecode_deserializer =
+            BytecodeDeserializer::new(self.config.max_datastore_value_length);
+        Ok(self
+            .sorted_ledger
+            .get_sub_entry_at_slot(addr, LedgerSubEntry::Bytecode, slot)?
+            .map(|bytes| {
+                bytecode_deserializer
+                    .deserialize::<DeserializeError>(&bytes)
+                    .expect("critical: invalid bytecode format")
+                    .1
+            }))
+    }
+
+    /// Gets a copy of the value of a datastore entry as it was at the end of a past final slot
+    ///
+    /// # Returns
+    /// A copy of the datastore value, or `None` if the datastore entry did not exist at that slot
+    fn get_data_entry_at_slot(
+        &self,
+        addr: &Address,
+        key: &[u8],
+        slot: Slot,
+    ) -> Result<Option<Vec<u8>>, MassaDBError> {
+        self.sorted_ledger
+            .get_sub_entry_at_slot(addr, LedgerSubEntry::Datastore(key.to_owned()), slot)
+    }
+
     /// Get every key of the datastore for a given address.
     ///
     /// # Returns
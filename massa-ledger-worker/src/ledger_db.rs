@@ -3,8 +3,8 @@
 //! Module to interact with the disk ledger
 
 use massa_db_exports::{
-    DBBatch, MassaDirection, MassaIteratorMode, ShareableMassaDBController, CRUD_ERROR,
-    KEY_SER_ERROR, LEDGER_PREFIX, STATE_CF,
+    DBBatch, MassaDBError, MassaDirection, MassaIteratorMode, ShareableMassaDBController,
+    CRUD_ERROR, KEY_SER_ERROR, LEDGER_PREFIX, STATE_CF,
 };
 use massa_ledger_exports::*;
 use massa_models::amount::AmountDeserializer;
@@ -167,6 +167,30 @@ impl LedgerDB {
         db.get_cf(STATE_CF, serialized_key).expect(CRUD_ERROR)
     }
 
+    /// Get the given sub-entry of a given address as it was at the end of a past final slot.
+    ///
+    /// # Arguments
+    /// * `addr`: associated address
+    /// * `ty`: type of the queried sub-entry
+    /// * `slot`: final slot at which the sub-entry is read
+    ///
+    /// # Returns
+    /// An Option of the sub-entry value as bytes, or an error if the archive does not cover the slot
+    pub fn get_sub_entry_at_slot(
+        &self,
+        addr: &Address,
+        ty: LedgerSubEntry,
+        slot: Slot,
+    ) -> Result<Option<Vec<u8>>, MassaDBError> {
+        let db = self.db.read();
+        let key = ty.derive_key(addr);
+        let mut serialized_key = Vec::new();
+        self.key_serializer_db
+            .serialize(&key, &mut serialized_key)
+            .expect(KEY_SER_ERROR);
+        db.get_state_value_at_slot(serialized_key, slot)
+    }
+
     /// Get every key of the datastore for a given address.
     ///
     /// # Returns
@@ -600,6 +624,8 @@ mod tests {
             max_history_length: 10,
             max_new_elements: 100,
             thread_count: 32,
+            enable_archive: false,
+            archive_retention_periods: 0,
         };
 
         let db = Arc::new(RwLock::new(
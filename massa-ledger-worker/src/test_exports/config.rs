@@ -20,6 +20,8 @@ impl Default for FinalLedger {
             max_history_length: 10,
             max_new_elements: 100,
             thread_count: THREAD_COUNT,
+            enable_archive: false,
+            archive_retention_periods: 0,
         };
         let db = MassaDB::new(db_config);
         let db = LedgerDB::new(
@@ -13,7 +13,7 @@ use std::{
 };
 
 use lazy_static::lazy_static;
-use prometheus::{register_int_gauge, Gauge, Histogram, IntCounter, IntGauge};
+use prometheus::{register_int_gauge, Gauge, Histogram, IntCounter, IntCounterVec, IntGauge, Opts};
 use tokio::sync::oneshot::Sender;
 use tracing::warn;
 
@@ -170,6 +170,15 @@ pub struct MassaMetrics {
     final_cursor_thread: IntGauge,
     final_cursor_period: IntGauge,
 
+    /// requests allowed by the access control of the public APIs, by API
+    api_requests_allowed: IntCounterVec,
+    /// weight consumed by the requests allowed by the access control of the public APIs, by API
+    api_requests_weight: IntCounterVec,
+    /// requests rejected for lack of a valid API key, by API
+    api_requests_unauthorized: IntCounterVec,
+    /// requests rejected because their client exceeded its quota, by API
+    api_requests_rate_limited: IntCounterVec,
+
     // peer bandwidth (bytes sent, bytes received)
     peers_bandwidth: Arc<RwLock<HashMap<String, (IntCounter, IntCounter)>>>,
 
@@ -407,6 +416,40 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        // public APIs access control
+        let api_requests_allowed = IntCounterVec::new(
+            Opts::new(
+                "api_requests_allowed",
+                "number of requests allowed by the API access control",
+            ),
+            &["api"],
+        )
+        .unwrap();
+        let api_requests_weight = IntCounterVec::new(
+            Opts::new(
+                "api_requests_weight",
+                "weight consumed by the requests allowed by the API access control",
+            ),
+            &["api"],
+        )
+        .unwrap();
+        let api_requests_unauthorized = IntCounterVec::new(
+            Opts::new(
+                "api_requests_unauthorized",
+                "number of API requests rejected for lack of a valid API key",
+            ),
+            &["api"],
+        )
+        .unwrap();
+        let api_requests_rate_limited = IntCounterVec::new(
+            Opts::new(
+                "api_requests_rate_limited",
+                "number of API requests rejected because their client exceeded its quota",
+            ),
+            &["api"],
+        )
+        .unwrap();
+
         let mut stopper = MetricsStopper::default();
 
         if enabled {
@@ -459,6 +502,10 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(current_time_period.clone()));
                 let _ = prometheus::register(Box::new(current_time_thread.clone()));
                 let _ = prometheus::register(Box::new(block_slot_delay.clone()));
+                let _ = prometheus::register(Box::new(api_requests_allowed.clone()));
+                let _ = prometheus::register(Box::new(api_requests_weight.clone()));
+                let _ = prometheus::register(Box::new(api_requests_unauthorized.clone()));
+                let _ = prometheus::register(Box::new(api_requests_rate_limited.clone()));
 
                 stopper = server::bind_metrics(addr);
             }
@@ -513,6 +560,10 @@ impl MassaMetrics {
                 active_cursor_period,
                 final_cursor_thread,
                 final_cursor_period,
+                api_requests_allowed,
+                api_requests_weight,
+                api_requests_unauthorized,
+                api_requests_rate_limited,
                 peers_bandwidth: Arc::new(RwLock::new(HashMap::new())),
                 tick_delay,
             },
@@ -703,6 +754,25 @@ impl MassaMetrics {
         self.block_slot_delay.observe(delay);
     }
 
+    pub fn inc_api_requests_allowed(&self, api: &str, weight: u64) {
+        self.api_requests_allowed.with_label_values(&[api]).inc();
+        self.api_requests_weight
+            .with_label_values(&[api])
+            .inc_by(weight);
+    }
+
+    pub fn inc_api_requests_unauthorized(&self, api: &str) {
+        self.api_requests_unauthorized
+            .with_label_values(&[api])
+            .inc();
+    }
+
+    pub fn inc_api_requests_rate_limited(&self, api: &str) {
+        self.api_requests_rate_limited
+            .with_label_values(&[api])
+            .inc();
+    }
+
     /// Update the bandwidth metrics for all peers
     /// HashMap<peer_id, (tx, rx)>
     pub fn update_peers_tx_rx(&self, data: HashMap<String, (u64, u64)>) {
@@ -15,6 +15,7 @@ use crate::{
     // slot::{Slot, SlotDeserializer, SlotSerializer},
 };
 // use massa_hash::{Hash, HashDeserializer};
+use massa_hash::Hash;
 use massa_serialization::{
     // DeserializeError,
     Deserializer,
@@ -65,11 +66,11 @@ pub struct FilledBlock {
 /// Block with assosciated meta-data and interfaces allowing trust of data in untrusted network
 pub type SecureShareBlock = SecureShare<Block, BlockId>;
 
-impl SecureShareContent for Block {
-    fn new_verifiable<SC: Serializer<Self>, U: Id>(
+impl Block {
+    /// Packages the block, whose signature and id are the ones of its header
+    fn into_secure_share<SC: Serializer<Self>, U: Id>(
         self,
         content_serializer: SC,
-        _keypair: &KeyPair,
     ) -> Result<SecureShare<Self, U>, ModelsError> {
         let mut content_serialized = Vec::new();
         content_serializer.serialize(&self, &mut content_serialized)?;
@@ -82,6 +83,31 @@ impl SecureShareContent for Block {
             serialized_data: content_serialized,
         })
     }
+}
+
+impl SecureShareContent for Block {
+    fn new_verifiable<SC: Serializer<Self>, U: Id>(
+        self,
+        content_serializer: SC,
+        _keypair: &KeyPair,
+    ) -> Result<SecureShare<Self, U>, ModelsError> {
+        self.into_secure_share(content_serializer)
+    }
+
+    /// The block is signed through its header: `sign` is not called
+    fn new_verifiable_with_signer<SC, U, F>(
+        self,
+        content_serializer: SC,
+        _public_key: &PublicKey,
+        _sign: F,
+    ) -> Result<SecureShare<Self, U>, ModelsError>
+    where
+        SC: Serializer<Self>,
+        U: Id,
+        F: FnOnce(&Hash) -> Result<Signature, ModelsError>,
+    {
+        self.into_secure_share(content_serializer)
+    }
 
     fn serialize(
         _signature: &Signature,
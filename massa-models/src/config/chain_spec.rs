@@ -0,0 +1,366 @@
+//! Chain specification: the network parameters and genesis data of a chain
+//!
+//! The chain spec is loaded once at startup by the node and injected into the
+//! configuration of every worker. When no chain spec file is given, the
+//! hard-coded values of `constants.rs` are used, so that the node joins the
+//! public network it was built for.
+//!
+//! A chain spec file can be written in any format supported by the settings
+//! (`toml`, `json`, ...). Missing fields take their hard-coded value, so a
+//! private devnet only has to set what differs, for example:
+//!
+//! ```toml
+//! thread_count = 2
+//! t0 = 1000
+//! periods_per_cycle = 16
+//! genesis_timestamp = 1700000000000
+//! initial_ledger_path = "initial_ledger.json"
+//! initial_rolls_path = "initial_rolls.json"
+//! ```
+//!
+//! Relative genesis file paths are resolved from the directory of the chain spec file.
+//!
+//! The hash of the chain spec (see [`ChainSpec::compute_hash`]) identifies the chain:
+//! nodes running different chain specs refuse to connect to each other.
+
+use super::constants::*;
+use crate::amount::Amount;
+use crate::error::ModelsError;
+use massa_hash::Hash;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use num::rational::Ratio;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Network parameters and genesis data of a chain
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainSpec {
+    /// Time in milliseconds when the blockclique started
+    pub genesis_timestamp: MassaTime,
+    /// Time when the blockclique is ended, if any
+    pub end_timestamp: Option<MassaTime>,
+    /// `KeyPair` to sign genesis blocks
+    pub genesis_key: KeyPair,
+    /// Proof of stake seed for the initial draw
+    pub initial_draw_seed: String,
+    /// Number of threads
+    pub thread_count: u8,
+    /// Time between the periods in the same thread
+    pub t0: MassaTime,
+    /// Cycle duration in periods
+    pub periods_per_cycle: u64,
+    /// Number of endorsements per block
+    pub endorsement_count: u32,
+    /// Threshold for fitness, usually `64 * (endorsement_count + 1)`
+    pub delta_f0: u64,
+    /// Maximum operation validity period count
+    pub operation_validity_periods: u64,
+    /// Maximum number of operations per block
+    pub max_operations_per_block: u32,
+    /// Maximum block size in bytes
+    pub max_block_size: u32,
+    /// Maximum of GAS allowed for a block
+    pub max_gas_per_block: u64,
+    /// Maximum of GAS allowed for asynchronous messages execution on one slot
+    pub max_async_gas: u64,
+    /// Maximum capacity of the asynchronous messages pool
+    pub max_async_pool_length: u64,
+    /// Price of a roll
+    pub roll_price: Amount,
+    /// Reward given for each block creation
+    pub block_reward: Amount,
+    /// Cost to store one byte in the ledger
+    pub ledger_cost_per_byte: Amount,
+    /// Cost of a base ledger entry
+    pub ledger_entry_base_cost: Amount,
+    /// Cycle miss rate (strictly) above which stakers are deactivated
+    pub pos_miss_rate_deactivation_threshold: Ratio<u64>,
+    /// Number of periods after which a denunciation expires
+    pub denunciation_expire_periods: u64,
+    /// Maximum number of denunciations that can be included in a block header
+    pub max_denunciations_per_block_header: u32,
+    /// Number of periods of executed operation and denunciation history to keep
+    pub keep_executed_history_extra_periods: u64,
+    /// Genesis ledger file. The node settings are used if not set
+    pub initial_ledger_path: Option<PathBuf>,
+    /// Initial rolls file. The node settings are used if not set
+    pub initial_rolls_path: Option<PathBuf>,
+    /// Initial deferred credits file. The node settings are used if not set
+    pub initial_deferred_credits_path: Option<PathBuf>,
+    /// MIP list of the chain. The MIP list built in the node is used if not set
+    pub mip_list: Option<Vec<ChainSpecMip>>,
+}
+
+/// MIP of a chain spec, see `MipInfo` in `massa_versioning`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainSpecMip {
+    /// MIP name
+    pub name: String,
+    /// Network version
+    pub version: u32,
+    /// Versioned components (by name) and their version
+    pub components: BTreeMap<String, u32>,
+    /// Timestamp at which the MIP gains its meaning
+    pub start: MassaTime,
+    /// Timestamp at which the deployment is considered failed
+    pub timeout: MassaTime,
+    /// Delay between the lock of the deployment and its activation
+    pub activation_delay: MassaTime,
+}
+
+impl Default for ChainSpec {
+    fn default() -> Self {
+        Self {
+            genesis_timestamp: *GENESIS_TIMESTAMP,
+            end_timestamp: *END_TIMESTAMP,
+            genesis_key: GENESIS_KEY.clone(),
+            initial_draw_seed: INITIAL_DRAW_SEED.to_string(),
+            thread_count: THREAD_COUNT,
+            t0: T0,
+            periods_per_cycle: PERIODS_PER_CYCLE,
+            endorsement_count: ENDORSEMENT_COUNT,
+            delta_f0: DELTA_F0,
+            operation_validity_periods: OPERATION_VALIDITY_PERIODS,
+            max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+            max_block_size: MAX_BLOCK_SIZE,
+            max_gas_per_block: MAX_GAS_PER_BLOCK,
+            max_async_gas: MAX_ASYNC_GAS,
+            max_async_pool_length: MAX_ASYNC_POOL_LENGTH,
+            roll_price: ROLL_PRICE,
+            block_reward: BLOCK_REWARD,
+            ledger_cost_per_byte: LEDGER_COST_PER_BYTE,
+            ledger_entry_base_cost: LEDGER_ENTRY_BASE_COST,
+            pos_miss_rate_deactivation_threshold: *POS_MISS_RATE_DEACTIVATION_THRESHOLD,
+            denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+            max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+            keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+            initial_ledger_path: None,
+            initial_rolls_path: None,
+            initial_deferred_credits_path: None,
+            mip_list: None,
+        }
+    }
+}
+
+impl ChainSpec {
+    /// Load a chain spec file, the missing fields taking their hard-coded value
+    pub fn from_file(path: &Path) -> Result<Self, ModelsError> {
+        let mut chain_spec: ChainSpec = config::Config::builder()
+            .add_source(config::File::from(path))
+            .build()
+            .and_then(|config| config.try_deserialize())
+            .map_err(|err| {
+                ModelsError::ChainSpecError(format!("could not load {}: {}", path.display(), err))
+            })?;
+
+        // genesis files are relative to the chain spec file
+        if let Some(dir) = path.parent() {
+            for genesis_path in [
+                &mut chain_spec.initial_ledger_path,
+                &mut chain_spec.initial_rolls_path,
+                &mut chain_spec.initial_deferred_credits_path,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                if genesis_path.is_relative() {
+                    *genesis_path = dir.join(&*genesis_path);
+                }
+            }
+        }
+
+        chain_spec.check()?;
+        Ok(chain_spec)
+    }
+
+    /// Check the consistency of the network parameters
+    pub fn check(&self) -> Result<(), ModelsError> {
+        if self.thread_count < 2 {
+            return Err(ModelsError::ChainSpecError(
+                "thread_count must be at least 2".to_string(),
+            ));
+        }
+        if self.t0.to_millis() == 0 {
+            return Err(ModelsError::ChainSpecError(
+                "t0 must be at least 1 ms".to_string(),
+            ));
+        }
+        if self.t0.to_millis() % (self.thread_count as u64) != 0 {
+            return Err(ModelsError::ChainSpecError(
+                "t0 (in ms) must be a multiple of thread_count".to_string(),
+            ));
+        }
+        if self.periods_per_cycle == 0 {
+            return Err(ModelsError::ChainSpecError(
+                "periods_per_cycle must be positive".to_string(),
+            ));
+        }
+        if self.endorsement_count == 0 {
+            return Err(ModelsError::ChainSpecError(
+                "endorsement_count must be positive".to_string(),
+            ));
+        }
+        if let Some(end) = self.end_timestamp {
+            if end <= self.genesis_timestamp {
+                return Err(ModelsError::ChainSpecError(
+                    "end_timestamp must be after genesis_timestamp".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compact representation of the consensus parameters, as shown by the APIs
+    pub fn compact_config(&self) -> super::CompactConfig {
+        super::CompactConfig {
+            genesis_timestamp: self.genesis_timestamp,
+            end_timestamp: self.end_timestamp,
+            thread_count: self.thread_count,
+            t0: self.t0,
+            delta_f0: self.delta_f0,
+            operation_validity_periods: self.operation_validity_periods,
+            periods_per_cycle: self.periods_per_cycle,
+            block_reward: self.block_reward,
+            roll_price: self.roll_price,
+            max_block_size: self.max_block_size,
+        }
+    }
+
+    /// Hash identifying the chain: every network parameter and the content of the genesis files.
+    ///
+    /// The hash does not depend on the format of the chain spec file.
+    pub fn compute_hash(&self) -> Result<Hash, ModelsError> {
+        let mut bytes: Vec<u8> = Vec::new();
+        let push_u64 =
+            |bytes: &mut Vec<u8>, value: u64| bytes.extend_from_slice(&value.to_be_bytes());
+        let push_data = |bytes: &mut Vec<u8>, data: &[u8]| {
+            bytes.extend_from_slice(&(data.len() as u64).to_be_bytes());
+            bytes.extend_from_slice(data);
+        };
+
+        push_u64(&mut bytes, self.genesis_timestamp.to_millis());
+        match self.end_timestamp {
+            Some(end) => push_data(&mut bytes, &end.to_millis().to_be_bytes()),
+            None => push_data(&mut bytes, &[]),
+        }
+        push_data(&mut bytes, &self.genesis_key.get_public_key().to_bytes());
+        push_data(&mut bytes, self.initial_draw_seed.as_bytes());
+        push_u64(&mut bytes, self.thread_count as u64);
+        push_u64(&mut bytes, self.t0.to_millis());
+        push_u64(&mut bytes, self.periods_per_cycle);
+        push_u64(&mut bytes, self.endorsement_count as u64);
+        push_u64(&mut bytes, self.delta_f0);
+        push_u64(&mut bytes, self.operation_validity_periods);
+        push_u64(&mut bytes, self.max_operations_per_block as u64);
+        push_u64(&mut bytes, self.max_block_size as u64);
+        push_u64(&mut bytes, self.max_gas_per_block);
+        push_u64(&mut bytes, self.max_async_gas);
+        push_u64(&mut bytes, self.max_async_pool_length);
+        push_u64(&mut bytes, self.roll_price.to_raw());
+        push_u64(&mut bytes, self.block_reward.to_raw());
+        push_u64(&mut bytes, self.ledger_cost_per_byte.to_raw());
+        push_u64(&mut bytes, self.ledger_entry_base_cost.to_raw());
+        push_u64(
+            &mut bytes,
+            *self.pos_miss_rate_deactivation_threshold.numer(),
+        );
+        push_u64(
+            &mut bytes,
+            *self.pos_miss_rate_deactivation_threshold.denom(),
+        );
+        push_u64(&mut bytes, self.denunciation_expire_periods);
+        push_u64(&mut bytes, self.max_denunciations_per_block_header as u64);
+        push_u64(&mut bytes, self.keep_executed_history_extra_periods);
+
+        for genesis_path in [
+            &self.initial_ledger_path,
+            &self.initial_rolls_path,
+            &self.initial_deferred_credits_path,
+        ] {
+            match genesis_path {
+                Some(path) => {
+                    let content = std::fs::read(path).map_err(|err| {
+                        ModelsError::ChainSpecError(format!(
+                            "could not read {}: {}",
+                            path.display(),
+                            err
+                        ))
+                    })?;
+                    push_data(&mut bytes, &content);
+                }
+                None => push_data(&mut bytes, &[]),
+            }
+        }
+
+        let mip_list = self.mip_list.as_deref().unwrap_or_default();
+        push_u64(&mut bytes, mip_list.len() as u64);
+        for mip in mip_list {
+            push_data(&mut bytes, mip.name.as_bytes());
+            push_u64(&mut bytes, mip.version as u64);
+            push_u64(&mut bytes, mip.components.len() as u64);
+            for (component, version) in &mip.components {
+                push_data(&mut bytes, component.as_bytes());
+                push_u64(&mut bytes, *version as u64);
+            }
+            push_u64(&mut bytes, mip.start.to_millis());
+            push_u64(&mut bytes, mip.timeout.to_millis());
+            push_u64(&mut bytes, mip.activation_delay.to_millis());
+        }
+
+        Ok(Hash::compute_from(&bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_chain_spec_is_valid() {
+        let chain_spec = ChainSpec::default();
+        chain_spec.check().unwrap();
+        assert_eq!(chain_spec.thread_count, THREAD_COUNT);
+        assert_eq!(chain_spec.t0, T0);
+    }
+
+    #[test]
+    fn test_chain_spec_from_file() {
+        let dir = std::env::temp_dir().join(format!("massa_chain_spec_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("chain_spec.toml");
+        std::fs::write(
+            &path,
+            "thread_count = 2\nt0 = 1000\nroll_price = \"1\"\ninitial_ledger_path = \"ledger.json\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("ledger.json"), "{}").unwrap();
+
+        let chain_spec = ChainSpec::from_file(&path).unwrap();
+        assert_eq!(chain_spec.thread_count, 2);
+        assert_eq!(chain_spec.t0, MassaTime::from_millis(1000));
+        assert_eq!(chain_spec.roll_price, Amount::const_init(1, 0));
+        // missing fields take their hard-coded value
+        assert_eq!(chain_spec.periods_per_cycle, PERIODS_PER_CYCLE);
+        assert_eq!(
+            chain_spec.initial_ledger_path,
+            Some(dir.join("ledger.json"))
+        );
+
+        // the hash identifies the chain
+        let hash = chain_spec.compute_hash().unwrap();
+        assert_eq!(hash, chain_spec.compute_hash().unwrap());
+        assert_ne!(hash, ChainSpec::default().compute_hash().unwrap());
+        std::fs::write(dir.join("ledger.json"), "{ }").unwrap();
+        assert_ne!(hash, chain_spec.compute_hash().unwrap());
+
+        // inconsistent parameters are rejected
+        std::fs::write(&path, "thread_count = 3\nt0 = 1000\n").unwrap();
+        assert!(ChainSpec::from_file(&path).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -76,9 +76,9 @@ lazy_static::lazy_static! {
     /// node version
     pub static ref VERSION: Version = {
         if cfg!(feature = "sandbox") {
-            "SAND.26.1"
+            "SAND.27.1"
         } else {
-            "TEST.26.1"
+            "TEST.27.1"
         }
         .parse()
         .unwrap()
@@ -33,6 +33,15 @@ use std::path::Path;
 /// 3. in path specified in `MASSA_CONFIG_OVERRIDE_PATH` environment variable (`config/config.toml` by default)
 #[inline]
 pub fn build_massa_settings<T: Deserialize<'static>>(app_name: &str, env_prefix: &str) -> T {
+    try_build_massa_settings(app_name, env_prefix).unwrap()
+}
+
+/// Same as `build_massa_settings`, returning an error instead of panicking
+/// if the configuration cannot be read, e.g. to reload it while the program runs.
+pub fn try_build_massa_settings<T: Deserialize<'static>>(
+    app_name: &str,
+    env_prefix: &str,
+) -> Result<T, config::ConfigError> {
     let mut builder = config::Config::builder();
     let config_path = std::env::var("MASSA_CONFIG_PATH")
         .unwrap_or_else(|_| "base_config/config.toml".to_string());
@@ -57,8 +66,7 @@ pub fn build_massa_settings<T: Deserialize<'static>>(app_name: &str, env_prefix:
 
     let s = builder
         .add_source(config::Environment::with_prefix(env_prefix))
-        .build()
-        .unwrap();
+        .build()?;
 
-    s.try_deserialize().unwrap()
+    s.try_deserialize()
 }
@@ -66,6 +66,9 @@ pub use constants::*;
 mod compact_config;
 pub use compact_config::CompactConfig;
 
+mod chain_spec;
+pub use chain_spec::{ChainSpec, ChainSpecMip};
+
 // Export tool to read user setting file
 mod massa_settings;
-pub use massa_settings::build_massa_settings;
+pub use massa_settings::{build_massa_settings, try_build_massa_settings};
@@ -226,6 +226,10 @@ impl From<OperationType> for grpc_model::OperationType {
                 grpc_operation_type.r#type =
                     Some(grpc_model::operation_type::Type::CallSc(call_sc));
             }
+            // multisig and sequenced operations are exposed as the operation they execute
+            OperationType::Multisig { op, .. } | OperationType::Sequenced { op, .. } => {
+                return (*op).into()
+            }
         }
 
         grpc_operation_type
@@ -250,6 +254,9 @@ impl From<OperationType> for grpc_model::OpType {
             OperationType::RollSell { .. } => grpc_model::OpType::RollSell,
             OperationType::ExecuteSC { .. } => grpc_model::OpType::ExecuteSc,
             OperationType::CallSC { .. } => grpc_model::OpType::CallSc,
+            OperationType::Multisig { op, .. } | OperationType::Sequenced { op, .. } => {
+                (*op).into()
+            }
         }
     }
 }
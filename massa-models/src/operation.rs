@@ -14,9 +14,14 @@ use crate::{
 };
 use massa_hash::{Hash, HashDeserializer};
 use massa_serialization::{
-    DeserializeError, Deserializer, SerializeError, Serializer, U16VarIntDeserializer,
-    U16VarIntSerializer, U32VarIntDeserializer, U32VarIntSerializer, U64VarIntDeserializer,
-    U64VarIntSerializer,
+    DeserializeError, Deserializer, OptionDeserializer, OptionSerializer, SerializeError,
+    Serializer, U16VarIntDeserializer, U16VarIntSerializer, U32VarIntDeserializer,
+    U32VarIntSerializer, U64VarIntDeserializer, U64VarIntSerializer,
+};
+use massa_signature::{
+    verify_signature_batch, KeyPair, MultiSigPublicKey, MultiSigPublicKeyDeserializer,
+    MultiSigPublicKeySerializer, MultiSignature, MultiSignatureDeserializer,
+    MultiSignatureSerializer, PublicKey, Signature,
 };
 use nom::error::{context, ErrorKind};
 use nom::multi::length_count;
@@ -40,6 +45,12 @@ use transition::Versioned;
 /// Size in bytes of the serialized operation ID prefix
 pub const OPERATION_ID_PREFIX_SIZE_BYTES: usize = 17;
 
+/// Domain separation tag of the hash signed by the co-signers of a multisig operation
+const MULTISIG_SIGNED_HASH_TAG: &[u8] = b"MASSA_MULTISIG_OPERATION";
+
+/// Domain separation tag of the ID recording that an operation was superseded
+const SUPERSESSION_ID_TAG: &[u8] = b"MASSA_SUPERSEDED_OPERATION";
+
 /// operation id
 #[allow(missing_docs)]
 #[transition::versioned(versions("0"))]
@@ -387,6 +398,8 @@ enum OperationTypeId {
     RollSell = 2,
     ExecuteSC = 3,
     CallSC = 4,
+    Multisig = 5,
+    Sequenced = 6,
 }
 
 /// the operation as sent in the network
@@ -411,6 +424,246 @@ impl std::fmt::Display for Operation {
     }
 }
 
+impl Operation {
+    /// get the range of periods during which an operation is valid
+    /// Range: `(op.expire_period - cfg.operation_validity_period) -> op.expire_period` (included)
+    pub fn get_validity_range(&self, operation_validity_period: u64) -> RangeInclusive<u64> {
+        let start = self.expire_period.saturating_sub(operation_validity_period);
+        start..=self.expire_period
+    }
+
+    /// Get the max amount of gas used by the operation (`max_gas`)
+    pub fn get_gas_usage(&self) -> u64 {
+        self.op.get_gas_usage()
+    }
+
+    /// get the addresses that are involved in this operation from a ledger point of view
+    ///
+    /// # Arguments
+    /// * `emitter_address`: address of the creator of the operation
+    pub fn get_ledger_involved_addresses(&self, emitter_address: Address) -> PreHashSet<Address> {
+        let mut res = PreHashSet::<Address>::default();
+        res.insert(emitter_address);
+        match &self.op {
+            OperationType::Transaction {
+                recipient_address, ..
+            } => {
+                res.insert(*recipient_address);
+            }
+            OperationType::RollBuy { .. } => {}
+            OperationType::RollSell { .. } => {}
+            OperationType::ExecuteSC { .. } => {}
+            OperationType::CallSC { target_addr, .. } => {
+                res.insert(*target_addr);
+            }
+            OperationType::Multisig {
+                multisig_public_key,
+                op,
+                ..
+            } => {
+                res.insert(Address::from_multisig_public_key(multisig_public_key));
+                match op.as_ref() {
+                    OperationType::Transaction {
+                        recipient_address, ..
+                    } => {
+                        res.insert(*recipient_address);
+                    }
+                    OperationType::CallSC { target_addr, .. } => {
+                        res.insert(*target_addr);
+                    }
+                    _ => {}
+                }
+            }
+            OperationType::Sequenced { op, .. } => match op.as_ref() {
+                OperationType::Transaction {
+                    recipient_address, ..
+                } => {
+                    res.insert(*recipient_address);
+                }
+                OperationType::CallSC { target_addr, .. } => {
+                    res.insert(*target_addr);
+                }
+                _ => {}
+            },
+        }
+        res
+    }
+
+    /// Gets the maximal amount of coins that may be spent by the operation creator (incl. fee).
+    /// For multisig operations, the creator only pays the fee:
+    /// the rest is spent from the multisig address (see `OperationType::get_max_non_fee_spending`).
+    pub fn get_max_spending(&self, roll_price: Amount) -> Amount {
+        // compute the max amount of coins spent outside of the fees
+        let max_non_fee_seq_spending = match &self.op {
+            OperationType::Multisig { .. } => Amount::zero(),
+            op => op.get_max_non_fee_spending(roll_price),
+        };
+
+        // add all fees and return
+        max_non_fee_seq_spending.saturating_add(self.fee)
+    }
+
+    /// Compute the hash that the co-signers of a multisig operation sign.
+    /// It commits to the operation creator and to the whole content except the co-signer signatures.
+    pub fn compute_multisig_signed_hash(
+        &self,
+        content_creator_pub_key: &PublicKey,
+    ) -> Result<Hash, ModelsError> {
+        let mut unsigned = self.clone();
+        if let OperationType::Multisig { signatures, .. } = &mut unsigned.op {
+            *signatures = MultiSignature::default();
+        }
+        let mut hash_data = MULTISIG_SIGNED_HASH_TAG.to_vec();
+        hash_data.extend(content_creator_pub_key.to_bytes());
+        OperationSerializer::new().serialize(&unsigned, &mut hash_data)?;
+        Ok(Hash::compute_from(&hash_data))
+    }
+
+    /// Get the (hash, signature, public key) triplets of the co-signer signatures of a multisig operation.
+    /// Returns an empty list for other operations.
+    pub fn get_multisig_signature_batch(
+        &self,
+        content_creator_pub_key: &PublicKey,
+    ) -> Result<Vec<(Hash, Signature, PublicKey)>, ModelsError> {
+        match &self.op {
+            OperationType::Multisig {
+                multisig_public_key,
+                signatures,
+                ..
+            } => Ok(multisig_public_key.get_signature_batch(
+                &self.compute_multisig_signed_hash(content_creator_pub_key)?,
+                signatures,
+            )?),
+            _ => Ok(Vec::new()),
+        }
+    }
+}
+
+impl OperationType {
+    /// Get the max amount of gas used by the operation type (`max_gas`)
+    pub fn get_gas_usage(&self) -> u64 {
+        match self {
+            OperationType::ExecuteSC { max_gas, .. } => *max_gas,
+            OperationType::CallSC { max_gas, .. } => *max_gas,
+            OperationType::RollBuy { .. } => 0,
+            OperationType::RollSell { .. } => 0,
+            OperationType::Transaction { .. } => 0,
+            OperationType::Multisig { op, .. } => op.get_gas_usage(),
+            OperationType::Sequenced { op, .. } => op.get_gas_usage(),
+        }
+    }
+
+    /// Gets the maximal amount of coins that may be spent by the sender of the operation type, excluding fees
+    pub fn get_max_non_fee_spending(&self, roll_price: Amount) -> Amount {
+        match self {
+            OperationType::Transaction { amount, .. } => *amount,
+            OperationType::RollBuy { roll_count } => roll_price.saturating_mul_u64(*roll_count),
+            OperationType::RollSell { .. } => Amount::zero(),
+            OperationType::ExecuteSC { max_coins, .. } => *max_coins,
+            OperationType::CallSC { coins, .. } => *coins,
+            OperationType::Multisig { op, .. } => op.get_max_non_fee_spending(roll_price),
+            OperationType::Sequenced { op, .. } => op.get_max_non_fee_spending(roll_price),
+        }
+    }
+
+    /// Get the operation superseded by a sequenced operation
+    pub fn get_superseded_op(&self) -> Option<SupersededOperation> {
+        match self {
+            OperationType::Sequenced { supersedes, .. } => *supersedes,
+            _ => None,
+        }
+    }
+
+    /// Get the ID of the operation superseded by a sequenced operation
+    pub fn get_superseded_op_id(&self) -> Option<OperationId> {
+        self.get_superseded_op().map(|superseded| superseded.id)
+    }
+
+    /// Get the ID of the operation that has to be executed before a sequenced operation
+    pub fn get_required_op_id(&self) -> Option<OperationId> {
+        match self {
+            OperationType::Sequenced { after, .. } => *after,
+            _ => None,
+        }
+    }
+}
+
+/// Whether an operation can be wrapped in a multisig operation.
+/// Multisig addresses cannot produce blocks, so they cannot buy or sell rolls,
+/// and wrapper operations cannot be nested.
+fn is_allowed_in_multisig(op: &OperationType) -> bool {
+    !matches!(
+        op,
+        OperationType::RollBuy { .. }
+            | OperationType::RollSell { .. }
+            | OperationType::Multisig { .. }
+            | OperationType::Sequenced { .. }
+    )
+}
+
+/// Multisig operation whose co-signer signatures are being collected.
+/// It is passed around between co-signers before being signed by its creator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartiallySignedOperation {
+    /// the operation, containing an `OperationType::Multisig`
+    pub content: Operation,
+    /// public key of the creator, that will sign the operation and pay its fee
+    pub content_creator_pub_key: PublicKey,
+}
+
+impl PartiallySignedOperation {
+    /// Creates a `PartiallySignedOperation` without any co-signer signature
+    pub fn new(
+        content: Operation,
+        content_creator_pub_key: PublicKey,
+    ) -> Result<Self, ModelsError> {
+        match &content.op {
+            OperationType::Multisig { op, .. } if !is_allowed_in_multisig(op) => {
+                return Err(ModelsError::CheckedOperationError(
+                    "multisig operations cannot wrap roll or multisig and sequenced operations"
+                        .to_string(),
+                ));
+            }
+            OperationType::Multisig { .. } => {}
+            _ => {
+                return Err(ModelsError::CheckedOperationError(
+                    "only multisig operations can be partially signed".to_string(),
+                ));
+            }
+        }
+        Ok(PartiallySignedOperation {
+            content,
+            content_creator_pub_key,
+        })
+    }
+
+    /// Add the signature of a co-signer
+    pub fn sign(&mut self, keypair: &KeyPair) -> Result<(), ModelsError> {
+        let hash = self
+            .content
+            .compute_multisig_signed_hash(&self.content_creator_pub_key)?;
+        match &mut self.content.op {
+            OperationType::Multisig {
+                multisig_public_key,
+                signatures,
+                ..
+            } => Ok(signatures.sign(multisig_public_key, keypair, &hash)?),
+            _ => Err(ModelsError::CheckedOperationError(
+                "only multisig operations can be partially signed".to_string(),
+            )),
+        }
+    }
+
+    /// Check that enough valid co-signer signatures were collected
+    pub fn verify_signatures(&self) -> Result<(), ModelsError> {
+        Ok(verify_signature_batch(
+            &self
+                .content
+                .get_multisig_signature_batch(&self.content_creator_pub_key)?,
+        )?)
+    }
+}
+
 /// signed operation
 pub type SecureShareOperation = SecureShare<Operation, OperationId>;
 
@@ -614,6 +867,129 @@ pub enum OperationType {
         /// Extra coins that are spent from the caller's balance and transferred to the target
         coins: Amount,
     },
+    /// Executes `op` on behalf of a multisig address, authorized by the signatures of its co-signers.
+    /// `op` cannot be a multisig operation itself.
+    Multisig {
+        /// public key of the multisig address
+        multisig_public_key: MultiSigPublicKey,
+        /// signatures of the co-signers (see `Operation::compute_multisig_signed_hash`)
+        signatures: MultiSignature,
+        /// operation executed with the multisig address as sender
+        op: Box<OperationType>,
+    },
+    /// Executes `op` with ordering constraints relative to other operations.
+    /// `op` cannot be a multisig or sequenced operation itself.
+    Sequenced {
+        /// Operation of the same sender replaced by this one. It is evicted from the pools
+        /// if this operation pays a higher fee, and cannot be executed once this one was,
+        /// unless it was created by another sender or expires at another period than declared.
+        supersedes: Option<SupersededOperation>,
+        /// Operation that has to be executed before this one can be included in a block
+        after: Option<OperationId>,
+        /// operation executed
+        op: Box<OperationType>,
+    },
+}
+
+/// Operation superseded by a sequenced operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SupersededOperation {
+    /// ID of the superseded operation
+    pub id: OperationId,
+    /// expiration period of the superseded operation
+    pub expire_period: u64,
+}
+
+impl SupersededOperation {
+    /// ID under which the execution records that `sender` superseded this operation.
+    /// It commits to the sender, so that only the operations of the sender of the replacement
+    /// are prevented from being executed. It is recorded until `expire_period`.
+    pub fn get_supersession_id(&self, sender: &Address) -> OperationId {
+        OperationId::new(Hash::compute_from_tuple(&[
+            SUPERSESSION_ID_TAG,
+            self.id.get_hash().to_bytes(),
+            &self.expire_period.to_be_bytes(),
+            &sender.to_prefixed_bytes(),
+        ]))
+    }
+}
+
+impl std::fmt::Display for SupersededOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (expires at period {})", self.id, self.expire_period)
+    }
+}
+
+/// Serializer for `SupersededOperation`
+#[derive(Default, Clone)]
+pub struct SupersededOperationSerializer {
+    op_id_serializer: OperationIdSerializer,
+    u64_serializer: U64VarIntSerializer,
+}
+
+impl SupersededOperationSerializer {
+    /// Creates a new `SupersededOperationSerializer`
+    pub fn new() -> Self {
+        Self {
+            op_id_serializer: OperationIdSerializer::new(),
+            u64_serializer: U64VarIntSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<SupersededOperation> for SupersededOperationSerializer {
+    fn serialize(
+        &self,
+        value: &SupersededOperation,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.op_id_serializer.serialize(&value.id, buffer)?;
+        self.u64_serializer.serialize(&value.expire_period, buffer)
+    }
+}
+
+/// Deserializer for `SupersededOperation`
+#[derive(Clone)]
+pub struct SupersededOperationDeserializer {
+    op_id_deserializer: OperationIdDeserializer,
+    expire_period_deserializer: U64VarIntDeserializer,
+}
+
+impl SupersededOperationDeserializer {
+    /// Creates a new `SupersededOperationDeserializer`
+    pub fn new() -> Self {
+        Self {
+            op_id_deserializer: OperationIdDeserializer::new(),
+            expire_period_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+        }
+    }
+}
+
+impl Default for SupersededOperationDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deserializer<SupersededOperation> for SupersededOperationDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], SupersededOperation, E> {
+        context(
+            "Failed SupersededOperation deserialization",
+            tuple((
+                context("Failed id deserialization", |input| {
+                    self.op_id_deserializer.deserialize(input)
+                }),
+                context("Failed expire_period deserialization", |input| {
+                    self.expire_period_deserializer.deserialize(input)
+                }),
+            )),
+        )
+        .map(|(id, expire_period)| SupersededOperation { id, expire_period })
+        .parse(buffer)
+    }
 }
 
 impl std::fmt::Display for OperationType {
@@ -659,6 +1035,39 @@ impl std::fmt::Display for OperationType {
                 writeln!(f, "\t- max_gas:{}", max_gas)?;
                 writeln!(f, "\t- coins:{}", coins)?;
             }
+            OperationType::Multisig {
+                multisig_public_key,
+                signatures,
+                op,
+            } => {
+                writeln!(f, "Multisig:")?;
+                writeln!(
+                    f,
+                    "\t- address:{}",
+                    Address::from_multisig_public_key(multisig_public_key)
+                )?;
+                writeln!(
+                    f,
+                    "\t- signatures:{}/{}",
+                    signatures.len(),
+                    multisig_public_key.get_threshold()
+                )?;
+                write!(f, "{}", op)?;
+            }
+            OperationType::Sequenced {
+                supersedes,
+                after,
+                op,
+            } => {
+                writeln!(f, "Sequenced:")?;
+                if let Some(supersedes) = supersedes {
+                    writeln!(f, "\t- supersedes:{}", supersedes)?;
+                }
+                if let Some(after) = after {
+                    writeln!(f, "\t- after:{}", after)?;
+                }
+                write!(f, "{}", op)?;
+            }
         }
         Ok(())
     }
@@ -673,6 +1082,11 @@ pub struct OperationTypeSerializer {
     address_serializer: AddressSerializer,
     function_name_serializer: StringSerializer<U16VarIntSerializer, u16>,
     datastore_serializer: DatastoreSerializer,
+    multisig_public_key_serializer: MultiSigPublicKeySerializer,
+    multi_signature_serializer: MultiSignatureSerializer,
+    opt_op_id_serializer: OptionSerializer<OperationId, OperationIdSerializer>,
+    opt_superseded_op_serializer:
+        OptionSerializer<SupersededOperation, SupersededOperationSerializer>,
 }
 
 impl OperationTypeSerializer {
@@ -686,6 +1100,12 @@ impl OperationTypeSerializer {
             address_serializer: AddressSerializer::new(),
             function_name_serializer: StringSerializer::new(U16VarIntSerializer::new()),
             datastore_serializer: DatastoreSerializer::new(),
+            multisig_public_key_serializer: MultiSigPublicKeySerializer::new(),
+            multi_signature_serializer: MultiSignatureSerializer::new(),
+            opt_op_id_serializer: OptionSerializer::new(OperationIdSerializer::new()),
+            opt_superseded_op_serializer: OptionSerializer::new(
+                SupersededOperationSerializer::new(),
+            ),
         }
     }
 }
@@ -766,6 +1186,31 @@ impl Serializer<OperationType> for OperationTypeSerializer {
                     .serialize(target_func, buffer)?;
                 self.vec_u8_serializer.serialize(param, buffer)?;
             }
+            OperationType::Multisig {
+                multisig_public_key,
+                signatures,
+                op,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(OperationTypeId::Multisig), buffer)?;
+                self.multisig_public_key_serializer
+                    .serialize(multisig_public_key, buffer)?;
+                self.multi_signature_serializer
+                    .serialize(signatures, buffer)?;
+                self.serialize(op.as_ref(), buffer)?;
+            }
+            OperationType::Sequenced {
+                supersedes,
+                after,
+                op,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(OperationTypeId::Sequenced), buffer)?;
+                self.opt_superseded_op_serializer
+                    .serialize(supersedes, buffer)?;
+                self.opt_op_id_serializer.serialize(after, buffer)?;
+                self.serialize(op.as_ref(), buffer)?;
+            }
         }
         Ok(())
     }
@@ -782,6 +1227,11 @@ pub struct OperationTypeDeserializer {
     function_name_deserializer: StringDeserializer<U16VarIntDeserializer, u16>,
     parameter_deserializer: VecU8Deserializer,
     datastore_deserializer: DatastoreDeserializer,
+    multisig_public_key_deserializer: MultiSigPublicKeyDeserializer,
+    multi_signature_deserializer: MultiSignatureDeserializer,
+    opt_op_id_deserializer: OptionDeserializer<OperationId, OperationIdDeserializer>,
+    opt_superseded_op_deserializer:
+        OptionDeserializer<SupersededOperation, SupersededOperationDeserializer>,
 }
 
 impl OperationTypeDeserializer {
@@ -820,48 +1270,21 @@ impl OperationTypeDeserializer {
                 max_op_datastore_key_length,
                 max_op_datastore_value_length,
             ),
+            multisig_public_key_deserializer: MultiSigPublicKeyDeserializer::new(),
+            multi_signature_deserializer: MultiSignatureDeserializer::new(),
+            opt_op_id_deserializer: OptionDeserializer::new(OperationIdDeserializer::new()),
+            opt_superseded_op_deserializer: OptionDeserializer::new(
+                SupersededOperationDeserializer::new(),
+            ),
         }
     }
-}
 
-impl Deserializer<OperationType> for OperationTypeDeserializer {
-    /// ## Example:
-    /// ```rust
-    /// use std::collections::BTreeMap;
-    /// use massa_models::{operation::{OperationTypeSerializer, OperationTypeDeserializer, OperationType}, address::Address, amount::Amount};
-    /// use massa_signature::KeyPair;
-    /// use massa_serialization::{Deserializer, Serializer, DeserializeError};
-    /// use std::str::FromStr;
-    ///
-    /// let keypair = KeyPair::generate(0).unwrap();
-    /// let op = OperationType::ExecuteSC {
-    ///    data: vec![0x01, 0x02, 0x03],
-    ///    max_gas: 100,
-    ///    max_coins: Amount::from_str("5000000").unwrap(),
-    ///    datastore: BTreeMap::from([(vec![1, 2], vec![254, 255])])
-    /// };
-    /// let mut buffer = Vec::new();
-    /// OperationTypeSerializer::new().serialize(&op, &mut buffer).unwrap();
-    /// let (rest, op_deserialized) = OperationTypeDeserializer::new(10000, 10000, 10000, 10, 255, 10_000).deserialize::<DeserializeError>(&buffer).unwrap();
-    /// assert_eq!(rest.len(), 0);
-    /// match op_deserialized {
-    ///    OperationType::ExecuteSC {
-    ///      data,
-    ///      max_gas,
-    ///      max_coins,
-    ///      datastore
-    ///   } => {
-    ///     assert_eq!(data, vec![0x01, 0x02, 0x03]);
-    ///     assert_eq!(max_gas, 100);
-    ///     assert_eq!(max_coins, Amount::from_str("5000000").unwrap());
-    ///     assert_eq!(datastore, BTreeMap::from([(vec![1, 2], vec![254, 255])]))
-    ///   }
-    ///   _ => panic!("Unexpected operation type"),
-    /// };
-    /// ```
-    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+    /// Deserializes an `OperationType`, refusing multisig and sequenced operations
+    /// when `allow_wrappers` is false so that they cannot be nested
+    fn deserialize_op_type<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
         &self,
         buffer: &'a [u8],
+        allow_wrappers: bool,
     ) -> IResult<&'a [u8], OperationType, E> {
         context("Failed OperationType deserialization", |buffer| {
             let (input, id) = self.id_deserializer.deserialize(buffer)?;
@@ -954,68 +1377,141 @@ impl Deserializer<OperationType> for OperationTypeDeserializer {
                     },
                 )
                 .parse(input),
+                OperationTypeId::Multisig => {
+                    if !allow_wrappers {
+                        return Err(nom::Err::Error(ParseError::from_error_kind(
+                            buffer,
+                            nom::error::ErrorKind::Verify,
+                        )));
+                    }
+                    context(
+                        "Failed Multisig deserialization",
+                        tuple((
+                            context("Failed multisig_public_key deserialization", |input| {
+                                self.multisig_public_key_deserializer.deserialize(input)
+                            }),
+                            context("Failed signatures deserialization", |input| {
+                                self.multi_signature_deserializer.deserialize(input)
+                            }),
+                            context("Failed op deserialization", |input| {
+                                let (rest, op) = self.deserialize_op_type(input, false)?;
+                                if !is_allowed_in_multisig(&op) {
+                                    return Err(nom::Err::Error(ParseError::from_error_kind(
+                                        input,
+                                        nom::error::ErrorKind::Verify,
+                                    )));
+                                }
+                                Ok((rest, op))
+                            }),
+                        )),
+                    )
+                    .map(
+                        |(multisig_public_key, signatures, op)| OperationType::Multisig {
+                            multisig_public_key,
+                            signatures,
+                            op: Box::new(op),
+                        },
+                    )
+                    .parse(input)
+                }
+                OperationTypeId::Sequenced => {
+                    if !allow_wrappers {
+                        return Err(nom::Err::Error(ParseError::from_error_kind(
+                            buffer,
+                            nom::error::ErrorKind::Verify,
+                        )));
+                    }
+                    context(
+                        "Failed Sequenced deserialization",
+                        tuple((
+                            context("Failed supersedes deserialization", |input| {
+                                self.opt_superseded_op_deserializer.deserialize(input)
+                            }),
+                            context("Failed after deserialization", |input| {
+                                self.opt_op_id_deserializer.deserialize(input)
+                            }),
+                            context("Failed op deserialization", |input| {
+                                self.deserialize_op_type(input, false)
+                            }),
+                        )),
+                    )
+                    .map(|(supersedes, after, op)| OperationType::Sequenced {
+                        supersedes,
+                        after,
+                        op: Box::new(op),
+                    })
+                    .parse(input)
+                }
             }
         })
         .parse(buffer)
     }
 }
 
+impl Deserializer<OperationType> for OperationTypeDeserializer {
+    /// ## Example:
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use massa_models::{operation::{OperationTypeSerializer, OperationTypeDeserializer, OperationType}, address::Address, amount::Amount};
+    /// use massa_signature::KeyPair;
+    /// use massa_serialization::{Deserializer, Serializer, DeserializeError};
+    /// use std::str::FromStr;
+    ///
+    /// let keypair = KeyPair::generate(0).unwrap();
+    /// let op = OperationType::ExecuteSC {
+    ///    data: vec![0x01, 0x02, 0x03],
+    ///    max_gas: 100,
+    ///    max_coins: Amount::from_str("5000000").unwrap(),
+    ///    datastore: BTreeMap::from([(vec![1, 2], vec![254, 255])])
+    /// };
+    /// let mut buffer = Vec::new();
+    /// OperationTypeSerializer::new().serialize(&op, &mut buffer).unwrap();
+    /// let (rest, op_deserialized) = OperationTypeDeserializer::new(10000, 10000, 10000, 10, 255, 10_000).deserialize::<DeserializeError>(&buffer).unwrap();
+    /// assert_eq!(rest.len(), 0);
+    /// match op_deserialized {
+    ///    OperationType::ExecuteSC {
+    ///      data,
+    ///      max_gas,
+    ///      max_coins,
+    ///      datastore
+    ///   } => {
+    ///     assert_eq!(data, vec![0x01, 0x02, 0x03]);
+    ///     assert_eq!(max_gas, 100);
+    ///     assert_eq!(max_coins, Amount::from_str("5000000").unwrap());
+    ///     assert_eq!(datastore, BTreeMap::from([(vec![1, 2], vec![254, 255])]))
+    ///   }
+    ///   _ => panic!("Unexpected operation type"),
+    /// };
+    /// ```
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], OperationType, E> {
+        self.deserialize_op_type(buffer, true)
+    }
+}
+
 impl SecureShareOperation {
     /// get the range of periods during which an operation is valid
     /// Range: `(op.expire_period - cfg.operation_validity_period) -> op.expire_period` (included)
     pub fn get_validity_range(&self, operation_validity_period: u64) -> RangeInclusive<u64> {
-        let start = self
-            .content
-            .expire_period
-            .saturating_sub(operation_validity_period);
-        start..=self.content.expire_period
+        self.content.get_validity_range(operation_validity_period)
     }
 
     /// Get the max amount of gas used by the operation (`max_gas`)
     pub fn get_gas_usage(&self) -> u64 {
-        match &self.content.op {
-            OperationType::ExecuteSC { max_gas, .. } => *max_gas,
-            OperationType::CallSC { max_gas, .. } => *max_gas,
-            OperationType::RollBuy { .. } => 0,
-            OperationType::RollSell { .. } => 0,
-            OperationType::Transaction { .. } => 0,
-        }
+        self.content.get_gas_usage()
     }
 
     /// get the addresses that are involved in this operation from a ledger point of view
     pub fn get_ledger_involved_addresses(&self) -> PreHashSet<Address> {
-        let mut res = PreHashSet::<Address>::default();
-        let emitter_address = Address::from_public_key(&self.content_creator_pub_key);
-        res.insert(emitter_address);
-        match &self.content.op {
-            OperationType::Transaction {
-                recipient_address, ..
-            } => {
-                res.insert(*recipient_address);
-            }
-            OperationType::RollBuy { .. } => {}
-            OperationType::RollSell { .. } => {}
-            OperationType::ExecuteSC { .. } => {}
-            OperationType::CallSC { target_addr, .. } => {
-                res.insert(*target_addr);
-            }
-        }
-        res
+        self.content
+            .get_ledger_involved_addresses(Address::from_public_key(&self.content_creator_pub_key))
     }
 
     /// Gets the maximal amount of coins that may be spent by this operation (incl. fee)
     pub fn get_max_spending(&self, roll_price: Amount) -> Amount {
-        // compute the max amount of coins spent outside of the fees
-        let max_non_fee_seq_spending = match &self.content.op {
-            OperationType::Transaction { amount, .. } => *amount,
-            OperationType::RollBuy { roll_count } => roll_price.saturating_mul_u64(*roll_count),
-            OperationType::RollSell { .. } => Amount::zero(),
-            OperationType::ExecuteSC { max_coins, .. } => *max_coins,
-            OperationType::CallSC { coins, .. } => *coins,
-        };
-
-        // add all fees and return
-        max_non_fee_seq_spending.saturating_add(self.content.fee)
+        self.content.get_max_spending(roll_price)
     }
 
     /// get the addresses that are involved in this operation from a rolls point of view
@@ -1031,9 +1527,31 @@ impl SecureShareOperation {
             }
             OperationType::ExecuteSC { .. } => {}
             OperationType::CallSC { .. } => {}
+            // multisig operations cannot buy or sell rolls
+            OperationType::Multisig { .. } => {}
+            OperationType::Sequenced { ref op, .. } => {
+                if matches!(
+                    op.as_ref(),
+                    OperationType::RollBuy { .. } | OperationType::RollSell { .. }
+                ) {
+                    res.insert(Address::from_public_key(&self.content_creator_pub_key));
+                }
+            }
         }
         Ok(res)
     }
+
+    /// Check the co-signer signatures of a multisig operation. Always succeeds for other operations.
+    pub fn verify_multisig_signatures(&self) -> Result<(), ModelsError> {
+        if !matches!(self.content.op, OperationType::Multisig { .. }) {
+            return Ok(());
+        }
+        Ok(verify_signature_batch(
+            &self
+                .content
+                .get_multisig_signature_batch(&self.content_creator_pub_key)?,
+        )?)
+    }
 }
 
 /// Set of operation id's prefix
@@ -1699,4 +2217,242 @@ mod tests {
 
         assert_eq!(op.get_validity_range(10), 40..=50);
     }
+
+    #[test]
+    #[serial]
+    fn test_multisig() {
+        let creator_keypair = KeyPair::generate(0).unwrap();
+        let cosigner_keypairs: Vec<_> = (0..3).map(|_| KeyPair::generate(0).unwrap()).collect();
+        let multisig_public_key = MultiSigPublicKey::new(
+            2,
+            cosigner_keypairs
+                .iter()
+                .map(|k| k.get_public_key())
+                .collect(),
+        )
+        .unwrap();
+        let multisig_address = Address::from_multisig_public_key(&multisig_public_key);
+        let recipient_address = Address::from_public_key(&creator_keypair.get_public_key());
+
+        let content = Operation {
+            fee: Amount::from_str("1").unwrap(),
+            expire_period: 50,
+            op: OperationType::Multisig {
+                multisig_public_key,
+                signatures: MultiSignature::default(),
+                op: Box::new(OperationType::Transaction {
+                    recipient_address,
+                    amount: Amount::from_str("300").unwrap(),
+                }),
+            },
+        };
+        // the creator only pays the fee
+        assert_eq!(
+            content.get_max_spending(Amount::from_str("100").unwrap()),
+            Amount::from_str("1").unwrap()
+        );
+
+        let mut partial =
+            PartiallySignedOperation::new(content, creator_keypair.get_public_key()).unwrap();
+        partial.sign(&cosigner_keypairs[2]).unwrap();
+        assert!(partial.verify_signatures().is_err());
+        partial.sign(&cosigner_keypairs[0]).unwrap();
+        partial.verify_signatures().unwrap();
+
+        let op = Operation::new_verifiable(
+            partial.content,
+            OperationSerializer::new(),
+            &creator_keypair,
+        )
+        .unwrap();
+        op.verify_signature().unwrap();
+        op.verify_multisig_signatures().unwrap();
+        assert!(op
+            .get_ledger_involved_addresses()
+            .contains(&multisig_address));
+
+        let mut ser_op = Vec::new();
+        SecureShareSerializer::new()
+            .serialize(&op, &mut ser_op)
+            .unwrap();
+        let (_, res_op): (&[u8], SecureShareOperation) =
+            SecureShareDeserializer::new(OperationDeserializer::new(
+                MAX_DATASTORE_VALUE_LENGTH,
+                MAX_FUNCTION_NAME_LENGTH,
+                MAX_PARAMETERS_SIZE,
+                MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+                MAX_OPERATION_DATASTORE_KEY_LENGTH,
+                MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+            ))
+            .deserialize::<DeserializeError>(&ser_op)
+            .unwrap();
+        assert_eq!(res_op, op);
+        res_op.verify_multisig_signatures().unwrap();
+
+        // signatures do not carry over to another creator
+        let other_creator = KeyPair::generate(0).unwrap();
+        let other_op = Operation::new_verifiable(
+            op.content.clone(),
+            OperationSerializer::new(),
+            &other_creator,
+        )
+        .unwrap();
+        assert!(other_op.verify_multisig_signatures().is_err());
+
+        // multisig operations cannot be nested
+        let nested = OperationType::Multisig {
+            multisig_public_key: MultiSigPublicKey::new(1, vec![creator_keypair.get_public_key()])
+                .unwrap(),
+            signatures: MultiSignature::default(),
+            op: Box::new(op.content.op.clone()),
+        };
+        let mut ser_type = Vec::new();
+        OperationTypeSerializer::new()
+            .serialize(&nested, &mut ser_type)
+            .unwrap();
+        assert!(OperationTypeDeserializer::new(
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        )
+        .deserialize::<DeserializeError>(&ser_type)
+        .is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_multisig_forbidden_operations() {
+        let creator_keypair = KeyPair::generate(0).unwrap();
+        let multisig_public_key =
+            MultiSigPublicKey::new(1, vec![KeyPair::generate(0).unwrap().get_public_key()])
+                .unwrap();
+        let deserializer = OperationTypeDeserializer::new(
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        );
+        let transaction = OperationType::Transaction {
+            recipient_address: Address::from_public_key(&creator_keypair.get_public_key()),
+            amount: Amount::from_str("300").unwrap(),
+        };
+
+        // multisig addresses cannot buy or sell rolls, and wrapper operations cannot be nested
+        for op in [
+            OperationType::RollBuy { roll_count: 1 },
+            OperationType::RollSell { roll_count: 1 },
+            OperationType::Multisig {
+                multisig_public_key: multisig_public_key.clone(),
+                signatures: MultiSignature::default(),
+                op: Box::new(transaction.clone()),
+            },
+            OperationType::Sequenced {
+                supersedes: None,
+                after: None,
+                op: Box::new(transaction.clone()),
+            },
+        ] {
+            let op_type = OperationType::Multisig {
+                multisig_public_key: multisig_public_key.clone(),
+                signatures: MultiSignature::default(),
+                op: Box::new(op),
+            };
+            let mut ser_type = Vec::new();
+            OperationTypeSerializer::new()
+                .serialize(&op_type, &mut ser_type)
+                .unwrap();
+            assert!(deserializer
+                .deserialize::<DeserializeError>(&ser_type)
+                .is_err());
+
+            let content = Operation {
+                fee: Amount::from_str("1").unwrap(),
+                expire_period: 50,
+                op: op_type,
+            };
+            assert!(
+                PartiallySignedOperation::new(content, creator_keypair.get_public_key()).is_err()
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_sequenced() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let recipient_address = Address::from_public_key(&keypair.get_public_key());
+        let superseded = SupersededOperation {
+            id: OperationId::new(Hash::compute_from(b"superseded")),
+            expire_period: 10,
+        };
+        let required_id = OperationId::new(Hash::compute_from(b"required"));
+
+        let op_type = OperationType::Sequenced {
+            supersedes: Some(superseded),
+            after: Some(required_id),
+            op: Box::new(OperationType::Transaction {
+                recipient_address,
+                amount: Amount::from_str("300").unwrap(),
+            }),
+        };
+        assert_eq!(op_type.get_superseded_op(), Some(superseded));
+        assert_eq!(op_type.get_required_op_id(), Some(required_id));
+        assert_eq!(
+            op_type.get_max_non_fee_spending(Amount::from_str("100").unwrap()),
+            Amount::from_str("300").unwrap()
+        );
+
+        let deserializer = OperationTypeDeserializer::new(
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        );
+        let mut ser_type = Vec::new();
+        OperationTypeSerializer::new()
+            .serialize(&op_type, &mut ser_type)
+            .unwrap();
+        let (rest, res_type) = deserializer
+            .deserialize::<DeserializeError>(&ser_type)
+            .unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(res_type, op_type);
+
+        // sequenced operations cannot be nested
+        let nested = OperationType::Sequenced {
+            supersedes: None,
+            after: None,
+            op: Box::new(op_type),
+        };
+        let mut ser_type = Vec::new();
+        OperationTypeSerializer::new()
+            .serialize(&nested, &mut ser_type)
+            .unwrap();
+        assert!(deserializer
+            .deserialize::<DeserializeError>(&ser_type)
+            .is_err());
+
+        // the supersession is bound to the sender and the expiration period of the superseded operation
+        let other_address =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        assert_ne!(
+            superseded.get_supersession_id(&recipient_address),
+            superseded.get_supersession_id(&other_address)
+        );
+        assert_ne!(
+            superseded.get_supersession_id(&recipient_address),
+            SupersededOperation {
+                expire_period: 11,
+                ..superseded
+            }
+            .get_supersession_id(&recipient_address)
+        );
+    }
 }
@@ -96,6 +96,34 @@ where
         })
     }
 
+    /// Same as `new_verifiable`, for keys that are not held locally: `sign` is given the hash to sign
+    /// and returns its signature by `public_key`. The returned signature is checked.
+    fn new_verifiable_with_signer<Ser, ID, F>(
+        self,
+        content_serializer: Ser,
+        public_key: &PublicKey,
+        sign: F,
+    ) -> Result<SecureShare<Self, ID>, ModelsError>
+    where
+        Ser: Serializer<Self>,
+        ID: Id,
+        F: FnOnce(&Hash) -> Result<Signature, ModelsError>,
+    {
+        let mut content_serialized = Vec::new();
+        content_serializer.serialize(&self, &mut content_serialized)?;
+        let hash = Self::compute_hash(&self, &content_serialized, public_key);
+        let signature = sign(&self.compute_signed_hash(public_key, &hash))?;
+        self.verify_signature(public_key, &hash, &signature)?;
+        Ok(SecureShare {
+            signature,
+            content_creator_pub_key: *public_key,
+            content_creator_address: Address::from_public_key(public_key),
+            content: self,
+            serialized_data: content_serialized,
+            id: ID::new(hash),
+        })
+    }
+
     /// Compute hash
     fn compute_hash(&self, content_serialized: &[u8], content_creator_pub_key: &PublicKey) -> Hash {
         let mut hash_data = Vec::new();
@@ -0,0 +1,208 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Single-node dev mode (`--dev`): a fresh local chain with a single staker owning all the rolls.
+//! Blocks are only produced on demand, see `node_dev_advance_slots`, or when operations arrive in the pool.
+
+use crate::settings::{DevSettings, SETTINGS};
+use anyhow::Context;
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::config::ChainSpec;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use num::rational::Ratio;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// number of threads of the dev chain
+const DEV_THREAD_COUNT: u8 = 2;
+/// number of periods of a cycle of the dev chain
+const DEV_PERIODS_PER_CYCLE: u64 = 16;
+/// file of the initial peers of the dev node, in the dev chain directory
+const INITIAL_PEERS_FILE: &str = "initial_peers.json";
+
+/// Dev chain written at startup
+pub struct DevChain {
+    /// chain spec of the dev chain, with a genesis right after the frozen clock
+    pub chain_spec: ChainSpec,
+    /// key of the staker owning all the rolls
+    pub staker: KeyPair,
+}
+
+impl DevChain {
+    /// staking wallet of the dev staker
+    pub fn staking_wallet_path() -> PathBuf {
+        SETTINGS.dev.genesis_dir.join("staking_wallet")
+    }
+
+    /// initial peers of the dev node: none
+    pub fn initial_peers_path() -> PathBuf {
+        SETTINGS.dev.genesis_dir.join(INITIAL_PEERS_FILE)
+    }
+
+    /// peers database of the dev node, kept apart from the one of the public network
+    pub fn peers_db_path() -> PathBuf {
+        SETTINGS.dev.genesis_dir.join("peers.json")
+    }
+
+    /// static peers of the dev node, kept apart from the ones of the public network
+    pub fn static_peers_path() -> PathBuf {
+        SETTINGS.dev.genesis_dir.join("static_peers.json")
+    }
+
+    /// signing journal of the dev staker
+    pub fn signing_journal_path() -> PathBuf {
+        SETTINGS.dev.genesis_dir.join("signing_journal.jsonl")
+    }
+}
+
+/// Recreate the dev chain directory with a new staker and a genesis right after `genesis_now`.
+/// The node clock must then be frozen at `genesis_now`, the chain only advancing on demand.
+pub fn create_dev_chain(genesis_now: MassaTime) -> anyhow::Result<DevChain> {
+    write_dev_chain(&SETTINGS.dev, genesis_now)
+}
+
+/// Recreate the dev chain directory of `settings`, see `create_dev_chain`
+fn write_dev_chain(settings: &DevSettings, genesis_now: MassaTime) -> anyhow::Result<DevChain> {
+    let dir = &settings.genesis_dir;
+    if dir.exists() {
+        std::fs::remove_dir_all(dir)
+            .with_context(|| format!("could not remove the dev chain in {}", dir.display()))?;
+    }
+    std::fs::create_dir_all(dir)?;
+
+    let staker = KeyPair::generate(0)?;
+    let staker_address = Address::from_public_key(&staker.get_public_key());
+
+    let mut balances = match &settings.prefunded_accounts_path {
+        Some(path) => load_prefunded_accounts(path)?,
+        None => BTreeMap::new(),
+    };
+    let staker_balance = balances.entry(staker_address).or_default();
+    *staker_balance = staker_balance.saturating_add(settings.staker_balance);
+
+    let ledger: Map<String, Value> = balances
+        .iter()
+        .map(|(addr, balance)| {
+            (
+                addr.to_string(),
+                json!({
+                    "balance": balance.to_string(),
+                    "datastore": {},
+                    "bytecode": []
+                }),
+            )
+        })
+        .collect();
+    let initial_ledger_path = dir.join("initial_ledger.json");
+    std::fs::write(&initial_ledger_path, serde_json::to_string_pretty(&ledger)?)?;
+    let initial_rolls_path = dir.join("initial_rolls.json");
+    std::fs::write(
+        &initial_rolls_path,
+        serde_json::to_string_pretty(&json!({
+            staker_address.to_string(): settings.staker_rolls
+        }))?,
+    )?;
+    let initial_deferred_credits_path = dir.join("deferred_credits.json");
+    std::fs::write(&initial_deferred_credits_path, "{}")?;
+    std::fs::write(dir.join(INITIAL_PEERS_FILE), "{}")?;
+
+    let chain_spec = ChainSpec {
+        // strictly after the frozen clock, so that the node starts from genesis instead of bootstrapping
+        genesis_timestamp: genesis_now.saturating_add(MassaTime::from_millis(1)),
+        end_timestamp: None,
+        genesis_key: KeyPair::generate(0)?,
+        thread_count: DEV_THREAD_COUNT,
+        t0: settings.t0,
+        periods_per_cycle: DEV_PERIODS_PER_CYCLE,
+        // no endorsements are produced in dev mode: a block is final as soon as it has a descendant
+        delta_f0: 0,
+        // the slots skipped by time jumps are missed, this must not deactivate the staker
+        pos_miss_rate_deactivation_threshold: Ratio::new(1, 1),
+        initial_ledger_path: Some(initial_ledger_path),
+        initial_rolls_path: Some(initial_rolls_path),
+        initial_deferred_credits_path: Some(initial_deferred_credits_path),
+        ..ChainSpec::default()
+    };
+    chain_spec.check()?;
+    Ok(DevChain { chain_spec, staker })
+}
+
+/// Load the prefunded accounts file: `{"<address>": "<balance>"}`
+fn load_prefunded_accounts(path: &Path) -> anyhow::Result<BTreeMap<Address, Amount>> {
+    let content = std::fs::read_to_string(path).with_context(|| {
+        format!(
+            "could not read the prefunded accounts file {}",
+            path.display()
+        )
+    })?;
+    serde_json::from_str(&content).with_context(|| {
+        format!(
+            "could not parse the prefunded accounts file {}",
+            path.display()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_write_dev_chain() {
+        let tmp = tempfile::tempdir().unwrap();
+        let prefunded_address =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let prefunded_accounts_path = tmp.path().join("dev_accounts.json");
+        std::fs::write(
+            &prefunded_accounts_path,
+            json!({ prefunded_address.to_string(): "500" }).to_string(),
+        )
+        .unwrap();
+        let settings = DevSettings {
+            genesis_dir: tmp.path().join("dev"),
+            prefunded_accounts_path: Some(prefunded_accounts_path),
+            ..SETTINGS.dev.clone()
+        };
+        // the dev chain of a previous start is removed
+        std::fs::create_dir_all(&settings.genesis_dir).unwrap();
+        std::fs::write(settings.genesis_dir.join("staking_wallet"), "stale").unwrap();
+
+        let now = MassaTime::from_millis(1_000_000);
+        let dev_chain = write_dev_chain(&settings, now).unwrap();
+        let staker_address = Address::from_public_key(&dev_chain.staker.get_public_key());
+
+        // the node starts from a genesis right after the frozen clock
+        let chain_spec = &dev_chain.chain_spec;
+        assert!(chain_spec.genesis_timestamp > now);
+        assert_eq!(chain_spec.thread_count, DEV_THREAD_COUNT);
+        assert_eq!(chain_spec.t0, settings.t0);
+        assert_eq!(chain_spec.delta_f0, 0);
+        assert!(!settings.genesis_dir.join("staking_wallet").exists());
+
+        // the staker owns all the rolls, and the prefunded accounts are funded at genesis
+        let read_json = |path: &Option<PathBuf>| -> Value {
+            serde_json::from_str(&std::fs::read_to_string(path.as_ref().unwrap()).unwrap()).unwrap()
+        };
+        let ledger = read_json(&chain_spec.initial_ledger_path);
+        let balance = |address: &Address| {
+            Amount::from_str(ledger[address.to_string()]["balance"].as_str().unwrap()).unwrap()
+        };
+        assert_eq!(ledger.as_object().unwrap().len(), 2);
+        assert_eq!(balance(&staker_address), settings.staker_balance);
+        assert_eq!(
+            balance(&prefunded_address),
+            Amount::from_str("500").unwrap()
+        );
+        assert_eq!(
+            read_json(&chain_spec.initial_rolls_path),
+            json!({ staker_address.to_string(): settings.staker_rolls })
+        );
+        assert_eq!(
+            std::fs::read_to_string(settings.genesis_dir.join(INITIAL_PEERS_FILE)).unwrap(),
+            "{}"
+        );
+    }
+}
@@ -5,6 +5,7 @@
 #![warn(unused_crate_dependencies)]
 extern crate massa_logging;
 
+use crate::dev::{create_dev_chain, DevChain};
 #[cfg(feature = "op_spammer")]
 use crate::operation_injector::start_operation_injector;
 use crate::settings::SETTINGS;
@@ -13,8 +14,11 @@ use crate::survey::MassaSurvey;
 use crossbeam_channel::TryRecvError;
 use dialoguer::Password;
 use massa_api::{ApiServer, ApiV2, Private, Public, RpcServer, StopHandle, API};
+use massa_api_access::AccessController;
 use massa_api_exports::config::APIConfig;
 use massa_async_pool::AsyncPoolConfig;
+use massa_block_archive_exports::{BlockArchiveConfig, BlockArchiveManager, BlockArchiveRetention};
+use massa_block_archive_worker::start_block_archive_worker;
 use massa_bootstrap::BootstrapError;
 use massa_bootstrap::{
     get_state, start_bootstrap_server, BootstrapConfig, BootstrapManager, BootstrapTcpListener,
@@ -26,52 +30,48 @@ use massa_consensus_exports::events::ConsensusEvent;
 use massa_consensus_exports::{ConsensusChannels, ConsensusConfig, ConsensusManager};
 use massa_consensus_worker::start_consensus_worker;
 use massa_db_exports::{MassaDBConfig, MassaDBController};
-use massa_db_worker::MassaDB;
+use massa_db_worker::{export_state, import_state, MassaDB};
 use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
 use massa_execution_exports::{
     ExecutionChannels, ExecutionConfig, ExecutionManager, GasCosts, StorageCostsConstants,
 };
 use massa_execution_worker::start_execution_worker;
 use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
-use massa_factory_worker::start_factory;
+use massa_factory_worker::{start_factory, SigningJournal};
 use massa_final_state::{FinalState, FinalStateConfig};
 use massa_grpc::config::{GrpcConfig, ServiceName};
 use massa_grpc::server::{MassaPrivateGrpc, MassaPublicGrpc};
+use massa_hash::Hash;
 use massa_ledger_exports::LedgerConfig;
 use massa_ledger_worker::FinalLedger;
 use massa_logging::massa_trace;
 use massa_metrics::{MassaMetrics, MetricsStopper};
 use massa_models::address::Address;
 use massa_models::config::constants::{
-    BLOCK_REWARD, BOOTSTRAP_RANDOMNESS_SIZE_BYTES, CHANNEL_SIZE, CONSENSUS_BOOTSTRAP_PART_SIZE,
-    DELTA_F0, DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, END_TIMESTAMP, GENESIS_KEY,
-    GENESIS_TIMESTAMP, INITIAL_DRAW_SEED, LEDGER_COST_PER_BYTE, LEDGER_ENTRY_BASE_COST,
-    LEDGER_ENTRY_DATASTORE_BASE_SIZE, MAX_ADVERTISE_LENGTH, MAX_ASYNC_GAS, MAX_ASYNC_MESSAGE_DATA,
-    MAX_ASYNC_POOL_LENGTH, MAX_BLOCK_SIZE, MAX_BOOTSTRAP_ASYNC_POOL_CHANGES, MAX_BOOTSTRAP_BLOCKS,
-    MAX_BOOTSTRAP_ERROR_LENGTH, MAX_BYTECODE_LENGTH, MAX_CONSENSUS_BLOCKS_IDS,
-    MAX_DATASTORE_ENTRY_COUNT, MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH,
-    MAX_DEFERRED_CREDITS_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+    BOOTSTRAP_RANDOMNESS_SIZE_BYTES, CHANNEL_SIZE, CONSENSUS_BOOTSTRAP_PART_SIZE,
+    LEDGER_ENTRY_DATASTORE_BASE_SIZE, MAX_ADVERTISE_LENGTH, MAX_ASYNC_MESSAGE_DATA,
+    MAX_BOOTSTRAP_ASYNC_POOL_CHANGES, MAX_BOOTSTRAP_BLOCKS, MAX_BOOTSTRAP_ERROR_LENGTH,
+    MAX_BYTECODE_LENGTH, MAX_CONSENSUS_BLOCKS_IDS, MAX_DATASTORE_ENTRY_COUNT,
+    MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH, MAX_DEFERRED_CREDITS_LENGTH,
     MAX_DENUNCIATION_CHANGES_LENGTH, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_EXECUTED_OPS_CHANGES_LENGTH,
-    MAX_EXECUTED_OPS_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK, MAX_LEDGER_CHANGES_COUNT,
-    MAX_LISTENERS_PER_PEER, MAX_OPERATIONS_PER_BLOCK, MAX_OPERATIONS_PER_MESSAGE,
-    MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
-    MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_OPERATION_STORAGE_TIME, MAX_PARAMETERS_SIZE,
-    MAX_PEERS_IN_ANNOUNCEMENT_LIST, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
-    MAX_SIZE_CHANNEL_COMMANDS_CONNECTIVITY, MAX_SIZE_CHANNEL_COMMANDS_PEERS,
-    MAX_SIZE_CHANNEL_COMMANDS_PEER_TESTERS, MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_BLOCKS,
+    MAX_EXECUTED_OPS_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_LEDGER_CHANGES_COUNT,
+    MAX_LISTENERS_PER_PEER, MAX_OPERATIONS_PER_MESSAGE, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+    MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+    MAX_OPERATION_STORAGE_TIME, MAX_PARAMETERS_SIZE, MAX_PEERS_IN_ANNOUNCEMENT_LIST,
+    MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, MAX_SIZE_CHANNEL_COMMANDS_CONNECTIVITY,
+    MAX_SIZE_CHANNEL_COMMANDS_PEERS, MAX_SIZE_CHANNEL_COMMANDS_PEER_TESTERS,
+    MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_BLOCKS,
     MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_ENDORSEMENTS,
     MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_OPERATIONS, MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_BLOCKS,
     MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_ENDORSEMENTS,
     MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_OPERATIONS, MAX_SIZE_CHANNEL_NETWORK_TO_BLOCK_HANDLER,
     MAX_SIZE_CHANNEL_NETWORK_TO_ENDORSEMENT_HANDLER, MAX_SIZE_CHANNEL_NETWORK_TO_OPERATION_HANDLER,
-    MAX_SIZE_CHANNEL_NETWORK_TO_PEER_HANDLER, MIP_STORE_STATS_BLOCK_CONSIDERED,
-    OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, POS_MISS_RATE_DEACTIVATION_THRESHOLD,
-    POS_SAVED_CYCLES, PROTOCOL_CONTROLLER_CHANNEL_SIZE, PROTOCOL_EVENT_CHANNEL_SIZE,
-    ROLL_COUNT_TO_SLASH_ON_DENUNCIATION, ROLL_PRICE, SELECTOR_DRAW_CACHE_SIZE, T0, THREAD_COUNT,
-    VERSION,
+    MAX_SIZE_CHANNEL_NETWORK_TO_PEER_HANDLER, MIP_STORE_STATS_BLOCK_CONSIDERED, POS_SAVED_CYCLES,
+    PROTOCOL_CONTROLLER_CHANNEL_SIZE, PROTOCOL_EVENT_CHANNEL_SIZE,
+    ROLL_COUNT_TO_SLASH_ON_DENUNCIATION, SELECTOR_DRAW_CACHE_SIZE, VERSION,
 };
 use massa_models::config::{
-    KEEP_EXECUTED_HISTORY_EXTRA_PERIODS, MAX_BOOTSTRAPPED_NEW_ELEMENTS, MAX_EVENT_DATA_SIZE,
+    try_build_massa_settings, ChainSpec, MAX_BOOTSTRAPPED_NEW_ELEMENTS, MAX_EVENT_DATA_SIZE,
     MAX_MESSAGE_SIZE, POOL_CONTROLLER_DENUNCIATIONS_CHANNEL_SIZE,
     POOL_CONTROLLER_ENDORSEMENTS_CHANNEL_SIZE, POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE,
 };
@@ -86,12 +86,14 @@ use massa_signature::KeyPair;
 use massa_storage::Storage;
 use massa_time::MassaTime;
 use massa_versioning::keypair_factory::KeyPairFactory;
-use massa_versioning::mips::get_mip_list;
+use massa_versioning::mips::{get_mip_list, get_mip_list_from_chain_spec};
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
-use massa_wallet::Wallet;
+#[cfg(unix)]
+use massa_wallet::RemoteSigner;
+use massa_wallet::{LocalSigner, Signer, Wallet};
 use num::rational::Ratio;
 use parking_lot::RwLock;
-use settings::GrpcSettings;
+use settings::{GrpcSettings, Settings};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -104,6 +106,7 @@ use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::filter::{filter_fn, LevelFilter};
 
+mod dev;
 #[cfg(feature = "op_spammer")]
 mod operation_injector;
 mod settings;
@@ -111,6 +114,8 @@ mod survey;
 
 async fn launch(
     args: &Args,
+    chain_spec: &ChainSpec,
+    chain_id: Hash,
     node_wallet: Arc<RwLock<Wallet>>,
     sig_int_toggled: Arc<(Mutex<bool>, Condvar)>,
 ) -> (
@@ -122,6 +127,7 @@ async fn launch(
     Box<dyn PoolManager>,
     Box<dyn ProtocolManager>,
     Box<dyn FactoryManager>,
+    Option<Box<dyn BlockArchiveManager>>,
     StopHandle,
     StopHandle,
     StopHandle,
@@ -135,8 +141,10 @@ async fn launch(
     // from desync if the bootstrap nodes keep a previous ledger
     #[cfg(all(not(feature = "sandbox"), not(feature = "bootstrap_server")))]
     {
-        if *GENESIS_TIMESTAMP > now {
-            let (days, hours, mins, secs) = GENESIS_TIMESTAMP
+        // in dev mode, the clock is frozen right before genesis
+        if chain_spec.genesis_timestamp > now && !args.dev {
+            let (days, hours, mins, secs) = chain_spec
+                .genesis_timestamp
                 .saturating_sub(now)
                 .days_hours_mins_secs()
                 .unwrap();
@@ -147,7 +155,7 @@ async fn launch(
         }
     }
 
-    if let Some(end) = *END_TIMESTAMP {
+    if let Some(end) = chain_spec.end_timestamp {
         if now > end {
             panic!("This episode has come to an end, please get the latest testnet node version to continue");
         }
@@ -166,9 +174,9 @@ async fn launch(
             .unwrap();
 
         if let Ok(Some(end_period)) = massa_models::timeslots::get_latest_block_slot_at_timestamp(
-            THREAD_COUNT,
-            T0,
-            *GENESIS_TIMESTAMP,
+            chain_spec.thread_count,
+            chain_spec.t0,
+            chain_spec.genesis_timestamp,
             DOWNTIME_END_TIMESTAMP,
         ) {
             panic!(
@@ -188,36 +196,42 @@ async fn launch(
 
     // init final state
     let ledger_config = LedgerConfig {
-        thread_count: THREAD_COUNT,
-        initial_ledger_path: SETTINGS.ledger.initial_ledger_path.clone(),
+        thread_count: chain_spec.thread_count,
+        initial_ledger_path: chain_spec
+            .initial_ledger_path
+            .clone()
+            .unwrap_or_else(|| SETTINGS.ledger.initial_ledger_path.clone()),
         disk_ledger_path: SETTINGS.ledger.disk_ledger_path.clone(),
         max_key_length: MAX_DATASTORE_KEY_LENGTH,
         max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
     };
     let async_pool_config = AsyncPoolConfig {
-        max_length: MAX_ASYNC_POOL_LENGTH,
-        thread_count: THREAD_COUNT,
+        max_length: chain_spec.max_async_pool_length,
+        thread_count: chain_spec.thread_count,
         max_async_message_data: MAX_ASYNC_MESSAGE_DATA,
         max_key_length: MAX_DATASTORE_KEY_LENGTH as u32,
     };
     let pos_config = PoSConfig {
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        thread_count: THREAD_COUNT,
+        periods_per_cycle: chain_spec.periods_per_cycle,
+        thread_count: chain_spec.thread_count,
         cycle_history_length: POS_SAVED_CYCLES,
         max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
         max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
         max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
-        initial_deferred_credits_path: SETTINGS.ledger.initial_deferred_credits_path.clone(),
+        initial_deferred_credits_path: chain_spec
+            .initial_deferred_credits_path
+            .clone()
+            .or_else(|| SETTINGS.ledger.initial_deferred_credits_path.clone()),
     };
     let executed_ops_config = ExecutedOpsConfig {
-        thread_count: THREAD_COUNT,
-        keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+        thread_count: chain_spec.thread_count,
+        keep_executed_history_extra_periods: chain_spec.keep_executed_history_extra_periods,
     };
     let executed_denunciations_config = ExecutedDenunciationsConfig {
-        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
-        thread_count: THREAD_COUNT,
-        endorsement_count: ENDORSEMENT_COUNT,
-        keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+        denunciation_expire_periods: chain_spec.denunciation_expire_periods,
+        thread_count: chain_spec.thread_count,
+        endorsement_count: chain_spec.endorsement_count,
+        keep_executed_history_extra_periods: chain_spec.keep_executed_history_extra_periods,
     };
     let final_state_config = FinalStateConfig {
         ledger_config: ledger_config.clone(),
@@ -226,31 +240,53 @@ async fn launch(
         executed_ops_config,
         executed_denunciations_config,
         final_history_length: SETTINGS.ledger.final_history_length,
-        thread_count: THREAD_COUNT,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        initial_seed_string: INITIAL_DRAW_SEED.into(),
-        initial_rolls_path: SETTINGS.selector.initial_rolls_path.clone(),
-        endorsement_count: ENDORSEMENT_COUNT,
+        thread_count: chain_spec.thread_count,
+        periods_per_cycle: chain_spec.periods_per_cycle,
+        initial_seed_string: chain_spec.initial_draw_seed.clone(),
+        initial_rolls_path: chain_spec
+            .initial_rolls_path
+            .clone()
+            .unwrap_or_else(|| SETTINGS.selector.initial_rolls_path.clone()),
+        endorsement_count: chain_spec.endorsement_count,
         max_executed_denunciations_length: MAX_DENUNCIATION_CHANGES_LENGTH,
-        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
-        t0: T0,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
+        max_denunciations_per_block_header: chain_spec.max_denunciations_per_block_header,
+        t0: chain_spec.t0,
+        genesis_timestamp: chain_spec.genesis_timestamp,
     };
 
     // Start massa metrics
     let (massa_metrics, metrics_stopper) = MassaMetrics::new(
         SETTINGS.metrics.enabled,
         SETTINGS.metrics.bind,
-        THREAD_COUNT,
+        chain_spec.thread_count,
         SETTINGS.metrics.tick_delay.to_duration(),
     );
 
+    // A bootstrap interrupted by a restart is resumed from the partially bootstrapped ledger.
+    // If its progress cannot be read, the ledger is deleted and the bootstrap restarts from scratch.
+    let resume_bootstrap = !args.keep_ledger
+        && !args.dev
+        && args.restart_from_snapshot_at_period.is_none()
+        && SETTINGS.ledger.disk_ledger_path.exists()
+        && match MassaDB::new(get_db_config(chain_spec)).get_bootstrap_progress() {
+            Ok(progress) => progress.is_some(),
+            Err(err) => {
+                warn!(
+                    "Could not read the progress of the interrupted bootstrap, restarting it from scratch: {}",
+                    err
+                );
+                false
+            }
+        };
+
     // Remove current disk ledger if there is one and we don't want to restart from snapshot
     // NOTE: this is temporary, since we cannot currently handle bootstrap from remaining ledger
     if args.keep_ledger || args.restart_from_snapshot_at_period.is_some() {
         info!("Loading old ledger for next episode");
     } else {
-        if SETTINGS.ledger.disk_ledger_path.exists() {
+        if resume_bootstrap {
+            info!("Keeping the partially bootstrapped ledger to resume the bootstrap");
+        } else if SETTINGS.ledger.disk_ledger_path.exists() {
             std::fs::remove_dir_all(SETTINGS.ledger.disk_ledger_path.clone())
                 .expect("disk ledger delete failed");
         }
@@ -260,14 +296,8 @@ async fn launch(
         }
     }
 
-    let db_config = MassaDBConfig {
-        path: SETTINGS.ledger.disk_ledger_path.clone(),
-        max_history_length: SETTINGS.ledger.final_history_length,
-        max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS as usize,
-        thread_count: THREAD_COUNT,
-    };
     let db = Arc::new(RwLock::new(
-        Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        Box::new(MassaDB::new(get_db_config(chain_spec))) as Box<(dyn MassaDBController + 'static)>,
     ));
 
     // Create final ledger
@@ -277,10 +307,10 @@ async fn launch(
     let (selector_manager, selector_controller) = start_selector_worker(SelectorConfig {
         max_draw_cache: SELECTOR_DRAW_CACHE_SIZE,
         channel_size: CHANNEL_SIZE,
-        thread_count: THREAD_COUNT,
-        endorsement_count: ENDORSEMENT_COUNT,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        genesis_address: Address::from_public_key(&GENESIS_KEY.get_public_key()),
+        thread_count: chain_spec.thread_count,
+        endorsement_count: chain_spec.endorsement_count,
+        periods_per_cycle: chain_spec.periods_per_cycle,
+        genesis_address: Address::from_public_key(&chain_spec.genesis_key.get_public_key()),
     })
     .expect("could not start selector worker");
 
@@ -319,11 +349,15 @@ async fn launch(
             }
             None => {
                 // The node is started in a normal way
-                // Read the mip list supported by the current software
+                // Read the mip list of the chain spec, or the one supported by the current software
                 // The resulting MIP store will likely be updated by the boostrap process in order
                 // to get the latest information for the MIP store (new states, votes...)
 
-                let mip_list = get_mip_list();
+                let mip_list = match &chain_spec.mip_list {
+                    Some(mips) => get_mip_list_from_chain_spec(mips, chain_spec.genesis_timestamp)
+                        .expect("invalid MIP list in the chain spec"),
+                    None => Vec::from(get_mip_list()),
+                };
                 debug!("MIP list: {:?}", mip_list);
                 let mip_store = MipStore::try_from((mip_list, mip_stats_config))
                     .expect("mip store creation failed");
@@ -334,7 +368,8 @@ async fn launch(
                     Box::new(ledger),
                     selector_controller.clone(),
                     mip_store,
-                    true,
+                    // the partially bootstrapped state is kept as is
+                    !resume_bootstrap,
                 )
                 .expect("could not init final state")
             }
@@ -344,7 +379,11 @@ async fn launch(
     let mip_store = final_state.read().mip_store.clone();
 
     let bootstrap_config: BootstrapConfig = BootstrapConfig {
-        bootstrap_list: SETTINGS.bootstrap.bootstrap_list.clone(),
+        bootstrap_list: if args.dev {
+            Vec::new()
+        } else {
+            SETTINGS.bootstrap.bootstrap_list.clone()
+        },
         bootstrap_protocol: SETTINGS.bootstrap.bootstrap_protocol,
         bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
         bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
@@ -362,22 +401,23 @@ async fn launch(
         keep_ledger: args.keep_ledger,
         max_listeners_per_peer: MAX_LISTENERS_PER_PEER as u32,
         max_simultaneous_bootstraps: SETTINGS.bootstrap.max_simultaneous_bootstraps,
+        max_parallel_bootstrap_servers: SETTINGS.bootstrap.max_parallel_bootstrap_servers,
         per_ip_min_interval: SETTINGS.bootstrap.per_ip_min_interval,
         ip_list_max_size: SETTINGS.bootstrap.ip_list_max_size,
         rate_limit: SETTINGS.bootstrap.rate_limit,
         max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
         randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
-        thread_count: THREAD_COUNT,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        endorsement_count: ENDORSEMENT_COUNT,
+        thread_count: chain_spec.thread_count,
+        periods_per_cycle: chain_spec.periods_per_cycle,
+        endorsement_count: chain_spec.endorsement_count,
         max_advertise_length: MAX_ADVERTISE_LENGTH,
         max_bootstrap_blocks_length: MAX_BOOTSTRAP_BLOCKS,
         max_bootstrap_error_length: MAX_BOOTSTRAP_ERROR_LENGTH,
         max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS,
         max_async_pool_changes: MAX_BOOTSTRAP_ASYNC_POOL_CHANGES,
-        max_async_pool_length: MAX_ASYNC_POOL_LENGTH,
+        max_async_pool_length: chain_spec.max_async_pool_length,
         max_async_message_data: MAX_ASYNC_MESSAGE_DATA,
-        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        max_operations_per_block: chain_spec.max_operations_per_block,
         max_datastore_entry_count: MAX_DATASTORE_ENTRY_COUNT,
         max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
         max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
@@ -395,7 +435,7 @@ async fn launch(
         consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
         max_consensus_block_ids: MAX_CONSENSUS_BLOCKS_IDS,
         mip_store_stats_block_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
-        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        max_denunciations_per_block_header: chain_spec.max_denunciations_per_block_header,
         max_denunciation_changes_length: MAX_DENUNCIATION_CHANGES_LENGTH,
     };
 
@@ -404,8 +444,8 @@ async fn launch(
         final_state.clone(),
         DefaultConnector,
         *VERSION,
-        *GENESIS_TIMESTAMP,
-        *END_TIMESTAMP,
+        chain_spec.genesis_timestamp,
+        chain_spec.end_timestamp,
         args.restart_from_snapshot_at_period,
         sig_int_toggled.clone(),
         massa_metrics.clone(),
@@ -436,10 +476,10 @@ async fn launch(
     let last_slot_before_downtime_ = final_state.read().last_slot_before_downtime;
     if let Some(last_slot_before_downtime) = last_slot_before_downtime_ {
         let last_shutdown_start = last_slot_before_downtime
-            .get_next_slot(THREAD_COUNT)
+            .get_next_slot(chain_spec.thread_count)
             .unwrap();
         let last_shutdown_end = Slot::new(final_state.read().last_start_period, 0)
-            .get_prev_slot(THREAD_COUNT)
+            .get_prev_slot(chain_spec.thread_count)
             .unwrap();
 
         final_state
@@ -448,18 +488,19 @@ async fn launch(
             .is_consistent_with_shutdown_period(
                 last_shutdown_start,
                 last_shutdown_end,
-                THREAD_COUNT,
-                T0,
-                *GENESIS_TIMESTAMP,
+                chain_spec.thread_count,
+                chain_spec.t0,
+                chain_spec.genesis_timestamp,
             )
             .expect("Mip store is not consistent with shutdown period")
     }
 
     // Storage costs constants
     let storage_costs_constants = StorageCostsConstants {
-        ledger_cost_per_byte: LEDGER_COST_PER_BYTE,
-        ledger_entry_base_cost: LEDGER_ENTRY_BASE_COST,
-        ledger_entry_datastore_base_cost: LEDGER_COST_PER_BYTE
+        ledger_cost_per_byte: chain_spec.ledger_cost_per_byte,
+        ledger_entry_base_cost: chain_spec.ledger_entry_base_cost,
+        ledger_entry_datastore_base_cost: chain_spec
+            .ledger_cost_per_byte
             .checked_mul_u64(LEDGER_ENTRY_DATASTORE_BASE_SIZE as u64)
             .expect("Overflow when creating constant ledger_entry_datastore_base_size"),
     };
@@ -468,19 +509,24 @@ async fn launch(
     let execution_config = ExecutionConfig {
         max_final_events: SETTINGS.execution.max_final_events,
         readonly_queue_length: SETTINGS.execution.readonly_queue_length,
-        cursor_delay: SETTINGS.execution.cursor_delay,
-        max_async_gas: MAX_ASYNC_GAS,
-        max_gas_per_block: MAX_GAS_PER_BLOCK,
-        roll_price: ROLL_PRICE,
-        thread_count: THREAD_COUNT,
-        t0: T0,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        block_reward: BLOCK_REWARD,
-        endorsement_count: ENDORSEMENT_COUNT as u64,
-        operation_validity_period: OPERATION_VALIDITY_PERIODS,
-        periods_per_cycle: PERIODS_PER_CYCLE,
+        // in dev mode, the blocks are executed as soon as they are produced
+        cursor_delay: if args.dev {
+            MassaTime::from_millis(0)
+        } else {
+            SETTINGS.execution.cursor_delay
+        },
+        max_async_gas: chain_spec.max_async_gas,
+        max_gas_per_block: chain_spec.max_gas_per_block,
+        roll_price: chain_spec.roll_price,
+        thread_count: chain_spec.thread_count,
+        t0: chain_spec.t0,
+        genesis_timestamp: chain_spec.genesis_timestamp,
+        block_reward: chain_spec.block_reward,
+        endorsement_count: chain_spec.endorsement_count as u64,
+        operation_validity_period: chain_spec.operation_validity_periods,
+        periods_per_cycle: chain_spec.periods_per_cycle,
         stats_time_window_duration: SETTINGS.execution.stats_time_window_duration,
-        max_miss_ratio: *POS_MISS_RATE_DEACTIVATION_THRESHOLD,
+        max_miss_ratio: chain_spec.pos_miss_rate_deactivation_threshold,
         max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
         max_bytecode_size: MAX_BYTECODE_LENGTH,
         max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
@@ -497,12 +543,16 @@ async fn launch(
         hd_cache_size: SETTINGS.execution.hd_cache_size,
         snip_amount: SETTINGS.execution.snip_amount,
         roll_count_to_slash_on_denunciation: ROLL_COUNT_TO_SLASH_ON_DENUNCIATION,
-        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+        denunciation_expire_periods: chain_spec.denunciation_expire_periods,
         broadcast_enabled: SETTINGS.api.enable_broadcast,
         broadcast_slot_execution_output_channel_capacity: SETTINGS
             .execution
             .broadcast_slot_execution_output_channel_capacity,
         max_event_size: MAX_EVENT_DATA_SIZE,
+        execution_traces_enabled: SETTINGS.execution.execution_traces_enabled,
+        execution_traces_slot_limit: SETTINGS.execution.execution_traces_slot_limit,
+        address_history_enabled: SETTINGS.execution.address_history_enabled,
+        address_history_path: SETTINGS.execution.address_history_path.clone(),
         max_function_length: MAX_FUNCTION_NAME_LENGTH,
         max_parameter_length: MAX_PARAMETERS_SIZE,
     };
@@ -526,15 +576,17 @@ async fn launch(
 
     // launch pool controller
     let pool_config = PoolConfig {
-        thread_count: THREAD_COUNT,
-        max_block_size: MAX_BLOCK_SIZE,
-        max_block_gas: MAX_GAS_PER_BLOCK,
-        roll_price: ROLL_PRICE,
-        max_block_endorsement_count: ENDORSEMENT_COUNT,
-        operation_validity_periods: OPERATION_VALIDITY_PERIODS,
-        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        thread_count: chain_spec.thread_count,
+        max_block_size: chain_spec.max_block_size,
+        max_block_gas: chain_spec.max_gas_per_block,
+        roll_price: chain_spec.roll_price,
+        max_block_endorsement_count: chain_spec.endorsement_count,
+        operation_validity_periods: chain_spec.operation_validity_periods,
+        max_operations_per_block: chain_spec.max_operations_per_block,
         max_operation_pool_size: SETTINGS.pool.max_operation_pool_size,
         max_operation_pool_excess_items: SETTINGS.pool.max_operation_pool_excess_items,
+        operation_drop_history_size: SETTINGS.pool.operation_drop_history_size,
+        fee_stats_periods: SETTINGS.pool.fee_stats_periods,
         operation_pool_refresh_interval: SETTINGS.pool.operation_pool_refresh_interval,
         operation_max_future_start_delay: SETTINGS.pool.operation_max_future_start_delay,
         max_endorsements_pool_size_per_thread: SETTINGS.pool.max_endorsements_pool_size_per_thread,
@@ -546,11 +598,11 @@ async fn launch(
             .pool
             .broadcast_endorsements_channel_capacity,
         broadcast_operations_channel_capacity: SETTINGS.pool.broadcast_operations_channel_capacity,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        t0: T0,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
-        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        genesis_timestamp: chain_spec.genesis_timestamp,
+        t0: chain_spec.t0,
+        periods_per_cycle: chain_spec.periods_per_cycle,
+        denunciation_expire_periods: chain_spec.denunciation_expire_periods,
+        max_denunciations_per_block_header: chain_spec.max_denunciations_per_block_header,
         last_start_period: final_state.read().last_start_period,
     };
 
@@ -567,13 +619,14 @@ async fn launch(
         &shared_storage,
         pool_channels.clone(),
         node_wallet.clone(),
+        mip_store.clone(),
     );
 
     // launch protocol controller
     let mut listeners = HashMap::default();
     listeners.insert(SETTINGS.protocol.bind, TransportType::Tcp);
     let protocol_config = ProtocolConfig {
-        thread_count: THREAD_COUNT,
+        thread_count: chain_spec.thread_count,
         ask_block_timeout: SETTINGS.protocol.ask_block_timeout,
         max_known_blocks_size: SETTINGS.protocol.max_known_blocks_size,
         max_node_known_blocks_size: SETTINGS.protocol.max_node_known_blocks_size,
@@ -594,21 +647,37 @@ async fn launch(
         operation_batch_proc_period: SETTINGS.protocol.operation_batch_proc_period,
         operation_announcement_interval: SETTINGS.protocol.operation_announcement_interval,
         max_operations_per_message: SETTINGS.protocol.max_operations_per_message,
-        max_serialized_operations_size_per_block: MAX_BLOCK_SIZE as usize,
-        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        max_serialized_operations_size_per_block: chain_spec.max_block_size as usize,
+        max_operations_per_block: chain_spec.max_operations_per_block,
         controller_channel_size: PROTOCOL_CONTROLLER_CHANNEL_SIZE,
         event_channel_size: PROTOCOL_EVENT_CHANNEL_SIZE,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        t0: T0,
-        endorsement_count: ENDORSEMENT_COUNT,
+        genesis_timestamp: chain_spec.genesis_timestamp,
+        t0: chain_spec.t0,
+        endorsement_count: chain_spec.endorsement_count,
         max_message_size: MAX_MESSAGE_SIZE as usize,
         max_ops_kept_for_propagation: SETTINGS.protocol.max_ops_kept_for_propagation,
         max_operations_propagation_time: SETTINGS.protocol.max_operations_propagation_time,
         max_endorsements_propagation_time: SETTINGS.protocol.max_endorsements_propagation_time,
         last_start_period: final_state.read().last_start_period,
         max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE as u64,
-        max_denunciations_in_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
-        initial_peers: SETTINGS.protocol.initial_peers_file.clone(),
+        max_denunciations_in_block_header: chain_spec.max_denunciations_per_block_header,
+        initial_peers: if args.dev {
+            DevChain::initial_peers_path()
+        } else {
+            SETTINGS.protocol.initial_peers_file.clone()
+        },
+        peers_db_file: if args.dev {
+            DevChain::peers_db_path()
+        } else {
+            SETTINGS.protocol.peers_db_file.clone()
+        },
+        max_peers_db_size: SETTINGS.protocol.max_peers_db_size,
+        static_peers_file: if args.dev {
+            DevChain::static_peers_path()
+        } else {
+            SETTINGS.protocol.static_peers_file.clone()
+        },
+        tested_addresses_max_age: SETTINGS.protocol.tested_addresses_max_age,
         listeners,
         keypair_file: SETTINGS.protocol.keypair_file.clone(),
         max_blocks_kept_for_propagation: SETTINGS.protocol.max_blocks_kept_for_propagation,
@@ -659,6 +728,7 @@ async fn launch(
         peers_categories: SETTINGS.protocol.peers_categories.clone(),
         default_category_info: SETTINGS.protocol.default_category_info,
         version: *VERSION,
+        chain_id,
         try_connection_timer_same_peer: SETTINGS.protocol.try_connection_timer_same_peer,
         test_oldest_peer_cooldown: SETTINGS.protocol.test_oldest_peer_cooldown,
         rate_limit: SETTINGS.protocol.rate_limit,
@@ -668,22 +738,22 @@ async fn launch(
         create_protocol_controller(protocol_config.clone());
 
     let consensus_config = ConsensusConfig {
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        end_timestamp: *END_TIMESTAMP,
-        thread_count: THREAD_COUNT,
-        t0: T0,
-        genesis_key: GENESIS_KEY.clone(),
+        genesis_timestamp: chain_spec.genesis_timestamp,
+        end_timestamp: chain_spec.end_timestamp,
+        thread_count: chain_spec.thread_count,
+        t0: chain_spec.t0,
+        genesis_key: chain_spec.genesis_key.clone(),
         max_discarded_blocks: SETTINGS.consensus.max_discarded_blocks,
         max_future_processing_blocks: SETTINGS.consensus.max_future_processing_blocks,
         max_dependency_blocks: SETTINGS.consensus.max_dependency_blocks,
-        delta_f0: DELTA_F0,
-        operation_validity_periods: OPERATION_VALIDITY_PERIODS,
-        periods_per_cycle: PERIODS_PER_CYCLE,
+        delta_f0: chain_spec.delta_f0,
+        operation_validity_periods: chain_spec.operation_validity_periods,
+        periods_per_cycle: chain_spec.periods_per_cycle,
         stats_timespan: SETTINGS.consensus.stats_timespan,
         force_keep_final_periods: SETTINGS.consensus.force_keep_final_periods,
-        endorsement_count: ENDORSEMENT_COUNT,
+        endorsement_count: chain_spec.endorsement_count,
         block_db_prune_interval: SETTINGS.consensus.block_db_prune_interval,
-        max_gas_per_block: MAX_GAS_PER_BLOCK,
+        max_gas_per_block: chain_spec.max_gas_per_block,
         channel_size: CHANNEL_SIZE,
         bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
         broadcast_enabled: SETTINGS.api.enable_broadcast,
@@ -700,6 +770,17 @@ async fn launch(
             .force_keep_final_periods_without_ops,
     };
 
+    // launch the block archive worker, if final blocks are archived
+    let (block_archive_manager, block_archive_controller) =
+        match get_block_archive_config(chain_spec) {
+            Some(block_archive_config) => {
+                let (manager, controller) = start_block_archive_worker(block_archive_config)
+                    .expect("could not start block archive worker");
+                (Some(manager), Some(controller))
+            }
+            None => (None, None),
+        };
+
     let (consensus_event_sender, consensus_event_receiver) =
         MassaChannel::new("consensus_event".to_string(), Some(CHANNEL_SIZE));
     let consensus_channels = ConsensusChannels {
@@ -717,6 +798,7 @@ async fn launch(
             consensus_config.broadcast_filled_blocks_channel_capacity,
         )
         .0,
+        block_archive_controller: block_archive_controller.clone(),
     };
 
     let (consensus_controller, consensus_manager) = start_consensus_worker(
@@ -741,20 +823,34 @@ async fn launch(
     .expect("could not start protocol controller");
 
     // launch factory
+    let signer: Arc<dyn Signer> = if args.dev {
+        // the dev staker key is only held by the wallet of the dev chain
+        Arc::new(LocalSigner::new(node_wallet.clone()))
+    } else {
+        create_signer(node_wallet.clone())
+    };
     let factory_config = FactoryConfig {
-        thread_count: THREAD_COUNT,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        t0: T0,
+        thread_count: chain_spec.thread_count,
+        genesis_timestamp: chain_spec.genesis_timestamp,
+        t0: chain_spec.t0,
         initial_delay: SETTINGS.factory.initial_delay,
-        max_block_size: MAX_BLOCK_SIZE as u64,
-        max_block_gas: MAX_GAS_PER_BLOCK,
-        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        max_block_size: chain_spec.max_block_size as u64,
+        max_block_gas: chain_spec.max_gas_per_block,
+        max_operations_per_block: chain_spec.max_operations_per_block,
         last_start_period: final_state.read().last_start_period,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+        periods_per_cycle: chain_spec.periods_per_cycle,
+        denunciation_expire_periods: chain_spec.denunciation_expire_periods,
         stop_production_when_zero_connections: SETTINGS
             .factory
-            .stop_production_when_zero_connections,
+            .stop_production_when_zero_connections
+            && !args.dev,
+        signing_journal_path: if args.dev {
+            DevChain::signing_journal_path()
+        } else {
+            SETTINGS.factory.signing_journal_path.clone()
+        },
+        dev_mode: args.dev,
+        dev_operations_check_interval: SETTINGS.dev.operations_check_interval,
     };
     let factory_channels = FactoryChannels {
         selector: selector_controller.clone(),
@@ -763,9 +859,9 @@ async fn launch(
         protocol: protocol_controller.clone(),
         storage: shared_storage.clone(),
     };
-    let factory_manager = start_factory(
+    let (factory_manager, factory_controller) = start_factory(
         factory_config,
-        node_wallet.clone(),
+        signer.clone(),
         factory_channels,
         mip_store.clone(),
     );
@@ -793,6 +889,17 @@ async fn launch(
         .expect("Could not start bootstrap server")
     });
 
+    // API keys and rate limits shared by the public APIs, reloaded from the configuration files on demand
+    let access_controller = AccessController::new(
+        SETTINGS.api_access.clone(),
+        Some(Arc::new(|| {
+            try_build_massa_settings::<Settings>("massa-node", "MASSA_NODE")
+                .map(|settings| settings.api_access)
+                .map_err(|err| err.to_string())
+        })),
+        massa_metrics.clone(),
+    );
+
     let api_config: APIConfig = APIConfig {
         bind_private: SETTINGS.api.bind_private,
         bind_public: SETTINGS.api.bind_public,
@@ -816,14 +923,15 @@ async fn launch(
         max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
         max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
         max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
-        max_gas_per_block: MAX_GAS_PER_BLOCK,
+        max_gas_per_block: chain_spec.max_gas_per_block,
         max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
         max_parameter_size: MAX_PARAMETERS_SIZE,
-        thread_count: THREAD_COUNT,
+        thread_count: chain_spec.thread_count,
         keypair: keypair.clone(),
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        t0: T0,
-        periods_per_cycle: PERIODS_PER_CYCLE,
+        genesis_timestamp: chain_spec.genesis_timestamp,
+        t0: chain_spec.t0,
+        periods_per_cycle: chain_spec.periods_per_cycle,
+        compact_config: chain_spec.compact_config(),
         last_start_period: final_state.read().last_start_period,
     };
 
@@ -835,6 +943,7 @@ async fn launch(
         pool_channels.clone(),
         api_config.clone(),
         *VERSION,
+        access_controller.clone(),
     );
     let api_handle = api
         .serve(&SETTINGS.api.bind_api, &api_config)
@@ -855,6 +964,7 @@ async fn launch(
         let grpc_public_config = configure_grpc(
             ServiceName::Public,
             &SETTINGS.grpc.public,
+            chain_spec,
             keypair.clone(),
             &final_state,
         );
@@ -876,6 +986,7 @@ async fn launch(
             keypair_factory: KeyPairFactory {
                 mip_store: mip_store.clone(),
             },
+            access_controller: access_controller.clone(),
         };
 
         // Spawn gRPC PUBLIC API
@@ -895,6 +1006,7 @@ async fn launch(
         let grpc_private_config = configure_grpc(
             ServiceName::Private,
             &SETTINGS.grpc.private,
+            chain_spec,
             keypair.clone(),
             &final_state,
         );
@@ -935,7 +1047,7 @@ async fn launch(
 
     #[cfg(feature = "op_spammer")]
     start_operation_injector(
-        *GENESIS_TIMESTAMP,
+        chain_spec.genesis_timestamp,
         shared_storage.clone_without_refs(),
         node_wallet.read().clone(),
         pool_controller.clone(),
@@ -947,9 +1059,12 @@ async fn launch(
     let api_private = API::<Private>::new(
         protocol_controller.clone(),
         execution_controller.clone(),
+        factory_controller,
         api_config.clone(),
         sig_int_toggled,
         node_wallet,
+        signer,
+        access_controller.clone(),
     );
     let api_private_handle = api_private
         .serve(&SETTINGS.api.bind_private, &api_config)
@@ -973,6 +1088,8 @@ async fn launch(
         node_id,
         shared_storage.clone(),
         mip_store.clone(),
+        block_archive_controller,
+        access_controller,
     );
     let api_public_handle = api_public
         .serve(&SETTINGS.api.bind_public, &api_config)
@@ -1035,6 +1152,7 @@ async fn launch(
         pool_manager,
         protocol_manager,
         factory_manager,
+        block_archive_manager,
         api_private_handle,
         api_public_handle,
         api_handle,
@@ -1049,6 +1167,7 @@ async fn launch(
 fn configure_grpc(
     name: ServiceName,
     settings: &GrpcSettings,
+    chain_spec: &ChainSpec,
     keypair: KeyPair,
     final_state: &Arc<RwLock<FinalState>>,
 ) -> GrpcConfig {
@@ -1080,9 +1199,9 @@ fn configure_grpc(
         http2_keepalive_timeout: settings.http2_keepalive_timeout.map(|t| t.to_duration()),
         http2_adaptive_window: settings.http2_adaptive_window,
         max_frame_size: settings.max_frame_size,
-        thread_count: THREAD_COUNT,
-        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
-        endorsement_count: ENDORSEMENT_COUNT,
+        thread_count: chain_spec.thread_count,
+        max_operations_per_block: chain_spec.max_operations_per_block,
+        endorsement_count: chain_spec.endorsement_count,
         max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE,
         max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
         max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
@@ -1092,15 +1211,16 @@ fn configure_grpc(
         max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
         max_parameter_size: MAX_PARAMETERS_SIZE,
         max_operations_per_message: MAX_OPERATIONS_PER_MESSAGE,
-        max_gas_per_block: MAX_GAS_PER_BLOCK,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        t0: T0,
-        periods_per_cycle: PERIODS_PER_CYCLE,
+        max_gas_per_block: chain_spec.max_gas_per_block,
+        genesis_timestamp: chain_spec.genesis_timestamp,
+        t0: chain_spec.t0,
+        periods_per_cycle: chain_spec.periods_per_cycle,
+        compact_config: chain_spec.compact_config(),
         keypair,
         max_channel_size: settings.max_channel_size,
         draw_lookahead_period_count: settings.draw_lookahead_period_count,
         last_start_period: final_state.read().last_start_period,
-        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        max_denunciations_per_block_header: chain_spec.max_denunciations_per_block_header,
         max_addresses_per_request: settings.max_addresses_per_request,
         max_slot_ranges_per_request: settings.max_slot_ranges_per_request,
         max_block_ids_per_request: settings.max_block_ids_per_request,
@@ -1126,6 +1246,7 @@ struct Managers {
     pool_manager: Box<dyn PoolManager>,
     protocol_manager: Box<dyn ProtocolManager>,
     factory_manager: Box<dyn FactoryManager>,
+    block_archive_manager: Option<Box<dyn BlockArchiveManager>>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1139,6 +1260,7 @@ async fn stop(
         mut pool_manager,
         mut protocol_manager,
         mut factory_manager,
+        block_archive_manager,
     }: Managers,
     api_private_handle: StopHandle,
     api_public_handle: StopHandle,
@@ -1196,6 +1318,11 @@ async fn stop(
     // stop consensus
     consensus_manager.stop();
 
+    // stop block archive, once the blocks finalized before consensus stopped are archived
+    if let Some(mut block_archive_manager) = block_archive_manager {
+        block_archive_manager.stop();
+    }
+
     // stop pool
     pool_manager.stop();
 
@@ -1212,18 +1339,184 @@ async fn stop(
     // note that FinalLedger gets destroyed as soon as its Arc count goes to zero
 }
 
+/// Configuration of the block archive, `None` if final blocks are not archived
+fn get_block_archive_config(chain_spec: &ChainSpec) -> Option<BlockArchiveConfig> {
+    if SETTINGS.block_archive.retention == BlockArchiveRetention::None {
+        return None;
+    }
+    Some(BlockArchiveConfig {
+        path: SETTINGS.block_archive.path.clone(),
+        retention: SETTINGS.block_archive.retention,
+        thread_count: chain_spec.thread_count,
+        periods_per_cycle: chain_spec.periods_per_cycle,
+        endorsement_count: chain_spec.endorsement_count,
+        max_operations_per_block: chain_spec.max_operations_per_block,
+        max_denunciations_per_block_header: chain_spec.max_denunciations_per_block_header,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+        max_parameter_size: MAX_PARAMETERS_SIZE,
+        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        channel_size: CHANNEL_SIZE,
+    })
+}
+
+/// Configuration of the final state database
+fn get_db_config(chain_spec: &ChainSpec) -> MassaDBConfig {
+    MassaDBConfig {
+        path: SETTINGS.ledger.disk_ledger_path.clone(),
+        max_history_length: SETTINGS.ledger.final_history_length,
+        max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS as usize,
+        thread_count: chain_spec.thread_count,
+        enable_archive: SETTINGS.ledger.enable_archive,
+        archive_retention_periods: SETTINGS.ledger.archive_retention_periods,
+    }
+}
+
+/// Export the final state database of the node to a portable file
+fn export_final_state(path: &Path, chain_spec: &ChainSpec) -> anyhow::Result<()> {
+    if !SETTINGS.ledger.disk_ledger_path.exists() {
+        anyhow::bail!(
+            "no final state database found at {}",
+            SETTINGS.ledger.disk_ledger_path.display()
+        );
+    }
+    let db = MassaDB::new(get_db_config(chain_spec));
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let info = export_state(&db, file)?;
+    info!(
+        "Final state at slot {} exported to {} ({} state entries, {} versioning entries)",
+        info.slot,
+        path.display(),
+        info.state_entry_count,
+        info.versioning_entry_count
+    );
+    Ok(())
+}
+
+/// Import a final state exported with `--export-state` as the final state database of the node.
+/// The import is rejected if its hash does not match the one recorded in the file.
+fn import_final_state(path: &Path, chain_spec: &ChainSpec) -> anyhow::Result<()> {
+    if SETTINGS.ledger.disk_ledger_path.exists() {
+        anyhow::bail!(
+            "a final state database already exists at {}, remove it before importing",
+            SETTINGS.ledger.disk_ledger_path.display()
+        );
+    }
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    let mut db = MassaDB::new(get_db_config(chain_spec));
+    match import_state(&mut db, file) {
+        Ok(info) => {
+            info!(
+                "Final state at slot {} imported from {} ({} state entries, {} versioning entries)",
+                info.slot,
+                path.display(),
+                info.state_entry_count,
+                info.versioning_entry_count
+            );
+            Ok(())
+        }
+        Err(err) => {
+            // do not leave a partial or corrupted state behind
+            drop(db);
+            std::fs::remove_dir_all(&SETTINGS.ledger.disk_ledger_path)?;
+            Err(err.into())
+        }
+    }
+}
+
+/// Create the holder of the staking keys: the remote signer if one is configured, the node wallet otherwise
+fn create_signer(node_wallet: Arc<RwLock<Wallet>>) -> Arc<dyn Signer> {
+    match &SETTINGS.factory.remote_signer_socket_path {
+        #[cfg(unix)]
+        Some(socket_path) => {
+            info!("Using remote signer at {}", socket_path.display());
+            Arc::new(RemoteSigner::new(
+                socket_path.clone(),
+                SETTINGS.factory.remote_signer_timeout.to_duration(),
+                SETTINGS
+                    .factory
+                    .remote_signer_key_cache_duration
+                    .to_duration(),
+            ))
+        }
+        #[cfg(not(unix))]
+        Some(_) => panic!("remote signers are only supported on Unix"),
+        None => Arc::new(LocalSigner::new(node_wallet)),
+    }
+}
+
+/// Export the signing journal of the given staking addresses (all of them if none is given),
+/// to move their keys to another node
+fn export_signing_journal(
+    path: &Path,
+    addresses: &[Address],
+    chain_spec: &ChainSpec,
+) -> anyhow::Result<()> {
+    let journal = SigningJournal::open(
+        &SETTINGS.factory.signing_journal_path,
+        chain_spec.denunciation_expire_periods,
+    )?;
+    let count = journal.export(addresses, path)?;
+    info!("{} signing records exported to {}", count, path.display());
+    Ok(())
+}
+
+/// Import signing records exported with `--export-signing-journal` into the signing journal of the node
+fn import_signing_journal(path: &Path, chain_spec: &ChainSpec) -> anyhow::Result<()> {
+    let mut journal = SigningJournal::open(
+        &SETTINGS.factory.signing_journal_path,
+        chain_spec.denunciation_expire_periods,
+    )?;
+    let count = journal.import(path)?;
+    info!("{} signing records imported from {}", count, path.display());
+    Ok(())
+}
+
 #[derive(StructOpt)]
 struct Args {
     #[structopt(long = "keep-ledger")]
     keep_ledger: bool,
+    /// chain spec file defining the network parameters and genesis data.
+    /// The parameters of the public network are used if not given
+    #[structopt(long = "chain-spec", parse(from_os_str))]
+    chain_spec: Option<PathBuf>,
     /// Wallet password
     #[structopt(short = "p", long = "pwd")]
     password: Option<String>,
 
+    /// single-node dev mode: start a fresh local chain whose clock only advances on demand,
+    /// see the `[dev]` settings and the `node_dev_advance_slots` and `node_dev_advance_time` private APIs
+    #[structopt(long = "dev")]
+    dev: bool,
+
     /// restart_from_snapshot_at_period
     #[structopt(long = "restart-from-snapshot-at-period")]
     restart_from_snapshot_at_period: Option<u64>,
 
+    /// export the final state to a portable file, then exit
+    #[structopt(long = "export-state", parse(from_os_str))]
+    export_state: Option<PathBuf>,
+
+    /// import the final state from a file written by --export-state, then exit.
+    /// The node keeps the imported state when started with --restart-from-snapshot-at-period
+    #[structopt(long = "import-state", parse(from_os_str))]
+    import_state: Option<PathBuf>,
+
+    /// export the signing journal of the staking addresses given with --signing-journal-addresses
+    /// (all addresses if none given) to a file, then exit
+    #[structopt(long = "export-signing-journal", parse(from_os_str))]
+    export_signing_journal: Option<PathBuf>,
+
+    /// staking addresses whose signing journal is exported by --export-signing-journal
+    #[structopt(long = "signing-journal-addresses")]
+    signing_journal_addresses: Vec<Address>,
+
+    /// import a file written by --export-signing-journal into the signing journal, then exit
+    #[structopt(long = "import-signing-journal", parse(from_os_str))]
+    import_signing_journal: Option<PathBuf>,
+
     #[cfg(feature = "op_spammer")]
     /// number of operations
     #[structopt(
@@ -1246,6 +1539,29 @@ struct Args {
     dl_interval: u64,
 }
 
+/// Load the chain spec file if one is given, the parameters of the public network otherwise.
+/// The genesis files not defined by the chain spec are taken from the node settings.
+fn load_chain_spec(path: Option<&Path>) -> anyhow::Result<ChainSpec> {
+    let mut chain_spec = match path {
+        Some(path) => {
+            info!("Loading chain spec from {}", path.display());
+            ChainSpec::from_file(path)?
+        }
+        None => ChainSpec::default(),
+    };
+    chain_spec
+        .initial_ledger_path
+        .get_or_insert_with(|| SETTINGS.ledger.initial_ledger_path.clone());
+    chain_spec
+        .initial_rolls_path
+        .get_or_insert_with(|| SETTINGS.selector.initial_rolls_path.clone());
+    if chain_spec.initial_deferred_credits_path.is_none() {
+        chain_spec.initial_deferred_credits_path =
+            SETTINGS.ledger.initial_deferred_credits_path.clone();
+    }
+    Ok(chain_spec)
+}
+
 /// Load wallet, asking for passwords if necessary
 fn load_wallet(password: Option<String>, path: &Path) -> anyhow::Result<Arc<RwLock<Wallet>>> {
     let password = if path.is_dir() {
@@ -1318,11 +1634,65 @@ async fn run(args: Args) -> anyhow::Result<()> {
 
     info!("Node version : {}", *VERSION);
 
+    let (chain_spec, dev_staker) = if cur_args.dev {
+        if cur_args.chain_spec.is_some()
+            || cur_args.keep_ledger
+            || cur_args.restart_from_snapshot_at_period.is_some()
+        {
+            anyhow::bail!(
+                "--dev can't be combined with --chain-spec, --keep-ledger or --restart-from-snapshot-at-period"
+            );
+        }
+        // the clock only moves forward on demand from now on
+        let now = MassaTime::now()?;
+        MassaTime::freeze_clock(now);
+        let dev_chain = create_dev_chain(now)?;
+        warn!(
+            "Dev mode: fresh local chain in {}, the clock is frozen and only advances on demand",
+            SETTINGS.dev.genesis_dir.display()
+        );
+        (dev_chain.chain_spec, Some(dev_chain.staker))
+    } else {
+        (load_chain_spec(cur_args.chain_spec.as_deref())?, None)
+    };
+    let chain_id = chain_spec.compute_hash()?;
+    info!("Chain spec hash : {}", chain_id);
+
+    // offline final state tools
+    if let Some(path) = &cur_args.export_state {
+        return export_final_state(path, &chain_spec);
+    }
+    if let Some(path) = &cur_args.import_state {
+        return import_final_state(path, &chain_spec);
+    }
+
+    // offline signing journal tools
+    if let Some(path) = &cur_args.export_signing_journal {
+        return export_signing_journal(path, &cur_args.signing_journal_addresses, &chain_spec);
+    }
+    if let Some(path) = &cur_args.import_signing_journal {
+        return import_signing_journal(path, &chain_spec);
+    }
+
     // load or create wallet, asking for password if necessary
-    let node_wallet = load_wallet(
-        cur_args.password.clone(),
-        &SETTINGS.factory.staking_wallet_path,
-    )?;
+    let node_wallet = match dev_staker {
+        Some(staker) => {
+            let wallet = load_wallet(
+                Some(cur_args.password.clone().unwrap_or_default()),
+                &DevChain::staking_wallet_path(),
+            )?;
+            let staker_address = wallet.write().add_keypairs(vec![staker.clone()])?;
+            info!(
+                "Dev staker: address {}, secret key {}",
+                staker_address[0], staker
+            );
+            wallet
+        }
+        None => load_wallet(
+            cur_args.password.clone(),
+            &SETTINGS.factory.staking_wallet_path,
+        )?,
+    };
 
     // interrupt signal listener
     let sig_int_toggled = Arc::new((Mutex::new(false), Condvar::new()));
@@ -1350,6 +1720,7 @@ async fn run(args: Args) -> anyhow::Result<()> {
             pool_manager,
             protocol_manager,
             factory_manager,
+            block_archive_manager,
             api_private_handle,
             api_public_handle,
             api_handle,
@@ -1357,7 +1728,14 @@ async fn run(args: Args) -> anyhow::Result<()> {
             grpc_public_handle,
             metrics_stopper,
             massa_survey_stopper,
-        ) = launch(&cur_args, node_wallet.clone(), Arc::clone(&sig_int_toggled)).await;
+        ) = launch(
+            &cur_args,
+            &chain_spec,
+            chain_id,
+            node_wallet.clone(),
+            Arc::clone(&sig_int_toggled),
+        )
+        .await;
 
         // loop over messages
         let restart = loop {
@@ -1416,6 +1794,7 @@ async fn run(args: Args) -> anyhow::Result<()> {
                 pool_manager,
                 protocol_manager,
                 factory_manager,
+                block_archive_manager,
             },
             api_private_handle,
             api_public_handle,
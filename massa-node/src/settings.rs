@@ -3,8 +3,10 @@
 //! Build here the default node settings from the configuration file toml
 use std::{collections::HashMap, path::PathBuf};
 
+use massa_api_access::AccessConfig;
+use massa_block_archive_exports::BlockArchiveRetention;
 use massa_bootstrap::IpType;
-use massa_models::{config::build_massa_settings, node::NodeId};
+use massa_models::{amount::Amount, config::build_massa_settings, node::NodeId};
 use massa_protocol_exports::PeerCategoryInfo;
 use massa_time::MassaTime;
 use serde::Deserialize;
@@ -34,6 +36,14 @@ pub struct ExecutionSettings {
     pub snip_amount: usize,
     /// slot execution outputs channel capacity
     pub broadcast_slot_execution_output_channel_capacity: usize,
+    /// whether call traces are recorded for executed operations and asynchronous messages
+    pub execution_traces_enabled: bool,
+    /// number of final slots for which execution traces are kept
+    pub execution_traces_slot_limit: usize,
+    /// whether the history of the addresses affected by final slots is indexed
+    pub address_history_enabled: bool,
+    /// path to the address history database
+    pub address_history_path: PathBuf,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -41,12 +51,23 @@ pub struct SelectionSettings {
     pub initial_rolls_path: PathBuf,
 }
 
+/// Archive of the final blocks pruned from consensus
+#[derive(Clone, Debug, Deserialize)]
+pub struct BlockArchiveSettings {
+    /// path of the archive database
+    pub path: PathBuf,
+    /// how long archived blocks are kept
+    pub retention: BlockArchiveRetention,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct LedgerSettings {
     pub initial_ledger_path: PathBuf,
     pub disk_ledger_path: PathBuf,
     pub final_history_length: usize,
     pub initial_deferred_credits_path: Option<PathBuf>,
+    pub enable_archive: bool,
+    pub archive_retention_periods: u64,
 }
 
 /// Bootstrap configuration.
@@ -67,6 +88,7 @@ pub struct BootstrapSettings {
     pub max_clock_delta: MassaTime,
     pub cache_duration: MassaTime,
     pub max_simultaneous_bootstraps: u32,
+    pub max_parallel_bootstrap_servers: usize,
     pub per_ip_min_interval: MassaTime,
     pub ip_list_max_size: usize,
     pub rate_limit: u64,
@@ -83,6 +105,31 @@ pub struct FactorySettings {
     pub staking_wallet_path: PathBuf,
     /// stop the production in case we are not connected to anyone
     pub stop_production_when_zero_connections: bool,
+    /// path of the journal of signed blocks and endorsements
+    pub signing_journal_path: PathBuf,
+    /// path of the Unix socket of the remote signer, if any
+    pub remote_signer_socket_path: Option<PathBuf>,
+    /// timeout of the requests to the remote signer
+    pub remote_signer_timeout: MassaTime,
+    /// duration during which the keys held by the remote signer are cached
+    pub remote_signer_key_cache_duration: MassaTime,
+}
+
+/// Settings of the single-node dev mode (`--dev`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct DevSettings {
+    /// directory where the dev chain (genesis files, staking wallet, peers) is written. Recreated at each start
+    pub genesis_dir: PathBuf,
+    /// optional JSON file of the accounts funded at genesis: `{"<address>": "<balance>"}`
+    pub prefunded_accounts_path: Option<PathBuf>,
+    /// genesis balance of the dev staker
+    pub staker_balance: Amount,
+    /// rolls of the dev staker
+    pub staker_rolls: u64,
+    /// time between two periods of a thread of the dev chain
+    pub t0: MassaTime,
+    /// interval at which the pool is checked for new operations to include in a block
+    pub operations_check_interval: MassaTime,
 }
 
 /// Pool configuration, read from a file configuration
@@ -90,6 +137,8 @@ pub struct FactorySettings {
 pub struct PoolSettings {
     pub max_operation_pool_size: usize,
     pub max_operation_pool_excess_items: usize,
+    pub operation_drop_history_size: usize,
+    pub fee_stats_periods: u64,
     pub operation_max_future_start_delay: MassaTime,
     pub operation_pool_refresh_interval: MassaTime,
     pub max_endorsements_pool_size_per_thread: usize,
@@ -129,16 +178,19 @@ pub struct Settings {
     pub protocol: ProtocolSettings,
     pub consensus: ConsensusSettings,
     pub api: APISettings,
+    pub api_access: AccessConfig,
     pub network: NetworkSettings,
     pub bootstrap: BootstrapSettings,
     pub pool: PoolSettings,
     pub execution: ExecutionSettings,
     pub ledger: LedgerSettings,
+    pub block_archive: BlockArchiveSettings,
     pub selector: SelectionSettings,
     pub factory: FactorySettings,
     pub grpc: GrpcApiSettings,
     pub metrics: MetricsSettings,
     pub versioning: VersioningSettings,
+    pub dev: DevSettings,
 }
 
 /// Consensus configuration
@@ -235,6 +287,14 @@ pub struct ProtocolSettings {
     pub max_endorsements_propagation_time: MassaTime,
     /// Path for initial peers
     pub initial_peers_file: PathBuf,
+    /// Path of the file where the peer database is persisted between restarts
+    pub peers_db_file: PathBuf,
+    /// Max number of peers kept in the persisted peer database
+    pub max_peers_db_size: usize,
+    /// Path of the file where the static peers added at runtime are persisted between restarts
+    pub static_peers_file: PathBuf,
+    /// Tested addresses older than this are pruned from the peer database
+    pub tested_addresses_max_age: MassaTime,
     /// Keypair
     pub keypair_file: PathBuf,
     /// Ip we are bind to listen to
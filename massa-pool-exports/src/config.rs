@@ -27,6 +27,10 @@ pub struct PoolConfig {
     pub max_operation_pool_size: usize,
     /// max excess on pool size (in-between refreshes)
     pub max_operation_pool_excess_items: usize,
+    /// number of operations that left the pool whose drop reason is remembered
+    pub operation_drop_history_size: usize,
+    /// number of recent periods over which the minimum fee of included operations is computed
+    pub fee_stats_periods: u64,
     /// max endorsement pool size per thread (in number of endorsements)
     pub max_endorsements_pool_size_per_thread: usize,
     /// max number of endorsements per block
@@ -9,6 +9,8 @@ use massa_models::{
 };
 use massa_storage::Storage;
 
+use crate::{OperationPoolStats, OperationPoolStatus};
+
 /// Trait defining a pool controller
 pub trait PoolController: Send + Sync {
     /// Asynchronously add operations to pool. Simply print a warning on failure.
@@ -48,6 +50,12 @@ pub trait PoolController: Send + Sync {
     /// Check if the pool contains a list of operations. Returns one boolean per item.
     fn contains_operations(&self, operations: &[OperationId]) -> Vec<bool>;
 
+    /// Get statistics about the operations of the pool and their fees
+    fn get_operation_pool_stats(&self) -> OperationPoolStats;
+
+    /// Get the status of a list of operations in the pool. Returns one status per item.
+    fn get_operation_pool_status(&self, operations: &[OperationId]) -> Vec<OperationPoolStatus>;
+
     /// Check if the pool contains a denunciation. Returns a boolean
     #[cfg(feature = "testing")]
     fn contains_denunciation(&self, denunciation: &Denunciation) -> bool;
@@ -19,6 +19,8 @@ impl Default for PoolConfig {
             max_block_size: MAX_BLOCK_SIZE,
             max_operation_pool_size: 32000,
             max_operation_pool_excess_items: 10000,
+            operation_drop_history_size: 10000,
+            fee_stats_periods: 10,
             max_endorsements_pool_size_per_thread: 1000,
             max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
             max_block_endorsement_count: ENDORSEMENT_COUNT,
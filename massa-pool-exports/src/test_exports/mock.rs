@@ -17,7 +17,7 @@ use massa_models::{
 use massa_storage::Storage;
 use massa_time::MassaTime;
 
-use crate::PoolController;
+use crate::{OperationPoolStats, OperationPoolStatus, PoolController};
 
 /// Test tool to mock pool controller responses
 pub struct PoolEventReceiver(pub Receiver<MockPoolControllerMessage>);
@@ -95,6 +95,18 @@ pub enum MockPoolControllerMessage {
         /// Response channel
         response_tx: mpsc::Sender<Vec<bool>>,
     },
+    /// Get the operation pool statistics
+    GetOperationPoolStats {
+        /// Response channel
+        response_tx: mpsc::Sender<OperationPoolStats>,
+    },
+    /// Get the pool status of operations
+    GetOperationPoolStatus {
+        /// ids to search
+        ids: Vec<OperationId>,
+        /// Response channel
+        response_tx: mpsc::Sender<Vec<OperationPoolStatus>>,
+    },
     /// Get stats of the pool
     GetStats {
         /// Response channel
@@ -247,6 +259,29 @@ impl PoolController for MockPoolController {
         response_rx.recv().unwrap()
     }
 
+    fn get_operation_pool_stats(&self) -> OperationPoolStats {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.q
+            .lock()
+            .unwrap()
+            .send(MockPoolControllerMessage::GetOperationPoolStats { response_tx })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
+    fn get_operation_pool_status(&self, operations: &[OperationId]) -> Vec<OperationPoolStatus> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.q
+            .lock()
+            .unwrap()
+            .send(MockPoolControllerMessage::GetOperationPoolStatus {
+                ids: operations.to_vec(),
+                response_tx,
+            })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
     fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         self.last_final_cs_periods = final_cs_periods.to_vec();
         self.q
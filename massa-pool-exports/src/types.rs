@@ -0,0 +1,105 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::amount::Amount;
+use serde::{Deserialize, Serialize};
+
+/// Percentiles of a fee distribution, in nanoMAS per unit (gas or byte)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FeePercentiles {
+    /// 10th percentile
+    pub p10: f64,
+    /// 25th percentile
+    pub p25: f64,
+    /// median
+    pub p50: f64,
+    /// 75th percentile
+    pub p75: f64,
+    /// 90th percentile
+    pub p90: f64,
+}
+
+impl FeePercentiles {
+    /// Compute the percentiles of a list of values with the nearest-rank method.
+    /// Returns `None` if the list is empty.
+    pub fn from_values(mut values: Vec<f64>) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_unstable_by(|a, b| a.total_cmp(b));
+        let percentile = |p: usize| {
+            let rank = ((p * values.len() + 99) / 100).max(1);
+            values[rank - 1]
+        };
+        Some(FeePercentiles {
+            p10: percentile(10),
+            p25: percentile(25),
+            p50: percentile(50),
+            p75: percentile(75),
+            p90: percentile(90),
+        })
+    }
+}
+
+/// Statistics about the operations of the pool and their fees
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationPoolStats {
+    /// number of operations in the pool
+    pub operation_count: usize,
+    /// maximum number of operations kept in the pool after a refresh
+    pub max_operation_count: usize,
+    /// number of operations in the pool, per thread
+    pub operation_count_per_thread: Vec<usize>,
+    /// fee per gas of the pooled operations using gas, `None` if there is none
+    pub fee_per_gas: Option<FeePercentiles>,
+    /// fee per byte of the pooled operations, `None` if the pool is empty
+    pub fee_per_byte: Option<FeePercentiles>,
+    /// minimum fee of the pooled operations recently included in blocks,
+    /// `None` if none was included recently
+    pub min_included_fee: Option<Amount>,
+    /// minimum fee per gas, in nanoMAS, of the pooled operations using gas recently included in blocks
+    pub min_included_fee_per_gas: Option<f64>,
+    /// minimum fee per byte, in nanoMAS, of the pooled operations recently included in blocks
+    pub min_included_fee_per_byte: Option<f64>,
+}
+
+/// Why an operation left the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationDropReason {
+    /// executed in a candidate or final block
+    Executed,
+    /// its validity period ended
+    Expired,
+    /// it uses more gas or space than a block allows
+    ExceedsBlockLimits,
+    /// this node has no block to produce during its validity period; other nodes may still include it
+    NoProductionOpportunity,
+    /// its sender cannot pay its fee and spendings, on top of the other pooled operations of the sender
+    InsufficientBalance,
+    /// the pool was full and the operation had a lower score than the kept ones
+    Evicted,
+    /// replaced by an operation of the same sender paying a higher fee
+    Superseded,
+    /// it declares superseding a pooled operation that pays a higher or equal fee
+    ReplacementUnderpriced,
+    /// its operation type is not enabled by versioning yet
+    NotEnabled,
+}
+
+/// Status of an operation in the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationPoolStatus {
+    /// the operation is in the pool
+    Pooled {
+        /// rank of the operation in the pool, from the best score (0).
+        /// Operations received since the last refresh are not scored yet and ranked last.
+        rank: usize,
+        /// rank of the operation among the pooled operations of its thread
+        thread_rank: usize,
+    },
+    /// the operation recently left the pool
+    Dropped(OperationDropReason),
+    /// the operation was never received, or left the pool long ago
+    Unknown,
+}
@@ -6,7 +6,9 @@ use massa_models::{
     block_id::BlockId, denunciation::Denunciation, denunciation::DenunciationPrecursor,
     endorsement::EndorsementId, operation::OperationId, slot::Slot,
 };
-use massa_pool_exports::{PoolConfig, PoolController, PoolManager};
+use massa_pool_exports::{
+    OperationPoolStats, OperationPoolStatus, PoolConfig, PoolController, PoolManager,
+};
 use massa_storage::Storage;
 use parking_lot::RwLock;
 use std::sync::mpsc::TrySendError;
@@ -216,6 +218,16 @@ impl PoolController for PoolControllerImpl {
         operations.iter().map(|id| lck.contains(id)).collect()
     }
 
+    /// Get statistics about the operations of the pool and their fees
+    fn get_operation_pool_stats(&self) -> OperationPoolStats {
+        self.operation_pool.read().get_stats()
+    }
+
+    /// Get the status of a list of operations in the pool. Returns one status per item.
+    fn get_operation_pool_status(&self, operations: &[OperationId]) -> Vec<OperationPoolStatus> {
+        self.operation_pool.read().get_statuses(operations)
+    }
+
     /// Check if the pool contains a denunciation. Returns a boolean
     #[cfg(feature = "testing")]
     fn contains_denunciation(&self, denunciation: &Denunciation) -> bool {
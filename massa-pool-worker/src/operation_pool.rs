@@ -3,21 +3,43 @@
 use massa_models::{
     address::Address,
     amount::Amount,
-    operation::OperationId,
+    operation::{OperationId, OperationType},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     slot::Slot,
     timeslots::get_latest_block_slot_at_timestamp,
 };
-use massa_pool_exports::{PoolChannels, PoolConfig};
+use massa_pool_exports::{
+    FeePercentiles, OperationDropReason, OperationPoolStats, OperationPoolStatus, PoolChannels,
+    PoolConfig,
+};
 use massa_storage::Storage;
 use massa_time::MassaTime;
+use massa_versioning::versioning::{MipComponent, MipStore};
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
-use std::{cmp::max, cmp::Ordering, cmp::PartialOrd, collections::BTreeSet, sync::Arc};
+use std::{
+    cmp::max,
+    cmp::Ordering,
+    cmp::PartialOrd,
+    collections::{BTreeSet, VecDeque},
+    sync::Arc,
+};
 use tracing::{debug, trace, warn};
 
 use crate::types::OperationInfo;
 
+/// Minimum fees of the pooled operations found included in blocks during a refresh
+struct IncludedFees {
+    /// period at which the operations were found included
+    period: u64,
+    /// minimum fee
+    min_fee: Amount,
+    /// minimum fee per gas, `None` if no included operation uses gas
+    min_fee_per_gas: Option<f64>,
+    /// minimum fee per byte
+    min_fee_per_byte: f64,
+}
+
 pub struct OperationPool {
     /// configuration
     config: PoolConfig,
@@ -36,6 +58,24 @@ pub struct OperationPool {
 
     /// staking wallet, to know which addresses we are using to stake
     wallet: Arc<RwLock<Wallet>>,
+
+    /// versioning store, to reject operations whose type is not enabled yet
+    mip_store: MipStore,
+
+    /// reasons why recently dropped operations left the pool
+    dropped_ops: PreHashMap<OperationId, OperationDropReason>,
+
+    /// recently dropped operations, from the oldest to the newest drop
+    dropped_ops_order: VecDeque<OperationId>,
+
+    /// minimum fees of the operations included in blocks during the last `fee_stats_periods` periods
+    included_fees: VecDeque<IncludedFees>,
+
+    /// operations superseded by a received operation, with the ID of their replacement
+    superseded_by: PreHashMap<OperationId, OperationId>,
+
+    /// operations required by pooled operations that were executed, as of the last refresh
+    executed_required_ops: PreHashSet<OperationId>,
 }
 
 impl OperationPool {
@@ -44,6 +84,7 @@ impl OperationPool {
         storage: &Storage,
         channels: PoolChannels,
         wallet: Arc<RwLock<Wallet>>,
+        mip_store: MipStore,
     ) -> Self {
         OperationPool {
             sorted_ops: Vec::with_capacity(
@@ -56,9 +97,28 @@ impl OperationPool {
             storage: storage.clone_without_refs(),
             channels,
             wallet,
+            mip_store,
+            dropped_ops: PreHashMap::default(),
+            dropped_ops_order: VecDeque::new(),
+            included_fees: VecDeque::new(),
+            superseded_by: PreHashMap::default(),
+            executed_required_ops: PreHashSet::default(),
         }
     }
 
+    /// Get the period of the latest slot at the current time
+    fn now_period(&self) -> u64 {
+        let now = MassaTime::now().expect("could not get current time");
+        get_latest_block_slot_at_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            now,
+        )
+        .expect("could not get current slot")
+        .map_or(0, |s| s.period)
+    }
+
     /// Get the relevant PoS draws of our staking addresses
     fn get_pos_draws(&mut self) -> BTreeSet<Slot> {
         let now = MassaTime::now().expect("could not get current time");
@@ -124,6 +184,29 @@ impl OperationPool {
             .collect()
     }
 
+    /// Get the operations required by pooled ops that were executed in final or candidate slots
+    fn get_executed_required_ops(&self) -> PreHashSet<OperationId> {
+        let op_ids: Vec<OperationId> = self
+            .sorted_ops
+            .iter()
+            .filter_map(|op_info| op_info.after)
+            .collect::<PreHashSet<OperationId>>()
+            .into_iter()
+            .collect();
+        if op_ids.is_empty() {
+            return PreHashSet::default();
+        }
+        self.channels
+            .execution_controller
+            .get_ops_exec_status(&op_ids)
+            .into_iter()
+            .zip(op_ids)
+            .filter_map(|((spec_status, final_status), op_id)| {
+                (spec_status.is_some() || final_status.is_some()).then_some(op_id)
+            })
+            .collect()
+    }
+
     /// Get the candidate balances of the addresses sending the ops.
     /// Addresses that don't exist are not returned.
     fn get_sender_balances(&self) -> PreHashMap<Address, Amount> {
@@ -151,49 +234,108 @@ impl OperationPool {
         pos_draws: &BTreeSet<Slot>,
         sender_balances: &PreHashMap<Address, Amount>,
     ) {
-        let mut removed = PreHashSet::default();
+        let mut removed = PreHashMap::default();
+        let mut included = Vec::new();
         self.sorted_ops.retain(|op_info| {
-            // filter out ops that use too much resources
-            let mut retain = (op_info.max_gas <= self.config.max_block_gas)
-                && (op_info.size <= self.config.max_block_size as usize);
-
-            // filter out ops that are not valid during our PoS draws
-            if retain {
-                retain = pos_draws.iter().any(|slot| {
-                    op_info.thread == slot.thread
-                        && op_info.validity_period_range.contains(&slot.period)
-                });
-            }
+            let reason = if exec_statuses.contains_key(&op_info.id) {
+                // filter out ops that have been executed in final or candidate slots
+                // TODO: in the re-execution followup, we should only filter out final-executed ops here (exec_status == Some(true))
+                included.push(op_info.clone());
+                Some(OperationDropReason::Executed)
+            } else if op_info.max_gas > self.config.max_block_gas
+                || op_info.size > self.config.max_block_size as usize
+            {
+                // filter out ops that use too much resources
+                Some(OperationDropReason::ExceedsBlockLimits)
+            } else if !pos_draws.iter().any(|slot| {
+                op_info.thread == slot.thread
+                    && op_info.validity_period_range.contains(&slot.period)
+            }) {
+                // filter out ops that are not valid during our PoS draws
+                if *op_info.validity_period_range.end()
+                    <= self.last_cs_final_periods[op_info.thread as usize]
+                {
+                    Some(OperationDropReason::Expired)
+                } else {
+                    Some(OperationDropReason::NoProductionOpportunity)
+                }
+            } else {
+                // filter out ops that spend more than the sender's balance,
+                // or for which the sender does not exist
+                match sender_balances.get(&op_info.creator_address) {
+                    Some(v) if &op_info.max_spending <= v => None,
+                    _ => Some(OperationDropReason::InsufficientBalance),
+                }
+            };
 
-            // filter out ops that have been executed in final or candidate slots
-            // TODO: in the re-execution followup, we should only filter out final-executed ops here (exec_status == Some(true))
-            if retain {
-                retain = !exec_statuses.contains_key(&op_info.id);
+            if let Some(reason) = reason {
+                removed.insert(op_info.id, reason);
+                return false;
             }
+            true
+        });
+        self.record_included_fees(&included);
+        self.drop_operations(removed);
+    }
 
-            // filter out ops that spend more than the sender's balance
-            if retain {
-                retain = match sender_balances.get(&op_info.creator_address) {
-                    Some(v) => &op_info.max_spending <= v,
-                    None => false, // filter out ops for which the sender does not exist
-                };
+    /// Remember the minimum fees of the pooled operations found included in blocks,
+    /// and forget the ones found more than `fee_stats_periods` periods ago.
+    fn record_included_fees(&mut self, included: &[OperationInfo]) {
+        let now_period = self.now_period();
+        while let Some(fees) = self.included_fees.front() {
+            if fees.period.saturating_add(self.config.fee_stats_periods) > now_period {
+                break;
             }
+            self.included_fees.pop_front();
+        }
 
-            if !retain {
-                removed.insert(op_info.id);
-                return false;
-            }
-            true
+        let Some(min_fee) = included.iter().map(|op_info| op_info.fee).min() else {
+            return;
+        };
+        let min_fee_per_gas = included
+            .iter()
+            .filter_map(OperationInfo::fee_per_gas)
+            .min_by(f64::total_cmp);
+        let min_fee_per_byte = included
+            .iter()
+            .map(OperationInfo::fee_per_byte)
+            .min_by(f64::total_cmp)
+            .unwrap_or_default();
+        self.included_fees.push_back(IncludedFees {
+            period: now_period,
+            min_fee,
+            min_fee_per_gas,
+            min_fee_per_byte,
         });
-        // drop from storage
-        self.storage.drop_operation_refs(&removed);
+    }
+
+    /// Drop operations from storage and remember why they left the pool
+    fn drop_operations(&mut self, removed: PreHashMap<OperationId, OperationDropReason>) {
+        let removed_ids: PreHashSet<OperationId> = removed.keys().copied().collect();
+        self.storage.drop_operation_refs(&removed_ids);
+        for (id, reason) in removed {
+            self.record_drop_reason(id, reason);
+        }
+    }
+
+    /// Remember why an operation left the pool,
+    /// forgetting the oldest drops beyond `operation_drop_history_size`
+    fn record_drop_reason(&mut self, id: OperationId, reason: OperationDropReason) {
+        if self.dropped_ops.insert(id, reason).is_none() {
+            self.dropped_ops_order.push_back(id);
+        }
+        while self.dropped_ops_order.len() > self.config.operation_drop_history_size {
+            if let Some(oldest) = self.dropped_ops_order.pop_front() {
+                self.dropped_ops.remove(&oldest);
+            }
+        }
     }
 
     /// Eliminate all operations that would cause a sender balance overflow.
     /// Assumes that the ops are sorted by ascending score.
     fn eliminate_balance_overflows(&mut self, sender_balances: &PreHashMap<Address, Amount>) {
         let mut balance_cache = PreHashMap::default();
-        let mut removed = PreHashSet::default();
+        let mut removed = PreHashMap::default();
         self.sorted_ops.retain(|op_info| {
             let balance = balance_cache
                 .entry(op_info.creator_address)
@@ -209,30 +351,26 @@ impl OperationPool {
                     true
                 }
                 None => {
-                    removed.insert(op_info.id);
+                    removed.insert(op_info.id, OperationDropReason::InsufficientBalance);
                     false
                 }
             }
         });
-        // drop from storage
-        self.storage.drop_operation_refs(&removed);
+        self.drop_operations(removed);
     }
 
     /// Truncates the container to the max allowed size
     fn truncate_container(&mut self) {
         if self.sorted_ops.len() > self.config.max_operation_pool_size {
-            let mut removed = PreHashSet::default();
-            for op_info in self
+            let removed = self
                 .sorted_ops
                 .iter()
                 .skip(self.config.max_operation_pool_size)
-            {
-                removed.insert(op_info.id);
-            }
+                .map(|op_info| (op_info.id, OperationDropReason::Evicted))
+                .collect();
             self.sorted_ops
                 .truncate(self.config.max_operation_pool_size);
-            // drop from storage
-            self.storage.drop_operation_refs(&removed);
+            self.drop_operations(removed);
         }
     }
 
@@ -242,15 +380,7 @@ impl OperationPool {
         _exec_statuses: &PreHashMap<OperationId, bool>,
         pos_draws: &BTreeSet<Slot>,
     ) -> PreHashMap<OperationId, f32> {
-        let now = MassaTime::now().expect("could not get current time");
-        let now_period = get_latest_block_slot_at_timestamp(
-            self.config.thread_count,
-            self.config.t0,
-            self.config.genesis_timestamp,
-            now,
-        )
-        .expect("could not get current slot")
-        .map_or(0, |s| s.period);
+        let now_period = self.now_period();
 
         let mut scores = PreHashMap::with_capacity(self.sorted_ops.len());
         for op_info in &self.sorted_ops {
@@ -357,6 +487,14 @@ impl OperationPool {
 
         // eliminate container size overflows
         self.truncate_container();
+
+        // get the executed operations required by the remaining ops
+        self.executed_required_ops = self.get_executed_required_ops();
+
+        // forget the replacements that left the storage
+        let ops = self.storage.read_operations();
+        self.superseded_by
+            .retain(|_, replacement_id| ops.get(replacement_id).is_some());
     }
 
     /// Get the number of stored elements
@@ -369,6 +507,70 @@ impl OperationPool {
         self.storage.get_op_refs().contains(id)
     }
 
+    /// Get statistics about the pooled operations and their fees
+    pub(crate) fn get_stats(&self) -> OperationPoolStats {
+        let mut operation_count_per_thread = vec![0; self.config.thread_count as usize];
+        for op_info in &self.sorted_ops {
+            operation_count_per_thread[op_info.thread as usize] += 1;
+        }
+        OperationPoolStats {
+            operation_count: self.sorted_ops.len(),
+            max_operation_count: self.config.max_operation_pool_size,
+            operation_count_per_thread,
+            fee_per_gas: FeePercentiles::from_values(
+                self.sorted_ops
+                    .iter()
+                    .filter_map(OperationInfo::fee_per_gas)
+                    .collect(),
+            ),
+            fee_per_byte: FeePercentiles::from_values(
+                self.sorted_ops
+                    .iter()
+                    .map(OperationInfo::fee_per_byte)
+                    .collect(),
+            ),
+            min_included_fee: self.included_fees.iter().map(|fees| fees.min_fee).min(),
+            min_included_fee_per_gas: self
+                .included_fees
+                .iter()
+                .filter_map(|fees| fees.min_fee_per_gas)
+                .min_by(f64::total_cmp),
+            min_included_fee_per_byte: self
+                .included_fees
+                .iter()
+                .map(|fees| fees.min_fee_per_byte)
+                .min_by(f64::total_cmp),
+        }
+    }
+
+    /// Get the status of a list of operations in the pool. Returns one status per item.
+    pub(crate) fn get_statuses(&self, ids: &[OperationId]) -> Vec<OperationPoolStatus> {
+        let requested: PreHashSet<OperationId> = ids.iter().copied().collect();
+        let mut pooled = PreHashMap::with_capacity(requested.len());
+        let mut thread_counts = vec![0; self.config.thread_count as usize];
+        for (rank, op_info) in self.sorted_ops.iter().enumerate() {
+            let thread_rank = thread_counts[op_info.thread as usize];
+            thread_counts[op_info.thread as usize] += 1;
+            if requested.contains(&op_info.id) {
+                pooled.insert(
+                    op_info.id,
+                    OperationPoolStatus::Pooled { rank, thread_rank },
+                );
+            }
+        }
+        ids.iter()
+            .map(|id| {
+                if let Some(status) = pooled.get(id) {
+                    *status
+                } else if let Some(reason) = self.dropped_ops.get(id) {
+                    OperationPoolStatus::Dropped(*reason)
+                } else {
+                    OperationPoolStatus::Unknown
+                }
+            })
+            .collect()
+    }
+
     /// notify of new final slot
     pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         // update internal final slot counter
@@ -397,6 +599,7 @@ impl OperationPool {
         for _ in 0..dropped_items {
             if let Some(id) = new_op_ids.iter().next().copied() {
                 new_op_ids.remove(&id);
+                self.record_drop_reason(id, OperationDropReason::Evicted);
             } else {
                 break;
             }
@@ -412,13 +615,77 @@ impl OperationPool {
         // Note that the added items are put at the end of the sorted ops
         // so that they can still be picked for block production before refresh but with low priority
         // because in that case we don't know anything about their quality.
+        //
+        // An operation declaring that it supersedes a pooled operation of the same sender,
+        // expiring at the declared period, replaces it if it pays a higher fee,
+        // and is rejected otherwise.
+        // Operations for which such a replacement was received are rejected.
+        let mut rejected = PreHashMap::default();
+        let mut replaced = PreHashMap::default();
         {
             let ops = ops_storage.read_operations();
+            let mut accepted = PreHashSet::default();
+            // multisig operations could not be included in a block before their activation
+            let multisig_enabled = self.mip_store.get_latest_component_version_at(
+                &MipComponent::Multisig,
+                MassaTime::now().expect("could not get current time"),
+            ) > 0;
             for new_op_id in &new_op_ids {
                 let op = ops
                     .get(new_op_id)
                     .expect("operation not found in storage but listed as owned");
 
+                if !multisig_enabled && matches!(op.content.op, OperationType::Multisig { .. }) {
+                    rejected.insert(*new_op_id, OperationDropReason::NotEnabled);
+                    continue;
+                }
+
+                let superseded = self
+                    .superseded_by
+                    .get(new_op_id)
+                    .and_then(|replacement_id| ops.get(replacement_id))
+                    .map_or(false, |replacement| {
+                        let declared_expire_period = replacement
+                            .content
+                            .op
+                            .get_superseded_op()
+                            .map(|superseded| superseded.expire_period);
+                        replacement.content_creator_address == op.content_creator_address
+                            && declared_expire_period == Some(op.content.expire_period)
+                            && replacement.content.fee > op.content.fee
+                    });
+                if superseded {
+                    rejected.insert(*new_op_id, OperationDropReason::Superseded);
+                    continue;
+                }
+
+                if let Some(superseded) = op.content.op.get_superseded_op() {
+                    let superseded_id = superseded.id;
+                    let pooled = self.storage.get_op_refs().contains(&superseded_id)
+                        || accepted.contains(&superseded_id);
+                    match ops.get(&superseded_id) {
+                        Some(superseded_op) if pooled => {
+                            if superseded_op.content_creator_address == op.content_creator_address
+                                && superseded_op.content.expire_period == superseded.expire_period
+                            {
+                                if op.content.fee <= superseded_op.content.fee {
+                                    rejected.insert(
+                                        *new_op_id,
+                                        OperationDropReason::ReplacementUnderpriced,
+                                    );
+                                    continue;
+                                }
+                                replaced.insert(superseded_id, OperationDropReason::Superseded);
+                                self.superseded_by.insert(superseded_id, *new_op_id);
+                            }
+                        }
+                        // the superseded operation may be received later
+                        _ => {
+                            self.superseded_by.insert(superseded_id, *new_op_id);
+                        }
+                    }
+                }
+
                 // Broadcast operations to active channel subscribers.
                 if self.config.broadcast_enabled {
                     if let Err(err) = self.channels.operation_sender.send(op.clone()) {
@@ -432,8 +699,17 @@ impl OperationPool {
                     self.config.roll_price,
                     self.config.thread_count,
                 ));
+                accepted.insert(*new_op_id);
             }
         }
+        for (id, reason) in rejected {
+            new_op_ids.remove(&id);
+            self.record_drop_reason(id, reason);
+        }
+        if !replaced.is_empty() {
+            self.sorted_ops
+                .retain(|op_info| !replaced.contains_key(&op_info.id));
+        }
 
         // This will add the new ops to the storage without taking locks.
         // It just take the local references from `ops_storage` if they are not in `self.storage` yet.
@@ -446,6 +722,9 @@ impl OperationPool {
             &new_op_ids,
             &Default::default(),
         ));
+
+        // drop the replaced operations
+        self.drop_operations(replaced);
     }
 
     /// get operations for block creation
@@ -464,6 +743,10 @@ impl OperationPool {
         // init remaining number of operations
         let mut remaining_ops = self.config.max_operations_per_block;
 
+        // operations waiting for the selection of the operation they require
+        let mut waiting: PreHashMap<OperationId, Vec<&OperationInfo>> = PreHashMap::default();
+        let mut selected = PreHashSet::default();
+
         // iterate over pool operations in the right thread, from best to worst
         for op_info in &self.sorted_ops {
             // if we have reached the maximum number of operations, stop
@@ -481,27 +764,48 @@ impl OperationPool {
                 continue;
             }
 
-            // exclude ops that are too large
-            if op_info.size > remaining_space {
-                continue;
+            // ops requiring an op that was neither executed nor selected yet wait for its selection
+            if let Some(after) = op_info.after {
+                if !self.executed_required_ops.contains(&after) && !selected.contains(&after) {
+                    waiting.entry(after).or_default().push(op_info);
+                    continue;
+                }
             }
 
-            // exclude ops that require too much gas
-            if op_info.max_gas > remaining_gas {
-                continue;
-            }
+            // select the op, then the ops that were waiting for it, from best to worst
+            let mut candidates = vec![op_info];
+            while let Some(candidate) = candidates.pop() {
+                if remaining_ops == 0 {
+                    break;
+                }
 
-            // here we consider the operation as accepted
-            op_ids.push(op_info.id);
+                // exclude ops that are too large
+                if candidate.size > remaining_space {
+                    continue;
+                }
+
+                // exclude ops that require too much gas
+                if candidate.max_gas > remaining_gas {
+                    continue;
+                }
+
+                // here we consider the operation as accepted
+                op_ids.push(candidate.id);
+                selected.insert(candidate.id);
 
-            // update remaining block space
-            remaining_space -= op_info.size;
+                // update remaining block space
+                remaining_space -= candidate.size;
 
-            // update remaining block gas
-            remaining_gas -= op_info.max_gas;
+                // update remaining block gas
+                remaining_gas -= candidate.max_gas;
 
-            // update remaining number of operations
-            remaining_ops -= 1;
+                // update remaining number of operations
+                remaining_ops -= 1;
+
+                if let Some(dependents) = waiting.remove(&candidate.id) {
+                    candidates.extend(dependents.into_iter().rev());
+                }
+            }
         }
 
         // generate storage
@@ -21,9 +21,17 @@ use crate::tests::tools::OpGenerator;
 
 use super::tools::{create_some_operations, operation_pool_test, PoolTestBoilerPlate};
 use massa_execution_exports::MockExecutionController;
-use massa_models::{amount::Amount, config::ENDORSEMENT_COUNT, operation::OperationId, slot::Slot};
-use massa_pool_exports::PoolConfig;
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    config::ENDORSEMENT_COUNT,
+    operation::{Operation, OperationId, OperationSerializer, OperationType, SupersededOperation},
+    secure_share::SecureShareContent,
+    slot::Slot,
+};
+use massa_pool_exports::{OperationDropReason, OperationPoolStatus, PoolConfig};
 use massa_pos_exports::{MockSelectorController, Selection};
+use massa_signature::{KeyPair, MultiSigPublicKey, MultiSignature};
 use std::{collections::BTreeMap, time::Duration};
 
 #[test]
@@ -181,6 +189,305 @@ fn test_add_irrelevant_operation() {
     );
 }
 
+/// Test the status and statistics of pooled and expired operations.
+#[test]
+fn test_operation_pool_status_and_stats() {
+    let pool_config = PoolConfig::default();
+    let thread_count = pool_config.thread_count;
+    let execution_controller = {
+        let mut res = Box::new(MockExecutionController::new());
+        res.expect_clone_box().returning(|| {
+            let mut story = MockExecutionController::new();
+            story
+                .expect_get_ops_exec_status()
+                .returning(|ops| vec![(None, None); ops.len()]);
+            story
+                .expect_get_final_and_candidate_balance()
+                .returning(|addrs| {
+                    vec![
+                        (
+                            // Operations need to be paid for
+                            Some(Amount::const_init(1_000_000_000, 0)),
+                            Some(Amount::const_init(1_000_000_000, 0)),
+                        );
+                        addrs.len()
+                    ]
+                });
+
+            Box::new(story)
+        });
+        res
+    };
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    let mut all_slots = BTreeMap::new();
+                    let addr = *opt_addrs
+                        .expect("No addresses filter given")
+                        .into_iter()
+                        .next()
+                        .expect("No addresses given");
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    operation_pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        |mut operation_pool, mut storage| {
+            let fee = Amount::const_init(1, 2);
+            let pooled_ops =
+                create_some_operations(5, &OpGenerator::default().expirery(10).fee(fee));
+            let expired_ops =
+                create_some_operations(5, &OpGenerator::default().expirery(2).fee(fee));
+            let pooled_ids: Vec<OperationId> = pooled_ops.iter().map(|op| op.id).collect();
+            let expired_ids: Vec<OperationId> = expired_ops.iter().map(|op| op.id).collect();
+            let unknown_id = OpGenerator::default().generate().id;
+            storage.store_operations(pooled_ops.into_iter().chain(expired_ops).collect());
+            operation_pool.notify_final_cs_periods(&vec![5; thread_count.into()]);
+            operation_pool.add_operations(storage);
+            // Allow some time for the pool to add the operations and refresh
+            std::thread::sleep(Duration::from_secs(3));
+
+            let statuses = operation_pool.get_operation_pool_status(&pooled_ids);
+            let mut ranks: Vec<usize> = statuses
+                .into_iter()
+                .map(|status| match status {
+                    OperationPoolStatus::Pooled { rank, .. } => rank,
+                    status => panic!("unexpected status of a valid operation: {:?}", status),
+                })
+                .collect();
+            ranks.sort_unstable();
+            assert_eq!(ranks, (0..5).collect::<Vec<usize>>());
+            assert!(
+                operation_pool
+                    .get_operation_pool_status(&expired_ids)
+                    .into_iter()
+                    .all(|status| status
+                        == OperationPoolStatus::Dropped(OperationDropReason::Expired))
+            );
+            assert_eq!(
+                operation_pool.get_operation_pool_status(&[unknown_id]),
+                vec![OperationPoolStatus::Unknown]
+            );
+
+            let stats = operation_pool.get_operation_pool_stats();
+            assert_eq!(stats.operation_count, 5);
+            assert_eq!(stats.operation_count_per_thread.iter().sum::<usize>(), 5);
+            assert!(stats.fee_per_gas.is_none());
+            assert!(stats.fee_per_byte.is_some());
+            assert!(stats.min_included_fee.is_none());
+        },
+    );
+}
+
+/// Test that an operation is replaced by an operation of the same sender superseding it
+/// with a higher fee, and not by one paying a lower fee.
+#[test]
+fn test_operation_replacement() {
+    let execution_controller = {
+        let mut res = Box::new(MockExecutionController::new());
+        res.expect_clone_box().returning(|| {
+            let mut story = MockExecutionController::new();
+            story
+                .expect_get_ops_exec_status()
+                .returning(|ops| vec![(None, None); ops.len()]);
+            story
+                .expect_get_final_and_candidate_balance()
+                .returning(|addrs| {
+                    vec![
+                        (
+                            // Operations need to be paid for
+                            Some(Amount::const_init(1_000_000_000, 0)),
+                            Some(Amount::const_init(1_000_000_000, 0)),
+                        );
+                        addrs.len()
+                    ]
+                });
+
+            Box::new(story)
+        });
+        res
+    };
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|_, _| Ok(BTreeMap::new()));
+            Box::new(story)
+        });
+        res
+    };
+    operation_pool_test(
+        PoolConfig::default(),
+        execution_controller,
+        selector_controller,
+        |mut operation_pool, storage| {
+            let creator = KeyPair::generate(0).unwrap();
+            let op_gen = OpGenerator::default().expirery(10).creator(creator.clone());
+            let original = op_gen.clone().fee(Amount::const_init(2, 2)).generate();
+            let superseded = SupersededOperation {
+                id: original.id,
+                expire_period: original.content.expire_period,
+            };
+            let underpriced = op_gen
+                .clone()
+                .fee(Amount::const_init(1, 2))
+                .supersedes(superseded)
+                .generate();
+            let replacement = op_gen
+                .clone()
+                .fee(Amount::const_init(3, 2))
+                .supersedes(superseded)
+                .generate();
+            let foreign = OpGenerator::default()
+                .expirery(10)
+                .fee(Amount::const_init(4, 2))
+                .supersedes(superseded)
+                .generate();
+            let wrong_expiry = op_gen
+                .fee(Amount::const_init(5, 2))
+                .supersedes(SupersededOperation {
+                    expire_period: 11,
+                    ..superseded
+                })
+                .generate();
+            let ids = [
+                original.id,
+                underpriced.id,
+                foreign.id,
+                wrong_expiry.id,
+                replacement.id,
+            ];
+
+            for op in [original, underpriced, foreign, wrong_expiry, replacement] {
+                let mut op_storage = storage.clone_without_refs();
+                op_storage.store_operations(vec![op]);
+                operation_pool.add_operations(op_storage);
+            }
+            // Allow some time for the pool to add the operations
+            std::thread::sleep(Duration::from_millis(500));
+
+            let statuses = operation_pool.get_operation_pool_status(&ids);
+            assert_eq!(
+                statuses[0],
+                OperationPoolStatus::Dropped(OperationDropReason::Superseded)
+            );
+            assert_eq!(
+                statuses[1],
+                OperationPoolStatus::Dropped(OperationDropReason::ReplacementUnderpriced)
+            );
+            // operations of other senders, or declaring another expiration period,
+            // cannot replace an operation
+            assert!(matches!(statuses[2], OperationPoolStatus::Pooled { .. }));
+            assert!(matches!(statuses[3], OperationPoolStatus::Pooled { .. }));
+            assert!(matches!(statuses[4], OperationPoolStatus::Pooled { .. }));
+        },
+    );
+}
+
+#[test]
+fn test_multisig_operation_before_activation() {
+    let execution_controller = {
+        let mut res = Box::new(MockExecutionController::new());
+        res.expect_clone_box().returning(|| {
+            let mut story = MockExecutionController::new();
+            story
+                .expect_get_ops_exec_status()
+                .returning(|ops| vec![(None, None); ops.len()]);
+            story
+                .expect_get_final_and_candidate_balance()
+                .returning(|addrs| {
+                    vec![
+                        (
+                            // Operations need to be paid for
+                            Some(Amount::const_init(1_000_000_000, 0)),
+                            Some(Amount::const_init(1_000_000_000, 0)),
+                        );
+                        addrs.len()
+                    ]
+                });
+
+            Box::new(story)
+        });
+        res
+    };
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|_, _| Ok(BTreeMap::new()));
+            Box::new(story)
+        });
+        res
+    };
+    operation_pool_test(
+        PoolConfig::default(),
+        execution_controller,
+        selector_controller,
+        |mut operation_pool, storage| {
+            let creator = KeyPair::generate(0).unwrap();
+            let cosigner = KeyPair::generate(0).unwrap();
+            let content = Operation {
+                fee: Amount::const_init(1, 2),
+                expire_period: 10,
+                op: OperationType::Multisig {
+                    multisig_public_key: MultiSigPublicKey::new(1, vec![cosigner.get_public_key()])
+                        .unwrap(),
+                    signatures: MultiSignature::default(),
+                    op: Box::new(OperationType::Transaction {
+                        recipient_address: Address::from_public_key(&creator.get_public_key()),
+                        amount: Amount::const_init(1, 0),
+                    }),
+                },
+            };
+            let multisig_op =
+                Operation::new_verifiable(content, OperationSerializer::new(), &creator).unwrap();
+            let transaction = OpGenerator::default().expirery(10).generate();
+            let ids = [multisig_op.id, transaction.id];
+
+            let mut op_storage = storage.clone_without_refs();
+            op_storage.store_operations(vec![multisig_op, transaction]);
+            operation_pool.add_operations(op_storage);
+            // Allow some time for the pool to add the operations
+            std::thread::sleep(Duration::from_millis(500));
+
+            // no MIP is active in the test versioning store
+            let statuses = operation_pool.get_operation_pool_status(&ids);
+            assert_eq!(
+                statuses[0],
+                OperationPoolStatus::Dropped(OperationDropReason::NotEnabled)
+            );
+            assert!(matches!(statuses[1], OperationPoolStatus::Pooled { .. }));
+        },
+    );
+}
+
 /// TODO refactor old tests
 #[test]
 fn test_pool() {
@@ -10,8 +10,11 @@ use massa_models::{
     address::Address,
     amount::Amount,
     block_id::BlockId,
+    config::MIP_STORE_STATS_BLOCK_CONSIDERED,
     endorsement::{Endorsement, EndorsementSerializer, SecureShareEndorsement},
-    operation::{Operation, OperationSerializer, OperationType, SecureShareOperation},
+    operation::{
+        Operation, OperationSerializer, OperationType, SecureShareOperation, SupersededOperation,
+    },
     prehash::PreHashMap,
     secure_share::SecureShareContent,
     slot::Slot,
@@ -20,17 +23,20 @@ use massa_pool_exports::{PoolChannels, PoolConfig, PoolController, PoolManager};
 use massa_pos_exports::MockSelectorController as AutoMockSelectorController;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
+use massa_versioning::versioning::{MipStatsConfig, MipStore};
 use massa_wallet::test_exports::create_test_wallet;
+use num::rational::Ratio;
 use parking_lot::RwLock;
 use tokio::sync::broadcast;
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub(crate) struct OpGenerator {
     creator: Option<KeyPair>,
     receiver: Option<KeyPair>,
     fee: Option<Amount>,
     amount: Option<Amount>,
     expirery: Option<u64>,
+    supersedes: Option<SupersededOperation>,
 }
 
 impl OpGenerator {
@@ -61,6 +67,11 @@ impl OpGenerator {
         self
     }
 
+    pub(crate) fn supersedes(mut self, supersedes: SupersededOperation) -> Self {
+        self.supersedes = Some(supersedes);
+        self
+    }
+
     pub(crate) fn generate(&self) -> SecureShareOperation {
         let creator = self
             .creator
@@ -74,10 +85,17 @@ impl OpGenerator {
         let amount = self.amount.unwrap_or_default();
         let expirery = self.expirery.unwrap_or_default();
 
-        let op = OperationType::Transaction {
+        let mut op = OperationType::Transaction {
             recipient_address: Address::from_public_key(&receiver.get_public_key()),
             amount,
         };
+        if self.supersedes.is_some() {
+            op = OperationType::Sequenced {
+                supersedes: self.supersedes,
+                after: None,
+                op: Box::new(op),
+            };
+        }
         let content = Operation {
             fee,
             op,
@@ -87,6 +105,15 @@ impl OpGenerator {
     }
 }
 
+/// Return a versioning store in which no MIP is active
+pub(crate) fn create_empty_mip_store() -> MipStore {
+    let mip_stats_config = MipStatsConfig {
+        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        warn_announced_version_ratio: Ratio::new_raw(30, 100),
+    };
+    MipStore::try_from(([], mip_stats_config)).unwrap()
+}
+
 /// Return `n` signed operations
 pub(crate) fn create_some_operations(n: usize, op_gen: &OpGenerator) -> Vec<SecureShareOperation> {
     (0..n).map(|_| op_gen.generate()).collect()
@@ -122,6 +149,7 @@ impl PoolTestBoilerPlate {
                 selector: selector_story,
             },
             wallet,
+            create_empty_mip_store(),
         );
 
         Self {
@@ -158,6 +186,7 @@ pub fn operation_pool_test<F>(
             selector,
         },
         wallet,
+        create_empty_mip_store(),
     );
     test(pool_controller, storage);
     pool_manager.stop();
@@ -16,6 +16,10 @@ pub struct OperationInfo {
     /// max amount that the op might spend from the sender's balance
     pub max_spending: Amount,
     pub validity_period_range: RangeInclusive<u64>,
+    /// operation of the same sender replaced by this one
+    pub supersedes: Option<OperationId>,
+    /// operation that has to be executed before this one
+    pub after: Option<OperationId>,
 }
 
 impl OperationInfo {
@@ -34,6 +38,21 @@ impl OperationInfo {
             thread: op.content_creator_address.get_thread(thread_count),
             validity_period_range: op.get_validity_range(operation_validity_periods),
             max_spending: op.get_max_spending(roll_price),
+            supersedes: op.content.op.get_superseded_op_id(),
+            after: op.content.op.get_required_op_id(),
         }
     }
+
+    /// Fee per unit of gas, in nanoMAS. `None` if the operation does not use gas.
+    pub fn fee_per_gas(&self) -> Option<f64> {
+        if self.max_gas == 0 {
+            return None;
+        }
+        Some(self.fee.to_raw() as f64 / self.max_gas as f64)
+    }
+
+    /// Fee per byte of the serialized operation, in nanoMAS
+    pub fn fee_per_byte(&self) -> f64 {
+        self.fee.to_raw() as f64 / self.size.max(1) as f64
+    }
 }
@@ -6,7 +6,7 @@ use crate::{
 use crate::{DeferredCredits, PoSConfig};
 use bitvec::vec::BitVec;
 use massa_db_exports::{
-    DBBatch, MassaDirection, MassaIteratorMode, ShareableMassaDBController,
+    DBBatch, MassaDBError, MassaDirection, MassaIteratorMode, ShareableMassaDBController,
     CYCLE_HISTORY_DESER_ERROR, CYCLE_HISTORY_PREFIX, CYCLE_HISTORY_SER_ERROR,
     DEFERRED_CREDITS_DESER_ERROR, DEFERRED_CREDITS_PREFIX, DEFERRED_CREDITS_SER_ERROR, STATE_CF,
 };
@@ -654,6 +654,41 @@ impl PoSFinalState {
             .unwrap_or_default()
     }
 
+    /// Retrieves the amount of rolls a given address had at the end of a past final slot.
+    /// Fails if the archive of the state does not cover that slot,
+    /// or if the cycle of the slot is older than the cycles kept in the cycle history.
+    pub fn get_rolls_for_at_slot(&self, addr: &Address, slot: Slot) -> Result<u64, MassaDBError> {
+        // the roll counts of the cycle of the slot are up to date at the end of the slot
+        let cycle = slot.get_cycle(self.config.periods_per_cycle);
+
+        // the roll counts of a cycle are only kept while the cycle is in the cycle history
+        match self.cycle_history_cache.front() {
+            Some((oldest_cycle, _)) if cycle >= *oldest_cycle => {}
+            _ => {
+                return Err(MassaDBError::ArchiveError(format!(
+                    "the roll counts of cycle {} (slot {}) are no longer kept in the cycle history",
+                    cycle, slot
+                )))
+            }
+        }
+        let key = roll_count_key!(self.cycle_history_cycle_prefix(cycle), addr);
+
+        Ok(self
+            .db
+            .read()
+            .get_state_value_at_slot(key, slot)?
+            .map(|serialized_value| {
+                self.cycle_info_deserializer
+                    .cycle_info_deserializer
+                    .rolls_deser
+                    .u64_deserializer
+                    .deserialize::<DeserializeError>(&serialized_value)
+                    .expect(CYCLE_HISTORY_DESER_ERROR)
+                    .1
+            })
+            .unwrap_or_default())
+    }
+
     /// Retrieves the amount of rolls a given address has at a given cycle
     pub fn get_address_active_rolls(&self, addr: &Address, cycle: u64) -> Option<u64> {
         match cycle.checked_sub(3) {
@@ -1614,51 +1649,27 @@ impl PoSFinalState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_exports::MockSelectorController;
+    use bitvec::prelude::*;
+    use massa_db_exports::{MassaDBConfig, MassaDBController};
+    use massa_db_worker::MassaDB;
+    use massa_models::config::constants::{
+        MAX_DEFERRED_CREDITS_LENGTH, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
+        POS_SAVED_CYCLES,
+    };
+    use massa_signature::KeyPair;
+    use parking_lot::RwLock;
     use std::collections::HashMap;
-
-    // This test checks that the initial deferred credits are loaded correctly
-    #[test]
-    fn test_initial_deferred_credits_loading() {
-        use crate::test_exports::MockSelectorController;
-        use crate::PoSFinalState;
-        use massa_db_exports::{MassaDBConfig, MassaDBController};
-        use massa_db_worker::MassaDB;
-        use massa_models::config::constants::{
-            MAX_DEFERRED_CREDITS_LENGTH, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
-            POS_SAVED_CYCLES,
-        };
-        use parking_lot::RwLock;
-        use std::str::FromStr;
-        use std::sync::Arc;
-
-        let initial_deferred_credits_file = tempfile::NamedTempFile::new()
-            .expect("could not create temporary initial deferred credits file");
-
-        // write down some deferred credits
-        let deferred_credits_file_contents = "{
-            \"AU12pAcVUzsgUBJHaYSAtDKVTYnUT9NorBDjoDovMfAFTLFa16MNa\": [
-                {
-                    \"slot\": {\"period\": 3, \"thread\": 0},
-                    \"amount\": \"5.01\"
-                },
-                {
-                    \"slot\": {\"period\": 4, \"thread\": 1},
-                    \"amount\": \"6.0\"
-                }
-            ],
-            \"AU1wN8rn4SkwYSTDF3dHFY4U28KtsqKL1NnEjDZhHnHEy6cEQm53\": [
-                {
-                    \"slot\": {\"period\": 3, \"thread\": 0},
-                    \"amount\": \"2.01\"
-                }
-            ]
-        }";
-        std::fs::write(
-            initial_deferred_credits_file.path(),
-            deferred_credits_file_contents.as_bytes(),
-        )
-        .expect("failed writing initial deferred credits file");
-
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// Creates a PoS state with an empty cycle history on a new database.
+    /// The returned directory holds the database and must be kept until the end of the test.
+    fn create_test_pos_state(
+        initial_deferred_credits_path: Option<PathBuf>,
+        enable_archive: bool,
+    ) -> (PoSFinalState, TempDir) {
         let pos_config = PoSConfig {
             periods_per_cycle: 2,
             thread_count: 2,
@@ -1666,16 +1677,18 @@ mod tests {
             max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
             max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
             max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
-            initial_deferred_credits_path: Some(initial_deferred_credits_file.path().to_path_buf()),
+            initial_deferred_credits_path,
         };
 
         // initialize the database and pos_state
-        let tempdir = tempfile::TempDir::new().expect("cannot create temp directory");
+        let tempdir = TempDir::new().expect("cannot create temp directory");
         let db_config = MassaDBConfig {
             path: tempdir.path().to_path_buf(),
             max_history_length: 10,
             max_new_elements: 100,
             thread_count: 2,
+            enable_archive,
+            archive_retention_periods: if enable_archive { 100 } else { 0 },
         };
         let db = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -1692,9 +1705,9 @@ mod tests {
             pos_config.max_production_stats_length,
         );
 
-        let mut pos_state = PoSFinalState {
+        let pos_state = PoSFinalState {
             config: pos_config,
-            db: db.clone(),
+            db,
             cycle_history_cache: Default::default(),
             rng_seed_cache: None,
             selector: selector_controller,
@@ -1705,13 +1718,54 @@ mod tests {
             cycle_info_serializer: CycleHistorySerializer::new(),
             cycle_info_deserializer,
         };
+        (pos_state, tempdir)
+    }
+
+    // This test checks that the initial deferred credits are loaded correctly
+    #[test]
+    fn test_initial_deferred_credits_loading() {
+        let initial_deferred_credits_file = tempfile::NamedTempFile::new()
+            .expect("could not create temporary initial deferred credits file");
+
+        // write down some deferred credits
+        let deferred_credits_file_contents = "{
+            \"AU12pAcVUzsgUBJHaYSAtDKVTYnUT9NorBDjoDovMfAFTLFa16MNa\": [
+                {
+                    \"slot\": {\"period\": 3, \"thread\": 0},
+                    \"amount\": \"5.01\"
+                },
+                {
+                    \"slot\": {\"period\": 4, \"thread\": 1},
+                    \"amount\": \"6.0\"
+                }
+            ],
+            \"AU1wN8rn4SkwYSTDF3dHFY4U28KtsqKL1NnEjDZhHnHEy6cEQm53\": [
+                {
+                    \"slot\": {\"period\": 3, \"thread\": 0},
+                    \"amount\": \"2.01\"
+                }
+            ]
+        }";
+        std::fs::write(
+            initial_deferred_credits_file.path(),
+            deferred_credits_file_contents.as_bytes(),
+        )
+        .expect("failed writing initial deferred credits file");
+
+        let (mut pos_state, _tempdir) = create_test_pos_state(
+            Some(initial_deferred_credits_file.path().to_path_buf()),
+            false,
+        );
 
         let mut batch = DBBatch::new();
         // load initial deferred credits
         pos_state
             .load_initial_deferred_credits(&mut batch)
             .expect("error while loading initial deferred credits");
-        db.write().write_batch(batch, DBBatch::new(), None);
+        pos_state
+            .db
+            .write()
+            .write_batch(batch, DBBatch::new(), None);
 
         let deferred_credits = pos_state.get_deferred_credits().credits;
 
@@ -1758,64 +1812,7 @@ mod tests {
     // If this is not handled properly, the node hangs as explained here: https://github.com/massalabs/massa/issues/4101
     #[test]
     fn test_pos_cache_recomputation() {
-        use crate::test_exports::MockSelectorController;
-        use crate::PoSFinalState;
-        use massa_db_exports::{MassaDBConfig, MassaDBController};
-        use massa_db_worker::MassaDB;
-        use massa_models::config::constants::{
-            MAX_DEFERRED_CREDITS_LENGTH, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
-            POS_SAVED_CYCLES,
-        };
-        use parking_lot::RwLock;
-        use std::sync::Arc;
-        use tempfile::TempDir;
-
-        let pos_config = PoSConfig {
-            periods_per_cycle: 2,
-            thread_count: 2,
-            cycle_history_length: POS_SAVED_CYCLES,
-            max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
-            max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
-            max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
-            initial_deferred_credits_path: None,
-        };
-
-        // initialize the database and pos_state
-        let tempdir = TempDir::new().expect("cannot create temp directory");
-        let db_config = MassaDBConfig {
-            path: tempdir.path().to_path_buf(),
-            max_history_length: 10,
-            max_new_elements: 100,
-            thread_count: 2,
-        };
-        let db = Arc::new(RwLock::new(
-            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
-        ));
-        let (selector_controller, _) = MockSelectorController::new_with_receiver();
-        let init_seed = Hash::compute_from(b"");
-        let initial_seeds = vec![Hash::compute_from(init_seed.to_bytes()), init_seed];
-
-        let deferred_credits_deserializer =
-            DeferredCreditsDeserializer::new(pos_config.thread_count, pos_config.max_credit_length);
-        let cycle_info_deserializer = CycleHistoryDeserializer::new(
-            pos_config.cycle_history_length as u64,
-            pos_config.max_rolls_length,
-            pos_config.max_production_stats_length,
-        );
-
-        let mut pos_state = PoSFinalState {
-            config: pos_config,
-            db: db.clone(),
-            cycle_history_cache: Default::default(),
-            rng_seed_cache: None,
-            selector: selector_controller,
-            initial_rolls: Default::default(),
-            initial_seeds,
-            deferred_credits_serializer: DeferredCreditsSerializer::new(),
-            deferred_credits_deserializer,
-            cycle_info_serializer: CycleHistorySerializer::new(),
-            cycle_info_deserializer,
-        };
+        let (mut pos_state, _tempdir) = create_test_pos_state(None, false);
 
         // Populate the disk with some cycle infos
         let mut cycle_infos = Vec::new();
@@ -1868,67 +1865,8 @@ mod tests {
     // This test aims to check that the basic workflow of apply changes to the PoS state works.
     #[test]
     fn test_pos_final_state_hash_computation() {
-        use crate::test_exports::MockSelectorController;
-        use crate::DeferredCredits;
-        use crate::PoSFinalState;
-        use bitvec::prelude::*;
-        use massa_db_exports::{MassaDBConfig, MassaDBController};
-        use massa_db_worker::MassaDB;
-        use massa_models::config::constants::{
-            MAX_DEFERRED_CREDITS_LENGTH, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
-            POS_SAVED_CYCLES,
-        };
-        use massa_signature::KeyPair;
-        use parking_lot::RwLock;
-        use std::sync::Arc;
-        use tempfile::TempDir;
-
-        let pos_config = PoSConfig {
-            periods_per_cycle: 2,
-            thread_count: 2,
-            cycle_history_length: POS_SAVED_CYCLES,
-            max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
-            max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
-            max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
-            initial_deferred_credits_path: None,
-        };
-
-        // initialize the database and pos_state
-        let tempdir = TempDir::new().expect("cannot create temp directory");
-        let db_config = MassaDBConfig {
-            path: tempdir.path().to_path_buf(),
-            max_history_length: 10,
-            max_new_elements: 100,
-            thread_count: 2,
-        };
-        let db = Arc::new(RwLock::new(
-            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
-        ));
-        let (selector_controller, _) = MockSelectorController::new_with_receiver();
-        let init_seed = Hash::compute_from(b"");
-        let initial_seeds = vec![Hash::compute_from(init_seed.to_bytes()), init_seed];
-
-        let deferred_credits_deserializer =
-            DeferredCreditsDeserializer::new(pos_config.thread_count, pos_config.max_credit_length);
-        let cycle_info_deserializer = CycleHistoryDeserializer::new(
-            pos_config.cycle_history_length as u64,
-            pos_config.max_rolls_length,
-            pos_config.max_production_stats_length,
-        );
-
-        let mut pos_state = PoSFinalState {
-            config: pos_config,
-            db: db.clone(),
-            cycle_history_cache: Default::default(),
-            rng_seed_cache: None,
-            selector: selector_controller,
-            initial_rolls: Default::default(),
-            initial_seeds,
-            deferred_credits_serializer: DeferredCreditsSerializer::new(),
-            deferred_credits_deserializer,
-            cycle_info_serializer: CycleHistorySerializer::new(),
-            cycle_info_deserializer,
-        };
+        let (mut pos_state, _tempdir) = create_test_pos_state(None, false);
+        let db = pos_state.db.clone();
 
         pos_state.recompute_pos_state_caches();
 
@@ -2040,4 +1978,20 @@ mod tests {
 
         assert_eq!(cycle_info_a, cycle_info_b, "cycle_info mismatch");
     }
+
+    // This test checks that the roll counts cannot be read at a slot whose cycle left the cycle history
+    #[test]
+    fn test_rolls_at_slot_before_cycle_history() {
+        let (mut pos_state, _tempdir) = create_test_pos_state(None, true);
+        pos_state.cycle_history_cache = vec![(3, true), (4, false)].into();
+
+        let addr =
+            Address::from_str("AU12cMW9zRKFDS43Z2W88VCmdQFxmHjAo54XvuVV34UzJeXRLXW9M").unwrap();
+
+        // cycle 2 is older than the oldest kept cycle
+        assert!(matches!(
+            pos_state.get_rolls_for_at_slot(&addr, Slot::new(5, 1)),
+            Err(MassaDBError::ArchiveError(_))
+        ));
+    }
 }
@@ -1,10 +1,11 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 
 use crate::error::ProtocolError;
 use crate::BootstrapPeers;
+use crate::ConnectedPeerInfo;
 
 use crate::PeerId;
 use massa_models::prehash::{PreHashMap, PreHashSet};
@@ -12,6 +13,7 @@ use massa_models::stats::NetworkStats;
 use massa_models::{block_header::SecuredHeader, block_id::BlockId};
 use massa_storage::Storage;
 use peernet::peer::PeerConnectionType;
+use peernet::transports::TransportType;
 
 #[cfg_attr(any(test, feature = "testing"), mockall::automock)]
 pub trait ProtocolController: Send + Sync {
@@ -69,6 +71,9 @@ pub trait ProtocolController: Send + Sync {
         ProtocolError,
     >;
 
+    /// Get detailed information about the currently connected peers
+    fn get_connected_peers(&self) -> Result<Vec<ConnectedPeerInfo>, ProtocolError>;
+
     /// Get a list of peers to be sent to someone that bootstrap to us
     fn get_bootstrap_peers(&self) -> Result<BootstrapPeers, ProtocolError>;
 
@@ -78,6 +83,34 @@ pub trait ProtocolController: Send + Sync {
     /// Unban a list of Peer Id
     fn unban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError>;
 
+    /// Ban a list of IP addresses and close the connections opened with them
+    fn ban_ips(&self, ips: Vec<IpAddr>) -> Result<(), ProtocolError>;
+
+    /// Unban a list of IP addresses
+    fn unban_ips(&self, ips: Vec<IpAddr>) -> Result<(), ProtocolError>;
+
+    /// Get the IP addresses of the peers whitelist
+    fn get_peers_whitelist(&self) -> Result<Vec<IpAddr>, ProtocolError>;
+
+    /// Add a list of IP addresses to the peers whitelist
+    fn add_to_peers_whitelist(&self, ips: Vec<IpAddr>) -> Result<(), ProtocolError>;
+
+    /// Remove a list of IP addresses from the peers whitelist
+    fn remove_from_peers_whitelist(&self, ips: Vec<IpAddr>) -> Result<(), ProtocolError>;
+
+    /// Get the static peers: listeners that are always kept connected
+    fn get_static_peers(&self) -> Result<HashMap<SocketAddr, TransportType>, ProtocolError>;
+
+    /// Add static peers. They are saved and kept connected across restarts,
+    /// and their connections don't take any connection slot.
+    fn add_static_peers(
+        &self,
+        peers: HashMap<SocketAddr, TransportType>,
+    ) -> Result<(), ProtocolError>;
+
+    /// Remove static peers. Their current connections are kept but not pinned anymore.
+    fn remove_static_peers(&self, addrs: Vec<SocketAddr>) -> Result<(), ProtocolError>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ProtocolController>`.
     fn clone_box(&self) -> Box<dyn ProtocolController>;
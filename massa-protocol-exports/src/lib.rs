@@ -2,6 +2,7 @@ mod bootstrap_peers;
 mod controller_trait;
 mod error;
 mod peer_id;
+mod peer_info;
 mod settings;
 
 pub use bootstrap_peers::{
@@ -10,6 +11,7 @@ pub use bootstrap_peers::{
 pub use controller_trait::{ProtocolController, ProtocolManager};
 pub use error::ProtocolError;
 pub use peer_id::{PeerId, PeerIdDeserializer, PeerIdSerializer};
+pub use peer_info::{ConnectedPeerInfo, ConnectedPeersFilter, PeerMessageCounts, PeerState};
 pub use peernet::peer::PeerConnectionType;
 pub use peernet::transports::TransportType;
 pub use settings::{PeerCategoryInfo, ProtocolConfig};
@@ -0,0 +1,111 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+
+use massa_models::node::NodeId;
+use massa_time::MassaTime;
+use peernet::peer::PeerConnectionType;
+use peernet::transports::TransportType;
+use serde::{Deserialize, Serialize};
+
+use crate::PeerId;
+
+/// State of a peer in the peer database
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum PeerState {
+    /// Banned peer, its messages are ignored
+    Banned,
+    /// Handshake in progress
+    InHandshake,
+    /// Last handshake failed
+    HandshakeFailed,
+    /// Last handshake succeeded
+    Trusted,
+}
+
+/// Number of messages received from a peer on its current connection, by message type
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerMessageCounts {
+    /// block messages
+    pub block: u64,
+    /// endorsement messages
+    pub endorsement: u64,
+    /// operation messages
+    pub operation: u64,
+    /// peer management messages
+    pub peer_management: u64,
+}
+
+/// Detailed information about a connected peer
+#[derive(Clone)]
+pub struct ConnectedPeerInfo {
+    /// id of the peer
+    pub peer_id: PeerId,
+    /// remote address of the connection
+    pub address: SocketAddr,
+    /// whether the connection is incoming or outgoing
+    pub connection_type: PeerConnectionType,
+    /// peer category of the connection, `None` for the default category
+    pub category: Option<String>,
+    /// when the connection was established, if known
+    pub connected_since: Option<MassaTime>,
+    /// bytes sent to the peer on this connection
+    pub bytes_sent: u64,
+    /// bytes received from the peer on this connection
+    pub bytes_received: u64,
+    /// messages received from the peer on this connection
+    pub messages_received: PeerMessageCounts,
+    /// listeners announced by the peer
+    pub listeners: HashMap<SocketAddr, TransportType>,
+    /// state of the peer in the peer database, if known
+    pub state: Option<PeerState>,
+}
+
+/// Filter on the connected peers. A peer matches if it matches every criterion that is set.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ConnectedPeersFilter {
+    /// only these peers
+    pub node_ids: Option<Vec<NodeId>>,
+    /// only peers connected from or to this IP
+    pub ip: Option<IpAddr>,
+    /// only outgoing (`true`) or incoming (`false`) connections
+    pub is_outgoing: Option<bool>,
+    /// only peers of this category
+    pub category: Option<String>,
+    /// only peers in this state
+    pub state: Option<PeerState>,
+}
+
+impl ConnectedPeersFilter {
+    /// Whether the given peer matches the filter
+    pub fn matches(&self, peer: &ConnectedPeerInfo) -> bool {
+        if let Some(node_ids) = &self.node_ids {
+            let node_id = NodeId::new(peer.peer_id.get_public_key());
+            if !node_ids.contains(&node_id) {
+                return false;
+            }
+        }
+        if let Some(ip) = &self.ip {
+            if *ip != peer.address.ip() {
+                return false;
+            }
+        }
+        if let Some(is_outgoing) = self.is_outgoing {
+            if is_outgoing != (peer.connection_type == PeerConnectionType::OUT) {
+                return false;
+            }
+        }
+        if let Some(category) = &self.category {
+            if peer.category.as_ref() != Some(category) {
+                return false;
+            }
+        }
+        if let Some(state) = &self.state {
+            if peer.state.as_ref() != Some(state) {
+                return false;
+            }
+        }
+        true
+    }
+}
@@ -6,6 +6,7 @@ use std::{
     path::PathBuf,
 };
 
+use massa_hash::Hash;
 use massa_models::version::Version;
 use massa_time::MassaTime;
 use peernet::transports::TransportType;
@@ -28,6 +29,14 @@ pub struct ProtocolConfig {
     pub listeners: HashMap<SocketAddr, TransportType>,
     /// initial peers path
     pub initial_peers: PathBuf,
+    /// path of the file where the peer database is saved on shutdown and loaded on startup
+    pub peers_db_file: PathBuf,
+    /// max number of peers kept when saving the peer database
+    pub max_peers_db_size: usize,
+    /// path of the file where the static peers added at runtime are saved
+    pub static_peers_file: PathBuf,
+    /// tested addresses older than this are pruned from the peer database
+    pub tested_addresses_max_age: MassaTime,
     /// after `ask_block_timeout` milliseconds we try to ask a block to another node
     pub ask_block_timeout: MassaTime,
     /// Max known blocks we keep during their propagation
@@ -170,6 +179,8 @@ pub struct ProtocolConfig {
     pub default_category_info: PeerCategoryInfo,
     /// Version
     pub version: Version,
+    /// Hash of the chain spec, exchanged in the handshake to refuse peers running another chain
+    pub chain_id: Hash,
     /// Cooldown before testing again an old peer
     pub test_oldest_peer_cooldown: MassaTime,
     /// Rate limit to apply on the data stream
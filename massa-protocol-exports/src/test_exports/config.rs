@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::{settings::PeerCategoryInfo, ProtocolConfig};
+use massa_hash::Hash;
 use massa_models::config::{ENDORSEMENT_COUNT, MAX_MESSAGE_SIZE};
 use massa_time::MassaTime;
 use tempfile::NamedTempFile;
@@ -48,6 +49,16 @@ impl Default for ProtocolConfig {
                 .expect("cannot create temp file")
                 .path()
                 .to_path_buf(),
+            peers_db_file: NamedTempFile::new()
+                .expect("cannot create temp file")
+                .path()
+                .to_path_buf(),
+            max_peers_db_size: 1000,
+            static_peers_file: NamedTempFile::new()
+                .expect("cannot create temp file")
+                .path()
+                .to_path_buf(),
+            tested_addresses_max_age: MassaTime::from_millis(ONE_DAY_MS),
             listeners: HashMap::default(),
             thread_tester_count: 2,
             max_size_channel_commands_connectivity: 1000,
@@ -93,6 +104,7 @@ impl Default for ProtocolConfig {
                 max_in_connections_per_ip: 0,
             },
             version: "TEST.23.2".parse().unwrap(),
+            chain_id: Hash::zero(),
             try_connection_timer_same_peer: MassaTime::from_millis(1000),
             test_oldest_peer_cooldown: MassaTime::from_millis(720000),
             rate_limit: 1024 * 1024 * 2,
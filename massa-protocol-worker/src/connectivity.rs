@@ -7,11 +7,15 @@ use massa_metrics::MassaMetrics;
 use massa_models::stats::NetworkStats;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{PeerCategoryInfo, PeerId, ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{
+    ConnectedPeerInfo, PeerCategoryInfo, PeerId, ProtocolConfig, ProtocolError,
+};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_versioning::versioning::MipStore;
 use parking_lot::RwLock;
 use peernet::peer::PeerConnectionType;
+use peernet::transports::TransportType;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::{collections::HashMap, net::IpAddr};
@@ -45,6 +49,9 @@ pub enum ConnectivityCommand {
             HashMap<PeerId, (SocketAddr, PeerConnectionType)>,
         )>,
     },
+    GetConnectedPeers {
+        responder: MassaSender<Vec<ConnectedPeerInfo>>,
+    },
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -118,6 +125,8 @@ pub(crate) fn start_connectivity_thread(
                 config.max_node_known_blocks_size.try_into().unwrap(),
             )));
 
+            let peer_stats = messages_handler.peer_stats.clone();
+
             // Start handlers
             let mut peer_management_handler = PeerManagementHandler::new(
                 initial_peers,
@@ -229,6 +238,30 @@ pub(crate) fn start_connectivity_thread(
                                 }).collect();
                                 responder.try_send((stats, peers)).unwrap_or_else(|_| warn!("Failed to send stats to responder"));
                             }
+                            Ok(ConnectivityCommand::GetConnectedPeers { responder }) => {
+                                let active_conn = network_controller.get_active_connections();
+                                let bandwidth = active_conn.get_peers_connections_bandwidth();
+                                let peer_db_read = peer_db.read();
+                                let peer_stats_read = peer_stats.read();
+                                let peers = active_conn.get_peers_connected().into_iter().map(|(peer_id, (address, connection_type, category))| {
+                                    let (bytes_sent, bytes_received) = bandwidth.get(&peer_id.to_string()).copied().unwrap_or_default();
+                                    let stats = peer_stats_read.get(&peer_id);
+                                    let peer_info = peer_db_read.peers.get(&peer_id);
+                                    ConnectedPeerInfo {
+                                        address,
+                                        connection_type,
+                                        category,
+                                        connected_since: stats.map(|stats| stats.connected_since),
+                                        bytes_sent,
+                                        bytes_received,
+                                        messages_received: stats.map(|stats| stats.get_message_counts()).unwrap_or_default(),
+                                        listeners: peer_info.and_then(|info| info.last_announce.as_ref()).map(|announce| announce.listeners.clone()).unwrap_or_default(),
+                                        state: peer_info.map(|info| info.state.clone()),
+                                        peer_id,
+                                    }
+                                }).collect();
+                                responder.try_send(peers).unwrap_or_else(|_| warn!("Failed to send connected peers to responder"));
+                            }
                             Err(_) => {
                                 warn!("Channel to connectivity thread is closed. Stopping the protocol");
                                 break;
@@ -242,6 +275,11 @@ pub(crate) fn start_connectivity_thread(
                         massa_metrics.set_active_connections(active_conn.get_nb_in_connections(), active_conn.get_nb_out_connections());
                         let peers_map = active_conn.get_peers_connections_bandwidth();
                         massa_metrics.update_peers_tx_rx(peers_map);
+                        // forget the stats of the closed connections, leaving some time to the fresh ones to be registered
+                        let peers_connected = active_conn.get_peer_ids_connected();
+                        if let Ok(min_time) = MassaTime::now().map(|now| now.saturating_sub(config.timeout_connection)) {
+                            peer_stats.write().retain(|peer_id, stats| peers_connected.contains(peer_id) || stats.connected_since >= min_time);
+                        }
                         let peer_db_read = peer_db.read();
                         massa_metrics.set_known_peers(peer_db_read.peers.len());
                         massa_metrics.set_banned_peers(peer_db_read.get_banned_peer_count() as usize);
@@ -263,9 +301,10 @@ pub(crate) fn start_connectivity_thread(
                             let peer_db_read = peer_db.read();
                             for (peer_id, peer_info) in &peer_db_read.peers {
 
-                                // If peer already connected, decrement the slots for the given category, or default category if none
+                                // If peer already connected, decrement the slots for the given category, or default category if none.
+                                // Static peers don't take any slot.
                                 if let Some(peer) = peers_connected.get(peer_id) {
-                                    if peer.1 == PeerConnectionType::OUT {
+                                    if peer.1 == PeerConnectionType::OUT && !peer_db_read.is_static_connection(peer_id, &peer.0) {
                                         if let Some(ref peer_category) = &peer.2 {
                                             if let Some(slots) = connection_slots.get_mut(peer_category.as_str()) {
                                                 *slots = slots.saturating_sub(1);
@@ -288,6 +327,9 @@ pub(crate) fn start_connectivity_thread(
 
                                         if let Some((addr, _)) = last_announce.listeners.iter().next() {
                                             let canonical_ip = to_canonical(addr.ip());
+                                            if peer_db_read.is_ip_banned(&canonical_ip) {
+                                                continue;
+                                            }
                                             let mut allowed_local_ips = false;
                                             // Check if the peer is in a category and we didn't reached out target yet
                                             let mut category_found = None;
@@ -323,6 +365,11 @@ pub(crate) fn start_connectivity_thread(
                                                 continue;
                                             }
 
+                                            // static peers are connected separately
+                                            if peer_db_read.is_static_peer(addr) {
+                                                continue;
+                                            }
+
                                             if !global(&canonical_ip) && !allowed_local_ips {
                                                 continue;
                                             }
@@ -336,8 +383,46 @@ pub(crate) fn start_connectivity_thread(
                             }
                         }
 
-                        // Sort addresses using the metadata
-                        addresses_can_connect.sort_by(|a, b| a.1.cmp(&b.1));
+                        // Static peers are always kept connected, regardless of the connection slots
+                        let static_peers_to_connect: Vec<(SocketAddr, TransportType)> = {
+                            let peer_db_read = peer_db.read();
+                            peer_db_read.static_peers.iter().filter(|(addr, _)| {
+                                if peers_connection_queue.contains(addr) {
+                                    return false;
+                                }
+                                let connected = peers_connected.iter().any(|(peer_id, (connection_addr, _, _))| {
+                                    connection_addr == *addr
+                                        || peer_db_read.peers.get(peer_id)
+                                            .and_then(|info| info.last_announce.as_ref())
+                                            .map(|announce| announce.listeners.contains_key(addr))
+                                            .unwrap_or(false)
+                                });
+                                if connected {
+                                    return false;
+                                }
+                                match peer_db_read.try_connect_history.get(addr) {
+                                    Some(ConnectionMetadata { last_try_connect: Some(lt), .. }) => {
+                                        lt.estimate_instant().map(|lt| lt.elapsed() >= config.try_connection_timer_same_peer.to_duration()).unwrap_or(true)
+                                    }
+                                    _ => true,
+                                }
+                            }).map(|(addr, transport)| (*addr, *transport)).collect()
+                        };
+                        for (addr, transport) in static_peers_to_connect {
+                            if let Err(err) = try_connect_peer(addr, transport, &mut network_controller, &peer_db, &config) {
+                                debug!("Failed to connect to static peer {}: {}", addr, err);
+                            }
+                        }
+
+                        // Sort addresses using the metadata, whitelisted addresses first
+                        {
+                            let peer_db_read = peer_db.read();
+                            addresses_can_connect.sort_by(|a, b| {
+                                peer_db_read.is_ip_whitelisted(&b.0.ip())
+                                    .cmp(&peer_db_read.is_ip_whitelisted(&a.0.ip()))
+                                    .then_with(|| a.1.cmp(&b.1))
+                            });
+                        }
 
                         // Connect to the given addresses, trying to fill all the slots available
                         let mut addresses_connected = vec![];
@@ -353,7 +438,7 @@ pub(crate) fn start_connectivity_thread(
                                     for (name, slots) in connection_slots.iter_mut() {
                                         if name == *cat && *slots > 0 {
                                             // In case the connection succeeds, we take a place in a slot
-                                            if try_connect_peer(*addr, &mut network_controller, &peer_db, &config).is_ok() {
+                                            if try_connect_peer(*addr, TransportType::Tcp, &mut network_controller, &peer_db, &config).is_ok() {
                                                 *slots = slots.saturating_sub(1);
                                                 addresses_connected.push(*addr);
                                             }
@@ -364,7 +449,7 @@ pub(crate) fn start_connectivity_thread(
                                 // Default category
                                 None if connection_slots["default"] > 0 => {
                                     // In case the connection succeeds, we take a place in a slot
-                                    if try_connect_peer(*addr, &mut network_controller, &peer_db, &config).is_err() {
+                                    if try_connect_peer(*addr, TransportType::Tcp, &mut network_controller, &peer_db, &config).is_err() {
                                         if let Some(v) = connection_slots.get_mut("default") {
                                             *v = v.saturating_sub(1);
                                         }
@@ -401,13 +486,18 @@ pub(crate) fn start_connectivity_thread(
 // Attempt to connect to peer
 fn try_connect_peer(
     addr: SocketAddr,
+    transport_type: TransportType,
     network_controller: &mut Box<dyn NetworkController>,
     peer_db: &Arc<RwLock<PeerDB>>,
     config: &ProtocolConfig,
 ) -> Result<(), ProtocolError> {
     debug!("Trying to connect to addr {}", addr);
 
-    let conn_res = network_controller.try_connect(addr, config.timeout_connection.to_duration());
+    let conn_res = network_controller.try_connect(
+        transport_type,
+        addr,
+        config.timeout_connection.to_duration(),
+    );
     {
         let mut peer_db_write = peer_db.write();
         peer_db_write
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, net::SocketAddr, time::Duration};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
 
 use massa_channel::{sender::MassaSender, MassaChannel};
 use massa_models::{
@@ -7,9 +11,12 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     stats::NetworkStats,
 };
-use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolController, ProtocolError};
+use massa_protocol_exports::{
+    BootstrapPeers, ConnectedPeerInfo, PeerId, ProtocolController, ProtocolError,
+};
 use massa_storage::Storage;
 use peernet::peer::PeerConnectionType;
+use peernet::transports::TransportType;
 
 use crate::{
     connectivity::ConnectivityCommand,
@@ -156,6 +163,20 @@ impl ProtocolController for ProtocolControllerImpl {
             .map_err(|_| ProtocolError::ChannelError("get_stats command receive error".into()))
     }
 
+    fn get_connected_peers(&self) -> Result<Vec<ConnectedPeerInfo>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_connected_peers".to_string(), Some(1));
+        self.sender_connectivity_thread
+            .as_ref()
+            .unwrap()
+            .try_send(ConnectivityCommand::GetConnectedPeers { responder: sender })
+            .map_err(|_| {
+                ProtocolError::ChannelError("get_connected_peers command send error".into())
+            })?;
+        receiver.recv_timeout(Duration::from_secs(10)).map_err(|_| {
+            ProtocolError::ChannelError("get_connected_peers command receive error".into())
+        })
+    }
+
     fn ban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError> {
         self.sender_peer_management_thread
             .as_ref()
@@ -172,6 +193,93 @@ impl ProtocolController for ProtocolControllerImpl {
             .map_err(|_| ProtocolError::ChannelError("unban_peers command send error".into()))
     }
 
+    fn ban_ips(&self, ips: Vec<IpAddr>) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::BanIps(ips))
+            .map_err(|_| ProtocolError::ChannelError("ban_ips command send error".into()))
+    }
+
+    fn unban_ips(&self, ips: Vec<IpAddr>) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::UnbanIps(ips))
+            .map_err(|_| ProtocolError::ChannelError("unban_ips command send error".into()))
+    }
+
+    fn get_peers_whitelist(&self) -> Result<Vec<IpAddr>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_peers_whitelist".to_string(), Some(1));
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::GetWhitelist { responder: sender })
+            .map_err(|_| {
+                ProtocolError::ChannelError("get_peers_whitelist command send error".into())
+            })?;
+        receiver.recv_timeout(Duration::from_secs(10)).map_err(|_| {
+            ProtocolError::ChannelError("get_peers_whitelist command receive error".into())
+        })
+    }
+
+    fn add_to_peers_whitelist(&self, ips: Vec<IpAddr>) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::AddToWhitelist(ips))
+            .map_err(|_| {
+                ProtocolError::ChannelError("add_to_peers_whitelist command send error".into())
+            })
+    }
+
+    fn remove_from_peers_whitelist(&self, ips: Vec<IpAddr>) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::RemoveFromWhitelist(ips))
+            .map_err(|_| {
+                ProtocolError::ChannelError("remove_from_peers_whitelist command send error".into())
+            })
+    }
+
+    fn get_static_peers(&self) -> Result<HashMap<SocketAddr, TransportType>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_static_peers".to_string(), Some(1));
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::GetStaticPeers { responder: sender })
+            .map_err(|_| {
+                ProtocolError::ChannelError("get_static_peers command send error".into())
+            })?;
+        receiver.recv_timeout(Duration::from_secs(10)).map_err(|_| {
+            ProtocolError::ChannelError("get_static_peers command receive error".into())
+        })
+    }
+
+    fn add_static_peers(
+        &self,
+        peers: HashMap<SocketAddr, TransportType>,
+    ) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::AddStaticPeers(peers))
+            .map_err(|_| {
+                ProtocolError::ChannelError("add_static_peers command send error".into())
+            })
+    }
+
+    fn remove_static_peers(&self, addrs: Vec<SocketAddr>) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::RemoveStaticPeers(addrs))
+            .map_err(|_| {
+                ProtocolError::ChannelError("remove_static_peers command send error".into())
+            })
+    }
+
     fn get_bootstrap_peers(&self) -> Result<BootstrapPeers, ProtocolError> {
         let (sender, receiver) = MassaChannel::new("get_bootstrap_peers".to_string(), Some(1));
         self.sender_peer_management_thread
@@ -3,7 +3,11 @@ use std::{
     sync::Arc,
 };
 
-use massa_models::operation::{OperationId, OperationPrefixId};
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    operation::{OperationId, OperationPrefixId, SecureShareOperation},
+};
 use massa_protocol_exports::PeerId;
 use parking_lot::RwLock;
 use schnellru::{ByLength, LruMap};
@@ -18,6 +22,8 @@ pub struct OperationCache {
     pub ops_known_by_peer: HashMap<PeerId, LruMap<OperationPrefixId, ()>>,
     /// Maximum number of operations known by a peer
     pub max_known_ops_by_peer: u32,
+    /// Operations superseded by checked operations, with the sender and fee of their replacement
+    pub replacements: LruMap<OperationId, (Address, Amount)>,
 }
 
 impl OperationCache {
@@ -28,9 +34,34 @@ impl OperationCache {
             checked_operations_prefix: LruMap::new(ByLength::new(max_known_ops)),
             ops_known_by_peer: HashMap::new(),
             max_known_ops_by_peer,
+            replacements: LruMap::new(ByLength::new(max_known_ops)),
         }
     }
 
+    /// Note the operation superseded by a checked operation, if any,
+    /// keeping the replacement paying the highest fee
+    pub fn insert_replacement(&mut self, operation: &SecureShareOperation) {
+        let Some(superseded_id) = operation.content.op.get_superseded_op_id() else {
+            return;
+        };
+        let replacement = (operation.content_creator_address, operation.content.fee);
+        match self.replacements.get(&superseded_id) {
+            Some((_, fee)) if *fee >= operation.content.fee => {}
+            _ => {
+                self.replacements.insert(superseded_id, replacement);
+            }
+        }
+    }
+
+    /// Check whether a replacement paying a higher fee was received for an operation
+    pub fn is_superseded(&self, operation: &SecureShareOperation) -> bool {
+        self.replacements
+            .peek(&operation.id)
+            .map_or(false, |(sender, fee)| {
+                *sender == operation.content_creator_address && *fee > operation.content.fee
+            })
+    }
+
     /// Mark a list of operation ID prefixes as known by a peer
     pub fn insert_peer_known_ops(&mut self, peer_id: &PeerId, ops: &[OperationPrefixId]) {
         let known_ops = self
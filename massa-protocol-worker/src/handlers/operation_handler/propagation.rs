@@ -47,14 +47,23 @@ impl PropagationThread {
                 Ok(internal_message) => {
                     match internal_message {
                         OperationHandlerPropagationCommand::PropagateOperations(operations) => {
-                            // Note operations as checked.
+                            // Note operations as checked, and the operations they supersede.
                             {
                                 let mut cache_write = self.cache.write();
                                 for op_id in operations.get_op_refs().iter().copied() {
                                     cache_write.insert_checked_operation(op_id);
                                 }
+                                let ops = operations.read_operations();
+                                for op_id in operations.get_op_refs() {
+                                    if let Some(operation) = ops.get(op_id) {
+                                        cache_write.insert_replacement(operation);
+                                    }
+                                }
                             }
 
+                            // stop propagating the operations superseded by the new ones
+                            self.drop_superseded_ops(&operations);
+
                             // add to propagation storage
                             let new_ops = operations.get_op_refs().clone();
                             self.stored_for_propagation
@@ -97,6 +106,30 @@ impl PropagationThread {
         }
     }
 
+    /// Stop propagating the operations superseded by new operations paying a higher fee.
+    fn drop_superseded_ops(&mut self, new_operations: &Storage) {
+        let superseded: PreHashSet<OperationId> = {
+            let cache_read = self.cache.read();
+            let ops = self.op_storage.read_operations();
+            new_operations
+                .get_op_refs()
+                .iter()
+                .filter_map(|id| ops.get(id)?.content.op.get_superseded_op_id())
+                .filter(|superseded_id| {
+                    self.op_storage.get_op_refs().contains(superseded_id)
+                        && ops
+                            .get(superseded_id)
+                            .map_or(false, |operation| cache_read.is_superseded(operation))
+                })
+                .collect()
+        };
+        if superseded.is_empty() {
+            return;
+        }
+        self.next_batch.retain(|id| !superseded.contains(id));
+        self.op_storage.drop_operation_refs(&superseded);
+    }
+
     /// Prune the list of operations kept for propagation.
     fn prune_propagation_storage(&mut self) {
         let mut removed = PreHashSet::default();
@@ -7,7 +7,6 @@ use massa_metrics::MassaMetrics;
 use massa_models::{
     operation::{OperationPrefixId, OperationPrefixIds, SecureShareOperation},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
-    secure_share::Id,
     slot::Slot,
     timeslots::get_block_slot_timestamp,
 };
@@ -22,7 +21,7 @@ use schnellru::{ByLength, LruMap};
 use crate::{
     handlers::peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
     messages::MessagesSerializer,
-    sig_verifier::verify_sigs_batch,
+    sig_verifier::verify_operations_sigs_batch,
     wrap_network::ActiveConnectionsTrait,
 };
 use tracing::{debug, info, warn};
@@ -419,12 +418,7 @@ pub(crate) fn note_operations_from_peer(
     }
 
     // optimized signature verification
-    verify_sigs_batch(
-        &new_operations
-            .iter()
-            .map(|(op_id, op)| (*op_id.get_hash(), op.signature, op.content_creator_pub_key))
-            .collect::<Vec<_>>(),
-    )?;
+    verify_operations_sigs_batch(new_operations.values())?;
 
     {
         // add to checked operations
@@ -435,6 +429,12 @@ pub(crate) fn note_operations_from_peer(
             cache_write.insert_checked_operation(op_id);
         }
 
+        // operations superseded by a replacement paying a higher fee are neither propagated nor pooled
+        for operation in new_operations.values() {
+            cache_write.insert_replacement(operation);
+        }
+        new_operations.retain(|_, operation| !cache_write.is_superseded(operation));
+
         // add to known ops
         cache_write.insert_peer_known_ops(
             source_peer_id,
@@ -1,10 +1,12 @@
 use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::{collections::HashMap, net::SocketAddr, thread::JoinHandle, time::Duration};
 
 use crossbeam::channel::tick;
 use crossbeam::select;
 use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
-use massa_hash::Hash;
+use massa_hash::{Hash, HASH_SIZE_BYTES};
 use massa_metrics::MassaMetrics;
 use massa_models::config::SIGNATURE_DESER_SIZE;
 use massa_models::version::{VersionDeserializer, VersionSerializer};
@@ -13,6 +15,7 @@ use massa_protocol_exports::{
 };
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::Signature;
+use massa_time::MassaTime;
 use peernet::context::Context as _;
 use peernet::messages::MessagesSerializer as _;
 use rand::{rngs::StdRng, RngCore, SeedableRng};
@@ -27,7 +30,8 @@ use tracing::log::{debug, error, info, warn};
 
 use crate::context::Context;
 use crate::handlers::peer_handler::models::PeerState;
-use crate::messages::{Message, MessagesHandler, MessagesSerializer};
+use crate::ip::to_canonical;
+use crate::messages::{Message, MessagesHandler, MessagesSerializer, PeerConnectionStats};
 use crate::wrap_network::ActiveConnectionsTrait;
 
 use self::models::{ConnectionMetadata, PeerInfo};
@@ -49,7 +53,7 @@ use self::{
 /// This file contains the definition of the peer management handler
 /// This handler is here to check that announcements we receive are valid and
 /// that all the endpoints we received are active.
-mod announcement;
+pub(crate) mod announcement;
 mod messages;
 pub mod models;
 mod tester;
@@ -61,6 +65,8 @@ pub struct PeerManagementHandler {
     pub thread_join: Option<JoinHandle<()>>,
     pub sender: PeerManagementChannel,
     testers: Vec<Tester>,
+    peers_db_file: PathBuf,
+    max_peers_db_size: usize,
 }
 
 impl PeerManagementHandler {
@@ -114,6 +120,7 @@ impl PeerManagementHandler {
                 loop {
                     select! {
                         recv(ticker) -> _ => {
+                            peer_db.write().prune_tested_addresses(config.tested_addresses_max_age);
                             let peers_to_send = peer_db.read().get_rand_peers_to_send(100);
                             if peers_to_send.is_empty() {
                                 continue;
@@ -146,6 +153,57 @@ impl PeerManagementHandler {
                                     peer_db.write().unban_peer(&peer_id);
                                 }
                             },
+                             Ok(PeerManagementCmd::BanIps(ips)) => {
+                                let banned_ips = peer_db.write().ban_ips(ips);
+                                for (peer_id, (addr, _, _)) in active_connections.get_peers_connected() {
+                                    if banned_ips.contains(&to_canonical(addr.ip())) {
+                                        active_connections.shutdown_connection(&peer_id);
+                                    }
+                                }
+                             },
+                             Ok(PeerManagementCmd::UnbanIps(ips)) => {
+                                peer_db.write().unban_ips(ips);
+                             },
+                             Ok(PeerManagementCmd::AddToWhitelist(ips)) => {
+                                peer_db.write().add_to_whitelist(ips);
+                             },
+                             Ok(PeerManagementCmd::RemoveFromWhitelist(ips)) => {
+                                peer_db.write().remove_from_whitelist(ips);
+                             },
+                             Ok(PeerManagementCmd::GetWhitelist { responder }) => {
+                                let mut whitelist: Vec<IpAddr> = peer_db.read().whitelisted_ips.iter().copied().collect();
+                                whitelist.sort();
+                                if let Err(err) = responder.try_send(whitelist) {
+                                    warn!("error sending peers whitelist: {:?}", err);
+                                }
+                             },
+                             Ok(PeerManagementCmd::AddStaticPeers(peers)) => {
+                                let mut peer_db_write = peer_db.write();
+                                for (addr, transport) in peers {
+                                    info!("Added static peer: {}", addr);
+                                    peer_db_write.static_peers.insert(addr, transport);
+                                }
+                                if let Err(err) = peer_db_write.save_static_peers(&config.static_peers_file) {
+                                    warn!("could not save static peers: {}", err);
+                                }
+                             },
+                             Ok(PeerManagementCmd::RemoveStaticPeers(addrs)) => {
+                                let mut peer_db_write = peer_db.write();
+                                for addr in addrs {
+                                    if peer_db_write.static_peers.remove(&addr).is_some() {
+                                        info!("Removed static peer: {}", addr);
+                                    }
+                                }
+                                if let Err(err) = peer_db_write.save_static_peers(&config.static_peers_file) {
+                                    warn!("could not save static peers: {}", err);
+                                }
+                             },
+                             Ok(PeerManagementCmd::GetStaticPeers { responder }) => {
+                                let static_peers = peer_db.read().static_peers.clone();
+                                if let Err(err) = responder.try_send(static_peers) {
+                                    warn!("error sending static peers: {:?}", err);
+                                }
+                             },
                              Ok(PeerManagementCmd::GetBootstrapPeers { responder }) => {
                                 let mut peers = peer_db.read().get_rand_peers_to_send(100);
                                 // Add myself
@@ -238,6 +296,8 @@ impl PeerManagementHandler {
                 command_sender: sender_cmd,
             },
             testers,
+            peers_db_file: config.peers_db_file.clone(),
+            max_peers_db_size: config.max_peers_db_size,
         }
     }
 
@@ -253,6 +313,14 @@ impl PeerManagementHandler {
                 join_handle.join().expect("Failed to join tester thread");
             }
         });
+
+        if let Err(err) = self
+            .peer_db
+            .read()
+            .save_to_file(&self.peers_db_file, self.max_peers_db_size)
+        {
+            warn!("could not save peer database: {}", err);
+        }
     }
 }
 
@@ -314,6 +382,12 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
         messages_handler: MessagesHandler,
     ) -> PeerNetResult<PeerId> {
         let addr = *endpoint.get_target_addr();
+        if self.peer_db.read().is_ip_banned(&addr.ip()) {
+            return Err(PeerNetError::HandshakeError.error(
+                "Massa Handshake",
+                Some(format!("Refused connection with banned ip {}", addr.ip())),
+            ));
+        }
         let mut bytes = vec![];
         self.peer_id_serializer
             .serialize(&context.get_peer_id(), &mut bytes)
@@ -333,6 +407,7 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                     Some(format!("Failed to serialize version: {}", err)),
                 )
             })?;
+        bytes.extend_from_slice(self.config.chain_id.to_bytes());
         bytes.push(0);
         let listeners_announcement = Announcement::new(
             listeners.clone(),
@@ -403,6 +478,10 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                     Some(format!("Received version incompatible: {}", version)),
                 ));
             }
+            let received = check_chain_id(received, &self.config.chain_id).map_err(|err| {
+                self.handshake_fail(&addr);
+                err
+            })?;
             let id = received.first().ok_or(
                 PeerNetError::HandshakeError
                     .error("Massa Handshake", Some("Failed to get id".to_string())),
@@ -517,6 +596,12 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
             match &res {
                 Ok((peer_id, Some(announcement))) => {
                     info!("Peer connected: {:?}", peer_id);
+                    if let Ok(now) = MassaTime::now() {
+                        self.message_handlers
+                            .peer_stats
+                            .write()
+                            .insert(peer_id.clone(), Arc::new(PeerConnectionStats::new(now)));
+                    }
                     peer_db_write
                         .try_connect_history
                         .entry(addr)
@@ -591,6 +676,7 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
         let version_serializer = self.version_serializer.clone();
         let peer_id_serializer = self.peer_id_serializer.clone();
         let version = self.config.version;
+        let chain_id = self.config.chain_id;
         std::thread::spawn(move || {
             let peers_to_send = db.read().get_rand_peers_to_send(100);
             let mut buf = vec![];
@@ -613,6 +699,7 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                 warn!("{}", err.to_string());
                 return;
             }
+            buf.extend_from_slice(chain_id.to_bytes());
             buf.push(1);
             let msg = PeerManagementMessage::ListPeers(peers_to_send).into();
             if let Err(err) = serializer.serialize(&msg, &mut buf) {
@@ -630,3 +717,23 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
         Ok(())
     }
 }
+
+/// Check the chain id following the version in a handshake, and return the rest of the data.
+/// Peers running another chain spec are refused.
+/// The chain id was added to the handshake with the major version 27:
+/// peers of previous versions are refused by the version check before.
+pub(crate) fn check_chain_id<'a>(data: &'a [u8], chain_id: &Hash) -> PeerNetResult<&'a [u8]> {
+    let received_chain_id = data.get(..HASH_SIZE_BYTES).ok_or_else(|| {
+        PeerNetError::HandshakeError.error(
+            "Massa Handshake",
+            Some("Failed to get chain id".to_string()),
+        )
+    })?;
+    if received_chain_id != chain_id.to_bytes() {
+        return Err(PeerNetError::HandshakeError.error(
+            "Massa Handshake",
+            Some("Received chain id incompatible: the peer runs another chain spec".to_string()),
+        ));
+    }
+    Ok(&data[HASH_SIZE_BYTES..])
+}
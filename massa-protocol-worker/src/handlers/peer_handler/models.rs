@@ -1,29 +1,38 @@
 use massa_channel::sender::MassaSender;
 use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolError};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_time::MassaTime;
 use parking_lot::RwLock;
 use peernet::transports::TransportType;
 use rand::seq::SliceRandom;
 use rand::{thread_rng, Rng};
-use std::cmp::Ordering;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
 use std::collections::HashSet;
+use std::net::IpAddr;
+use std::path::Path;
 use std::time::Duration;
 use std::{collections::HashMap, net::SocketAddr, sync::Arc};
-use tracing::log::info;
+use tracing::log::{info, warn};
 
-use super::announcement::Announcement;
+use crate::ip::to_canonical;
+
+use super::announcement::{
+    Announcement, AnnouncementDeserializer, AnnouncementDeserializerArgs, AnnouncementSerializer,
+};
 
 const THREE_DAYS_MS: u64 = 3 * 24 * 60 * 60 * 1_000;
 
 pub type InitialPeers = HashMap<PeerId, HashMap<SocketAddr, TransportType>>;
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ConnectionMetadata {
     pub last_success: Option<MassaTime>,
     pub last_failure: Option<MassaTime>,
     pub last_try_connect: Option<MassaTime>,
     pub last_test_success: Option<MassaTime>,
     pub last_test_failure: Option<MassaTime>,
+    #[serde(skip, default = "rand::random")]
     random_priority: u64,
 }
 
@@ -148,12 +157,19 @@ impl ConnectionMetadata {
 #[derive(Default)]
 pub struct PeerDB {
     pub peers: HashMap<PeerId, PeerInfo>,
-    /// Tested addresses used to avoid testing the same address too often. //TODO: Need to be pruned
+    /// Tested addresses used to avoid testing the same address too often. Pruned with `prune_tested_addresses`
     pub tested_addresses: HashMap<SocketAddr, MassaTime>,
     /// history of try connection to peers
     pub try_connect_history: HashMap<SocketAddr, ConnectionMetadata>,
     /// peers currently tested
     pub peers_in_test: HashSet<SocketAddr>,
+    /// IPs banned by the node operator. Connections from and to these IPs are refused
+    pub banned_ips: HashSet<IpAddr>,
+    /// IPs whitelisted by the node operator. They can't be banned by IP and are connected to first
+    pub whitelisted_ips: HashSet<IpAddr>,
+    /// Listeners pinned by the node operator. They are always kept connected
+    /// and their connections don't take any connection slot
+    pub static_peers: HashMap<SocketAddr, TransportType>,
 }
 
 pub type SharedPeerDB = Arc<RwLock<PeerDB>>;
@@ -166,25 +182,51 @@ pub struct PeerInfo {
     pub state: PeerState,
 }
 
-#[warn(dead_code)]
-#[derive(Eq, PartialEq, Clone, Debug)]
-pub enum PeerState {
-    Banned,
-    InHandshake,
-    HandshakeFailed,
-    Trusted,
-}
+pub use massa_protocol_exports::PeerState;
 
 #[derive(Clone)]
 pub enum PeerManagementCmd {
     Ban(Vec<PeerId>),
     Unban(Vec<PeerId>),
+    BanIps(Vec<IpAddr>),
+    UnbanIps(Vec<IpAddr>),
+    AddToWhitelist(Vec<IpAddr>),
+    RemoveFromWhitelist(Vec<IpAddr>),
+    GetWhitelist {
+        responder: MassaSender<Vec<IpAddr>>,
+    },
+    AddStaticPeers(HashMap<SocketAddr, TransportType>),
+    RemoveStaticPeers(Vec<SocketAddr>),
+    GetStaticPeers {
+        responder: MassaSender<HashMap<SocketAddr, TransportType>>,
+    },
     GetBootstrapPeers {
         responder: MassaSender<BootstrapPeers>,
     },
     Stop,
 }
 
+/// On-disk representation of the `PeerDB`
+#[derive(Default, Serialize, Deserialize)]
+struct PeerDBDump {
+    peers: Vec<PeerDump>,
+    tested_addresses: HashMap<SocketAddr, MassaTime>,
+    try_connect_history: HashMap<SocketAddr, ConnectionMetadata>,
+    #[serde(default)]
+    banned_ips: HashSet<IpAddr>,
+    #[serde(default)]
+    whitelisted_ips: HashSet<IpAddr>,
+}
+
+/// On-disk representation of a peer. The announcement is kept in its signed
+/// binary form so that it can be verified again when loaded.
+#[derive(Serialize, Deserialize)]
+struct PeerDump {
+    peer_id: PeerId,
+    state: PeerState,
+    last_announce: Option<Vec<u8>>,
+}
+
 pub struct PeerManagementChannel {
     pub msg_sender: MassaSender<PeerMessageTuple>,
     pub command_sender: MassaSender<PeerManagementCmd>,
@@ -210,6 +252,119 @@ impl PeerDB {
         };
     }
 
+    /// Ban the given IPs, except the whitelisted ones.
+    /// Returns the IPs that were actually banned.
+    pub fn ban_ips(&mut self, ips: Vec<IpAddr>) -> HashSet<IpAddr> {
+        let mut banned = HashSet::new();
+        for ip in ips.into_iter().map(to_canonical) {
+            if self.whitelisted_ips.contains(&ip) {
+                info!("Tried to ban whitelisted ip: {}", ip);
+                continue;
+            }
+            self.banned_ips.insert(ip);
+            banned.insert(ip);
+            info!("Banned ip: {}", ip);
+        }
+        banned
+    }
+
+    pub fn unban_ips(&mut self, ips: Vec<IpAddr>) {
+        for ip in ips.into_iter().map(to_canonical) {
+            if self.banned_ips.remove(&ip) {
+                info!("Unbanned ip: {}", ip);
+            } else {
+                info!("Tried to unban ip that is not banned: {}", ip);
+            }
+        }
+    }
+
+    /// Whitelist the given IPs. A whitelisted IP is also unbanned.
+    pub fn add_to_whitelist(&mut self, ips: Vec<IpAddr>) {
+        for ip in ips.into_iter().map(to_canonical) {
+            self.banned_ips.remove(&ip);
+            self.whitelisted_ips.insert(ip);
+        }
+    }
+
+    pub fn remove_from_whitelist(&mut self, ips: Vec<IpAddr>) {
+        for ip in ips.into_iter().map(to_canonical) {
+            self.whitelisted_ips.remove(&ip);
+        }
+    }
+
+    /// Whether connections from and to this IP are refused
+    pub fn is_ip_banned(&self, ip: &IpAddr) -> bool {
+        self.banned_ips.contains(&to_canonical(*ip))
+    }
+
+    /// Whether this IP is whitelisted
+    pub fn is_ip_whitelisted(&self, ip: &IpAddr) -> bool {
+        self.whitelisted_ips.contains(&to_canonical(*ip))
+    }
+
+    /// Whether this listener is a static peer
+    pub fn is_static_peer(&self, addr: &SocketAddr) -> bool {
+        self.static_peers.contains_key(addr)
+    }
+
+    /// Whether the given connected peer is a static peer, either because we are connected
+    /// to a static listener or because it announced one.
+    pub fn is_static_connection(&self, peer_id: &PeerId, addr: &SocketAddr) -> bool {
+        if self.static_peers.is_empty() {
+            return false;
+        }
+        self.is_static_peer(addr)
+            || self
+                .peers
+                .get(peer_id)
+                .and_then(|info| info.last_announce.as_ref())
+                .map(|announce| {
+                    announce
+                        .listeners
+                        .keys()
+                        .any(|listener| self.is_static_peer(listener))
+                })
+                .unwrap_or(false)
+    }
+
+    /// Load the static peers saved with `save_static_peers`. A missing file yields no static peer.
+    pub fn load_static_peers(&mut self, path: &Path) {
+        if !path.is_file() {
+            return;
+        }
+        match std::fs::read(path)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| {
+                serde_json::from_slice::<HashMap<SocketAddr, TransportType>>(&bytes)
+                    .map_err(|err| err.to_string())
+            }) {
+            Ok(static_peers) => {
+                info!(
+                    "loaded {} static peers from {}",
+                    static_peers.len(),
+                    path.display()
+                );
+                self.static_peers = static_peers;
+            }
+            Err(err) => warn!(
+                "could not load static peers from {}: {}",
+                path.display(),
+                err
+            ),
+        }
+    }
+
+    /// Save the static peers to `path`
+    pub fn save_static_peers(&self, path: &Path) -> Result<(), ProtocolError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(&self.static_peers)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
     /// Retrieve the peer with the oldest test date.
     pub fn get_oldest_peer(
         &self,
@@ -285,8 +440,168 @@ impl PeerDB {
             .count() as u64
     }
 
-    // Flush PeerDB to disk ?
-    fn _flush(&self) -> Result<(), ProtocolError> {
-        unimplemented!()
+    /// Remove the tested addresses that were last tested more than `max_age` ago,
+    /// unless they are still announced by a trusted peer.
+    pub fn prune_tested_addresses(&mut self, max_age: MassaTime) {
+        let min_time = MassaTime::now()
+            .expect("Unable to get MassaTime::now")
+            .saturating_sub(max_age);
+        let trusted_listeners: HashSet<SocketAddr> = self
+            .peers
+            .values()
+            .filter(|peer| peer.state == PeerState::Trusted)
+            .filter_map(|peer| peer.last_announce.as_ref())
+            .flat_map(|announce| announce.listeners.keys().copied())
+            .collect();
+        self.tested_addresses
+            .retain(|addr, timestamp| *timestamp >= min_time || trusted_listeners.contains(addr));
+    }
+
+    /// Load a `PeerDB` previously saved with `save_to_file`.
+    ///
+    /// A missing file yields an empty database. A corrupted file is ignored with a warning,
+    /// and peers whose announcement can't be deserialized or verified are skipped.
+    pub fn load_from_file(
+        path: &Path,
+        max_listeners: u64,
+        tested_addresses_max_age: MassaTime,
+    ) -> Self {
+        if !path.is_file() {
+            return PeerDB::default();
+        }
+        let dump = match std::fs::read(path)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| {
+                serde_json::from_slice::<PeerDBDump>(&bytes).map_err(|err| err.to_string())
+            }) {
+            Ok(dump) => dump,
+            Err(err) => {
+                warn!(
+                    "could not load peer database from {}: {}",
+                    path.display(),
+                    err
+                );
+                return PeerDB::default();
+            }
+        };
+
+        let announcement_deserializer =
+            AnnouncementDeserializer::new(AnnouncementDeserializerArgs { max_listeners });
+        let mut peer_db = PeerDB::default();
+        for peer in dump.peers {
+            let last_announce = match peer.last_announce {
+                Some(bytes) => {
+                    let announcement =
+                        match announcement_deserializer.deserialize::<DeserializeError>(&bytes) {
+                            Ok((rest, announcement)) if rest.is_empty() => announcement,
+                            _ => {
+                                warn!(
+                                    "skipping peer {} with invalid announcement in peer database",
+                                    peer.peer_id
+                                );
+                                continue;
+                            }
+                        };
+                    if peer
+                        .peer_id
+                        .verify_signature(&announcement.hash, &announcement.signature)
+                        .is_err()
+                    {
+                        warn!(
+                            "skipping peer {} with invalid announcement signature in peer database",
+                            peer.peer_id
+                        );
+                        continue;
+                    }
+                    Some(announcement)
+                }
+                None => None,
+            };
+            // A handshake can't survive a restart: force the peer to be tested again
+            let state = match peer.state {
+                PeerState::InHandshake => PeerState::HandshakeFailed,
+                state => state,
+            };
+            peer_db.peers.insert(
+                peer.peer_id,
+                PeerInfo {
+                    last_announce,
+                    state,
+                },
+            );
+        }
+        peer_db.tested_addresses = dump.tested_addresses;
+        peer_db.try_connect_history = dump.try_connect_history;
+        peer_db.banned_ips = dump.banned_ips;
+        peer_db.whitelisted_ips = dump.whitelisted_ips;
+        peer_db.prune_tested_addresses(tested_addresses_max_age);
+        info!(
+            "loaded {} peers from peer database {}",
+            peer_db.peers.len(),
+            path.display()
+        );
+        peer_db
+    }
+
+    /// Save the `PeerDB` to `path`, keeping at most `max_peers` peers.
+    ///
+    /// Peers with the most recent announcements are kept first. The file is written
+    /// to a temporary path and then renamed so that a crash never leaves a partial file.
+    pub fn save_to_file(&self, path: &Path, max_peers: usize) -> Result<(), ProtocolError> {
+        let announcement_serializer = AnnouncementSerializer::new();
+        let mut peers: Vec<(&PeerId, &PeerInfo)> = self.peers.iter().collect();
+        peers.sort_by_key(|(_, info)| {
+            Reverse(
+                info.last_announce
+                    .as_ref()
+                    .map(|announce| announce.timestamp),
+            )
+        });
+        peers.truncate(max_peers);
+
+        let mut dump = PeerDBDump::default();
+        let mut kept_listeners = HashSet::new();
+        for (peer_id, info) in peers {
+            let last_announce = match &info.last_announce {
+                Some(announce) => {
+                    kept_listeners.extend(announce.listeners.keys().copied());
+                    let mut bytes = Vec::new();
+                    announcement_serializer
+                        .serialize(announce, &mut bytes)
+                        .map_err(|err| ProtocolError::GeneralProtocolError(err.to_string()))?;
+                    Some(bytes)
+                }
+                None => None,
+            };
+            dump.peers.push(PeerDump {
+                peer_id: peer_id.clone(),
+                state: info.state.clone(),
+                last_announce,
+            });
+        }
+        dump.try_connect_history = self
+            .try_connect_history
+            .iter()
+            .filter(|(addr, _)| kept_listeners.contains(addr))
+            .map(|(addr, metadata)| (*addr, metadata.clone()))
+            .collect();
+        let mut tested_addresses: Vec<(&SocketAddr, &MassaTime)> =
+            self.tested_addresses.iter().collect();
+        tested_addresses.sort_by_key(|(_, timestamp)| Reverse(**timestamp));
+        dump.tested_addresses = tested_addresses
+            .into_iter()
+            .take(max_peers)
+            .map(|(addr, timestamp)| (*addr, *timestamp))
+            .collect();
+        dump.banned_ips = self.banned_ips.clone();
+        dump.whitelisted_ips = self.whitelisted_ips.clone();
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(&dump)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
     }
 }
@@ -153,6 +153,7 @@ impl Tester {
                         Some(format!("Received version incompatible: {}", version)),
                     ));
                 }
+                let data = super::check_chain_id(data, &config.chain_id)?;
                 let id = data.first().ok_or(
                     PeerNetError::HandshakeError
                         .error("Massa Handshake", Some("Failed to get id".to_string())),
@@ -1,9 +1,15 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
 use massa_channel::sender::MassaSender;
-use massa_protocol_exports::PeerId;
+use massa_protocol_exports::{PeerId, PeerMessageCounts};
 use massa_serialization::{
     DeserializeError, Deserializer, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
 };
+use massa_time::MassaTime;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use parking_lot::RwLock;
 use peernet::{
     error::{PeerNetError, PeerNetResult},
     messages::{
@@ -217,6 +223,49 @@ impl PeerNetMessagesSerializer<Message> for MessagesSerializer {
     }
 }
 
+/// Statistics of the current connection with a peer
+pub struct PeerConnectionStats {
+    pub connected_since: MassaTime,
+    block: AtomicU64,
+    endorsement: AtomicU64,
+    operation: AtomicU64,
+    peer_management: AtomicU64,
+}
+
+impl PeerConnectionStats {
+    pub fn new(connected_since: MassaTime) -> Self {
+        Self {
+            connected_since,
+            block: AtomicU64::new(0),
+            endorsement: AtomicU64::new(0),
+            operation: AtomicU64::new(0),
+            peer_management: AtomicU64::new(0),
+        }
+    }
+
+    fn record_message(&self, id: &MessageTypeId) {
+        let counter = match id {
+            MessageTypeId::Block => &self.block,
+            MessageTypeId::Endorsement => &self.endorsement,
+            MessageTypeId::Operation => &self.operation,
+            MessageTypeId::PeerManagement => &self.peer_management,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get_message_counts(&self) -> PeerMessageCounts {
+        PeerMessageCounts {
+            block: self.block.load(Ordering::Relaxed),
+            endorsement: self.endorsement.load(Ordering::Relaxed),
+            operation: self.operation.load(Ordering::Relaxed),
+            peer_management: self.peer_management.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Connection statistics of the peers, reset at each successful handshake
+pub type SharedPeerStats = Arc<RwLock<HashMap<PeerId, Arc<PeerConnectionStats>>>>;
+
 #[derive(Clone)]
 pub struct MessagesHandler {
     pub id_deserializer: U64VarIntDeserializer,
@@ -224,6 +273,7 @@ pub struct MessagesHandler {
     pub sender_endorsements: MassaSender<PeerMessageTuple>,
     pub sender_operations: MassaSender<PeerMessageTuple>,
     pub sender_peers: MassaSender<PeerMessageTuple>,
+    pub peer_stats: SharedPeerStats,
 }
 
 impl PeerNetMessagesHandler<PeerId> for MessagesHandler {
@@ -243,6 +293,9 @@ impl PeerNetMessagesHandler<PeerId> for MessagesHandler {
                 Some(String::from("Invalid message type id")),
             )
         })?;
+        if let Some(stats) = self.peer_stats.read().get(peer_id) {
+            stats.record_message(&id);
+        }
         match id {
             // Blocks are high-priority: we block if the channel is full.
             // This means that the sender will be blocked until the message is sent.
@@ -3,6 +3,7 @@
 //! Optimized batch signature verifier
 
 use massa_hash::Hash;
+use massa_models::{operation::SecureShareOperation, secure_share::Id};
 use massa_protocol_exports::ProtocolError;
 use massa_signature::{verify_signature_batch, PublicKey, Signature};
 use rayon::{prelude::ParallelIterator, slice::ParallelSlice};
@@ -14,6 +15,11 @@ const SMALL_BATCH_LIMIT: usize = 2;
 /// Efficiently verifies a batch of signatures in parallel.
 /// Returns an error if at least one of them fails to verify.
 pub fn verify_sigs_batch(ops: &[(Hash, Signature, PublicKey)]) -> Result<(), ProtocolError> {
+    // nothing to verify
+    if ops.is_empty() {
+        return Ok(());
+    }
+
     // if it's a small batch, use single-core verification
     if ops.len() <= SMALL_BATCH_LIMIT {
         return verify_signature_batch(ops).map_err(|_err| ProtocolError::WrongSignature);
@@ -28,3 +34,21 @@ pub fn verify_sigs_batch(ops: &[(Hash, Signature, PublicKey)]) -> Result<(), Pro
         .try_for_each(verify_signature_batch)
         .map_err(|_err| ProtocolError::WrongSignature)
 }
+
+/// Efficiently verifies the signatures of a batch of operations,
+/// including the co-signer signatures of multisig operations.
+/// Returns an error if at least one of them fails to verify.
+pub fn verify_operations_sigs_batch<'a>(
+    ops: impl IntoIterator<Item = &'a SecureShareOperation>,
+) -> Result<(), ProtocolError> {
+    let mut sigs = Vec::new();
+    for op in ops {
+        sigs.push((*op.id.get_hash(), op.signature, op.content_creator_pub_key));
+        sigs.extend(
+            op.content
+                .get_multisig_signature_batch(&op.content_creator_pub_key)
+                .map_err(|_err| ProtocolError::WrongSignature)?,
+        );
+    }
+    verify_sigs_batch(&sigs)
+}
@@ -0,0 +1,69 @@
+use std::ops::Bound::Included;
+use std::sync::Arc;
+
+use massa_channel::MassaChannel;
+use massa_protocol_exports::{PeerId, PeerMessageCounts};
+use massa_serialization::{Serializer, U64VarIntDeserializer, U64VarIntSerializer};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use peernet::messages::MessagesHandler as _;
+
+use crate::messages::{MessageTypeId, MessagesHandler, PeerConnectionStats};
+
+fn message(id: MessageTypeId) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    U64VarIntSerializer::new()
+        .serialize(&id.into(), &mut bytes)
+        .unwrap();
+    bytes
+}
+
+#[test]
+fn test_connected_peer_message_counts() {
+    let (sender_blocks, _receiver_blocks) = MassaChannel::new("blocks".to_string(), Some(10));
+    let (sender_endorsements, _receiver_endorsements) =
+        MassaChannel::new("endorsements".to_string(), Some(10));
+    let (sender_operations, _receiver_operations) =
+        MassaChannel::new("operations".to_string(), Some(10));
+    let (sender_peers, _receiver_peers) = MassaChannel::new("peers".to_string(), Some(10));
+    let handler = MessagesHandler {
+        id_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+        sender_blocks,
+        sender_endorsements,
+        sender_operations,
+        sender_peers,
+        peer_stats: Default::default(),
+    };
+
+    let connected_peer = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+    let unknown_peer = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+    let stats = Arc::new(PeerConnectionStats::new(MassaTime::now().unwrap()));
+    handler
+        .peer_stats
+        .write()
+        .insert(connected_peer.clone(), stats.clone());
+
+    handler
+        .handle(&message(MessageTypeId::Operation), &connected_peer)
+        .unwrap();
+    handler
+        .handle(&message(MessageTypeId::Operation), &connected_peer)
+        .unwrap();
+    handler
+        .handle(&message(MessageTypeId::Block), &connected_peer)
+        .unwrap();
+    handler
+        .handle(&message(MessageTypeId::Endorsement), &unknown_peer)
+        .unwrap();
+
+    assert_eq!(
+        stats.get_message_counts(),
+        PeerMessageCounts {
+            block: 1,
+            endorsement: 0,
+            operation: 2,
+            peer_management: 0,
+        }
+    );
+    assert!(!handler.peer_stats.read().contains_key(&unknown_peer));
+}
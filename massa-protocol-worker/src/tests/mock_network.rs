@@ -226,6 +226,7 @@ impl NetworkController for MockNetworkController {
 
     fn try_connect(
         &mut self,
+        _transport_type: peernet::transports::TransportType,
         _addr: std::net::SocketAddr,
         _timeout: std::time::Duration,
     ) -> Result<(), massa_protocol_exports::ProtocolError> {
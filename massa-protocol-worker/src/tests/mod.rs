@@ -18,11 +18,13 @@ use crate::{create_protocol_controller, start_protocol_controller};
 mod ban_nodes_scenarios;
 mod block_scenarios;
 mod cache_scenarios;
+mod connected_peers;
 mod context;
 mod endorsements_scenarios;
 mod in_block_operations_scenarios;
 mod mock_network;
 mod operations_scenarios;
+mod peer_db_persistence;
 mod peer_priorization;
 mod tools;
 
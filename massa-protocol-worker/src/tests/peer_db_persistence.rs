@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use massa_protocol_exports::PeerId;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use peernet::transports::TransportType;
+use tempfile::NamedTempFile;
+
+use crate::handlers::peer_handler::announcement::Announcement;
+use crate::handlers::peer_handler::models::{ConnectionMetadata, PeerDB, PeerInfo, PeerState};
+
+const ONE_DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+fn create_peer(ip: [u8; 4], state: PeerState) -> (PeerId, PeerInfo, SocketAddr) {
+    let keypair = KeyPair::generate(0).unwrap();
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::from(ip)), 31244);
+    let announcement = Announcement::new(
+        HashMap::from([(addr, TransportType::Tcp)]),
+        Some(addr.ip()),
+        &keypair,
+    )
+    .unwrap();
+    (
+        PeerId::from_public_key(keypair.get_public_key()),
+        PeerInfo {
+            last_announce: Some(announcement),
+            state,
+        },
+        addr,
+    )
+}
+
+#[test]
+fn test_peer_db_save_and_load() {
+    let file = NamedTempFile::new().unwrap();
+    let now = MassaTime::now().unwrap();
+
+    let mut peer_db = PeerDB::default();
+    let (trusted_id, trusted_info, trusted_addr) = create_peer([1, 2, 3, 4], PeerState::Trusted);
+    let (banned_id, banned_info, _) = create_peer([5, 6, 7, 8], PeerState::Banned);
+    let (handshake_id, handshake_info, _) = create_peer([9, 10, 11, 12], PeerState::InHandshake);
+    peer_db
+        .peers
+        .insert(trusted_id.clone(), trusted_info.clone());
+    peer_db.peers.insert(banned_id.clone(), banned_info);
+    peer_db.peers.insert(handshake_id.clone(), handshake_info);
+    let mut metadata = ConnectionMetadata::default();
+    metadata.success();
+    peer_db
+        .try_connect_history
+        .insert(trusted_addr, metadata.clone());
+
+    // an old address announced by a trusted peer is kept, an old unknown one is pruned
+    let old = now.saturating_sub(MassaTime::from_millis(2 * ONE_DAY_MS));
+    let unknown_addr: SocketAddr = "13.14.15.16:31244".parse().unwrap();
+    let recent_addr: SocketAddr = "17.18.19.20:31244".parse().unwrap();
+    peer_db.tested_addresses.insert(trusted_addr, old);
+    peer_db.tested_addresses.insert(unknown_addr, old);
+    peer_db.tested_addresses.insert(recent_addr, now);
+
+    peer_db.save_to_file(file.path(), 100).unwrap();
+    let loaded = PeerDB::load_from_file(file.path(), 100, MassaTime::from_millis(ONE_DAY_MS));
+
+    assert_eq!(loaded.peers.len(), 3);
+    let trusted = loaded.peers.get(&trusted_id).unwrap();
+    assert_eq!(trusted.state, PeerState::Trusted);
+    assert_eq!(trusted.last_announce, trusted_info.last_announce);
+    assert_eq!(
+        loaded.peers.get(&banned_id).unwrap().state,
+        PeerState::Banned
+    );
+    assert_eq!(
+        loaded.peers.get(&handshake_id).unwrap().state,
+        PeerState::HandshakeFailed
+    );
+    assert_eq!(
+        loaded
+            .try_connect_history
+            .get(&trusted_addr)
+            .unwrap()
+            .last_success,
+        metadata.last_success
+    );
+    assert!(loaded.tested_addresses.contains_key(&trusted_addr));
+    assert!(loaded.tested_addresses.contains_key(&recent_addr));
+    assert!(!loaded.tested_addresses.contains_key(&unknown_addr));
+}
+
+#[test]
+fn test_peer_db_save_is_bounded() {
+    let file = NamedTempFile::new().unwrap();
+    let mut peer_db = PeerDB::default();
+    for i in 0..10 {
+        let (peer_id, info, _) = create_peer([1, 1, 1, i], PeerState::Trusted);
+        peer_db.peers.insert(peer_id, info);
+    }
+    peer_db.save_to_file(file.path(), 4).unwrap();
+    let loaded = PeerDB::load_from_file(file.path(), 100, MassaTime::from_millis(ONE_DAY_MS));
+    assert_eq!(loaded.peers.len(), 4);
+}
+
+#[test]
+fn test_peer_db_load_corrupted() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), b"{\"peers\": [{\"peer_id\": ").unwrap();
+    let loaded = PeerDB::load_from_file(file.path(), 100, MassaTime::from_millis(ONE_DAY_MS));
+    assert!(loaded.peers.is_empty());
+
+    // a peer with a tampered announcement is skipped while the others are kept
+    let mut peer_db = PeerDB::default();
+    let (valid_id, valid_info, _) = create_peer([1, 2, 3, 4], PeerState::Trusted);
+    peer_db.peers.insert(valid_id.clone(), valid_info);
+    peer_db.save_to_file(file.path(), 100).unwrap();
+    let mut dump: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(file.path()).unwrap()).unwrap();
+    let (other_id, _, _) = create_peer([5, 6, 7, 8], PeerState::Trusted);
+    let mut tampered = dump["peers"][0].clone();
+    tampered["peer_id"] = serde_json::to_value(&other_id).unwrap();
+    dump["peers"].as_array_mut().unwrap().push(tampered);
+    std::fs::write(file.path(), serde_json::to_vec(&dump).unwrap()).unwrap();
+
+    let loaded = PeerDB::load_from_file(file.path(), 100, MassaTime::from_millis(ONE_DAY_MS));
+    assert_eq!(loaded.peers.len(), 1);
+    assert!(loaded.peers.contains_key(&valid_id));
+}
+
+#[test]
+fn test_peer_db_ip_bans_and_whitelist() {
+    let file = NamedTempFile::new().unwrap();
+    let banned_ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+    let whitelisted_ip = IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8));
+
+    let mut peer_db = PeerDB::default();
+    peer_db.add_to_whitelist(vec![whitelisted_ip]);
+    let banned = peer_db.ban_ips(vec![banned_ip, whitelisted_ip]);
+
+    // a whitelisted ip can't be banned
+    assert_eq!(banned.len(), 1);
+    assert!(peer_db.is_ip_banned(&banned_ip));
+    assert!(!peer_db.is_ip_banned(&whitelisted_ip));
+    // ipv4-mapped ipv6 addresses are matched on their canonical form
+    assert!(peer_db.is_ip_banned(&IpAddr::V6(Ipv4Addr::new(1, 2, 3, 4).to_ipv6_mapped())));
+
+    peer_db.save_to_file(file.path(), 100).unwrap();
+    let mut loaded = PeerDB::load_from_file(file.path(), 100, MassaTime::from_millis(ONE_DAY_MS));
+    assert!(loaded.is_ip_banned(&banned_ip));
+    assert!(loaded.is_ip_whitelisted(&whitelisted_ip));
+
+    loaded.unban_ips(vec![banned_ip]);
+    loaded.remove_from_whitelist(vec![whitelisted_ip]);
+    assert!(loaded.banned_ips.is_empty());
+    assert!(loaded.whitelisted_ips.is_empty());
+}
+
+#[test]
+fn test_peer_db_static_peers_persistence() {
+    let file = NamedTempFile::new().unwrap();
+    let (peer_id, peer_info, addr) = create_peer([9, 9, 9, 9], PeerState::Trusted);
+    let (other_peer_id, other_peer_info, other_addr) =
+        create_peer([8, 8, 8, 8], PeerState::Trusted);
+    let quic_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(7, 7, 7, 7)), 31245);
+
+    let mut peer_db = PeerDB::default();
+    peer_db.peers.insert(peer_id, peer_info);
+    peer_db.peers.insert(other_peer_id, other_peer_info);
+    peer_db.static_peers =
+        HashMap::from([(addr, TransportType::Tcp), (quic_addr, TransportType::Quic)]);
+
+    // a peer is static when connected from a static address or when it announced one
+    let ephemeral_addr = SocketAddr::new(addr.ip(), 50000);
+    assert!(peer_db.is_static_connection(&peer_id, &ephemeral_addr));
+    assert!(!peer_db.is_static_connection(&other_peer_id, &other_addr));
+
+    peer_db.save_static_peers(file.path()).unwrap();
+    let mut loaded = PeerDB::default();
+    loaded.load_static_peers(file.path());
+    assert_eq!(loaded.static_peers, peer_db.static_peers);
+    assert!(loaded.is_static_peer(&quic_addr));
+}
@@ -188,7 +188,13 @@ pub fn start_protocol_controller(
     massa_metrics: MassaMetrics,
 ) -> Result<(Box<dyn ProtocolManager>, KeyPair, NodeId), ProtocolError> {
     debug!("starting protocol controller");
-    let peer_db = Arc::new(RwLock::new(PeerDB::default()));
+    let mut peer_db = PeerDB::load_from_file(
+        &config.peers_db_file,
+        config.max_size_listeners_per_peer,
+        config.tested_addresses_max_age,
+    );
+    peer_db.load_static_peers(&config.static_peers_file);
+    let peer_db = Arc::new(RwLock::new(peer_db));
 
     let (sender_operations, receiver_operations) = MassaChannel::new(
         "sender_operations".to_string(),
@@ -214,6 +220,7 @@ pub fn start_protocol_controller(
         sender_operations: sender_operations.clone(),
         sender_peers: sender_peers.clone(),
         id_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+        peer_stats: Default::default(),
     };
 
     // try to read node keypair from file, otherwise generate it & write to file. Then derive nodeId
@@ -128,6 +128,7 @@ pub trait NetworkController: Send + Sync {
     ) -> Result<(), ProtocolError>;
     fn try_connect(
         &mut self,
+        transport_type: TransportType,
         addr: SocketAddr,
         timeout: std::time::Duration,
     ) -> Result<(), ProtocolError>;
@@ -174,12 +175,12 @@ impl NetworkController for NetworkControllerImpl {
 
     fn try_connect(
         &mut self,
+        transport_type: TransportType,
         addr: SocketAddr,
         timeout: std::time::Duration,
     ) -> Result<(), ProtocolError> {
-        //TODO: Change when we support multiple transports
         self.peernet_manager
-            .try_connect(TransportType::Tcp, addr, timeout)
+            .try_connect(transport_type, addr, timeout)
             .map_err(|err| ProtocolError::GeneralProtocolError(err.to_string()))?;
         Ok(())
     }
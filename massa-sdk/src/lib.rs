@@ -18,14 +18,19 @@ use massa_api_exports::page::PagedVecV2;
 use massa_api_exports::ApiRequest;
 use massa_api_exports::{
     address::AddressInfo,
+    async_message::AsyncMessageOutput,
     block::{BlockInfo, BlockSummary},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    execution::{
+        ExecuteReadOnlyResponse, OperationSimulation, OperationSimulationResponse,
+        ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    node::{ConnectedPeer, ConnectedPeersFilter, NodeStatus, TransportType},
     operation::{OperationInfo, OperationInput},
     TimeInterval,
 };
+use massa_async_pool::{AsyncMessageFilter, AsyncMessageId};
 use massa_models::secure_share::SecureShare;
 use massa_models::{
     address::Address,
@@ -44,6 +49,8 @@ use massa_models::{
 };
 use massa_proto_rs::massa::api::v1::private_service_client::PrivateServiceClient;
 use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
+use massa_time::MassaTime;
+use std::collections::BTreeMap;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use thiserror::Error;
@@ -185,6 +192,35 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Dev mode only: produce a block at each of the next `count` slots.
+    /// Returns the ids of the produced blocks.
+    pub async fn node_dev_advance_slots(&self, count: u64) -> RpcResult<Vec<BlockId>> {
+        self.http_client
+            .request("node_dev_advance_slots", rpc_params![count])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Dev mode only: move the clock forward without producing blocks.
+    /// Returns the new time.
+    pub async fn node_dev_advance_time(&self, duration: MassaTime) -> RpcResult<MassaTime> {
+        self.http_client
+            .request("node_dev_advance_time", rpc_params![duration])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns detailed information about the connected peers matching the optional filter
+    pub async fn node_connected_peers(
+        &self,
+        filter: Option<ConnectedPeersFilter>,
+    ) -> RpcResult<Vec<ConnectedPeer>> {
+        self.http_client
+            .request("node_connected_peers", rpc_params![filter])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Bans given ip address(es)
     /// No confirmation to expect.
     pub async fn node_ban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
@@ -221,6 +257,35 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Returns node static peers with their transport.
+    pub async fn node_static_peers(&self) -> RpcResult<BTreeMap<SocketAddr, TransportType>> {
+        self.http_client
+            .request("node_static_peers", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Add node static peers.
+    /// No confirmation to expect.
+    pub async fn node_add_static_peers(
+        &self,
+        peers: BTreeMap<SocketAddr, TransportType>,
+    ) -> RpcResult<()> {
+        self.http_client
+            .request("node_add_static_peers", rpc_params![peers])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Remove node static peers.
+    /// No confirmation to expect.
+    pub async fn node_remove_static_peers(&self, addrs: Vec<SocketAddr>) -> RpcResult<()> {
+        self.http_client
+            .request("node_remove_static_peers", rpc_params![addrs])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Returns node peers whitelist IP address(es).
     pub async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         self.http_client
@@ -302,6 +367,14 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Reload the API keys and rate limits of the public APIs from the node configuration.
+    pub async fn node_reload_api_access(&self) -> RpcResult<()> {
+        self.http_client
+            .request("node_reload_api_access", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     ////////////////
     // public-api //
     ////////////////
@@ -374,6 +447,18 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Get asynchronous messages with various filters, starting after the message `start_after` if provided
+    pub async fn get_async_messages(
+        &self,
+        filter: AsyncMessageFilter,
+        start_after: Option<AsyncMessageId>,
+    ) -> RpcResult<Vec<AsyncMessageOutput>> {
+        self.http_client
+            .request("get_async_messages", rpc_params![filter, start_after])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Get the block graph within the specified time interval.
     /// Optional parameters: from `<time_start>` (included) and to `<time_end>` (excluded) millisecond timestamp
     pub(crate) async fn _get_graph_interval(
@@ -453,6 +538,22 @@ impl RpcClient {
                 to_error_obj("missing return value on execute_read_only_call".to_owned())
             })
     }
+
+    /// simulate an operation on top of the candidate state
+    pub async fn simulate_operation(
+        &self,
+        simulation: OperationSimulation,
+    ) -> RpcResult<OperationSimulationResponse> {
+        self.http_client
+            .request::<Vec<OperationSimulationResponse>, Vec<Vec<OperationSimulation>>>(
+                "simulate_operations",
+                vec![vec![simulation]],
+            )
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))?
+            .pop()
+            .ok_or_else(|| to_error_obj("missing return value on simulate_operations".to_owned()))
+    }
 }
 
 /// Client V2
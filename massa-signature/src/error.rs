@@ -18,4 +18,10 @@ pub enum MassaSignatureError {
 
     /// invalid version identifier: {0}
     InvalidVersionError(String),
+
+    /// Multi-signature error: {0}
+    MultiSigError(String),
+
+    /// HD derivation error: {0}
+    HDError(String),
 }
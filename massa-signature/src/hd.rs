@@ -0,0 +1,187 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Hierarchical deterministic key derivation.
+//!
+//! Seeds are obtained from BIP-39 mnemonics, and keys are derived from the seed following
+//! SLIP-10 for Ed25519. SLIP-10 Ed25519 only defines hardened derivation, so every index of a
+//! derivation path is hardened. Account `n` is derived along the path `m/44'/632'/n'/0'/0'`.
+
+use crate::error::MassaSignatureError;
+use crate::signature_impl::KeyPair;
+use bip39::Mnemonic;
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Size in bytes of a seed derived from a mnemonic
+pub const HD_SEED_SIZE: usize = 64;
+
+/// Offset added to an index to make it hardened
+pub const HD_HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// Coin type of Massa registered in SLIP-44
+pub const HD_MASSA_COIN_TYPE: u32 = 632;
+
+/// HMAC key used to compute the master key from the seed (SLIP-10, Ed25519 curve)
+const SLIP10_ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+
+/// Generates a new random English BIP-39 mnemonic of `word_count` words (12, 15, 18, 21 or 24)
+pub fn generate_mnemonic(word_count: usize) -> Result<String, MassaSignatureError> {
+    Mnemonic::generate(word_count)
+        .map(|mnemonic| mnemonic.to_string())
+        .map_err(|err| MassaSignatureError::HDError(format!("cannot generate mnemonic: {}", err)))
+}
+
+/// Checks an English BIP-39 mnemonic and computes its seed, protected by an optional passphrase
+pub fn mnemonic_to_seed(
+    mnemonic: &str,
+    passphrase: &str,
+) -> Result<[u8; HD_SEED_SIZE], MassaSignatureError> {
+    let words = mnemonic.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mnemonic = Mnemonic::parse(words)
+        .map_err(|err| MassaSignatureError::HDError(format!("invalid mnemonic: {}", err)))?;
+    Ok(mnemonic.to_seed(passphrase))
+}
+
+/// SLIP-10 extended private key: a secret key and its chain code
+#[derive(Clone)]
+pub struct ExtendedSecretKey {
+    secret_key: [u8; 32],
+    chain_code: [u8; 32],
+}
+
+impl ExtendedSecretKey {
+    /// Computes the master key of a seed
+    pub fn from_seed(seed: &[u8]) -> Self {
+        Self::from_hmac(SLIP10_ED25519_SEED_KEY, &[seed])
+    }
+
+    /// Derives the hardened child key of the given index (`HD_HARDENED_OFFSET` is added to it).
+    /// Errors if the index is already in the hardened range.
+    pub fn derive_child(&self, index: u32) -> Result<Self, MassaSignatureError> {
+        if index >= HD_HARDENED_OFFSET {
+            return Err(MassaSignatureError::HDError(format!(
+                "derivation index {} is out of range",
+                index
+            )));
+        }
+        Ok(Self::from_hmac(
+            &self.chain_code,
+            &[
+                &[0u8],
+                &self.secret_key,
+                &(index + HD_HARDENED_OFFSET).to_be_bytes(),
+            ],
+        ))
+    }
+
+    /// Derives the key at the given path from this key, each index being hardened
+    pub fn derive_path(&self, path: &[u32]) -> Result<Self, MassaSignatureError> {
+        path.iter()
+            .try_fold(self.clone(), |key, index| key.derive_child(*index))
+    }
+
+    /// Get the secret key bytes
+    pub fn secret_key(&self) -> &[u8; 32] {
+        &self.secret_key
+    }
+
+    /// Get the chain code
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    /// Converts the secret key to a `KeyPair` of the given version
+    pub fn to_keypair(&self, version: u64) -> Result<KeyPair, MassaSignatureError> {
+        KeyPair::from_secret_bytes(version, &self.secret_key)
+    }
+
+    fn from_hmac(key: &[u8], data: &[&[u8]]) -> Self {
+        let mut mac = HmacSha512::new_from_slice(key).expect("HMAC accepts keys of any size");
+        for chunk in data {
+            mac.update(chunk);
+        }
+        let result = mac.finalize().into_bytes();
+        let mut secret_key = [0u8; 32];
+        let mut chain_code = [0u8; 32];
+        secret_key.copy_from_slice(&result[..32]);
+        chain_code.copy_from_slice(&result[32..]);
+        ExtendedSecretKey {
+            secret_key,
+            chain_code,
+        }
+    }
+}
+
+/// Returns the derivation path of the given account: `m/44'/632'/account'/0'/0'`
+pub fn account_derivation_path(account: u32) -> [u32; 5] {
+    [44, HD_MASSA_COIN_TYPE, account, 0, 0]
+}
+
+/// Derives the `KeyPair` of the given account from a seed
+pub fn derive_account_keypair(
+    seed: &[u8],
+    account: u32,
+    version: u64,
+) -> Result<KeyPair, MassaSignatureError> {
+    ExtendedSecretKey::from_seed(seed)
+        .derive_path(&account_derivation_path(account))?
+        .to_keypair(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_slip10_vector() {
+        // SLIP-10 test vector 1 for ed25519
+        let master = ExtendedSecretKey::from_seed(&from_hex("000102030405060708090a0b0c0d0e0f"));
+        assert_eq!(
+            master.chain_code().to_vec(),
+            from_hex("90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb")
+        );
+        assert_eq!(
+            master.secret_key().to_vec(),
+            from_hex("2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7")
+        );
+        let child = master.derive_path(&[0]).unwrap();
+        assert_eq!(
+            child.chain_code().to_vec(),
+            from_hex("8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69")
+        );
+        assert_eq!(
+            child.secret_key().to_vec(),
+            from_hex("68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3")
+        );
+        assert!(master.derive_child(HD_HARDENED_OFFSET).is_err());
+    }
+
+    #[test]
+    fn test_account_derivation() {
+        let mnemonic = generate_mnemonic(24).unwrap();
+        let seed = mnemonic_to_seed(&mnemonic, "").unwrap();
+        assert_eq!(
+            mnemonic_to_seed(&format!(" {} ", mnemonic), "").unwrap(),
+            seed
+        );
+        assert_ne!(mnemonic_to_seed(&mnemonic, "passphrase").unwrap(), seed);
+        assert!(mnemonic_to_seed("not a valid mnemonic", "").is_err());
+
+        let account_0 = derive_account_keypair(&seed, 0, 0).unwrap();
+        let account_1 = derive_account_keypair(&seed, 1, 0).unwrap();
+        assert_eq!(
+            derive_account_keypair(&seed, 0, 0).unwrap().to_bytes(),
+            account_0.to_bytes()
+        );
+        assert_ne!(account_0.get_public_key(), account_1.get_public_key());
+    }
+}
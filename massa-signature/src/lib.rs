@@ -4,9 +4,19 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 mod error;
+mod hd;
+mod multisig;
 mod signature_impl;
 
 pub use error::MassaSignatureError;
+pub use hd::{
+    account_derivation_path, derive_account_keypair, generate_mnemonic, mnemonic_to_seed,
+    ExtendedSecretKey, HD_HARDENED_OFFSET, HD_MASSA_COIN_TYPE, HD_SEED_SIZE,
+};
+pub use multisig::{
+    MultiSigPublicKey, MultiSigPublicKeyDeserializer, MultiSigPublicKeySerializer, MultiSignature,
+    MultiSignatureDeserializer, MultiSignatureSerializer, MULTISIG_MAX_PUBLIC_KEYS,
+};
 pub use signature_impl::{
     verify_signature_batch, KeyPair, PublicKey, PublicKeyDeserializer, PublicKeyV0, Signature,
     SignatureDeserializer,
@@ -0,0 +1,486 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! m-of-n multi-signatures built from independent signatures of the co-signers
+
+use crate::error::MassaSignatureError;
+use crate::signature_impl::{
+    verify_signature_batch, KeyPair, PublicKey, PublicKeyDeserializer, Signature,
+    SignatureDeserializer,
+};
+use massa_hash::Hash;
+use massa_serialization::{
+    Deserializer, SerializeError, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
+};
+use nom::{
+    error::{context, ContextError, ParseError},
+    multi::length_count,
+    sequence::tuple,
+    IResult, Parser,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Bound::Included;
+
+/// Maximum number of co-signers of a multi-signature public key
+pub const MULTISIG_MAX_PUBLIC_KEYS: usize = 20;
+
+/// Public key of an m-of-n multi-signature: the public keys of the co-signers (sorted, without duplicates)
+/// and the number of them that must sign
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedMultiSigPublicKey")]
+pub struct MultiSigPublicKey {
+    threshold: u8,
+    public_keys: Vec<PublicKey>,
+}
+
+/// Deserialized fields of a `MultiSigPublicKey`, before they are checked by `MultiSigPublicKey::new`
+#[derive(Deserialize)]
+struct UncheckedMultiSigPublicKey {
+    threshold: u8,
+    public_keys: Vec<PublicKey>,
+}
+
+impl TryFrom<UncheckedMultiSigPublicKey> for MultiSigPublicKey {
+    type Error = MassaSignatureError;
+
+    fn try_from(value: UncheckedMultiSigPublicKey) -> Result<Self, Self::Error> {
+        MultiSigPublicKey::new(value.threshold, value.public_keys)
+    }
+}
+
+impl MultiSigPublicKey {
+    /// Create a multi-signature public key requiring `threshold` signatures among `public_keys`.
+    /// The public keys are sorted, so that their order does not change the key.
+    ///
+    /// ```
+    /// # use massa_signature::{KeyPair, MultiSigPublicKey};
+    /// let public_keys: Vec<_> = (0..3).map(|_| KeyPair::generate(0).unwrap().get_public_key()).collect();
+    /// let multisig_public_key = MultiSigPublicKey::new(2, public_keys).unwrap();
+    /// assert_eq!(multisig_public_key.get_threshold(), 2);
+    /// ```
+    pub fn new(
+        threshold: u8,
+        mut public_keys: Vec<PublicKey>,
+    ) -> Result<Self, MassaSignatureError> {
+        public_keys.sort();
+        public_keys.dedup();
+        if public_keys.len() > MULTISIG_MAX_PUBLIC_KEYS {
+            return Err(MassaSignatureError::MultiSigError(format!(
+                "at most {} public keys are allowed, got {}",
+                MULTISIG_MAX_PUBLIC_KEYS,
+                public_keys.len()
+            )));
+        }
+        if threshold == 0 || threshold as usize > public_keys.len() {
+            return Err(MassaSignatureError::MultiSigError(format!(
+                "threshold {} is not in the range 1..={}",
+                threshold,
+                public_keys.len()
+            )));
+        }
+        Ok(MultiSigPublicKey {
+            threshold,
+            public_keys,
+        })
+    }
+
+    /// Number of co-signers that must sign
+    pub fn get_threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Public keys of the co-signers, sorted
+    pub fn get_public_keys(&self) -> &[PublicKey] {
+        &self.public_keys
+    }
+
+    /// Position of a public key among the co-signers
+    pub fn get_index(&self, public_key: &PublicKey) -> Option<u8> {
+        self.public_keys
+            .binary_search(public_key)
+            .ok()
+            .map(|index| index as u8)
+    }
+
+    /// Serialize the multi-signature public key as bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        MultiSigPublicKeySerializer::new()
+            .serialize(self, &mut buffer)
+            .expect("multi-signature public key serialization never fails");
+        buffer
+    }
+
+    /// Get the (hash, signature, public key) triplets to verify to check a multi-signature,
+    /// which allows batching them with other signatures.
+    /// Fails if the multi-signature does not hold enough signatures of known co-signers,
+    /// and always if it holds none.
+    pub fn get_signature_batch(
+        &self,
+        hash: &Hash,
+        multi_signature: &MultiSignature,
+    ) -> Result<Vec<(Hash, Signature, PublicKey)>, MassaSignatureError> {
+        if multi_signature.is_empty() || multi_signature.len() < self.threshold as usize {
+            return Err(MassaSignatureError::MultiSigError(format!(
+                "{} signatures are required, got {}",
+                self.threshold,
+                multi_signature.0.len()
+            )));
+        }
+        multi_signature
+            .0
+            .iter()
+            .map(|(index, signature)| {
+                let public_key = self.public_keys.get(*index as usize).ok_or_else(|| {
+                    MassaSignatureError::MultiSigError(format!("unknown co-signer {}", index))
+                })?;
+                Ok((*hash, *signature, *public_key))
+            })
+            .collect()
+    }
+
+    /// Check that a multi-signature of `hash` holds enough valid signatures of the co-signers
+    pub fn verify_signature(
+        &self,
+        hash: &Hash,
+        multi_signature: &MultiSignature,
+    ) -> Result<(), MassaSignatureError> {
+        verify_signature_batch(&self.get_signature_batch(hash, multi_signature)?)
+    }
+}
+
+/// Signatures of some co-signers of a `MultiSigPublicKey`, indexed by the position of their public key
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiSignature(pub BTreeMap<u8, Signature>);
+
+impl MultiSignature {
+    /// Sign `hash` with one of the co-signers of `multisig_public_key` and add the signature
+    ///
+    /// ```
+    /// # use massa_signature::{KeyPair, MultiSigPublicKey, MultiSignature};
+    /// # use massa_hash::Hash;
+    /// let keypairs: Vec<_> = (0..3).map(|_| KeyPair::generate(0).unwrap()).collect();
+    /// let multisig_public_key =
+    ///     MultiSigPublicKey::new(2, keypairs.iter().map(|k| k.get_public_key()).collect()).unwrap();
+    /// let hash = Hash::compute_from("Hello World!".as_bytes());
+    /// let mut multi_signature = MultiSignature::default();
+    /// multi_signature.sign(&multisig_public_key, &keypairs[0], &hash).unwrap();
+    /// assert!(multisig_public_key.verify_signature(&hash, &multi_signature).is_err());
+    /// multi_signature.sign(&multisig_public_key, &keypairs[2], &hash).unwrap();
+    /// assert!(multisig_public_key.verify_signature(&hash, &multi_signature).is_ok());
+    /// ```
+    pub fn sign(
+        &mut self,
+        multisig_public_key: &MultiSigPublicKey,
+        keypair: &KeyPair,
+        hash: &Hash,
+    ) -> Result<(), MassaSignatureError> {
+        let index = multisig_public_key
+            .get_index(&keypair.get_public_key())
+            .ok_or_else(|| {
+                MassaSignatureError::MultiSigError(format!(
+                    "{} is not a co-signer",
+                    keypair.get_public_key()
+                ))
+            })?;
+        self.0.insert(index, keypair.sign(hash)?);
+        Ok(())
+    }
+
+    /// Number of signatures collected
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no signature was collected yet
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Serializer for `MultiSigPublicKey`
+#[derive(Default, Clone)]
+pub struct MultiSigPublicKeySerializer {
+    u64_serializer: U64VarIntSerializer,
+}
+
+impl MultiSigPublicKeySerializer {
+    /// Creates a `MultiSigPublicKeySerializer`
+    pub const fn new() -> Self {
+        Self {
+            u64_serializer: U64VarIntSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<MultiSigPublicKey> for MultiSigPublicKeySerializer {
+    fn serialize(
+        &self,
+        value: &MultiSigPublicKey,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.u64_serializer
+            .serialize(&(value.threshold as u64), buffer)?;
+        self.u64_serializer
+            .serialize(&(value.public_keys.len() as u64), buffer)?;
+        for public_key in &value.public_keys {
+            buffer.extend(public_key.to_bytes());
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for `MultiSigPublicKey`
+#[derive(Clone)]
+pub struct MultiSigPublicKeyDeserializer {
+    threshold_deserializer: U64VarIntDeserializer,
+    length_deserializer: U64VarIntDeserializer,
+    public_key_deserializer: PublicKeyDeserializer,
+}
+
+impl MultiSigPublicKeyDeserializer {
+    /// Creates a `MultiSigPublicKeyDeserializer`
+    pub const fn new() -> Self {
+        Self {
+            threshold_deserializer: U64VarIntDeserializer::new(
+                Included(1),
+                Included(MULTISIG_MAX_PUBLIC_KEYS as u64),
+            ),
+            length_deserializer: U64VarIntDeserializer::new(
+                Included(1),
+                Included(MULTISIG_MAX_PUBLIC_KEYS as u64),
+            ),
+            public_key_deserializer: PublicKeyDeserializer::new(),
+        }
+    }
+}
+
+impl Default for MultiSigPublicKeyDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deserializer<MultiSigPublicKey> for MultiSigPublicKeyDeserializer {
+    /// ```
+    /// use massa_signature::{KeyPair, MultiSigPublicKey, MultiSigPublicKeyDeserializer};
+    /// use massa_serialization::{DeserializeError, Deserializer};
+    ///
+    /// let public_keys: Vec<_> = (0..3).map(|_| KeyPair::generate(0).unwrap().get_public_key()).collect();
+    /// let multisig_public_key = MultiSigPublicKey::new(2, public_keys).unwrap();
+    /// let serialized = multisig_public_key.to_bytes();
+    /// let (rest, deserialized) = MultiSigPublicKeyDeserializer::new().deserialize::<DeserializeError>(&serialized).unwrap();
+    /// assert!(rest.is_empty());
+    /// assert_eq!(multisig_public_key, deserialized);
+    /// ```
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], MultiSigPublicKey, E> {
+        let (rest, (threshold, public_keys)) = context(
+            "Failed MultiSigPublicKey deserialization",
+            tuple((
+                context("Failed threshold deserialization", |input| {
+                    self.threshold_deserializer.deserialize(input)
+                }),
+                length_count(
+                    context("Failed public_keys length deserialization", |input| {
+                        self.length_deserializer.deserialize(input)
+                    }),
+                    context("Failed public_key deserialization", |input| {
+                        self.public_key_deserializer.deserialize(input)
+                    }),
+                ),
+            )),
+        )
+        .parse(buffer)?;
+        // only the canonical form (sorted keys without duplicates) is accepted
+        let multisig_public_key = MultiSigPublicKey::new(threshold as u8, public_keys.clone())
+            .ok()
+            .filter(|multisig_public_key| multisig_public_key.public_keys == public_keys)
+            .ok_or_else(|| {
+                nom::Err::Error(ParseError::from_error_kind(
+                    buffer,
+                    nom::error::ErrorKind::Verify,
+                ))
+            })?;
+        Ok((rest, multisig_public_key))
+    }
+}
+
+/// Serializer for `MultiSignature`
+#[derive(Default, Clone)]
+pub struct MultiSignatureSerializer {
+    u64_serializer: U64VarIntSerializer,
+}
+
+impl MultiSignatureSerializer {
+    /// Creates a `MultiSignatureSerializer`
+    pub const fn new() -> Self {
+        Self {
+            u64_serializer: U64VarIntSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<MultiSignature> for MultiSignatureSerializer {
+    fn serialize(
+        &self,
+        value: &MultiSignature,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.u64_serializer
+            .serialize(&(value.0.len() as u64), buffer)?;
+        for (index, signature) in &value.0 {
+            self.u64_serializer.serialize(&(*index as u64), buffer)?;
+            buffer.extend(signature.to_bytes());
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for `MultiSignature`
+#[derive(Clone)]
+pub struct MultiSignatureDeserializer {
+    length_deserializer: U64VarIntDeserializer,
+    index_deserializer: U64VarIntDeserializer,
+}
+
+impl MultiSignatureDeserializer {
+    /// Creates a `MultiSignatureDeserializer`
+    pub const fn new() -> Self {
+        Self {
+            length_deserializer: U64VarIntDeserializer::new(
+                Included(0),
+                Included(MULTISIG_MAX_PUBLIC_KEYS as u64),
+            ),
+            index_deserializer: U64VarIntDeserializer::new(
+                Included(0),
+                Included(MULTISIG_MAX_PUBLIC_KEYS as u64 - 1),
+            ),
+        }
+    }
+}
+
+impl Default for MultiSignatureDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deserializer<MultiSignature> for MultiSignatureDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], MultiSignature, E> {
+        let (rest, signatures) = context(
+            "Failed MultiSignature deserialization",
+            length_count(
+                context("Failed signatures length deserialization", |input| {
+                    self.length_deserializer.deserialize(input)
+                }),
+                tuple((
+                    context("Failed index deserialization", |input| {
+                        self.index_deserializer.deserialize(input)
+                    }),
+                    context("Failed signature deserialization", |input| {
+                        SignatureDeserializer::new().deserialize(input)
+                    }),
+                )),
+            ),
+        )
+        .parse(buffer)?;
+        let len = signatures.len();
+        let signatures: BTreeMap<u8, Signature> = signatures
+            .into_iter()
+            .map(|(index, signature)| (index as u8, signature))
+            .collect();
+        // a co-signer can only sign once
+        if signatures.len() != len {
+            return Err(nom::Err::Error(ParseError::from_error_kind(
+                buffer,
+                nom::error::ErrorKind::Verify,
+            )));
+        }
+        Ok((rest, MultiSignature(signatures)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_serialization::DeserializeError;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_multisig() {
+        let keypairs: Vec<_> = (0..3).map(|_| KeyPair::generate(0).unwrap()).collect();
+        let multisig_public_key =
+            MultiSigPublicKey::new(2, keypairs.iter().map(|k| k.get_public_key()).collect())
+                .unwrap();
+        let hash = Hash::compute_from("Hello World!".as_bytes());
+
+        let mut multi_signature = MultiSignature::default();
+        multi_signature
+            .sign(&multisig_public_key, &keypairs[1], &hash)
+            .unwrap();
+        // a co-signer signing twice does not count twice
+        multi_signature
+            .sign(&multisig_public_key, &keypairs[1], &hash)
+            .unwrap();
+        assert!(multisig_public_key
+            .verify_signature(&hash, &multi_signature)
+            .is_err());
+        multi_signature
+            .sign(&multisig_public_key, &keypairs[0], &hash)
+            .unwrap();
+        multisig_public_key
+            .verify_signature(&hash, &multi_signature)
+            .unwrap();
+        assert!(multisig_public_key
+            .verify_signature(&Hash::compute_from("other".as_bytes()), &multi_signature)
+            .is_err());
+
+        // outsiders cannot sign
+        let outsider = KeyPair::generate(0).unwrap();
+        assert!(multi_signature
+            .sign(&multisig_public_key, &outsider, &hash)
+            .is_err());
+
+        let mut buffer = Vec::new();
+        MultiSignatureSerializer::new()
+            .serialize(&multi_signature, &mut buffer)
+            .unwrap();
+        let (rest, deserialized) = MultiSignatureDeserializer::new()
+            .deserialize::<DeserializeError>(&buffer)
+            .unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(deserialized, multi_signature);
+
+        // invalid thresholds are rejected
+        let public_keys: Vec<_> = keypairs.iter().map(|k| k.get_public_key()).collect();
+        assert!(MultiSigPublicKey::new(0, public_keys.clone()).is_err());
+        assert!(MultiSigPublicKey::new(4, public_keys.clone()).is_err());
+
+        // serde goes through the same checks
+        let json = serde_json::to_value(&multisig_public_key).unwrap();
+        assert_eq!(
+            serde_json::from_value::<MultiSigPublicKey>(json.clone()).unwrap(),
+            multisig_public_key
+        );
+        let mut zero_threshold = json;
+        zero_threshold["threshold"] = serde_json::json!(0);
+        assert!(serde_json::from_value::<MultiSigPublicKey>(zero_threshold).is_err());
+
+        // an empty multi-signature never verifies, even against an unchecked key
+        let unchecked = MultiSigPublicKey {
+            threshold: 0,
+            public_keys,
+        };
+        assert!(unchecked
+            .verify_signature(&hash, &MultiSignature::default())
+            .is_err());
+        assert!(verify_signature_batch(&[]).is_err());
+    }
+}
@@ -109,6 +109,27 @@ impl KeyPair {
         }
     }
 
+    /// Builds a KeyPair of the version given as parameter from raw secret key bytes.
+    /// Errors if the version number does not exist or if the bytes are not a valid secret key.
+    ///
+    /// # Example
+    ///  ```
+    /// # use massa_signature::KeyPair;
+    /// let keypair = KeyPair::from_secret_bytes(0, &[42u8; 32]).unwrap();
+    /// assert_eq!(keypair.get_version(), 0);
+    /// ```
+    pub fn from_secret_bytes(version: u64, secret: &[u8]) -> Result<Self, MassaSignatureError> {
+        match version {
+            <KeyPair!["0"]>::VERSION => {
+                Ok(KeyPairVariant!["0"](<KeyPair!["0"]>::from_bytes(secret)?))
+            }
+            _ => Err(MassaSignatureError::InvalidVersionError(format!(
+                "KeyPair version {} doesn't exist.",
+                version
+            ))),
+        }
+    }
+
     /// Returns the Signature produced by signing
     /// data bytes with a `KeyPair`.
     ///
@@ -1227,13 +1248,15 @@ impl Deserializer<Signature> for SignatureDeserializer {
     }
 }
 
-/// Verifies a batch of signatures
+/// Verifies a batch of signatures.
+/// An empty batch is rejected: it would otherwise be taken as valid without checking anything.
 pub fn verify_signature_batch(
     batch: &[(Hash, Signature, PublicKey)],
 ) -> Result<(), MassaSignatureError> {
-    // nothing to verify
     if batch.is_empty() {
-        return Ok(());
+        return Err(MassaSignatureError::SignatureError(
+            "empty signature batch".to_string(),
+        ));
     }
 
     // normal verif is fastest for size 1 batches
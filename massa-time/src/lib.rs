@@ -12,6 +12,7 @@ use nom::IResult;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::ops::Bound;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::{
     convert::{TryFrom, TryInto},
@@ -20,6 +21,10 @@ use std::{
 use time::format_description::well_known::Rfc3339;
 use time::{Date, OffsetDateTime};
 
+/// Time returned by `MassaTime::now()` when the clock is frozen, 0 if it is not.
+/// See `MassaTime::freeze_clock`.
+static FROZEN_CLOCK: AtomicU64 = AtomicU64::new(0);
+
 /// Time structure used everywhere.
 /// milliseconds since 01/01/1970.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -211,6 +216,10 @@ impl MassaTime {
     /// assert!(max(now_massa_time.saturating_sub(converted), converted.saturating_sub(now_massa_time)) < MassaTime::from_millis(100))
     /// ```
     pub fn now() -> Result<Self, TimeError> {
+        let frozen = FROZEN_CLOCK.load(Ordering::Acquire);
+        if frozen != 0 {
+            return Ok(MassaTime(frozen));
+        }
         let now: u64 = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map_err(|_| TimeError::TimeOverflowError)?
@@ -220,6 +229,28 @@ impl MassaTime {
         Ok(MassaTime(now))
     }
 
+    /// Freezes the time returned by `MassaTime::now()` at `time` for the whole process.
+    /// The clock then only moves when this function is called again, and never goes backwards.
+    /// Used by the node in dev mode to advance time on demand.
+    ///
+    /// ```
+    /// # use massa_time::*;
+    /// let time = MassaTime::now().unwrap().saturating_add(MassaTime::from_millis(3_600_000));
+    /// MassaTime::freeze_clock(time);
+    /// assert_eq!(MassaTime::now().unwrap(), time);
+    /// MassaTime::freeze_clock(MassaTime::from_millis(42));
+    /// assert_eq!(MassaTime::now().unwrap(), time);
+    /// assert!(MassaTime::is_clock_frozen());
+    /// ```
+    pub fn freeze_clock(time: MassaTime) {
+        FROZEN_CLOCK.fetch_max(time.0, Ordering::AcqRel);
+    }
+
+    /// Whether the clock was frozen by `MassaTime::freeze_clock`
+    pub fn is_clock_frozen() -> bool {
+        FROZEN_CLOCK.load(Ordering::Acquire) != 0
+    }
+
     /// Conversion to `std::time::Duration`.
     /// ```
     /// # use std::time::Duration;
@@ -6,6 +6,7 @@ use massa_time::MassaTime;
 
 #[allow(unused_imports)]
 use crate::versioning::{MipComponent, MipInfo, MipState};
+use massa_models::config::ChainSpecMip;
 
 pub fn get_mip_list() -> [(MipInfo, MipState); 0] {
     // placeholder
@@ -30,3 +31,44 @@ pub fn get_mip_list() -> [(MipInfo, MipState); 0] {
     #[allow(clippy::let_and_return)]
     mip_list
 }
+
+/// Build the MIP list defined in a chain spec, the MIPs being defined at the given timestamp
+pub fn get_mip_list_from_chain_spec(
+    mips: &[ChainSpecMip],
+    defined: MassaTime,
+) -> Result<Vec<(MipInfo, MipState)>, String> {
+    mips.iter()
+        .map(|mip| {
+            let components = mip
+                .components
+                .iter()
+                .map(|(name, version)| Ok((parse_mip_component(name)?, *version)))
+                .collect::<Result<BTreeMap<_, _>, String>>()
+                .map_err(|err| format!("MIP {}: {}", mip.name, err))?;
+            Ok((
+                MipInfo {
+                    name: mip.name.clone(),
+                    version: mip.version,
+                    components,
+                    start: mip.start,
+                    timeout: mip.timeout,
+                    activation_delay: mip.activation_delay,
+                },
+                MipState::new(defined),
+            ))
+        })
+        .collect()
+}
+
+fn parse_mip_component(name: &str) -> Result<MipComponent, String> {
+    match name {
+        "Address" => Ok(MipComponent::Address),
+        "KeyPair" => Ok(MipComponent::KeyPair),
+        "Block" => Ok(MipComponent::Block),
+        "VM" => Ok(MipComponent::VM),
+        "FinalStateHashKind" => Ok(MipComponent::FinalStateHashKind),
+        "Multisig" => Ok(MipComponent::Multisig),
+        "SequencedOperations" => Ok(MipComponent::SequencedOperations),
+        _ => Err(format!("unknown component {}", name)),
+    }
+}
@@ -45,6 +45,10 @@ pub enum MipComponent {
     Block,
     VM,
     FinalStateHashKind,
+    // Multisig addresses and operations (disabled at version 0)
+    Multisig,
+    // Sequenced operations (disabled at version 0)
+    SequencedOperations,
     #[doc(hidden)]
     #[num_enum(default)]
     __Nonexhaustive,
@@ -765,6 +769,16 @@ impl<const N: usize> TryFrom<([(MipInfo, MipState); N], MipStatsConfig)> for Mip
     }
 }
 
+impl TryFrom<(Vec<(MipInfo, MipState)>, MipStatsConfig)> for MipStore {
+    type Error = UpdateWithError;
+
+    fn try_from(
+        (value, cfg): (Vec<(MipInfo, MipState)>, MipStatsConfig),
+    ) -> Result<Self, Self::Error> {
+        MipStoreRaw::try_from((value, cfg)).map(|store_raw| Self(Arc::new(RwLock::new(store_raw))))
+    }
+}
+
 /// Statistics in MipStoreRaw
 #[derive(Debug, Clone, PartialEq)]
 pub struct MipStatsConfig {
@@ -1487,6 +1501,16 @@ impl<const N: usize> TryFrom<([(MipInfo, MipState); N], MipStatsConfig)> for Mip
 
     fn try_from(
         (value, cfg): ([(MipInfo, MipState); N], MipStatsConfig),
+    ) -> Result<Self, Self::Error> {
+        Self::try_from((Vec::from(value), cfg))
+    }
+}
+
+impl TryFrom<(Vec<(MipInfo, MipState)>, MipStatsConfig)> for MipStoreRaw {
+    type Error = UpdateWithError;
+
+    fn try_from(
+        (value, cfg): (Vec<(MipInfo, MipState)>, MipStatsConfig),
     ) -> Result<Self, Self::Error> {
         // Build an empty store
         let mut store = Self {
@@ -1496,7 +1520,7 @@ impl<const N: usize> TryFrom<([(MipInfo, MipState); N], MipStatsConfig)> for Mip
 
         // Build another one with given value
         let other_store = Self {
-            store: BTreeMap::from(value),
+            store: value.into_iter().collect(),
             stats: MipStoreStats::new(cfg),
         };
 
@@ -2474,6 +2498,8 @@ mod test {
             max_history_length: 100,
             max_new_elements: 100,
             thread_count: THREAD_COUNT,
+            enable_archive: false,
+            archive_retention_periods: 0,
         };
         let db = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
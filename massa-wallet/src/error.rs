@@ -24,4 +24,12 @@ pub enum WalletError {
     MissingKeyError(Address),
     /// `MassaCipher` error: {0}
     MassaCipherError(#[from] massa_cipher::CipherError),
+    /// JSON error: {0}
+    JSONError(#[from] serde_json::Error),
+    /// Keystore error: {0}
+    KeystoreError(String),
+    /// HD seed error: {0}
+    HDSeedError(String),
+    /// Signer error: {0}
+    SignerError(String),
 }
@@ -0,0 +1,368 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! JSON keystore format used to export wallet keys to other tools and import them back.
+//!
+//! A keystore holds a list of keys, each one encrypted with AES-256-GCM using a key derived
+//! from the keystore password with PBKDF2-HMAC-SHA256. All the parameters needed to decrypt
+//! a key are stored alongside it, binary values being base58 encoded:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "keys": [
+//!     {
+//!       "address": "AU12...",
+//!       "public_key": "P1...",
+//!       "crypto": {
+//!         "cipher": "aes-256-gcm",
+//!         "cipher_params": { "nonce": "..." },
+//!         "ciphertext": "...",
+//!         "kdf": "pbkdf2-hmac-sha256",
+//!         "kdf_params": { "rounds": 600000, "salt": "...", "key_length": 32 }
+//!       }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! The ciphertext is the encryption of the secret key bytes (`KeyPair::to_bytes`).
+
+use crate::WalletError;
+use massa_cipher::{
+    decrypt_with_rounds, encrypt_with_rounds, CipherData, KEY_SIZE, NONCE_SIZE, PBKDF2_ROUNDS,
+    SALT_SIZE,
+};
+use massa_models::address::Address;
+use massa_signature::{KeyPair, PublicKey};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Current version of the keystore format
+pub const KEYSTORE_VERSION: u64 = 1;
+
+/// Cipher used to encrypt the keys
+const KEYSTORE_CIPHER: &str = "aes-256-gcm";
+
+/// Key derivation function used to derive the cipher key from the password
+const KEYSTORE_KDF: &str = "pbkdf2-hmac-sha256";
+
+/// Minimum number of key derivation rounds accepted when decrypting a key,
+/// fewer rounds would make the password too easy to brute-force
+pub const KEYSTORE_MIN_ROUNDS: u32 = 10_000;
+
+/// Maximum number of key derivation rounds accepted when decrypting a key,
+/// so that a crafted keystore cannot stall the node or the client
+pub const KEYSTORE_MAX_ROUNDS: u32 = 10_000_000;
+
+/// A set of encrypted keys
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Keystore {
+    /// keystore format version
+    pub version: u64,
+    /// encrypted keys
+    pub keys: Vec<KeystoreEntry>,
+}
+
+/// An encrypted key of a keystore
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KeystoreEntry {
+    /// address of the key
+    pub address: String,
+    /// public key
+    pub public_key: String,
+    /// encrypted secret key and encryption parameters
+    pub crypto: KeystoreCrypto,
+}
+
+/// Encrypted secret key and the parameters needed to decrypt it
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KeystoreCrypto {
+    /// cipher name
+    pub cipher: String,
+    /// cipher parameters
+    pub cipher_params: KeystoreCipherParams,
+    /// base58 encoded encrypted secret key
+    pub ciphertext: String,
+    /// key derivation function name
+    pub kdf: String,
+    /// key derivation function parameters
+    pub kdf_params: KeystoreKdfParams,
+}
+
+/// Parameters of the cipher
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KeystoreCipherParams {
+    /// base58 encoded nonce
+    pub nonce: String,
+}
+
+/// Parameters of the key derivation function
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KeystoreKdfParams {
+    /// number of rounds
+    pub rounds: u32,
+    /// base58 encoded salt
+    pub salt: String,
+    /// length of the derived key in bytes
+    pub key_length: usize,
+}
+
+impl KeystoreEntry {
+    /// Encrypts a keypair with the given password
+    pub fn encrypt(keypair: &KeyPair, password: &str) -> Result<Self, WalletError> {
+        let encrypted = encrypt_with_rounds(password, &keypair.to_bytes(), PBKDF2_ROUNDS)?;
+        let public_key = keypair.get_public_key();
+        Ok(KeystoreEntry {
+            address: Address::from_public_key(&public_key).to_string(),
+            public_key: public_key.to_string(),
+            crypto: KeystoreCrypto {
+                cipher: KEYSTORE_CIPHER.to_string(),
+                cipher_params: KeystoreCipherParams {
+                    nonce: bs58::encode(encrypted.nonce).into_string(),
+                },
+                ciphertext: bs58::encode(encrypted.encrypted_bytes).into_string(),
+                kdf: KEYSTORE_KDF.to_string(),
+                kdf_params: KeystoreKdfParams {
+                    rounds: PBKDF2_ROUNDS,
+                    salt: bs58::encode(encrypted.salt).into_string(),
+                    key_length: KEY_SIZE,
+                },
+            },
+        })
+    }
+
+    /// Decrypts the keypair with the given password,
+    /// and checks that it matches the public key and address of the entry
+    pub fn decrypt(&self, password: &str) -> Result<KeyPair, WalletError> {
+        let crypto = &self.crypto;
+        if crypto.cipher != KEYSTORE_CIPHER {
+            return Err(WalletError::KeystoreError(format!(
+                "unsupported cipher: {}",
+                crypto.cipher
+            )));
+        }
+        if crypto.kdf != KEYSTORE_KDF || crypto.kdf_params.key_length != KEY_SIZE {
+            return Err(WalletError::KeystoreError(format!(
+                "unsupported key derivation: {} with a {} bytes key",
+                crypto.kdf, crypto.kdf_params.key_length
+            )));
+        }
+        if !(KEYSTORE_MIN_ROUNDS..=KEYSTORE_MAX_ROUNDS).contains(&crypto.kdf_params.rounds) {
+            return Err(WalletError::KeystoreError(format!(
+                "unsupported number of key derivation rounds: {} (expected {} to {})",
+                crypto.kdf_params.rounds, KEYSTORE_MIN_ROUNDS, KEYSTORE_MAX_ROUNDS
+            )));
+        }
+        let secret_key = decrypt_with_rounds(
+            password,
+            CipherData {
+                salt: decode_array::<SALT_SIZE>(&crypto.kdf_params.salt, "salt")?,
+                nonce: decode_array::<NONCE_SIZE>(&crypto.cipher_params.nonce, "nonce")?,
+                encrypted_bytes: decode(&crypto.ciphertext, "ciphertext")?,
+            },
+            crypto.kdf_params.rounds,
+        )?;
+        let keypair = KeyPair::from_bytes(&secret_key)?;
+        let public_key = keypair.get_public_key();
+        if PublicKey::from_str(&self.public_key)? != public_key
+            || Address::from_str(&self.address)? != Address::from_public_key(&public_key)
+        {
+            return Err(WalletError::KeystoreError(format!(
+                "the decrypted key does not match address {}",
+                self.address
+            )));
+        }
+        Ok(keypair)
+    }
+}
+
+impl Keystore {
+    /// Encrypts the given keypairs with the given password
+    pub fn new<'a>(
+        keypairs: impl IntoIterator<Item = &'a KeyPair>,
+        password: &str,
+    ) -> Result<Self, WalletError> {
+        Ok(Keystore {
+            version: KEYSTORE_VERSION,
+            keys: keypairs
+                .into_iter()
+                .map(|keypair| KeystoreEntry::encrypt(keypair, password))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Decrypts all the keypairs of the keystore with the given password
+    pub fn decrypt(&self, password: &str) -> Result<Vec<KeyPair>, WalletError> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(WalletError::KeystoreError(format!(
+                "unsupported keystore version: {}",
+                self.version
+            )));
+        }
+        self.keys
+            .iter()
+            .map(|entry| entry.decrypt(password))
+            .collect()
+    }
+
+    /// Reads a keystore from a JSON file
+    pub fn load(path: &Path) -> Result<Self, WalletError> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    /// Writes the keystore to a JSON file
+    pub fn save(&self, path: &Path) -> Result<(), WalletError> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+fn decode(value: &str, name: &str) -> Result<Vec<u8>, WalletError> {
+    bs58::decode(value)
+        .into_vec()
+        .map_err(|err| WalletError::KeystoreError(format!("invalid {}: {}", name, err)))
+}
+
+fn decode_array<const N: usize>(value: &str, name: &str) -> Result<[u8; N], WalletError> {
+    decode(value, name)?
+        .try_into()
+        .map_err(|_| WalletError::KeystoreError(format!("invalid {}: expected {} bytes", name, N)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Wallet;
+    use massa_models::prehash::PreHashSet;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_keystore_entry_encrypt_decrypt() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let entry = KeystoreEntry::encrypt(&keypair, "password").unwrap();
+        assert_eq!(
+            entry.address,
+            Address::from_public_key(&keypair.get_public_key()).to_string()
+        );
+        assert_eq!(entry.crypto.kdf_params.rounds, PBKDF2_ROUNDS);
+        assert_eq!(
+            entry.decrypt("password").unwrap().to_bytes(),
+            keypair.to_bytes()
+        );
+
+        // wrong password
+        assert!(matches!(
+            entry.decrypt("wrong password"),
+            Err(WalletError::MassaCipherError(_))
+        ));
+
+        // entry whose address does not match its key
+        let mut other_address = entry.clone();
+        other_address.address =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key()).to_string();
+        assert!(matches!(
+            other_address.decrypt("password"),
+            Err(WalletError::KeystoreError(_))
+        ));
+    }
+
+    #[test]
+    fn test_keystore_rounds_bounds() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let entry = KeystoreEntry::encrypt(&keypair, "password").unwrap();
+        for rounds in [
+            0,
+            KEYSTORE_MIN_ROUNDS - 1,
+            KEYSTORE_MAX_ROUNDS + 1,
+            u32::MAX,
+        ] {
+            let mut invalid = entry.clone();
+            invalid.crypto.kdf_params.rounds = rounds;
+            assert!(matches!(
+                invalid.decrypt("password"),
+                Err(WalletError::KeystoreError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_keystore_file_round_trip() {
+        let keypairs: Vec<_> = (0..2).map(|_| KeyPair::generate(0).unwrap()).collect();
+        let keystore = Keystore::new(&keypairs, "password").unwrap();
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join("keystore.json");
+        keystore.save(&path).unwrap();
+
+        let loaded = Keystore::load(&path).unwrap();
+        assert_eq!(loaded.version, KEYSTORE_VERSION);
+        let decrypted = loaded.decrypt("password").unwrap();
+        assert_eq!(
+            decrypted.iter().map(|k| k.to_bytes()).collect::<Vec<_>>(),
+            keypairs.iter().map(|k| k.to_bytes()).collect::<Vec<_>>()
+        );
+        assert!(loaded.decrypt("wrong password").is_err());
+
+        let mut other_version = loaded;
+        other_version.version = KEYSTORE_VERSION + 1;
+        assert!(matches!(
+            other_version.decrypt("password"),
+            Err(WalletError::KeystoreError(_))
+        ));
+    }
+
+    #[test]
+    fn test_wallet_export_import_keystore() {
+        let source_folder = TempDir::new().unwrap();
+        let mut source =
+            Wallet::new(source_folder.path().to_path_buf(), "source".to_string()).unwrap();
+        let keypairs: Vec<_> = (0..3).map(|_| KeyPair::generate(0).unwrap()).collect();
+        let addresses = source.add_keypairs(keypairs).unwrap();
+
+        // export a subset of the keys
+        let keystore = source.export_keystore(&addresses[..2], "export").unwrap();
+        assert_eq!(keystore.keys.len(), 2);
+        let unknown = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        assert!(matches!(
+            source.export_keystore(&[unknown], "export"),
+            Err(WalletError::MissingKeyError(_))
+        ));
+
+        let target_folder = TempDir::new().unwrap();
+        let mut target =
+            Wallet::new(target_folder.path().to_path_buf(), "target".to_string()).unwrap();
+        assert!(target.import_keystore(&keystore, "wrong password").is_err());
+        assert!(target.keys.is_empty());
+        assert_eq!(
+            target.import_keystore(&keystore, "export").unwrap(),
+            addresses[..2].to_vec()
+        );
+
+        // the imported keys are persisted with the password of the target wallet
+        let reloaded =
+            Wallet::new(target_folder.path().to_path_buf(), "target".to_string()).unwrap();
+        assert_eq!(
+            reloaded.get_wallet_address_list(),
+            addresses[..2].iter().copied().collect::<PreHashSet<_>>()
+        );
+    }
+
+    #[test]
+    fn test_wallet_change_password() {
+        let folder = TempDir::new().unwrap();
+        let mut wallet = Wallet::new(folder.path().to_path_buf(), "old".to_string()).unwrap();
+        let addresses = wallet
+            .add_keypairs(vec![KeyPair::generate(0).unwrap()])
+            .unwrap();
+        wallet.change_password("new".to_string()).unwrap();
+
+        assert!(Wallet::new(folder.path().to_path_buf(), "old".to_string()).is_err());
+        let reloaded = Wallet::new(folder.path().to_path_buf(), "new".to_string()).unwrap();
+        assert_eq!(
+            reloaded.get_wallet_address_list(),
+            addresses.into_iter().collect::<PreHashSet<_>>()
+        );
+        // no temporary file is left behind
+        assert_eq!(std::fs::read_dir(folder.path()).unwrap().count(), 1);
+    }
+}
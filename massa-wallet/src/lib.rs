@@ -5,22 +5,29 @@
 #![warn(unused_crate_dependencies)]
 
 pub use error::WalletError;
+pub use keystore::*;
+pub use signer::*;
 
 use massa_cipher::{decrypt, encrypt, CipherData, Salt};
 use massa_hash::Hash;
 use massa_models::address::Address;
 use massa_models::composite::PubkeySig;
-use massa_models::operation::{Operation, OperationSerializer, SecureShareOperation};
+use massa_models::operation::{
+    Operation, OperationSerializer, PartiallySignedOperation, SecureShareOperation,
+};
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::secure_share::SecureShareContent;
-use massa_signature::{KeyPair, PublicKey};
+use massa_signature::{derive_account_keypair, mnemonic_to_seed, KeyPair, PublicKey};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 mod error;
+mod keystore;
+mod signer;
 
 /// Contains the keypairs created in the wallet.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -31,6 +38,26 @@ pub struct Wallet {
     wallet_path: PathBuf,
     /// Password
     password: String,
+    /// HD seed the accounts are derived from, if any
+    #[serde(skip)]
+    hd_seed: Option<HDSeed>,
+}
+
+/// Name of the file containing the encrypted HD seed, in the wallet directory
+const HD_SEED_FILE_NAME: &str = "hd_seed.yaml";
+
+/// Extension of the files being written, renamed to the wallet files once all of them are written
+const TEMP_FILE_EXTENSION: &str = "tmp";
+
+/// Decrypted HD seed and derivation state
+#[derive(Clone, Debug)]
+struct HDSeed {
+    /// seed computed from the mnemonic
+    seed: Vec<u8>,
+    /// version of the derived keypairs
+    keypair_version: u64,
+    /// index of the next account to derive, accounts `0..next_account_index` were derived
+    next_account_index: u32,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -46,15 +73,33 @@ struct WalletFileFormat {
     public_key: Vec<u8>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// Encrypted HD seed and derivation state, stored next to the key files
+struct HDSeedFileFormat {
+    version: u64,
+    salt: Salt,
+    nonce: [u8; 12],
+    ciphered_seed: Vec<u8>,
+    next_account_index: u32,
+}
+
 impl Wallet {
     /// Generates a new wallet initialized with the provided file content
     pub fn new(path: PathBuf, password: String) -> Result<Wallet, WalletError> {
         if path.is_dir() {
             let mut keys = PreHashMap::default();
+            let hd_seed_path = path.join(HD_SEED_FILE_NAME);
+            let hd_seed = if hd_seed_path.is_file() {
+                Some(Wallet::load_hd_seed(&hd_seed_path, &password)?)
+            } else {
+                None
+            };
             for entry in std::fs::read_dir(&path)? {
                 let entry = entry?;
                 let path = entry.path();
-                if path.is_file() {
+                let is_temp_file = path.extension() == Some(OsStr::new(TEMP_FILE_EXTENSION));
+                if path.is_file() && path != hd_seed_path && !is_temp_file {
                     let content = &std::fs::read(&path)?[..];
                     let wallet = serde_yaml::from_slice::<WalletFileFormat>(content)?;
                     let secret_key = decrypt(
@@ -75,12 +120,14 @@ impl Wallet {
                 keys,
                 wallet_path: path,
                 password,
+                hd_seed,
             })
         } else {
             let wallet = Wallet {
                 keys: PreHashMap::default(),
                 wallet_path: path,
                 password,
+                hd_seed: None,
             };
             wallet.save()?;
             Ok(wallet)
@@ -154,6 +201,8 @@ impl Wallet {
     }
 
     /// Save the wallets in a directory, each wallet in a yaml file.
+    /// All the files are first written to temporary files, then renamed,
+    /// so that a failed write leaves the existing files untouched.
     pub fn save(&self) -> Result<(), WalletError> {
         let mut existing_keys: HashSet<PathBuf> = HashSet::new();
         if !self.wallet_path.exists() {
@@ -164,7 +213,35 @@ impl Wallet {
                 existing_keys.insert(path?.path());
             }
         }
+        let mut written_files: Vec<(PathBuf, PathBuf)> = Vec::new();
+        if let Err(err) = self.write_temp_files(&mut written_files) {
+            for (temp_path, _) in &written_files {
+                let _ = std::fs::remove_file(temp_path);
+            }
+            return Err(err);
+        }
+
         let mut persisted_keys: HashSet<PathBuf> = HashSet::new();
+        for (temp_path, file_path) in written_files {
+            std::fs::rename(&temp_path, &file_path)?;
+            persisted_keys.insert(file_path);
+        }
+
+        // temporary files left by an interrupted save may have been renamed already
+        let to_remove = existing_keys.difference(&persisted_keys);
+        for path in to_remove.filter(|path| path.exists()) {
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write every wallet file to a temporary file next to it,
+    /// `written_files` receives the (temporary path, final path) of the files written so far
+    fn write_temp_files(
+        &self,
+        written_files: &mut Vec<(PathBuf, PathBuf)>,
+    ) -> Result<(), WalletError> {
         // write the keys in the directory
         for (addr, keypair) in &self.keys {
             let encrypted_secret = encrypt(&self.password, &keypair.to_bytes())?;
@@ -179,24 +256,157 @@ impl Wallet {
             };
             let ser_keys = serde_yaml::to_string(&file_formatted)?;
             let file_path = self.wallet_path.join(format!("wallet_{}.yaml", addr));
-
-            std::fs::write(&file_path, ser_keys)?;
-            persisted_keys.insert(file_path);
+            write_temp_file(written_files, file_path, ser_keys)?;
         }
 
-        let to_remove = existing_keys.difference(&persisted_keys);
-        for path in to_remove {
-            std::fs::remove_file(path)?;
+        // write the HD seed
+        if let Some(hd_seed) = &self.hd_seed {
+            let encrypted_seed = encrypt(&self.password, &hd_seed.seed)?;
+            let file_formatted = HDSeedFileFormat {
+                version: hd_seed.keypair_version,
+                salt: encrypted_seed.salt,
+                nonce: encrypted_seed.nonce,
+                ciphered_seed: encrypted_seed.encrypted_bytes,
+                next_account_index: hd_seed.next_account_index,
+            };
+            let file_path = self.wallet_path.join(HD_SEED_FILE_NAME);
+            write_temp_file(
+                written_files,
+                file_path,
+                serde_yaml::to_string(&file_formatted)?,
+            )?;
         }
 
         Ok(())
     }
 
+    /// Reads and decrypts the HD seed file
+    fn load_hd_seed(path: &Path, password: &str) -> Result<HDSeed, WalletError> {
+        let file = serde_yaml::from_slice::<HDSeedFileFormat>(&std::fs::read(path)?)?;
+        let seed = decrypt(
+            password,
+            CipherData {
+                salt: file.salt,
+                nonce: file.nonce,
+                encrypted_bytes: file.ciphered_seed,
+            },
+        )?;
+        Ok(HDSeed {
+            seed,
+            keypair_version: file.version,
+            next_account_index: file.next_account_index,
+        })
+    }
+
+    /// Sets the HD seed of the wallet from a BIP-39 mnemonic and an optional passphrase,
+    /// then derives its first `account_count` accounts and returns their addresses.
+    /// Errors if the wallet already has an HD seed. The wallet file is updated.
+    pub fn set_hd_seed_from_mnemonic(
+        &mut self,
+        mnemonic: &str,
+        passphrase: &str,
+        keypair_version: u64,
+        account_count: u32,
+    ) -> Result<Vec<Address>, WalletError> {
+        if self.hd_seed.is_some() {
+            return Err(WalletError::HDSeedError(
+                "the wallet already has an HD seed".to_string(),
+            ));
+        }
+        let seed = mnemonic_to_seed(mnemonic, passphrase)?.to_vec();
+        let keypairs = (0..account_count)
+            .map(|account| derive_account_keypair(&seed, account, keypair_version))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.hd_seed = Some(HDSeed {
+            seed,
+            keypair_version,
+            next_account_index: account_count,
+        });
+        let addresses = keypairs
+            .into_iter()
+            .map(|keypair| {
+                let address = Address::from_public_key(&keypair.get_public_key());
+                self.keys.entry(address).or_insert(keypair);
+                address
+            })
+            .collect();
+        self.save()?;
+        Ok(addresses)
+    }
+
+    /// Derives the next account from the HD seed of the wallet and returns its address.
+    /// The wallet file is updated.
+    pub fn derive_next_account(&mut self) -> Result<Address, WalletError> {
+        let hd_seed = self
+            .hd_seed
+            .as_mut()
+            .ok_or_else(|| WalletError::HDSeedError("the wallet has no HD seed".to_string()))?;
+        let keypair = derive_account_keypair(
+            &hd_seed.seed,
+            hd_seed.next_account_index,
+            hd_seed.keypair_version,
+        )?;
+        hd_seed.next_account_index += 1;
+        let address = Address::from_public_key(&keypair.get_public_key());
+        self.keys.entry(address).or_insert(keypair);
+        self.save()?;
+        Ok(address)
+    }
+
+    /// Get the number of accounts derived from the HD seed, or `None` if the wallet has no HD seed
+    pub fn get_hd_account_count(&self) -> Option<u32> {
+        self.hd_seed
+            .as_ref()
+            .map(|hd_seed| hd_seed.next_account_index)
+    }
+
     /// Export keys and addresses
     pub fn get_full_wallet(&self) -> &PreHashMap<Address, KeyPair> {
         &self.keys
     }
 
+    /// Exports the keys of the given addresses (or all the keys if `addresses` is empty)
+    /// in a keystore encrypted with `password`
+    pub fn export_keystore(
+        &self,
+        addresses: &[Address],
+        password: &str,
+    ) -> Result<Keystore, WalletError> {
+        if addresses.is_empty() {
+            return Keystore::new(self.keys.values(), password);
+        }
+        let keypairs = addresses
+            .iter()
+            .map(|address| {
+                self.find_associated_keypair(address)
+                    .ok_or(WalletError::MissingKeyError(*address))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Keystore::new(keypairs, password)
+    }
+
+    /// Imports the keys of a keystore encrypted with `password`, returns their addresses.
+    /// The wallet file is updated.
+    pub fn import_keystore(
+        &mut self,
+        keystore: &Keystore,
+        password: &str,
+    ) -> Result<Vec<Address>, WalletError> {
+        let keypairs = keystore.decrypt(password)?;
+        self.add_keypairs(keypairs)
+    }
+
+    /// Changes the wallet password and re-encrypts every wallet file with it.
+    /// If a file cannot be written, the wallet files and password are left unchanged.
+    pub fn change_password(&mut self, new_password: String) -> Result<(), WalletError> {
+        let old_password = std::mem::replace(&mut self.password, new_password);
+        if let Err(err) = self.save() {
+            self.password = old_password;
+            return Err(err);
+        }
+        Ok(())
+    }
+
     /// Signs an operation with the keypair corresponding to the given address
     pub fn create_operation(
         &self,
@@ -208,6 +418,48 @@ impl Wallet {
             .ok_or_else(|| WalletError::MissingKeyError(address))?;
         Ok(Operation::new_verifiable(content, OperationSerializer::new(), sender_keypair).unwrap())
     }
+
+    /// Adds the signature of the keypair corresponding to the given address
+    /// to a multisig operation whose co-signer signatures are being collected
+    pub fn cosign_operation(
+        &self,
+        operation: &mut PartiallySignedOperation,
+        address: &Address,
+    ) -> Result<(), WalletError> {
+        let cosigner_keypair = self
+            .find_associated_keypair(address)
+            .ok_or_else(|| WalletError::MissingKeyError(*address))?;
+        operation.sign(cosigner_keypair)?;
+        Ok(())
+    }
+
+    /// Signs a multisig operation that collected enough co-signer signatures
+    /// with the keypair of its creator
+    pub fn finalize_multisig_operation(
+        &self,
+        operation: PartiallySignedOperation,
+    ) -> Result<SecureShareOperation, WalletError> {
+        operation.verify_signatures()?;
+        self.create_operation(
+            operation.content,
+            Address::from_public_key(&operation.content_creator_pub_key),
+        )
+    }
+}
+
+/// Write `content` to a temporary file next to `file_path`, after recording both paths in `written_files`
+fn write_temp_file(
+    written_files: &mut Vec<(PathBuf, PathBuf)>,
+    file_path: PathBuf,
+    content: String,
+) -> Result<(), WalletError> {
+    let mut temp_path = file_path.clone().into_os_string();
+    temp_path.push(".");
+    temp_path.push(TEMP_FILE_EXTENSION);
+    let temp_path = PathBuf::from(temp_path);
+    written_files.push((temp_path.clone(), file_path));
+    std::fs::write(&temp_path, content)?;
+    Ok(())
 }
 
 impl std::fmt::Display for Wallet {
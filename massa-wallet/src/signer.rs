@@ -0,0 +1,398 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Abstraction over the holder of the staking keys.
+//!
+//! A `Signer` signs on behalf of the addresses whose keys it holds, without exposing the keys.
+//! `LocalSigner` uses the keys of the node wallet, while `RemoteSigner` forwards the signature
+//! requests to a separate signer process, so that the staking keys do not need to be stored on
+//! the node host. The signer process is told what is being signed (`SigningPayload`) so that
+//! it can apply its own double-signing and rate policies.
+//!
+//! The remote signer protocol runs over a Unix socket: each `SignerRequest` is written as a line
+//! of JSON, and the signer process answers with a `SignerResponse` on a single line.
+
+use crate::{Wallet, WalletError};
+use massa_hash::Hash;
+use massa_models::{
+    address::Address,
+    prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
+};
+use massa_signature::{PublicKey, Signature};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Description of the content being signed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SigningPayload {
+    /// block header
+    Block {
+        /// slot of the block
+        slot: Slot,
+    },
+    /// endorsement
+    Endorsement {
+        /// slot of the endorsement
+        slot: Slot,
+        /// index of the endorsement
+        index: u32,
+    },
+    /// arbitrary message
+    Message,
+}
+
+/// Holder of keys able to sign on behalf of addresses
+pub trait Signer: Send + Sync {
+    /// Get the addresses whose keys are held by the signer
+    fn get_addresses(&self) -> Result<PreHashSet<Address>, WalletError>;
+
+    /// Get the public key of an address, `None` if the signer does not hold its key
+    fn get_public_key(&self, address: &Address) -> Result<Option<PublicKey>, WalletError>;
+
+    /// Signs a hash with the key of an address, `payload` describing the signed content
+    fn sign(
+        &self,
+        address: &Address,
+        payload: &SigningPayload,
+        hash: &Hash,
+    ) -> Result<Signature, WalletError>;
+}
+
+/// Signer using the keys of a local wallet
+#[derive(Clone)]
+pub struct LocalSigner {
+    wallet: Arc<RwLock<Wallet>>,
+}
+
+impl LocalSigner {
+    /// Creates a signer using the keys of the given wallet
+    pub fn new(wallet: Arc<RwLock<Wallet>>) -> Self {
+        LocalSigner { wallet }
+    }
+}
+
+impl Signer for LocalSigner {
+    fn get_addresses(&self) -> Result<PreHashSet<Address>, WalletError> {
+        Ok(self.wallet.read().get_wallet_address_list())
+    }
+
+    fn get_public_key(&self, address: &Address) -> Result<Option<PublicKey>, WalletError> {
+        Ok(self.wallet.read().find_associated_public_key(address))
+    }
+
+    fn sign(
+        &self,
+        address: &Address,
+        _payload: &SigningPayload,
+        hash: &Hash,
+    ) -> Result<Signature, WalletError> {
+        let wallet = self.wallet.read();
+        let keypair = wallet
+            .find_associated_keypair(address)
+            .ok_or(WalletError::MissingKeyError(*address))?;
+        Ok(keypair.sign(hash)?)
+    }
+}
+
+/// Request sent to a remote signer process
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum SignerRequest {
+    /// list the public keys held by the signer
+    GetPublicKeys,
+    /// sign a hash with the key of an address
+    Sign {
+        /// signing address
+        address: Address,
+        /// description of the signed content
+        payload: SigningPayload,
+        /// hash to sign
+        hash: Hash,
+    },
+}
+
+/// Response of a remote signer process
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum SignerResponse {
+    /// public keys held by the signer
+    PublicKeys {
+        /// public keys
+        public_keys: Vec<PublicKey>,
+    },
+    /// signature of the requested hash
+    Signature {
+        /// signature
+        signature: Signature,
+    },
+    /// the request was refused or failed
+    Error {
+        /// reason
+        message: String,
+    },
+}
+
+#[cfg(unix)]
+pub use remote::RemoteSigner;
+
+#[cfg(unix)]
+mod remote {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    /// Signer forwarding the signature requests to a signer process listening on a Unix socket
+    pub struct RemoteSigner {
+        /// path of the socket of the signer process
+        socket_path: PathBuf,
+        /// read and write timeout of the requests
+        timeout: Duration,
+        /// how long the list of public keys of the signer is cached
+        key_cache_duration: Duration,
+        /// connection to the signer process, opened on the first request
+        connection: Mutex<Option<BufReader<UnixStream>>>,
+        /// cached public keys and the instant they were fetched
+        public_keys: RwLock<Option<(PreHashMap<Address, PublicKey>, Instant)>>,
+    }
+
+    impl RemoteSigner {
+        /// Creates a signer talking to the signer process listening on `socket_path`
+        pub fn new(socket_path: PathBuf, timeout: Duration, key_cache_duration: Duration) -> Self {
+            RemoteSigner {
+                socket_path,
+                timeout,
+                key_cache_duration,
+                connection: Mutex::new(None),
+                public_keys: RwLock::new(None),
+            }
+        }
+
+        /// Sends a request and waits for its response
+        fn request(&self, request: &SignerRequest) -> Result<SignerResponse, WalletError> {
+            let mut connection = self.connection.lock();
+            if connection.is_none() {
+                let stream = UnixStream::connect(&self.socket_path)?;
+                stream.set_read_timeout(Some(self.timeout))?;
+                stream.set_write_timeout(Some(self.timeout))?;
+                *connection = Some(BufReader::new(stream));
+            }
+            let result = connection
+                .as_mut()
+                .map(|reader| Self::exchange(reader, request))
+                .expect("connection is open");
+            if result.is_err() {
+                // the stream may be out of sync, reconnect on the next request
+                *connection = None;
+            }
+            match result? {
+                SignerResponse::Error { message } => Err(WalletError::SignerError(message)),
+                response => Ok(response),
+            }
+        }
+
+        fn exchange(
+            reader: &mut BufReader<UnixStream>,
+            request: &SignerRequest,
+        ) -> Result<SignerResponse, WalletError> {
+            let mut line = serde_json::to_vec(request)?;
+            line.push(b'\n');
+            reader.get_mut().write_all(&line)?;
+            let mut response = String::new();
+            if reader.read_line(&mut response)? == 0 {
+                return Err(WalletError::SignerError(
+                    "connection closed by the signer".to_string(),
+                ));
+            }
+            Ok(serde_json::from_str(&response)?)
+        }
+
+        /// Get the public keys of the signer, from the cache if it is recent enough
+        fn get_public_keys(&self) -> Result<PreHashMap<Address, PublicKey>, WalletError> {
+            if let Some((public_keys, fetched_at)) = &*self.public_keys.read() {
+                if fetched_at.elapsed() < self.key_cache_duration {
+                    return Ok(public_keys.clone());
+                }
+            }
+            let public_keys: PreHashMap<Address, PublicKey> =
+                match self.request(&SignerRequest::GetPublicKeys)? {
+                    SignerResponse::PublicKeys { public_keys } => public_keys
+                        .into_iter()
+                        .map(|public_key| (Address::from_public_key(&public_key), public_key))
+                        .collect(),
+                    response => {
+                        return Err(WalletError::SignerError(format!(
+                            "unexpected signer response: {:?}",
+                            response
+                        )))
+                    }
+                };
+            *self.public_keys.write() = Some((public_keys.clone(), Instant::now()));
+            Ok(public_keys)
+        }
+    }
+
+    impl Signer for RemoteSigner {
+        fn get_addresses(&self) -> Result<PreHashSet<Address>, WalletError> {
+            Ok(self.get_public_keys()?.into_keys().collect())
+        }
+
+        fn get_public_key(&self, address: &Address) -> Result<Option<PublicKey>, WalletError> {
+            Ok(self.get_public_keys()?.get(address).copied())
+        }
+
+        /// The returned signature is checked against the public key of the address,
+        /// so that a faulty signer cannot make the node publish invalid signatures.
+        fn sign(
+            &self,
+            address: &Address,
+            payload: &SigningPayload,
+            hash: &Hash,
+        ) -> Result<Signature, WalletError> {
+            let public_key = self
+                .get_public_key(address)?
+                .ok_or(WalletError::MissingKeyError(*address))?;
+            let request = SignerRequest::Sign {
+                address: *address,
+                payload: payload.clone(),
+                hash: *hash,
+            };
+            let signature = match self.request(&request)? {
+                SignerResponse::Signature { signature } => signature,
+                response => {
+                    return Err(WalletError::SignerError(format!(
+                        "unexpected signer response: {:?}",
+                        response
+                    )))
+                }
+            };
+            public_key
+                .verify_signature(hash, &signature)
+                .map_err(|err| {
+                    WalletError::SignerError(format!(
+                        "invalid signature returned by the signer for {}: {}",
+                        address, err
+                    ))
+                })?;
+            Ok(signature)
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+    use std::path::Path;
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// Serves one connection on `socket_path`, advertising the public key of `advertised_keypair`
+    /// and answering the signature requests with `signing_keypair`
+    fn spawn_mock_signer(
+        socket_path: &Path,
+        advertised_keypair: KeyPair,
+        signing_keypair: KeyPair,
+    ) -> JoinHandle<Vec<SignerRequest>> {
+        let listener = UnixListener::bind(socket_path).unwrap();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut requests = Vec::new();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 {
+                let request: SignerRequest = serde_json::from_str(&line).unwrap();
+                line.clear();
+                let response = match &request {
+                    SignerRequest::GetPublicKeys => SignerResponse::PublicKeys {
+                        public_keys: vec![advertised_keypair.get_public_key()],
+                    },
+                    SignerRequest::Sign { hash, .. } => SignerResponse::Signature {
+                        signature: signing_keypair.sign(hash).unwrap(),
+                    },
+                };
+                requests.push(request);
+                let mut response = serde_json::to_vec(&response).unwrap();
+                response.push(b'\n');
+                reader.get_mut().write_all(&response).unwrap();
+            }
+            requests
+        })
+    }
+
+    #[test]
+    fn test_remote_signer() {
+        let folder = TempDir::new().unwrap();
+        let socket_path = folder.path().join("signer.sock");
+        let keypair = KeyPair::generate(0).unwrap();
+        let address = Address::from_public_key(&keypair.get_public_key());
+        let server = spawn_mock_signer(&socket_path, keypair.clone(), keypair.clone());
+
+        let signer =
+            RemoteSigner::new(socket_path, Duration::from_secs(5), Duration::from_secs(60));
+        assert_eq!(
+            signer.get_addresses().unwrap(),
+            std::iter::once(address).collect::<PreHashSet<_>>()
+        );
+        assert_eq!(
+            signer.get_public_key(&address).unwrap(),
+            Some(keypair.get_public_key())
+        );
+        let hash = Hash::compute_from(b"block header");
+        let payload = SigningPayload::Block {
+            slot: Slot::new(1, 0),
+        };
+        let signature = signer.sign(&address, &payload, &hash).unwrap();
+        keypair
+            .get_public_key()
+            .verify_signature(&hash, &signature)
+            .unwrap();
+
+        // addresses unknown to the signer are not forwarded
+        let unknown = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        assert!(matches!(
+            signer.sign(&unknown, &payload, &hash),
+            Err(WalletError::MissingKeyError(_))
+        ));
+
+        drop(signer);
+        let requests = server.join().unwrap();
+        // the public keys were fetched once, then served from the cache
+        assert_eq!(requests.len(), 2);
+        assert!(matches!(
+            &requests[1],
+            SignerRequest::Sign { address: a, payload: p, hash: h }
+                if *a == address && *p == payload && *h == hash
+        ));
+    }
+
+    #[test]
+    fn test_remote_signer_rejects_invalid_signature() {
+        let folder = TempDir::new().unwrap();
+        let socket_path = folder.path().join("signer.sock");
+        let keypair = KeyPair::generate(0).unwrap();
+        let address = Address::from_public_key(&keypair.get_public_key());
+        // the signer answers with the signature of another key
+        let server = spawn_mock_signer(&socket_path, keypair, KeyPair::generate(0).unwrap());
+
+        let signer =
+            RemoteSigner::new(socket_path, Duration::from_secs(5), Duration::from_secs(60));
+        assert!(matches!(
+            signer.sign(
+                &address,
+                &SigningPayload::Message,
+                &Hash::compute_from(b"message")
+            ),
+            Err(WalletError::SignerError(_))
+        ));
+        drop(signer);
+        server.join().unwrap();
+    }
+}